@@ -0,0 +1,17 @@
+-- Your SQL goes here
+CREATE TABLE "funding_fee_events" (
+    id SERIAL PRIMARY KEY NOT NULL,
+    position_id INTEGER NOT NULL REFERENCES positions (id),
+    trader_pubkey TEXT NOT NULL,
+    -- Positive if the trader pays the coordinator, negative if the coordinator pays the trader.
+    amount_sats BIGINT NOT NULL,
+    funding_rate REAL NOT NULL,
+    mark_price REAL NOT NULL,
+    index_price REAL NOT NULL,
+    -- The start of the funding interval the fee was charged for.
+    due_date TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- Set once the fee has been settled with the rollover of the position.
+    paid_date TIMESTAMP WITH TIME ZONE,
+    timestamp TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (position_id, due_date)
+);
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE trigger_orders;
+DROP TYPE "TriggerOrderState_Type";
+DROP TYPE "TriggerType_Type";
+
+-- Postgres does not allow removing enum type values. One can only re-create an enum type with fewer values and replace the references.
+-- However, there is no proper way to replace the values to be removed where they are used (i.e. referenced in `orders` table)
+-- We opt to NOT remove enum values that were added at a later point.
@@ -0,0 +1,22 @@
+-- Your SQL goes here
+-- Note that the `IF NOT EXISTS` is essential because there is no `down` migration for removing this value because it is not really feasible to remove enum values!
+-- In order to allow re-running this migration we thus have to make sure to only add the value if it does not exist yet.
+ALTER TYPE "OrderReason_Type"
+ADD
+    VALUE IF NOT EXISTS 'Triggered';
+
+CREATE TYPE "TriggerType_Type" AS ENUM ('StopLoss', 'TakeProfit');
+CREATE TYPE "TriggerOrderState_Type" AS ENUM ('Active', 'Triggered', 'Cancelled');
+
+CREATE TABLE "trigger_orders" (
+    id SERIAL PRIMARY KEY NOT NULL,
+    trigger_order_id UUID UNIQUE NOT NULL,
+    position_id INTEGER NOT NULL REFERENCES positions (id),
+    trader_pubkey TEXT NOT NULL,
+    contract_symbol "ContractSymbol_Type" NOT NULL,
+    trigger_type "TriggerType_Type" NOT NULL,
+    trigger_price REAL NOT NULL,
+    trigger_order_state "TriggerOrderState_Type" NOT NULL,
+    creation_timestamp TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    update_timestamp TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE "price_history" (
+    id SERIAL PRIMARY KEY NOT NULL,
+    contract_symbol "ContractSymbol_Type" NOT NULL,
+    index_price REAL NOT NULL,
+    mark_price REAL NOT NULL,
+    timestamp TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX price_history_contract_symbol_timestamp ON price_history (contract_symbol, timestamp);
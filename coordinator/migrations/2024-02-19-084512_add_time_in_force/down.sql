@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE orders
+    DROP COLUMN "time_in_force",
+    DROP COLUMN "post_only";
+
+DROP TYPE "TimeInForce_Type";
@@ -0,0 +1,12 @@
+-- Your SQL goes here
+CREATE TYPE "TimeInForce_Type" AS ENUM (
+    'ImmediateOrCancel',
+    'FillOrKill',
+    'GoodTillCancelled',
+    'GoodTillTime'
+);
+
+-- All orders created so far were bound to their expiry.
+ALTER TABLE "orders"
+    ADD COLUMN "time_in_force" "TimeInForce_Type" NOT NULL DEFAULT 'GoodTillTime',
+    ADD COLUMN "post_only" BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE transactions ADD COLUMN "replaced_by" TEXT;
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- The order a partially filled limit order has been split off from.
+ALTER TABLE "orders"
+    ADD COLUMN "parent_order_id" UUID REFERENCES orders (trader_order_id);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE funding_fee_events
+    DROP COLUMN "rollover_date";
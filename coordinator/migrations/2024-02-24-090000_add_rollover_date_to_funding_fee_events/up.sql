@@ -0,0 +1,5 @@
+-- Your SQL goes here
+-- The cut-off of the last rollover proposed to settle the fee. The fee is only marked as paid once
+-- that rollover has been finalized.
+ALTER TABLE "funding_fee_events"
+    ADD COLUMN "rollover_date" TIMESTAMP WITH TIME ZONE;
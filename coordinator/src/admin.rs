@@ -12,8 +12,11 @@ use axum::Json;
 use bdk::FeeRate;
 use bdk::LocalUtxo;
 use bdk::TransactionDetails;
+use bitcoin::psbt::PartiallySignedTransaction;
 use bitcoin::secp256k1::PublicKey;
+use bitcoin::Address;
 use bitcoin::OutPoint;
+use bitcoin::Txid;
 use commons::CollaborativeRevertCoordinatorRequest;
 use commons::LegacyCollaborativeRevertCoordinatorRequest;
 use dlc_manager::channel::Channel;
@@ -21,7 +24,9 @@ use dlc_manager::contract::Contract;
 use dlc_manager::Storage;
 use lightning::chain::chaininterface::ConfirmationTarget;
 use lightning_invoice::Bolt11Invoice;
+use ln_dlc_node::node::Fee;
 use ln_dlc_node::node::NodeInfo;
+use ln_dlc_node::node::PsbtWallet;
 use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
@@ -72,16 +77,172 @@ pub async fn get_balance(State(state): State<Arc<AppState>>) -> Result<Json<Bala
     .map_err(|e| AppError::InternalServerError(format!("Failed to get balance: {e:#}")))?
 }
 
-pub async fn get_utxos(
+pub async fn get_watch_only_balance(
     State(state): State<Arc<AppState>>,
-) -> Result<Json<Vec<LocalUtxo>>, AppError> {
+) -> Result<Json<bdk::Balance>, AppError> {
+    spawn_blocking(move || {
+        let balance = state.node.inner.get_watch_only_balance().map_err(|e| {
+            AppError::InternalServerError(format!("Failed to get watch-only balance: {e:#}"))
+        })?;
+
+        Ok(Json(balance))
+    })
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Failed to get balance: {e:#}")))?
+}
+
+pub async fn get_watch_only_address(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<String>, AppError> {
+    let address = state.node.inner.get_watch_only_address().map_err(|e| {
+        AppError::InternalServerError(format!("Failed to get watch-only address: {e:#}"))
+    })?;
+
+    Ok(Json(address.to_string()))
+}
+
+#[derive(Serialize)]
+pub struct Utxo {
+    #[serde(flatten)]
+    pub utxo: LocalUtxo,
+    pub label: Option<String>,
+    /// Frozen UTXOs are left out of automatic coin selection.
+    pub frozen: bool,
+}
+
+pub async fn get_utxos(State(state): State<Arc<AppState>>) -> Result<Json<Vec<Utxo>>, AppError> {
     let utxos = state.node.inner.ldk_wallet().get_utxos().map_err(|error| {
         AppError::InternalServerError(format!("Failed to retrieve UTXOs {error}"))
     })?;
 
+    let mut metadata = state.node.inner.get_utxo_metadata().map_err(|error| {
+        AppError::InternalServerError(format!("Failed to retrieve UTXO metadata {error:#}"))
+    })?;
+
+    let utxos = utxos
+        .into_iter()
+        .map(|utxo| {
+            let metadata = metadata.remove(&utxo.outpoint).unwrap_or_default();
+
+            Utxo {
+                utxo,
+                label: metadata.label,
+                frozen: metadata.frozen,
+            }
+        })
+        .collect();
+
     Ok(Json(utxos))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UtxoParams {
+    /// Whether the UTXO should be left out of automatic coin selection. Unchanged if not
+    /// provided.
+    frozen: Option<bool>,
+    /// The new label of the UTXO. Unchanged if not provided, removed if empty.
+    label: Option<String>,
+}
+
+#[instrument(skip_all, err(Debug))]
+pub async fn put_utxo(
+    State(state): State<Arc<AppState>>,
+    Path(outpoint): Path<String>,
+    Json(params): Json<UtxoParams>,
+) -> Result<(), AppError> {
+    let outpoint = OutPoint::from_str(&outpoint)
+        .map_err(|e| AppError::BadRequest(format!("Invalid outpoint {outpoint}: {e:#}")))?;
+
+    if let Some(frozen) = params.frozen {
+        state
+            .node
+            .inner
+            .set_utxo_frozen(outpoint, frozen)
+            .map_err(|e| AppError::InternalServerError(format!("{e:#}")))?;
+    }
+
+    if let Some(label) = params.label {
+        state
+            .node
+            .inner
+            .set_utxo_label(outpoint, Some(label))
+            .map_err(|e| AppError::InternalServerError(format!("{e:#}")))?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SendParams {
+    address: String,
+    amount: u64,
+    /// The UTXOs to spend. If empty, they are selected automatically, leaving out frozen UTXOs.
+    #[serde(default)]
+    inputs: Vec<OutPoint>,
+    /// If not provided, it will default to the normal fee rate estimate.
+    sats_vbyte: Option<f32>,
+}
+
+#[instrument(skip_all, err(Debug))]
+pub async fn send_on_chain(
+    State(state): State<Arc<AppState>>,
+    Json(params): Json<SendParams>,
+) -> Result<Json<Txid>, AppError> {
+    let address = Address::from_str(&params.address)
+        .map_err(|e| AppError::BadRequest(format!("Invalid address: {e:#}")))?;
+
+    let fee = match params.sats_vbyte {
+        Some(sats_vbyte) => Fee::FeeRate(FeeRate::from_sat_per_vb(sats_vbyte)),
+        None => Fee::Priority(ConfirmationTarget::Normal),
+    };
+
+    spawn_blocking(move || {
+        let txid = state
+            .node
+            .inner
+            .send_to_address(&address, params.amount, fee, &params.inputs)
+            .map_err(|e| AppError::InternalServerError(format!("Failed to send: {e:#}")))?;
+
+        Ok(Json(txid))
+    })
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Failed to send: {e:#}")))?
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConsolidateParams {
+    /// The UTXOs to merge. If empty, all UTXOs which are not frozen are merged.
+    #[serde(default)]
+    inputs: Vec<OutPoint>,
+    /// If not provided, it will default to the background fee rate estimate.
+    sats_vbyte: Option<f32>,
+}
+
+#[instrument(skip_all, err(Debug))]
+pub async fn consolidate_utxos(
+    State(state): State<Arc<AppState>>,
+    Json(params): Json<ConsolidateParams>,
+) -> Result<Json<Txid>, AppError> {
+    let fee = match params.sats_vbyte {
+        Some(sats_vbyte) => Fee::FeeRate(FeeRate::from_sat_per_vb(sats_vbyte)),
+        None => Fee::Priority(ConfirmationTarget::Background),
+    };
+
+    spawn_blocking(move || {
+        let txid = state
+            .node
+            .inner
+            .consolidate_utxos(&params.inputs, fee)
+            .map_err(|e| {
+                AppError::InternalServerError(format!("Failed to consolidate UTXOs: {e:#}"))
+            })?;
+
+        Ok(Json(txid))
+    })
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Failed to consolidate UTXOs: {e:#}")))?
+}
+
 #[derive(Serialize)]
 pub struct FeeRateEstimation(u32);
 
@@ -455,6 +616,167 @@ pub async fn list_on_chain_transactions(
     .map_err(|e| AppError::InternalServerError(format!("Failed to list transactions: {e:#}")))?
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeBumpMethod {
+    /// Replace the transaction with one paying a higher fee. Only works for transactions we sent.
+    Rbf,
+    /// Spend one of our outputs of the transaction with a child paying for both.
+    Cpfp,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeeBumpParams {
+    txid: Txid,
+    method: FeeBumpMethod,
+    /// Defines the fee rate the transaction should be bumped to. If not provided, it will default
+    /// to the high priority fee rate estimate.
+    sats_vbyte: Option<f32>,
+}
+
+#[instrument(skip_all, err(Debug))]
+pub async fn bump_fee(
+    State(state): State<Arc<AppState>>,
+    Json(params): Json<FeeBumpParams>,
+) -> Result<Json<Txid>, AppError> {
+    let fee = match params.sats_vbyte {
+        Some(sats_vbyte) => Fee::FeeRate(FeeRate::from_sat_per_vb(sats_vbyte)),
+        None => Fee::Priority(ConfirmationTarget::HighPriority),
+    };
+
+    spawn_blocking(move || {
+        let txid = match params.method {
+            FeeBumpMethod::Rbf => state.node.inner.replace_by_fee(&params.txid, fee),
+            FeeBumpMethod::Cpfp => state.node.inner.child_pays_for_parent(&params.txid, fee),
+        }
+        .map_err(|e| {
+            AppError::InternalServerError(format!(
+                "Failed to bump fee of transaction {}: {e:#}",
+                params.txid
+            ))
+        })?;
+
+        Ok(Json(txid))
+    })
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Failed to bump fee: {e:#}")))?
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PsbtWalletParam {
+    Hot,
+    WatchOnly,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePsbtParams {
+    wallet: PsbtWalletParam,
+    address: String,
+    /// The amount to send. If `0`, all funds of the wallet are sent.
+    amount: u64,
+    /// Defines the fee rate for the transaction. If not provided, it will default to the normal
+    /// fee rate estimate.
+    sats_vbyte: Option<f32>,
+}
+
+/// Create an unsigned PSBT for a withdrawal, to be signed externally and submitted via
+/// [`broadcast_psbt`].
+#[instrument(skip_all, err(Debug))]
+pub async fn create_psbt(
+    State(state): State<Arc<AppState>>,
+    Json(params): Json<CreatePsbtParams>,
+) -> Result<Json<String>, AppError> {
+    let address = Address::from_str(&params.address)
+        .map_err(|e| AppError::BadRequest(format!("Invalid address provided: {e:#}")))?;
+
+    let wallet = match params.wallet {
+        PsbtWalletParam::Hot => PsbtWallet::Hot,
+        PsbtWalletParam::WatchOnly => PsbtWallet::WatchOnly,
+    };
+
+    let fee = match params.sats_vbyte {
+        Some(sats_vbyte) => Fee::FeeRate(FeeRate::from_sat_per_vb(sats_vbyte)),
+        None => Fee::Priority(ConfirmationTarget::Normal),
+    };
+
+    let psbt = state
+        .node
+        .inner
+        .create_unsigned_psbt(wallet, &address, params.amount, fee)
+        .map_err(|e| AppError::InternalServerError(format!("Failed to create PSBT: {e:#}")))?;
+
+    Ok(Json(psbt.to_string()))
+}
+
+#[derive(Serialize)]
+pub struct FundingPsbt {
+    temporary_channel_id: String,
+    counterparty: PublicKey,
+    psbt: String,
+}
+
+/// The unsigned funding PSBTs of externally funded channels.
+pub async fn list_funding_psbts(State(state): State<Arc<AppState>>) -> Json<Vec<FundingPsbt>> {
+    let psbts = state
+        .node
+        .inner
+        .pending_funding_psbts()
+        .into_iter()
+        .map(|pending| FundingPsbt {
+            temporary_channel_id: hex::encode(pending.temporary_channel_id.0),
+            counterparty: pending.counterparty_node_id,
+            psbt: pending.psbt.to_string(),
+        })
+        .collect();
+
+    Json(psbts)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BroadcastPsbtParams {
+    /// The signed PSBT, base64 encoded.
+    psbt: String,
+}
+
+#[instrument(skip_all, err(Debug))]
+pub async fn broadcast_psbt(
+    State(state): State<Arc<AppState>>,
+    Json(params): Json<BroadcastPsbtParams>,
+) -> Result<Json<Txid>, AppError> {
+    let psbt = PartiallySignedTransaction::from_str(&params.psbt)
+        .map_err(|e| AppError::BadRequest(format!("Invalid PSBT provided: {e:#}")))?;
+
+    spawn_blocking(move || {
+        let txid = state.node.inner.broadcast_signed_psbt(psbt).map_err(|e| {
+            AppError::InternalServerError(format!("Failed to broadcast PSBT: {e:#}"))
+        })?;
+
+        Ok(Json(txid))
+    })
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Failed to broadcast PSBT: {e:#}")))?
+}
+
+/// Abandon an unsigned PSBT, releasing its inputs. If the PSBT funds a channel, the channel is
+/// closed.
+#[instrument(skip_all, err(Debug))]
+pub async fn abandon_psbt(
+    State(state): State<Arc<AppState>>,
+    Path(txid): Path<String>,
+) -> Result<(), AppError> {
+    let txid = Txid::from_str(&txid)
+        .map_err(|e| AppError::BadRequest(format!("Invalid txid provided: {e:#}")))?;
+
+    state
+        .node
+        .inner
+        .abandon_psbt(&txid)
+        .map_err(|e| AppError::InternalServerError(format!("Failed to abandon PSBT: {e:#}")))?;
+
+    Ok(())
+}
+
 pub async fn list_peers(State(state): State<Arc<AppState>>) -> Json<Vec<PublicKey>> {
     let peers = state.node.inner.list_peers();
     Json(peers)
@@ -487,6 +809,10 @@ pub struct ChannelParams {
     /// Defines the fee rate for the channel opening transaction. If not provided, it will default
     /// to system settings
     sats_vbyte: Option<f32>,
+    /// If set, the channel is funded from the watch-only wallet. The unsigned funding PSBT is
+    /// listed under `/api/admin/psbt/channel-fundings` once the counterparty accepted the channel.
+    #[serde(default)]
+    external_funding: bool,
 }
 
 #[derive(Deserialize)]
@@ -521,6 +847,14 @@ pub async fn open_channel(
         pending_channel_opening.insert(pubkey, FeeRate::from_sat_per_vb(fee_rate));
     }
 
+    if channel_params.external_funding {
+        state
+            .node
+            .inner
+            .fund_channel_externally(pubkey)
+            .map_err(|e| AppError::BadRequest(format!("Cannot fund channel externally: {e:#}")))?;
+    }
+
     let channel_id = state
         .node
         .inner
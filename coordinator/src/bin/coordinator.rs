@@ -6,6 +6,7 @@ use coordinator::cli::Opts;
 use coordinator::dlc_handler;
 use coordinator::dlc_handler::DlcHandler;
 use coordinator::logger;
+use coordinator::market_data;
 use coordinator::message::spawn_delivering_messages_to_authenticated_users;
 use coordinator::message::NewUserMessage;
 use coordinator::metrics;
@@ -13,14 +14,21 @@ use coordinator::metrics::init_meter;
 use coordinator::node;
 use coordinator::node::connection;
 use coordinator::node::expired_positions;
+use coordinator::node::funding_fee;
+use coordinator::node::liquidated_positions;
 use coordinator::node::rollover;
 use coordinator::node::storage::NodeStorage;
+use coordinator::node::trigger_orders;
 use coordinator::node::unrealized_pnl;
 use coordinator::node::Node;
 use coordinator::notifications::NotificationService;
 use coordinator::orderbook::async_match;
 use coordinator::orderbook::collaborative_revert;
+use coordinator::orderbook::depth;
+use coordinator::orderbook::depth::OrderbookDepth;
 use coordinator::orderbook::trading;
+use coordinator::price;
+use coordinator::price::PriceService;
 use coordinator::routes::router;
 use coordinator::run_migration;
 use coordinator::scheduler::NotificationScheduler;
@@ -53,6 +61,7 @@ const PROCESS_PROMETHEUS_METRICS: Duration = Duration::from_secs(10);
 const PROCESS_INCOMING_DLC_MESSAGES_INTERVAL: Duration = Duration::from_millis(200);
 const EXPIRED_POSITION_SYNC_INTERVAL: Duration = Duration::from_secs(5 * 60);
 const UNREALIZED_PNL_SYNC_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const FUNDING_FEE_SYNC_INTERVAL: Duration = Duration::from_secs(10 * 60);
 const CONNECTION_CHECK_INTERVAL: Duration = Duration::from_secs(30);
 
 const NODE_ALIAS: &str = "10101.finance";
@@ -148,10 +157,29 @@ async fn main() -> Result<()> {
         node_event_handler.subscribe(),
     );
 
+    if let Some(descriptor) = &opts.watch_only_descriptor {
+        node.load_watch_only_wallet(
+            data_dir.as_path(),
+            descriptor,
+            opts.watch_only_change_descriptor.as_deref(),
+        )
+        .await
+        .context("Failed to load watch-only wallet")?;
+    }
+
     let event_handler = CoordinatorEventHandler::new(node.clone(), Some(node_event_sender));
     let running = node.start(event_handler, false)?;
     let node = Node::new(node, running, pool.clone(), settings.to_node_settings());
 
+    let oracle_params = settings
+        .oracle
+        .to_oracle_params(node.inner.oracle_pubkey, &node.inner.oracle_pk())
+        .context("Invalid oracle settings")?;
+    tracing::info!(?oracle_params, "Using oracles for new contracts");
+
+    let price_service = PriceService::from_settings(&settings.price, network, pool.clone());
+    let _handle = price::monitor(pool.clone(), price_service.clone());
+
     // TODO: Pass the tokio metrics into Prometheus
     if let Some(interval) = opts.tokio_metrics_interval_seconds {
         let handle = tokio::runtime::Handle::current();
@@ -212,10 +240,11 @@ async fn main() -> Result<()> {
 
     tokio::spawn({
         let node = node.clone();
+        let price_service = price_service.clone();
         async move {
             loop {
                 tokio::time::sleep(UNREALIZED_PNL_SYNC_INTERVAL).await;
-                if let Err(e) = unrealized_pnl::sync(node.clone()).await {
+                if let Err(e) = unrealized_pnl::sync(node.clone(), &price_service).await {
                     tracing::error!(
                         "Failed to sync unrealized PnL with positions in database: {e:#}"
                     );
@@ -224,6 +253,19 @@ async fn main() -> Result<()> {
         }
     });
 
+    tokio::spawn({
+        let node = node.clone();
+        let price_service = price_service.clone();
+        async move {
+            loop {
+                tokio::time::sleep(FUNDING_FEE_SYNC_INTERVAL).await;
+                if let Err(e) = funding_fee::charge(node.clone(), &price_service).await {
+                    tracing::error!("Failed to charge funding fees: {e:#}");
+                }
+            }
+        }
+    });
+
     let (tx_user_feed, _rx) = broadcast::channel::<NewUserMessage>(100);
 
     let (tx_price_feed, _rx) = broadcast::channel(100);
@@ -241,14 +283,14 @@ async fn main() -> Result<()> {
         tx_price_feed.clone(),
         auth_users_notifier.clone(),
         network,
-        node.inner.oracle_pubkey,
+        oracle_params.clone(),
     );
     let _handle = async_match::monitor(
         pool.clone(),
         tx_user_feed.clone(),
         auth_users_notifier.clone(),
         network,
-        node.inner.oracle_pubkey,
+        oracle_params,
     );
     let _handle = rollover::monitor(
         pool.clone(),
@@ -262,6 +304,14 @@ async fn main() -> Result<()> {
         tx_user_feed.clone(),
         auth_users_notifier.clone(),
     );
+    let _handle =
+        liquidated_positions::monitor(pool.clone(), price_service.clone(), trading_sender.clone());
+    let _handle =
+        trigger_orders::monitor(pool.clone(), price_service.clone(), trading_sender.clone());
+    let _handle = market_data::monitor(pool.clone(), tx_price_feed.clone());
+
+    let orderbook_depth = OrderbookDepth::default();
+    let _handle = depth::monitor(pool.clone(), tx_price_feed.clone(), orderbook_depth.clone());
 
     tokio::spawn({
         let node = node.clone();
@@ -296,6 +346,7 @@ async fn main() -> Result<()> {
         tx_user_feed,
         auth_users_notifier.clone(),
         user_backup,
+        orderbook_depth,
     );
 
     let sender = notification_service.get_sender();
@@ -78,6 +78,16 @@ pub struct Opts {
         default_value = "16f88cf7d21e6c0f46bcbc983a4e3b19726c6c98858cc31c83551a88fde171c0"
     )]
     pub oracle_pubkey: String,
+
+    /// The public descriptor of a watch-only wallet for funds kept in cold storage, e.g.
+    /// `wpkh([fingerprint/84'/0'/0']xpub.../0/*)`. Funds of this wallet can only be spent with
+    /// PSBTs which are signed externally.
+    #[clap(long)]
+    pub watch_only_descriptor: Option<String>,
+
+    /// The public change descriptor of the watch-only wallet.
+    #[clap(long, requires = "watch_only_descriptor")]
+    pub watch_only_change_descriptor: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
@@ -0,0 +1,106 @@
+use crate::schema::funding_fee_events;
+use bitcoin::secp256k1::PublicKey;
+use bitcoin::SignedAmount;
+use diesel::prelude::*;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use time::OffsetDateTime;
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = funding_fee_events)]
+struct NewFundingFeeEvent {
+    position_id: i32,
+    trader_pubkey: String,
+    amount_sats: i64,
+    funding_rate: f32,
+    mark_price: f32,
+    index_price: f32,
+    due_date: OffsetDateTime,
+}
+
+/// Inserts a funding fee event for the given position.
+///
+/// Returns false if the position has already been charged for the funding interval starting at
+/// `due_date`.
+#[allow(clippy::too_many_arguments)]
+pub fn insert(
+    conn: &mut PgConnection,
+    position_id: i32,
+    trader_pubkey: PublicKey,
+    amount: SignedAmount,
+    funding_rate: Decimal,
+    mark_price: Decimal,
+    index_price: Decimal,
+    due_date: OffsetDateTime,
+) -> QueryResult<bool> {
+    let affected_rows = diesel::insert_into(funding_fee_events::table)
+        .values(NewFundingFeeEvent {
+            position_id,
+            trader_pubkey: trader_pubkey.to_string(),
+            amount_sats: amount.to_sat(),
+            funding_rate: funding_rate.to_f32().expect("to fit into f32"),
+            mark_price: mark_price.to_f32().expect("to fit into f32"),
+            index_price: index_price.to_f32().expect("to fit into f32"),
+            due_date,
+        })
+        .on_conflict((
+            funding_fee_events::position_id,
+            funding_fee_events::due_date,
+        ))
+        .do_nothing()
+        .execute(conn)?;
+
+    Ok(affected_rows > 0)
+}
+
+/// Reserves all funding fees of the given position which have not been paid yet and were charged
+/// before the given timestamp for the rollover proposed at that time. Returns their sum.
+///
+/// Fees reserved for an earlier rollover which has not been finalized are reserved again, as that
+/// rollover has been abandoned. Fees charged after the proposal are left for the next rollover.
+///
+/// A positive amount has to be paid by the trader, a negative amount by the coordinator.
+pub fn reserve_for_rollover(
+    conn: &mut PgConnection,
+    position_id: i32,
+    before: OffsetDateTime,
+) -> QueryResult<SignedAmount> {
+    let amounts: Vec<i64> = diesel::update(funding_fee_events::table)
+        .filter(funding_fee_events::position_id.eq(position_id))
+        .filter(funding_fee_events::paid_date.is_null())
+        .filter(funding_fee_events::timestamp.le(before))
+        .set(funding_fee_events::rollover_date.eq(before))
+        .returning(funding_fee_events::amount_sats)
+        .get_results(conn)?;
+
+    Ok(SignedAmount::from_sat(amounts.into_iter().sum()))
+}
+
+/// Returns the sum of all funding fees of the given position which were charged before the given
+/// timestamp, regardless of whether they have been paid already.
+pub fn get_total_fees(
+    conn: &mut PgConnection,
+    position_id: i32,
+    before: OffsetDateTime,
+) -> QueryResult<SignedAmount> {
+    let amounts: Vec<i64> = funding_fee_events::table
+        .select(funding_fee_events::amount_sats)
+        .filter(funding_fee_events::position_id.eq(position_id))
+        .filter(funding_fee_events::timestamp.le(before))
+        .load(conn)?;
+
+    Ok(SignedAmount::from_sat(amounts.into_iter().sum()))
+}
+
+/// Marks the funding fees of the given position which have been reserved for its rollover as paid.
+///
+/// Must only be called once the rollover proposed last has been finalized, see
+/// [`reserve_for_rollover`].
+pub fn mark_as_paid(conn: &mut PgConnection, position_id: i32) -> QueryResult<usize> {
+    diesel::update(funding_fee_events::table)
+        .filter(funding_fee_events::position_id.eq(position_id))
+        .filter(funding_fee_events::paid_date.is_null())
+        .filter(funding_fee_events::rollover_date.is_not_null())
+        .set(funding_fee_events::paid_date.eq(OffsetDateTime::now_utc()))
+        .execute(conn)
+}
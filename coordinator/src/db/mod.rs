@@ -2,6 +2,7 @@ pub mod channels;
 pub mod collaborative_reverts;
 pub mod custom_types;
 pub mod dlc_messages;
+pub mod funding_fee_events;
 pub mod last_outbound_dlc_message;
 pub mod legacy_collaborative_reverts;
 pub mod liquidity;
@@ -10,6 +11,7 @@ pub mod payments;
 pub mod polls;
 pub mod positions;
 pub mod positions_helper;
+pub mod price_history;
 pub mod routing_fees;
 pub mod spendable_outputs;
 pub mod trades;
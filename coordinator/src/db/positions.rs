@@ -181,6 +181,22 @@ impl Position {
         Ok(())
     }
 
+    /// Locks the open position of the trader until the end of the surrounding DB transaction.
+    ///
+    /// Returns the id of the locked position, or `None` if the trader has no open position.
+    pub fn lock_open_position(
+        conn: &mut PgConnection,
+        trader_pubkey: PublicKey,
+    ) -> QueryResult<Option<i32>> {
+        positions::table
+            .filter(positions::trader_pubkey.eq(trader_pubkey.to_string()))
+            .filter(positions::position_state.eq(PositionState::Open))
+            .select(positions::id)
+            .for_update()
+            .first(conn)
+            .optional()
+    }
+
     /// sets the status of all open position to closing (note, we expect that number to be always
     /// exactly 1)
     pub fn set_open_position_to_closing(
@@ -328,6 +344,33 @@ impl Position {
         Ok(())
     }
 
+    /// Updates the margins and the liquidation price of the position, e.g. after funding fees
+    /// have been settled from the margin of one party.
+    pub fn update_margins(
+        conn: &mut PgConnection,
+        id: i32,
+        coordinator_margin: i64,
+        trader_margin: i64,
+        trader_liquidation_price: f32,
+    ) -> Result<()> {
+        let affected_rows = diesel::update(positions::table)
+            .filter(positions::id.eq(id))
+            .set((
+                positions::coordinator_margin.eq(coordinator_margin),
+                positions::trader_margin.eq(trader_margin),
+                positions::trader_liquidation_price.eq(trader_liquidation_price),
+                positions::update_timestamp.eq(OffsetDateTime::now_utc()),
+            ))
+            .execute(conn)?;
+
+        ensure!(
+            affected_rows > 0,
+            "Could not update margins of position {id}"
+        );
+
+        Ok(())
+    }
+
     pub fn update_unrealized_pnl(conn: &mut PgConnection, id: i32, pnl: i64) -> Result<()> {
         let affected_rows = diesel::update(positions::table)
             .filter(positions::id.eq(id))
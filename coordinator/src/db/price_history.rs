@@ -0,0 +1,79 @@
+use crate::db::positions::ContractSymbol;
+use crate::schema::price_history;
+use diesel::prelude::*;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use time::OffsetDateTime;
+
+#[derive(Queryable, Debug, Clone)]
+struct PriceHistory {
+    // this id is only internally but needs to be here or diesel complains
+    #[allow(dead_code)]
+    id: i32,
+    #[allow(dead_code)]
+    contract_symbol: ContractSymbol,
+    index_price: f32,
+    mark_price: f32,
+    timestamp: OffsetDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = price_history)]
+struct NewPriceHistory {
+    contract_symbol: ContractSymbol,
+    index_price: f32,
+    mark_price: f32,
+    timestamp: OffsetDateTime,
+}
+
+/// The index and mark price of a contract at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceEntry {
+    pub index_price: Decimal,
+    pub mark_price: Decimal,
+    pub timestamp: OffsetDateTime,
+}
+
+impl From<PriceHistory> for PriceEntry {
+    fn from(value: PriceHistory) -> Self {
+        PriceEntry {
+            index_price: Decimal::from_f32(value.index_price).expect("to fit into decimal"),
+            mark_price: Decimal::from_f32(value.mark_price).expect("to fit into decimal"),
+            timestamp: value.timestamp,
+        }
+    }
+}
+
+pub fn insert(
+    conn: &mut PgConnection,
+    contract_symbol: trade::ContractSymbol,
+    index_price: Decimal,
+    mark_price: Decimal,
+    timestamp: OffsetDateTime,
+) -> QueryResult<()> {
+    diesel::insert_into(price_history::table)
+        .values(NewPriceHistory {
+            contract_symbol: contract_symbol.into(),
+            index_price: index_price.to_f32().expect("to fit into f32"),
+            mark_price: mark_price.to_f32().expect("to fit into f32"),
+            timestamp,
+        })
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Returns the most recent price of the given contract.
+pub fn get_latest(
+    conn: &mut PgConnection,
+    contract_symbol: trade::ContractSymbol,
+) -> QueryResult<Option<PriceEntry>> {
+    let price: Option<PriceHistory> = price_history::table
+        .filter(price_history::contract_symbol.eq(ContractSymbol::from(contract_symbol)))
+        .order_by(price_history::timestamp.desc())
+        .first(conn)
+        .optional()?;
+
+    Ok(price.map(PriceEntry::from))
+}
@@ -65,6 +65,46 @@ pub fn get_latest_for_position(
     Ok(trade.map(crate::trade::models::Trade::from))
 }
 
+/// Returns all trades of the given contract executed within the given time range, ordered by
+/// their timestamp.
+pub fn get_by_contract_symbol_between(
+    conn: &mut PgConnection,
+    contract_symbol: trade::ContractSymbol,
+    from: OffsetDateTime,
+    to: OffsetDateTime,
+) -> Result<Vec<crate::trade::models::Trade>> {
+    let trades = trades::table
+        .filter(trades::contract_symbol.eq(ContractSymbol::from(contract_symbol)))
+        .filter(trades::timestamp.ge(from))
+        .filter(trades::timestamp.lt(to))
+        .order_by((trades::timestamp.asc(), trades::id.asc()))
+        .load::<Trade>(conn)?;
+
+    Ok(trades
+        .into_iter()
+        .map(crate::trade::models::Trade::from)
+        .collect())
+}
+
+/// Returns all trades inserted after the trade with the given id, ordered by their id.
+pub fn get_after_id(conn: &mut PgConnection, id: i32) -> Result<Vec<crate::trade::models::Trade>> {
+    let trades = trades::table
+        .filter(trades::id.gt(id))
+        .order_by(trades::id.asc())
+        .load::<Trade>(conn)?;
+
+    Ok(trades
+        .into_iter()
+        .map(crate::trade::models::Trade::from)
+        .collect())
+}
+
+pub fn get_latest_id(conn: &mut PgConnection) -> QueryResult<Option<i32>> {
+    trades::table
+        .select(diesel::dsl::max(trades::id))
+        .first::<Option<i32>>(conn)
+}
+
 /// Returns the position by trader pub key
 pub fn is_payment_hash_registered_as_trade_fee(
     conn: &mut PgConnection,
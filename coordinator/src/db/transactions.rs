@@ -24,6 +24,7 @@ pub(crate) struct Transaction {
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
     pub raw: String,
+    pub replaced_by: Option<String>,
 }
 
 pub(crate) fn get(txid: &str, conn: &mut PgConnection) -> QueryResult<Option<Transaction>> {
@@ -60,6 +61,7 @@ impl From<ln_dlc_node::transaction::Transaction> for Transaction {
             created_at: value.created_at(),
             updated_at: value.updated_at(),
             raw: value.raw(),
+            replaced_by: value.replaced_by().map(|txid| txid.to_string()),
         }
     }
 }
@@ -72,6 +74,9 @@ impl From<Transaction> for ln_dlc_node::transaction::Transaction {
             value.created_at,
             value.updated_at,
             value.raw,
+            value
+                .replaced_by
+                .map(|txid| Txid::from_str(&txid).expect("valid txid")),
         )
     }
 }
@@ -27,17 +27,20 @@ pub mod db;
 pub mod dlc_handler;
 mod leaderboard;
 pub mod logger;
+pub mod market_data;
 pub mod message;
 pub mod metrics;
 pub mod node;
 pub mod notifications;
 pub mod orderbook;
 pub mod position;
+pub mod price;
 pub mod routes;
 pub mod routing_fee;
 pub mod scheduler;
 pub mod schema;
 pub mod settings;
+pub mod signed_request;
 pub mod storage;
 pub mod trade;
 
@@ -0,0 +1,224 @@
+use crate::db;
+use anyhow::ensure;
+use anyhow::Result;
+use commons::aggregate_candles;
+use commons::Candle;
+use commons::CandleResolution;
+use commons::MarketTrade;
+use commons::Message;
+use diesel::r2d2::ConnectionManager;
+use diesel::r2d2::Pool;
+use diesel::PgConnection;
+use futures::future::RemoteHandle;
+use futures::FutureExt;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::sync::broadcast;
+use tokio::task::spawn_blocking;
+use trade::ContractSymbol;
+
+/// How often we check for new trades to be published.
+const NEW_TRADES_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The maximum number of intervals which can be requested at once.
+const MAX_CANDLES: i32 = 1_000;
+
+#[derive(Debug, Deserialize)]
+pub struct CandleQueryParams {
+    pub resolution: CandleResolution,
+    /// Defaults to [`MAX_CANDLES`] intervals before `to`.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub from: Option<OffsetDateTime>,
+    /// Defaults to now.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub to: Option<OffsetDateTime>,
+}
+
+/// Returns the candles of the given contract within the given time range, oldest first.
+///
+/// The last candle might still be open, i.e. it can change with further trades.
+pub fn get_candles(
+    conn: &mut PgConnection,
+    contract_symbol: ContractSymbol,
+    resolution: CandleResolution,
+    from: OffsetDateTime,
+    to: OffsetDateTime,
+) -> Result<Vec<Candle>> {
+    // The first candle is always complete, even if the time range starts in its interval.
+    let from = resolution.open_time(from);
+
+    let trades = db::trades::get_by_contract_symbol_between(conn, contract_symbol, from, to)?
+        .into_iter()
+        .map(MarketTrade::from)
+        .collect::<Vec<_>>();
+
+    Ok(aggregate_candles(&trades, resolution))
+}
+
+/// Validates the requested time range, filling in the defaults of [`CandleQueryParams`].
+pub fn candle_time_range(
+    params: &CandleQueryParams,
+    now: OffsetDateTime,
+) -> Result<(OffsetDateTime, OffsetDateTime)> {
+    let max_range = params.resolution.duration() * MAX_CANDLES;
+
+    let to = params.to.unwrap_or(now);
+    let from = params.from.unwrap_or(to - max_range);
+
+    ensure!(from < to, "Start of time range has to be before its end");
+    ensure!(
+        to - from <= max_range,
+        "Cannot request more than {MAX_CANDLES} candles at once"
+    );
+
+    Ok((from, to))
+}
+
+/// Publishes every new trade and the resulting candles to all connected clients.
+pub fn monitor(
+    pool: Pool<ConnectionManager<PgConnection>>,
+    tx_price_feed: broadcast::Sender<Message>,
+) -> RemoteHandle<()> {
+    let (fut, remote_handle) = async move {
+        let mut last_trade_id = None;
+
+        loop {
+            match spawn_blocking({
+                let pool = pool.clone();
+                move || collect_new_trades(pool, last_trade_id)
+            })
+            .await
+            .expect("task to complete")
+            {
+                Ok((trade_id, messages)) => {
+                    last_trade_id = trade_id;
+                    for message in messages {
+                        // An error only means that no client is connected at the moment.
+                        let _ = tx_price_feed.send(message);
+                    }
+                }
+                Err(e) => tracing::error!("Failed to publish new trades: {e:#}"),
+            }
+
+            tokio::time::sleep(NEW_TRADES_POLL_INTERVAL).await;
+        }
+    }
+    .remote_handle();
+
+    tokio::spawn(fut);
+
+    remote_handle
+}
+
+/// Collects the messages for all trades executed after the trade with the given id.
+///
+/// Returns the id of the latest trade, so that only trades executed afterwards are collected with
+/// the next call. If no trade id is given, nothing is collected, as we only want to publish trades
+/// executed while we are running.
+fn collect_new_trades(
+    pool: Pool<ConnectionManager<PgConnection>>,
+    last_trade_id: Option<i32>,
+) -> Result<(Option<i32>, Vec<Message>)> {
+    let mut conn = pool.get()?;
+
+    let last_trade_id = match last_trade_id {
+        Some(last_trade_id) => last_trade_id,
+        None => {
+            let latest_trade_id = db::trades::get_latest_id(&mut conn)?.unwrap_or_default();
+            return Ok((Some(latest_trade_id), vec![]));
+        }
+    };
+
+    let trades = db::trades::get_after_id(&mut conn, last_trade_id)?;
+    let latest_trade_id = trades.last().map(|trade| trade.id).unwrap_or(last_trade_id);
+
+    let contract_symbols = trades
+        .iter()
+        .map(|trade| trade.contract_symbol)
+        .collect::<HashSet<_>>();
+
+    let mut messages = trades
+        .into_iter()
+        .map(|trade| Message::MarketTrade(trade.into()))
+        .collect::<Vec<_>>();
+
+    // The new trades might be slightly in the future if the clocks are not perfectly in sync.
+    let now = OffsetDateTime::now_utc() + time::Duration::SECOND;
+    for contract_symbol in contract_symbols {
+        for resolution in CandleResolution::ALL {
+            let candle = get_candles(
+                &mut conn,
+                contract_symbol,
+                resolution,
+                resolution.open_time(now),
+                now,
+            )?
+            .pop();
+
+            if let Some(candle) = candle {
+                messages.push(Message::Candle(candle));
+            }
+        }
+    }
+
+    Ok((Some(latest_trade_id), messages))
+}
+
+impl From<crate::trade::models::Trade> for MarketTrade {
+    fn from(value: crate::trade::models::Trade) -> Self {
+        MarketTrade {
+            contract_symbol: value.contract_symbol,
+            price: Decimal::try_from(value.average_price).expect("to fit into decimal"),
+            quantity: Decimal::try_from(value.quantity).expect("to fit into decimal"),
+            direction: value.direction,
+            timestamp: value.timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    const NOW: OffsetDateTime = datetime!(2024-02-15 10:17:42 UTC);
+
+    #[test]
+    fn time_range_defaults_to_max_candles_until_now() {
+        let params = CandleQueryParams {
+            resolution: CandleResolution::OneMinute,
+            from: None,
+            to: None,
+        };
+
+        let (from, to) = candle_time_range(&params, NOW).unwrap();
+
+        assert_eq!(to, NOW);
+        assert_eq!(from, NOW - time::Duration::minutes(MAX_CANDLES as i64));
+    }
+
+    #[test]
+    fn time_range_exceeding_max_candles_is_rejected() {
+        let params = CandleQueryParams {
+            resolution: CandleResolution::OneHour,
+            from: Some(NOW - time::Duration::hours(MAX_CANDLES as i64 + 1)),
+            to: None,
+        };
+
+        assert!(candle_time_range(&params, NOW).is_err());
+    }
+
+    #[test]
+    fn inverted_time_range_is_rejected() {
+        let params = CandleQueryParams {
+            resolution: CandleResolution::OneDay,
+            from: Some(NOW),
+            to: Some(NOW - time::Duration::days(1)),
+        };
+
+        assert!(candle_time_range(&params, NOW).is_err());
+    }
+}
@@ -4,6 +4,7 @@ use crate::node::Node;
 use crate::storage::CoordinatorTenTenOneStorage;
 use dlc_manager::subchannel::SubChannelState;
 use lazy_static::lazy_static;
+use lightning::chain::chaininterface::ConfirmationTarget;
 use lightning::ln::channelmanager::ChannelDetails;
 use opentelemetry::global;
 use opentelemetry::metrics::Meter;
@@ -17,9 +18,17 @@ use opentelemetry::KeyValue;
 use opentelemetry_prometheus::PrometheusExporter;
 use std::sync::Arc;
 use std::time::Duration;
+use time::OffsetDateTime;
 use trade::ContractSymbol;
 use trade::Direction;
 
+const CONFIRMATION_TARGETS: [ConfirmationTarget; 4] = [
+    ConfirmationTarget::MempoolMinimum,
+    ConfirmationTarget::Background,
+    ConfirmationTarget::Normal,
+    ConfirmationTarget::HighPriority,
+];
+
 lazy_static! {
     pub static ref METER: Meter = global::meter("maker");
 
@@ -59,6 +68,28 @@ lazy_static! {
         .with_description("Node balance in satoshi")
         .init();
 
+    // fee rate metrics
+    pub static ref FEE_RATE_SATS_PER_VBYTE: ObservableGauge<f64> = METER
+        .f64_observable_gauge("fee_rate_sats_per_vbyte")
+        .with_description("Fee rate estimate used by the node in sats/vbyte")
+        .init();
+    pub static ref FEE_RATE_IS_STALE: ObservableGauge<u64> = METER
+        .u64_observable_gauge("fee_rate_is_stale")
+        .with_description("If the fee rate estimates are stale or still the defaults")
+        .init();
+    pub static ref FEE_RATE_SOURCE_SATS_PER_VBYTE: ObservableGauge<f64> = METER
+        .f64_observable_gauge("fee_rate_source_sats_per_vbyte")
+        .with_description("Latest fee rate estimate of a fee rate source in sats/vbyte")
+        .init();
+    pub static ref FEE_RATE_SOURCE_AGE_SECONDS: ObservableGauge<u64> = METER
+        .u64_observable_gauge("fee_rate_source_age_seconds")
+        .with_description("Time since the last successful update of a fee rate source")
+        .init();
+    pub static ref FEE_RATE_SOURCE_IS_STALE: ObservableGauge<u64> = METER
+        .u64_observable_gauge("fee_rate_source_is_stale")
+        .with_description("If the estimates of a fee rate source are too old to be used")
+        .init();
+
     // position metrics
     pub static ref POSITION_QUANTITY: ObservableGauge<f64> = METER
         .f64_observable_gauge("position_quantity_contracts")
@@ -125,6 +156,7 @@ pub fn collect(node: Node) {
     }
     let channels = inner_node.channel_manager.list_channels();
     channel_metrics(&cx, channels);
+    fee_rate_metrics(&cx, &inner_node);
     node_metrics(&cx, inner_node);
 }
 
@@ -145,66 +177,68 @@ fn position_metrics(cx: &Context, node: &Node) {
         }
     };
 
-    let mut margin_long = 0;
-    let mut margin_short = 0;
-    let mut quantity_long = 0.0;
-    let mut quantity_short = 0.0;
+    for contract_symbol in ContractSymbol::ALL {
+        let mut margin_long = 0;
+        let mut margin_short = 0;
+        let mut quantity_long = 0.0;
+        let mut quantity_short = 0.0;
 
-    // Note: we should filter positions here by BTCUSD once we have multiple contract symbols
-
-    for position in positions {
-        debug_assert!(
-            position.contract_symbol == ContractSymbol::BtcUsd,
-            "We should filter positions here by BTCUSD once we have multiple contract symbols"
-        );
-        match position.trader_direction {
-            Direction::Long => {
-                // TODO: fix me: this was meant to be the traders margin
-                margin_long += position.coordinator_margin;
-                quantity_long += position.quantity;
-            }
-            Direction::Short => {
-                margin_short += position.coordinator_margin;
-                quantity_short += position.quantity;
+        for position in positions
+            .iter()
+            .filter(|position| position.contract_symbol == contract_symbol)
+        {
+            match position.trader_direction {
+                Direction::Long => {
+                    // TODO: fix me: this was meant to be the traders margin
+                    margin_long += position.coordinator_margin;
+                    quantity_long += position.quantity;
+                }
+                Direction::Short => {
+                    margin_short += position.coordinator_margin;
+                    quantity_short += position.quantity;
+                }
             }
         }
+
+        let symbol = contract_symbol.label().to_uppercase();
+
+        POSITION_QUANTITY.observe(
+            cx,
+            quantity_long as f64,
+            &[
+                KeyValue::new("symbol", symbol.clone()),
+                KeyValue::new("status", "open"),
+                KeyValue::new("direction", "long"),
+            ],
+        );
+        POSITION_QUANTITY.observe(
+            cx,
+            quantity_short as f64,
+            &[
+                KeyValue::new("symbol", symbol.clone()),
+                KeyValue::new("status", "open"),
+                KeyValue::new("direction", "short"),
+            ],
+        );
+        POSITION_MARGIN.observe(
+            cx,
+            margin_long,
+            &[
+                KeyValue::new("symbol", symbol.clone()),
+                KeyValue::new("status", "open"),
+                KeyValue::new("direction", "long"),
+            ],
+        );
+        POSITION_MARGIN.observe(
+            cx,
+            margin_short,
+            &[
+                KeyValue::new("symbol", symbol),
+                KeyValue::new("status", "open"),
+                KeyValue::new("direction", "short"),
+            ],
+        );
     }
-    POSITION_QUANTITY.observe(
-        cx,
-        quantity_long as f64,
-        &[
-            KeyValue::new("symbol", "BTCUSD"),
-            KeyValue::new("status", "open"),
-            KeyValue::new("direction", "long"),
-        ],
-    );
-    POSITION_QUANTITY.observe(
-        cx,
-        quantity_short as f64,
-        &[
-            KeyValue::new("symbol", "BTCUSD"),
-            KeyValue::new("status", "open"),
-            KeyValue::new("direction", "short"),
-        ],
-    );
-    POSITION_MARGIN.observe(
-        cx,
-        margin_long,
-        &[
-            KeyValue::new("symbol", "BTCUSD"),
-            KeyValue::new("status", "open"),
-            KeyValue::new("direction", "long"),
-        ],
-    );
-    POSITION_MARGIN.observe(
-        cx,
-        margin_short,
-        &[
-            KeyValue::new("symbol", "BTCUSD"),
-            KeyValue::new("status", "open"),
-            KeyValue::new("direction", "short"),
-        ],
-    );
 }
 
 fn channel_metrics(cx: &Context, channels: Vec<ChannelDetails>) {
@@ -229,6 +263,46 @@ fn channel_metrics(cx: &Context, channels: Vec<ChannelDetails>) {
     }
 }
 
+fn fee_rate_metrics(
+    cx: &Context,
+    inner_node: &ln_dlc_node::node::Node<CoordinatorTenTenOneStorage, NodeStorage>,
+) {
+    let fee_rate_estimator = &inner_node.fee_rate_estimator;
+    for target in CONFIRMATION_TARGETS {
+        let fee_rate = fee_rate_estimator.get(target);
+        FEE_RATE_SATS_PER_VBYTE.observe(
+            cx,
+            fee_rate.as_sat_per_vb() as f64,
+            &[KeyValue::new("target", format!("{target:?}"))],
+        );
+    }
+
+    FEE_RATE_IS_STALE.observe(cx, fee_rate_estimator.is_stale() as u64, &[]);
+
+    let now = OffsetDateTime::now_utc();
+    for status in fee_rate_estimator.source_statuses() {
+        let source = KeyValue::new("source", status.name);
+
+        for (target, fee_rate) in status.estimates {
+            FEE_RATE_SOURCE_SATS_PER_VBYTE.observe(
+                cx,
+                fee_rate.as_sat_per_vb() as f64,
+                &[
+                    source.clone(),
+                    KeyValue::new("target", format!("{target:?}")),
+                ],
+            );
+        }
+
+        if let Some(last_update) = status.last_update {
+            let age = (now - last_update).whole_seconds().max(0) as u64;
+            FEE_RATE_SOURCE_AGE_SECONDS.observe(cx, age, &[source.clone()]);
+        }
+
+        FEE_RATE_SOURCE_IS_STALE.observe(cx, status.is_stale as u64, &[source]);
+    }
+}
+
 fn node_metrics(
     cx: &Context,
     inner_node: Arc<ln_dlc_node::node::Node<CoordinatorTenTenOneStorage, NodeStorage>>,
@@ -61,9 +61,12 @@ use uuid::Uuid;
 
 pub mod connection;
 pub mod expired_positions;
+pub mod funding_fee;
+pub mod liquidated_positions;
 pub mod rollover;
 pub mod routing_fees;
 pub mod storage;
+pub mod trigger_orders;
 pub mod unrealized_pnl;
 
 #[derive(Debug, Clone)]
@@ -193,7 +196,7 @@ impl Node {
         let order = orders::get_with_id(connection, order_id)?.context("Could not find order")?;
 
         ensure!(
-            order.expiry > OffsetDateTime::now_utc(),
+            !order.is_expired(OffsetDateTime::now_utc()),
             "Can't execute a trade on an expired order"
         );
         ensure!(
@@ -263,10 +266,10 @@ impl Node {
             0,
             trade_params.quantity,
             trade_params.contract_symbol,
+            &trade_params.filled_with.oracle_params,
         )
         .context("Could not build contract descriptor")?;
 
-        let contract_symbol = trade_params.contract_symbol.label();
         let maturity_time = trade_params.filled_with.expiry_timestamp;
         let maturity_time = maturity_time.unix_timestamp();
 
@@ -283,11 +286,11 @@ impl Node {
 
         // The contract input to be used for setting up the trade between the trader and the
         // coordinator.
-        let event_id = format!("{contract_symbol}{maturity_time}");
+        let event_id = trade_params.contract_symbol.oracle_event_id(maturity_time);
 
         tracing::debug!(
             event_id,
-            oracle=%trade_params.filled_with.oracle_pk,
+            oracle_params=?trade_params.filled_with.oracle_params,
             "Proposing DLC channel"
         );
 
@@ -300,9 +303,9 @@ impl Node {
             contract_infos: vec![ContractInputInfo {
                 contract_descriptor,
                 oracles: OracleInput {
-                    public_keys: vec![trade_params.filled_with.oracle_pk],
+                    public_keys: trade_params.filled_with.oracle_params.pubkeys.clone(),
                     event_id,
-                    threshold: 1,
+                    threshold: trade_params.filled_with.oracle_params.threshold,
                 },
             }],
         };
@@ -416,10 +419,10 @@ impl Node {
             trader_collateral_reserve,
             trade_params.quantity,
             trade_params.contract_symbol,
+            &trade_params.filled_with.oracle_params,
         )
         .context("Could not build contract descriptor")?;
 
-        let contract_symbol = trade_params.contract_symbol.label();
         let maturity_time = trade_params.filled_with.expiry_timestamp;
         let maturity_time = maturity_time.unix_timestamp();
 
@@ -436,11 +439,11 @@ impl Node {
 
         // The contract input to be used for setting up the trade between the trader and the
         // coordinator.
-        let event_id = format!("{contract_symbol}{maturity_time}");
+        let event_id = trade_params.contract_symbol.oracle_event_id(maturity_time);
 
         tracing::debug!(
             event_id,
-            oracle=%trade_params.filled_with.oracle_pk,
+            oracle_params=?trade_params.filled_with.oracle_params,
             "Proposing DLC channel update"
         );
 
@@ -451,9 +454,9 @@ impl Node {
             contract_infos: vec![ContractInputInfo {
                 contract_descriptor,
                 oracles: OracleInput {
-                    public_keys: vec![trade_params.filled_with.oracle_pk],
+                    public_keys: trade_params.filled_with.oracle_params.pubkeys.clone(),
                     event_id,
-                    threshold: 1,
+                    threshold: trade_params.filled_with.oracle_params.threshold,
                 },
             }],
         };
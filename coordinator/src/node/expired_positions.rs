@@ -2,8 +2,10 @@ use crate::db;
 use crate::node::Node;
 use crate::orderbook;
 use crate::orderbook::trading::NewOrderMessage;
+use crate::orderbook::trading::TradingError;
 use crate::position::models::Position;
 use crate::position::models::PositionState;
+use anyhow::bail;
 use anyhow::Context;
 use anyhow::Result;
 use commons::average_execution_price;
@@ -14,6 +16,8 @@ use commons::Order;
 use commons::OrderReason;
 use commons::OrderState;
 use commons::OrderType;
+use commons::TimeInForce;
+use diesel::PgConnection;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use std::ops::Add;
@@ -40,86 +44,123 @@ pub async fn close(node: Node, trading_sender: mpsc::Sender<NewOrderMessage>) ->
         .collect::<Vec<Position>>();
 
     for position in positions.into_iter() {
-        if let Some(order) = orderbook::db::orders::get_by_trader_id_and_state(
-            &mut conn,
-            position.trader,
-            OrderState::Matched,
-        )? {
-            let trader_id = order.trader_id.to_string();
-            let order_id = order.id.to_string();
-
-            if order.expiry < OffsetDateTime::now_utc() {
-                tracing::warn!(trader_id, order_id, "Matched order expired! Giving up on that position, looks like the corresponding dlc channel has to get force closed.");
-                orderbook::db::orders::set_order_state(&mut conn, order.id, OrderState::Failed)?;
-
-                orderbook::db::matches::set_match_state_by_order_id(
-                    &mut conn,
-                    order.id,
-                    MatchState::Failed,
-                )?;
-
-                let matches = orderbook::db::matches::get_matches_by_order_id(&mut conn, order.id)?;
-                let matches: Vec<Match> = matches.into_iter().map(Match::from).collect();
-
-                let closing_price = average_execution_price(matches)
-                    .to_f32()
-                    .expect("to fit into f32");
-                db::positions::Position::set_open_position_to_closing(
-                    &mut conn,
-                    position.trader.to_string(),
-                    closing_price,
-                )?;
-                continue;
-            } else {
-                tracing::trace!(trader_id, order_id, "Skipping expired position as match has already been found. Waiting for trader to come online to execute the trade.");
-                continue;
-            }
+        tracing::debug!(trader_pk=%position.trader, %position.expiry_timestamp, "Attempting to close expired position");
+
+        if let Err(e) =
+            close_position(&mut conn, &position, OrderReason::Expired, &trading_sender).await
+        {
+            tracing::error!(trader_pk=%position.trader, "Failed to close expired position. Error: {e:#}");
         }
+    }
 
-        tracing::debug!(trader_pk=%position.trader, %position.expiry_timestamp, "Attempting to close expired position");
+    Ok(())
+}
+
+/// The outcome of [`close_position`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseOutcome {
+    /// A closing order has been submitted on behalf of the trader.
+    Submitted,
+    /// No closing order has been submitted, because the trader already has a matched order.
+    Skipped,
+}
+
+/// Closes the given position by submitting a market order in the opposite direction on behalf of
+/// the trader.
+///
+/// If the trader already has a matched order we do not submit another one, as we are still waiting
+/// for the trader to come online and execute the trade. If that matched order expired in the
+/// meantime we give up on the position. In both cases [`CloseOutcome::Skipped`] is returned.
+///
+/// This function is called concurrently for the same position by the tasks closing expired,
+/// liquidated and triggered positions. The check for a matched order here is only a shortcut: the
+/// orderbook matches the closing order while holding a lock on the position, and rejects it if
+/// another closing order has been matched in the meantime. That is reported as
+/// [`CloseOutcome::Skipped`] too.
+pub async fn close_position(
+    conn: &mut PgConnection,
+    position: &Position,
+    order_reason: OrderReason,
+    trading_sender: &mpsc::Sender<NewOrderMessage>,
+) -> Result<CloseOutcome> {
+    if let Some(order) = orderbook::db::orders::get_by_trader_id_and_state(
+        conn,
+        position.trader,
+        OrderState::Matched,
+    )? {
+        let trader_id = order.trader_id.to_string();
+        let order_id = order.id.to_string();
 
-        let new_order = NewOrder {
-            id: uuid::Uuid::new_v4(),
-            contract_symbol: position.contract_symbol,
-            // TODO(holzeis): we should not have to set the price for a market order. we propably
-            // need separate models for a limit and a market order.
-            price: Decimal::ZERO,
-            quantity: Decimal::try_from(position.quantity).expect("to fit into decimal"),
-            trader_id: position.trader,
-            direction: position.trader_direction.opposite(),
-            leverage: position.trader_leverage,
-            order_type: OrderType::Market,
-            // This order can basically not expire, but if the user does not come back online within
-            // a certain time period we can assume the channel to be abandoned and we should force
-            // close.
-            expiry: OffsetDateTime::now_utc().add(EXPIRED_POSITION_TIMEOUT),
-            stable: position.stable,
-        };
-
-        let (sender, mut receiver) = mpsc::channel::<Result<Order>>(1);
-        let message = NewOrderMessage {
-            new_order: new_order.clone(),
-            order_reason: OrderReason::Expired,
-            sender,
-        };
-
-        if let Err(e) = trading_sender.send(message).await {
-            tracing::error!(order_id=%new_order.id, trader_id=%new_order.trader_id, "Failed to submit new order for closing expired position. Error: {e:#}");
-            continue;
+        if order.is_expired(OffsetDateTime::now_utc()) {
+            tracing::warn!(trader_id, order_id, "Matched order expired! Giving up on that position, looks like the corresponding dlc channel has to get force closed.");
+            orderbook::db::orders::set_order_state(conn, order.id, OrderState::Failed)?;
+
+            orderbook::db::matches::set_match_state_by_order_id(
+                conn,
+                order.id,
+                MatchState::Failed,
+            )?;
+
+            let matches = orderbook::db::matches::get_matches_by_order_id(conn, order.id)?;
+            let matches: Vec<Match> = matches.into_iter().map(Match::from).collect();
+
+            let closing_price = average_execution_price(matches)
+                .to_f32()
+                .expect("to fit into f32");
+            db::positions::Position::set_open_position_to_closing(
+                conn,
+                position.trader.to_string(),
+                closing_price,
+            )?;
+        } else {
+            tracing::trace!(trader_id, order_id, "Skipping position as match has already been found. Waiting for trader to come online to execute the trade.");
         }
 
-        match receiver.recv().await {
-            Some(Ok(order)) => order,
-            Some(Err(e)) => {
-                tracing::error!(order_id=%new_order.id, trader_id=%new_order.trader_id, "Failed to submit new order for closing expired position. Error: {e:#}");
-                continue;
-            }
-            None => {
-                tracing::error!(order_id=%new_order.id, trader_id=%new_order.trader_id, "Failed to receive response from trading.");
-                continue;
-            }
-        };
+        return Ok(CloseOutcome::Skipped);
     }
 
-    Ok(())
+    let new_order = NewOrder {
+        id: uuid::Uuid::new_v4(),
+        contract_symbol: position.contract_symbol,
+        // TODO(holzeis): we should not have to set the price for a market order. we propably
+        // need separate models for a limit and a market order.
+        price: Decimal::ZERO,
+        quantity: Decimal::try_from(position.quantity).expect("to fit into decimal"),
+        trader_id: position.trader,
+        direction: position.trader_direction.opposite(),
+        leverage: position.trader_leverage,
+        order_type: OrderType::Market,
+        // This order can basically not expire, but if the user does not come back online within a
+        // certain time period we can assume the channel to be abandoned and we should force close.
+        expiry: OffsetDateTime::now_utc().add(EXPIRED_POSITION_TIMEOUT),
+        stable: position.stable,
+        // The position has to be closed completely.
+        time_in_force: TimeInForce::FillOrKill,
+        post_only: false,
+    };
+
+    let (sender, mut receiver) = mpsc::channel::<Result<Order>>(1);
+    let message = NewOrderMessage {
+        new_order: new_order.clone(),
+        order_reason,
+        sender,
+    };
+
+    trading_sender
+        .send(message)
+        .await
+        .context("Failed to submit new order for closing position")?;
+
+    match receiver.recv().await {
+        Some(Ok(_)) => Ok(CloseOutcome::Submitted),
+        Some(Err(e)) if matches!(e.downcast_ref(), Some(TradingError::OrderInExecution(_))) => {
+            tracing::debug!(trader_id=%position.trader, order_id=%new_order.id, "Closing order has been rejected as another order of the trader is in execution");
+            Ok(CloseOutcome::Skipped)
+        }
+        Some(Err(e)) => Err(e.context(format!(
+            "Failed to submit new order {} for closing position",
+            new_order.id
+        ))),
+        None => bail!("Failed to receive response from trading"),
+    }
 }
@@ -0,0 +1,225 @@
+use crate::db;
+use crate::node::Node;
+use crate::orderbook;
+use crate::position::models::Position;
+use crate::price::PriceService;
+use anyhow::Result;
+use bitcoin::SignedAmount;
+use diesel::PgConnection;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use time::Duration;
+use time::OffsetDateTime;
+use trade::ContractSymbol;
+use trade::Direction;
+
+/// The interval for which a funding fee is charged. Every open position is charged once per
+/// interval.
+pub const FUNDING_RATE_INTERVAL: Duration = Duration::hours(8);
+
+/// The maximum funding rate per interval (0.75%).
+///
+/// Caps the funding fees if our orderbook moves far away from the index price, e.g. due to a lack
+/// of liquidity.
+const MAX_FUNDING_RATE: Decimal = Decimal::from_parts(75, 0, 0, false, 4);
+
+/// Charges all open positions the funding fee for the current funding interval.
+///
+/// The funding rate is derived from the premium of the mid price of our own orderbook over the
+/// index price of the [`PriceService`]. If the orderbook price is above the index price, longs pay
+/// shorts, otherwise shorts pay longs. As the coordinator is the counterparty of every trader,
+/// funding fees are always exchanged between the trader and the coordinator.
+///
+/// Charging a position is idempotent, so this can be called more often than once per interval.
+/// The accrued funding fees are settled when the position is rolled over.
+pub async fn charge(node: Node, price_service: &PriceService) -> Result<()> {
+    let mut conn = node.pool.get()?;
+
+    let now = OffsetDateTime::now_utc();
+    let due_date = funding_interval_start(now);
+
+    let orders = orderbook::db::orders::all_limit_orders(&mut conn)?;
+    let prices = commons::best_current_price(&orders);
+
+    let positions = db::positions::Position::get_all_open_positions(&mut conn)?;
+
+    for contract_symbol in ContractSymbol::ALL {
+        let orderbook_price = match prices.get(&contract_symbol).and_then(|price| price.mid()) {
+            Some(orderbook_price) => orderbook_price,
+            None => {
+                tracing::warn!(%contract_symbol, "Not charging funding fees as the orderbook has no mid price");
+                continue;
+            }
+        };
+
+        let index_price = match price_service.get_prices(contract_symbol) {
+            Ok(prices) => prices.index_price,
+            Err(e) => {
+                tracing::warn!(%contract_symbol, "Not charging funding fees without index price: {e:#}");
+                continue;
+            }
+        };
+
+        let funding_rate = calculate_funding_rate(orderbook_price, index_price);
+
+        for position in positions.iter().filter(|position| {
+            position.contract_symbol == contract_symbol && position.creation_timestamp <= due_date
+        }) {
+            if let Err(e) = charge_position(
+                &mut conn,
+                position,
+                funding_rate,
+                orderbook_price,
+                index_price,
+                due_date,
+            ) {
+                tracing::error!(position_id=%position.id, "Failed to charge funding fee: {e:#}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn charge_position(
+    conn: &mut PgConnection,
+    position: &Position,
+    funding_rate: Decimal,
+    mark_price: Decimal,
+    index_price: Decimal,
+    due_date: OffsetDateTime,
+) -> Result<()> {
+    let amount = calculate_funding_fee(
+        position.quantity,
+        index_price,
+        funding_rate,
+        position.trader_direction,
+    );
+
+    let inserted = db::funding_fee_events::insert(
+        conn,
+        position.id,
+        position.trader,
+        amount,
+        funding_rate,
+        mark_price,
+        index_price,
+        due_date,
+    )?;
+
+    if inserted {
+        tracing::debug!(
+            position_id = %position.id,
+            trader_pubkey = %position.trader,
+            %funding_rate,
+            amount_sats = amount.to_sat(),
+            "Charged funding fee"
+        );
+    }
+
+    Ok(())
+}
+
+/// Calculates the funding rate for one [`FUNDING_RATE_INTERVAL`] as the premium of the mark price
+/// over the index price, capped by [`MAX_FUNDING_RATE`].
+pub fn calculate_funding_rate(mark_price: Decimal, index_price: Decimal) -> Decimal {
+    if index_price.is_zero() {
+        return Decimal::ZERO;
+    }
+
+    let premium = (mark_price - index_price) / index_price;
+    premium.clamp(-MAX_FUNDING_RATE, MAX_FUNDING_RATE)
+}
+
+/// Calculates the funding fee of a position with the given quantity (in contracts).
+///
+/// Returns a positive amount if the trader has to pay the coordinator and a negative amount if the
+/// coordinator has to pay the trader.
+pub fn calculate_funding_fee(
+    quantity: f32,
+    index_price: Decimal,
+    funding_rate: Decimal,
+    trader_direction: Direction,
+) -> SignedAmount {
+    if index_price.is_zero() {
+        return SignedAmount::ZERO;
+    }
+
+    let quantity = Decimal::try_from(quantity).expect("to fit into decimal");
+
+    // The value of an inverse contract is denominated in bitcoin.
+    let position_value_btc = quantity / index_price;
+    let funding_fee_btc = position_value_btc * funding_rate;
+
+    let funding_fee_sat = (funding_fee_btc * Decimal::from(100_000_000))
+        .round()
+        .to_i64()
+        .expect("to fit into i64");
+
+    match trader_direction {
+        Direction::Long => SignedAmount::from_sat(funding_fee_sat),
+        Direction::Short => SignedAmount::from_sat(-funding_fee_sat),
+    }
+}
+
+/// The start of the funding interval the given timestamp falls into.
+fn funding_interval_start(timestamp: OffsetDateTime) -> OffsetDateTime {
+    let interval = FUNDING_RATE_INTERVAL.whole_seconds();
+    let start = timestamp.unix_timestamp() - timestamp.unix_timestamp().rem_euclid(interval);
+
+    OffsetDateTime::from_unix_timestamp(start).expect("to be a valid timestamp")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use time::macros::datetime;
+
+    #[test]
+    fn given_mark_price_above_index_price_then_positive_funding_rate() {
+        let funding_rate = calculate_funding_rate(dec!(40_040), dec!(40_000));
+
+        assert_eq!(funding_rate, dec!(0.001));
+    }
+
+    #[test]
+    fn given_large_premium_then_funding_rate_is_capped() {
+        assert_eq!(
+            calculate_funding_rate(dec!(50_000), dec!(40_000)),
+            MAX_FUNDING_RATE
+        );
+        assert_eq!(
+            calculate_funding_rate(dec!(30_000), dec!(40_000)),
+            -MAX_FUNDING_RATE
+        );
+    }
+
+    #[test]
+    fn given_positive_funding_rate_then_long_trader_pays() {
+        let long = calculate_funding_fee(40_000.0, dec!(40_000), dec!(0.001), Direction::Long);
+        let short = calculate_funding_fee(40_000.0, dec!(40_000), dec!(0.001), Direction::Short);
+
+        assert_eq!(long, SignedAmount::from_sat(100_000));
+        assert_eq!(short, SignedAmount::from_sat(-100_000));
+    }
+
+    #[test]
+    fn given_negative_funding_rate_then_short_trader_pays() {
+        let short = calculate_funding_fee(40_000.0, dec!(40_000), dec!(-0.001), Direction::Short);
+
+        assert_eq!(short, SignedAmount::from_sat(100_000));
+    }
+
+    #[test]
+    fn funding_interval_start_is_aligned_to_interval() {
+        assert_eq!(
+            funding_interval_start(datetime!(2024-02-13 09:42:17 UTC)),
+            datetime!(2024-02-13 08:00:00 UTC)
+        );
+        assert_eq!(
+            funding_interval_start(datetime!(2024-02-13 16:00:00 UTC)),
+            datetime!(2024-02-13 16:00:00 UTC)
+        );
+    }
+}
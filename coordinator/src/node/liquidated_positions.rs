@@ -0,0 +1,130 @@
+use crate::db;
+use crate::node::expired_positions::close_position;
+use crate::orderbook::trading::NewOrderMessage;
+use crate::price::PriceService;
+use anyhow::Result;
+use bitcoin::secp256k1::PublicKey;
+use commons::OrderReason;
+use diesel::r2d2::ConnectionManager;
+use diesel::r2d2::Pool;
+use diesel::PgConnection;
+use futures::future::RemoteHandle;
+use futures::FutureExt;
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tokio::task::spawn_blocking;
+use trade::ContractSymbol;
+
+/// The minimum time between two attempts to liquidate the position of the same trader.
+///
+/// Prevents us from flooding the orderbook with liquidation orders while the price keeps moving
+/// around the liquidation price.
+const LIQUIDATION_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often open positions are checked for liquidation.
+///
+/// Matches the interval at which the [`PriceService`] updates the mark price.
+const LIQUIDATION_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Periodically closes every open position whose liquidation price has been crossed by the mark
+/// price.
+///
+/// We do not liquidate against the best bid and ask of the orderbook, as any trader could move them
+/// with a single resting limit order on a thin book and thereby liquidate every position on the
+/// other side.
+pub fn monitor(
+    pool: Pool<ConnectionManager<PgConnection>>,
+    price_service: PriceService,
+    trading_sender: mpsc::Sender<NewOrderMessage>,
+) -> RemoteHandle<()> {
+    let (fut, remote_handle) = async move {
+        let mut last_attempts = HashMap::new();
+
+        loop {
+            tokio::time::sleep(LIQUIDATION_CHECK_INTERVAL).await;
+
+            if let Err(e) = liquidate_positions(
+                pool.clone(),
+                &price_service,
+                &trading_sender,
+                &mut last_attempts,
+            )
+            .await
+            {
+                tracing::error!("Failed to liquidate positions. Error: {e:#}");
+            }
+        }
+    }
+    .remote_handle();
+
+    tokio::spawn(fut);
+
+    remote_handle
+}
+
+/// Closes all open positions which got liquidated at the current mark price.
+async fn liquidate_positions(
+    pool: Pool<ConnectionManager<PgConnection>>,
+    price_service: &PriceService,
+    trading_sender: &mpsc::Sender<NewOrderMessage>,
+    last_attempts: &mut HashMap<PublicKey, Instant>,
+) -> Result<()> {
+    let mut mark_prices = HashMap::new();
+    for contract_symbol in ContractSymbol::ALL {
+        match price_service.get_prices(contract_symbol) {
+            Ok(prices) => {
+                mark_prices.insert(contract_symbol, prices.mark_price);
+            }
+            Err(e) => {
+                tracing::warn!(%contract_symbol, "Not checking positions for liquidation: {e:#}");
+            }
+        }
+    }
+
+    if mark_prices.is_empty() {
+        return Ok(());
+    }
+
+    let mut conn = spawn_blocking(move || pool.get())
+        .await
+        .expect("task to complete")?;
+
+    let positions = db::positions::Position::get_all_open_positions(&mut conn)?;
+
+    last_attempts.retain(|_, attempt| attempt.elapsed() < LIQUIDATION_RETRY_INTERVAL);
+
+    for position in positions {
+        let mark_price = match mark_prices.get(&position.contract_symbol) {
+            Some(mark_price) => *mark_price,
+            None => continue,
+        };
+
+        if !position.is_liquidated(mark_price) || last_attempts.contains_key(&position.trader) {
+            continue;
+        }
+
+        tracing::info!(
+            trader_pk=%position.trader,
+            liquidation_price=%position.trader_liquidation_price,
+            %mark_price,
+            "Attempting to close liquidated position"
+        );
+
+        last_attempts.insert(position.trader, Instant::now());
+
+        if let Err(e) = close_position(
+            &mut conn,
+            &position,
+            OrderReason::Liquidated,
+            trading_sender,
+        )
+        .await
+        {
+            tracing::error!(trader_pk=%position.trader, "Failed to close liquidated position. Error: {e:#}");
+        }
+    }
+
+    Ok(())
+}
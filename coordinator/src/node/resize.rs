@@ -242,7 +242,7 @@ impl Node {
 
             let contract_symbol = old_position.contract_symbol;
             let maturity_time = expiry_timestamp.unix_timestamp();
-            let event_id = format!("{contract_symbol}{maturity_time}");
+            let event_id = contract_symbol.oracle_event_id(maturity_time);
 
             let total_collateral = margin_coordinator + margin_trader;
 
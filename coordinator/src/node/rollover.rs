@@ -1,20 +1,29 @@
 use crate::db;
 use crate::db::positions;
+use crate::decimal_from_f32;
+use crate::f32_from_decimal;
 use crate::message::NewUserMessage;
 use crate::message::OrderbookMessage;
 use crate::node::Node;
+use crate::payout_curve;
+use crate::position::models::Position;
 use crate::position::models::PositionState;
 use anyhow::anyhow;
 use anyhow::bail;
+use anyhow::ensure;
 use anyhow::Context;
 use anyhow::Result;
 use bitcoin::hashes::hex::ToHex;
 use bitcoin::secp256k1::PublicKey;
 use bitcoin::Network;
-use bitcoin::XOnlyPublicKey;
+use bitcoin::SignedAmount;
+use commons::DifferenceParams;
 use commons::Message;
+use commons::OracleParams;
+use commons::RolloverResponse;
 use diesel::r2d2::ConnectionManager;
 use diesel::r2d2::Pool;
+use diesel::Connection;
 use diesel::PgConnection;
 use dlc_manager::channel::signed_channel::SignedChannel;
 use dlc_manager::contract::contract_input::ContractInput;
@@ -26,13 +35,16 @@ use dlc_manager::ContractId;
 use dlc_manager::DlcChannelId;
 use futures::future::RemoteHandle;
 use futures::FutureExt;
-use std::str::FromStr;
+use rust_decimal::Decimal;
 use time::OffsetDateTime;
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::mpsc;
 use tokio::task::spawn_blocking;
+use trade::cfd::calculate_long_liquidation_price;
+use trade::cfd::calculate_short_liquidation_price;
 use trade::ContractSymbol;
+use trade::Direction;
 
 #[derive(Debug, Clone)]
 struct Rollover {
@@ -41,7 +53,7 @@ struct Rollover {
     margin_coordinator: u64,
     margin_trader: u64,
     contract_symbol: ContractSymbol,
-    oracle_pk: XOnlyPublicKey,
+    oracle_params: OracleParams,
     contract_tx_fee_rate: u64,
     network: Network,
 }
@@ -124,6 +136,29 @@ impl Rollover {
             bail!("Cannot rollover an expired position");
         }
 
+        // The rolled over contract is built on the same oracles as the original contract.
+        let difference_params =
+            match &contract_info.contract_descriptor {
+                ContractDescriptor::Numerical(descriptor) => descriptor
+                    .difference_params
+                    .as_ref()
+                    .map(|params| DifferenceParams {
+                        max_error_exp: params.max_error_exp,
+                        min_support_exp: params.min_support_exp,
+                        maximize_coverage: params.maximize_coverage,
+                    }),
+                ContractDescriptor::Enum(_) => None,
+            };
+        let oracle_params = OracleParams {
+            pubkeys: contract_info
+                .oracle_announcements
+                .iter()
+                .map(|announcement| announcement.oracle_public_key)
+                .collect(),
+            threshold: contract_info.threshold as u16,
+            difference_params,
+        };
+
         let margin_coordinator = offered_contract.offer_params.collateral;
         let margin_trader = offered_contract.total_collateral - margin_coordinator;
 
@@ -133,9 +168,9 @@ impl Rollover {
             contract_descriptor: contract_info.clone().contract_descriptor,
             margin_coordinator,
             margin_trader,
-            oracle_pk: oracle_announcement.oracle_public_key,
-            contract_symbol: ContractSymbol::from_str(
-                &oracle_announcement.oracle_event.event_id[..6],
+            oracle_params,
+            contract_symbol: ContractSymbol::from_oracle_event_id(
+                &oracle_announcement.oracle_event.event_id,
             )?,
             contract_tx_fee_rate,
             network,
@@ -144,13 +179,66 @@ impl Rollover {
 
     pub fn event_id(&self) -> String {
         let maturity_time = self.maturity_time().unix_timestamp();
-        format!("{}{maturity_time}", self.contract_symbol)
+        self.contract_symbol.oracle_event_id(maturity_time)
     }
 
     /// Calculates the maturity time based on the current expiry timestamp.
     pub fn maturity_time(&self) -> OffsetDateTime {
         commons::calculate_next_expiry(OffsetDateTime::now_utc(), self.network)
     }
+
+    /// Settles the given funding fee by shifting it from the collateral of the paying party to
+    /// the collateral of the receiving party.
+    ///
+    /// A positive funding fee is paid by the trader, a negative one by the coordinator. The fee is
+    /// taken from the collateral reserve of the paying party first. If the reserve is not
+    /// sufficient, the remainder is taken from their margin. The receiving party gets the fee added
+    /// to their collateral reserve. Fails if the fee exceeds the collateral of the paying party, as
+    /// we would not be able to charge it in full.
+    ///
+    /// As the payout curve depends on the margins and reserves of both parties, the contract
+    /// descriptor is rebuilt from the given position.
+    fn apply_funding_fee(&mut self, position: &Position, funding_fee: SignedAmount) -> Result<()> {
+        let fee = funding_fee.abs().to_sat() as u64;
+
+        let (margin_coordinator, margin_trader) = if funding_fee.is_positive() {
+            ensure!(
+                fee <= self.margin_trader,
+                "Funding fee of {fee} sats exceeds the collateral of the trader"
+            );
+            (self.margin_coordinator + fee, self.margin_trader - fee)
+        } else {
+            ensure!(
+                fee <= self.margin_coordinator,
+                "Funding fee of {fee} sats exceeds the collateral of the coordinator"
+            );
+            (self.margin_coordinator - fee, self.margin_trader + fee)
+        };
+
+        let (position_margin_coordinator, position_margin_trader) =
+            position_margins(position, margin_coordinator, margin_trader);
+
+        let contract_descriptor = payout_curve::build_contract_descriptor(
+            decimal_from_f32(position.average_entry_price),
+            position_margin_coordinator,
+            position_margin_trader,
+            position.coordinator_leverage,
+            position.trader_leverage,
+            position.trader_direction.opposite(),
+            margin_coordinator - position_margin_coordinator,
+            margin_trader - position_margin_trader,
+            position.quantity,
+            position.contract_symbol,
+            &self.oracle_params,
+        )
+        .context("Could not build contract descriptor")?;
+
+        self.margin_coordinator = margin_coordinator;
+        self.margin_trader = margin_trader;
+        self.contract_descriptor = contract_descriptor;
+
+        Ok(())
+    }
 }
 
 impl Node {
@@ -214,15 +302,52 @@ impl Node {
     }
 
     /// Initiates the rollover protocol with the app.
+    ///
+    /// Settles the outstanding funding fees of the position with the renewed contract. Returns the
+    /// total funding fee of the position including the fees settled with this rollover, together
+    /// with the margin and liquidation price of the trader in the renewed contract.
     pub async fn propose_rollover(
         &self,
         dlc_channel_id: &DlcChannelId,
         network: Network,
-    ) -> Result<()> {
+    ) -> Result<RolloverResponse> {
         let contract = self.inner.get_contract_by_dlc_channel_id(dlc_channel_id)?;
-        let rollover = Rollover::new(contract, network)?;
+        let mut rollover = Rollover::new(contract, network)?;
 
-        tracing::debug!(node_id=%rollover.counterparty_pubkey, "Rollover dlc channel");
+        let mut connection = self.pool.get()?;
+        let position = positions::Position::get_position_by_trader(
+            &mut connection,
+            rollover.counterparty_pubkey,
+            vec![PositionState::Open],
+        )?
+        .context("Could not find open position to rollover")?;
+
+        let now = OffsetDateTime::now_utc();
+        // The fees are only marked as paid once the rollover has been finalized. Fees charged in
+        // the meantime are not part of the renewed contract, hence they are left for the next
+        // rollover.
+        let funding_fee =
+            db::funding_fee_events::reserve_for_rollover(&mut connection, position.id, now)?;
+        let total_funding_fee =
+            db::funding_fee_events::get_total_fees(&mut connection, position.id, now)?;
+
+        tracing::debug!(
+            node_id=%rollover.counterparty_pubkey,
+            position_id=position.id,
+            funding_fee_sat=funding_fee.to_sat(),
+            "Rollover dlc channel"
+        );
+
+        if funding_fee != SignedAmount::ZERO {
+            rollover.apply_funding_fee(&position, funding_fee)?;
+        }
+
+        let (_, trader_margin) = position_margins(
+            &position,
+            rollover.margin_coordinator,
+            rollover.margin_trader,
+        );
+        let trader_liquidation_price = liquidation_price(&position, trader_margin);
 
         let contract_input: ContractInput = rollover.clone().into();
 
@@ -231,12 +356,17 @@ impl Node {
             .await?;
 
         // Sets the position state to rollover indicating that a rollover is in progress.
-        let mut connection = self.pool.get()?;
         db::positions::Position::rollover_position(
             &mut connection,
             rollover.counterparty_pubkey.to_string(),
             &rollover.maturity_time(),
-        )
+        )?;
+
+        Ok(RolloverResponse {
+            funding_fee: total_funding_fee,
+            trader_margin,
+            trader_liquidation_price,
+        })
     }
 
     pub fn is_in_rollover(&self, trader_id: PublicKey) -> Result<bool> {
@@ -260,11 +390,38 @@ impl Node {
         );
 
         let mut connection = self.pool.get()?;
-        db::positions::Position::set_position_to_open(
+        let position = positions::Position::get_position_by_trader(
             &mut connection,
-            contract.get_counter_party_id().to_string(),
-            contract.get_temporary_id(),
-        )
+            trader_id,
+            vec![PositionState::Rollover],
+        )?
+        .context("Could not find position in rollover")?;
+
+        // Settling the funding fees might have reduced the margin of the paying party.
+        let (margin_coordinator, margin_trader) = collateral(&contract)?;
+        let (position_margin_coordinator, position_margin_trader) =
+            position_margins(&position, margin_coordinator, margin_trader);
+        let trader_liquidation_price = liquidation_price(&position, position_margin_trader);
+
+        connection.transaction::<_, anyhow::Error, _>(|connection| {
+            // The funding fees reserved when the rollover has been proposed, have been settled
+            // with the renewed contract.
+            db::funding_fee_events::mark_as_paid(connection, position.id)?;
+
+            db::positions::Position::update_margins(
+                connection,
+                position.id,
+                position_margin_coordinator as i64,
+                position_margin_trader as i64,
+                trader_liquidation_price,
+            )?;
+
+            db::positions::Position::set_position_to_open(
+                connection,
+                contract.get_counter_party_id().to_string(),
+                contract.get_temporary_id(),
+            )
+        })
     }
 
     fn rollback_channel_if_needed(
@@ -296,6 +453,64 @@ impl Node {
     }
 }
 
+/// The collateral of the coordinator and the trader in the given contract.
+fn collateral(contract: &Contract) -> Result<(u64, u64)> {
+    let offered_contract = match contract {
+        Contract::Signed(contract) | Contract::Confirmed(contract) => {
+            &contract.accepted_contract.offered_contract
+        }
+        _ => bail!("Cannot get the collateral of a contract that is not signed. {contract:?}"),
+    };
+
+    let margin_coordinator = offered_contract.offer_params.collateral;
+    let margin_trader = offered_contract.total_collateral - margin_coordinator;
+
+    Ok((margin_coordinator, margin_trader))
+}
+
+/// The margins of the coordinator and the trader in the given position, once their collateral
+/// changed to `margin_coordinator` and `margin_trader`.
+///
+/// The margin of a party is only reduced once their collateral reserve is used up.
+fn position_margins(
+    position: &Position,
+    margin_coordinator: u64,
+    margin_trader: u64,
+) -> (u64, u64) {
+    (
+        (position.coordinator_margin as u64).min(margin_coordinator),
+        (position.trader_margin as u64).min(margin_trader),
+    )
+}
+
+/// The liquidation price of the trader in the given position, once their margin changed to
+/// `trader_margin`.
+///
+/// A reduced margin corresponds to a higher leverage on the same quantity.
+fn liquidation_price(position: &Position, trader_margin: u64) -> f32 {
+    if trader_margin as i64 >= position.trader_margin {
+        return position.trader_liquidation_price;
+    }
+
+    // Without any margin left, the position is liquidated at any loss.
+    if trader_margin == 0 {
+        return position.average_entry_price;
+    }
+
+    let average_entry_price = decimal_from_f32(position.average_entry_price);
+
+    let leverage = decimal_from_f32(position.trader_leverage)
+        * Decimal::from(position.trader_margin)
+        / Decimal::from(trader_margin);
+
+    let liquidation_price = match position.trader_direction {
+        Direction::Long => calculate_long_liquidation_price(leverage, average_entry_price),
+        Direction::Short => calculate_short_liquidation_price(leverage, average_entry_price),
+    };
+
+    f32_from_decimal(liquidation_price)
+}
+
 fn is_channel_in_intermediate_state(signed_channel: &SignedChannel) -> bool {
     use dlc_manager::channel::signed_channel::SignedChannelState;
     matches!(
@@ -316,9 +531,9 @@ impl From<Rollover> for ContractInput {
             contract_infos: vec![ContractInputInfo {
                 contract_descriptor: rollover.clone().contract_descriptor,
                 oracles: OracleInput {
-                    public_keys: vec![rollover.oracle_pk],
+                    public_keys: rollover.oracle_params.pubkeys.clone(),
                     event_id: rollover.event_id(),
-                    threshold: 1,
+                    threshold: rollover.oracle_params.threshold,
                 },
             }],
         }
@@ -333,6 +548,7 @@ mod tests {
     use bitcoin::PackedLockTime;
     use bitcoin::Script;
     use bitcoin::Transaction;
+    use bitcoin::XOnlyPublicKey;
     use dlc::DlcTransactions;
     use dlc::PartyParams;
     use dlc_manager::contract::accepted_contract::AcceptedContract;
@@ -346,6 +562,7 @@ mod tests {
     use dlc_messages::oracle_msgs::OracleEvent;
     use dlc_messages::FundingSignatures;
     use rand::Rng;
+    use std::str::FromStr;
 
     #[test]
     fn test_new_rollover_from_signed_contract() {
@@ -355,6 +572,10 @@ mod tests {
         assert_eq!(rollover.contract_symbol, ContractSymbol::BtcUsd);
         assert_eq!(rollover.margin_trader, 100);
         assert_eq!(rollover.margin_coordinator, 200);
+        assert_eq!(
+            rollover.oracle_params,
+            OracleParams::single(XOnlyPublicKey::from(dummy_pubkey()))
+        );
     }
 
     #[test]
@@ -377,7 +598,7 @@ mod tests {
             margin_coordinator,
             margin_trader,
             contract_symbol: ContractSymbol::BtcUsd,
-            oracle_pk: XOnlyPublicKey::from(dummy_pubkey()),
+            oracle_params: OracleParams::single(XOnlyPublicKey::from(dummy_pubkey())),
             contract_tx_fee_rate: 1,
             network: Network::Bitcoin,
         };
@@ -388,6 +609,110 @@ mod tests {
         assert_eq!(contract_input.contract_infos.len(), 1);
     }
 
+    #[test]
+    fn test_apply_funding_fee_paid_by_trader() {
+        let mut rollover = Rollover {
+            counterparty_pubkey: dummy_pubkey(),
+            contract_descriptor: dummy_contract_descriptor(),
+            margin_coordinator: 125_000,
+            margin_trader: 125_000,
+            contract_symbol: ContractSymbol::BtcUsd,
+            oracle_params: OracleParams::single(XOnlyPublicKey::from(dummy_pubkey())),
+            contract_tx_fee_rate: 1,
+            network: Network::Bitcoin,
+        };
+
+        rollover
+            .apply_funding_fee(&dummy_position(), SignedAmount::from_sat(1_000))
+            .unwrap();
+
+        assert_eq!(rollover.margin_coordinator, 126_000);
+        assert_eq!(rollover.margin_trader, 124_000);
+        assert!(matches!(
+            rollover.contract_descriptor,
+            ContractDescriptor::Numerical(_)
+        ));
+    }
+
+    #[test]
+    fn test_apply_funding_fee_paid_by_coordinator() {
+        let mut rollover = Rollover {
+            counterparty_pubkey: dummy_pubkey(),
+            contract_descriptor: dummy_contract_descriptor(),
+            margin_coordinator: 125_000,
+            margin_trader: 125_000,
+            contract_symbol: ContractSymbol::BtcUsd,
+            oracle_params: OracleParams::single(XOnlyPublicKey::from(dummy_pubkey())),
+            contract_tx_fee_rate: 1,
+            network: Network::Bitcoin,
+        };
+
+        rollover
+            .apply_funding_fee(&dummy_position(), SignedAmount::from_sat(-1_000))
+            .unwrap();
+
+        assert_eq!(rollover.margin_coordinator, 124_000);
+        assert_eq!(rollover.margin_trader, 126_000);
+    }
+
+    #[test]
+    fn test_apply_funding_fee_exceeding_collateral() {
+        let mut rollover = Rollover {
+            counterparty_pubkey: dummy_pubkey(),
+            contract_descriptor: dummy_contract_descriptor(),
+            margin_coordinator: 125_000,
+            margin_trader: 125_000,
+            contract_symbol: ContractSymbol::BtcUsd,
+            oracle_params: OracleParams::single(XOnlyPublicKey::from(dummy_pubkey())),
+            contract_tx_fee_rate: 1,
+            network: Network::Bitcoin,
+        };
+
+        assert!(rollover
+            .apply_funding_fee(&dummy_position(), SignedAmount::from_sat(125_001))
+            .is_err());
+        assert!(rollover
+            .apply_funding_fee(&dummy_position(), SignedAmount::from_sat(-125_001))
+            .is_err());
+
+        assert_eq!(rollover.margin_coordinator, 125_000);
+        assert_eq!(rollover.margin_trader, 125_000);
+    }
+
+    #[test]
+    fn test_position_margins_after_funding_fee() {
+        let position = dummy_position();
+
+        // The trader paid 1_000 sats out of their margin, the coordinator received them into their
+        // reserve.
+        let (margin_coordinator, margin_trader) = position_margins(&position, 126_000, 124_000);
+
+        assert_eq!(margin_coordinator, 125_000);
+        assert_eq!(margin_trader, 124_000);
+    }
+
+    #[test]
+    fn test_liquidation_price_after_margin_reduction() {
+        let position = dummy_position();
+
+        assert_eq!(
+            liquidation_price(&position, 125_000),
+            position.trader_liquidation_price
+        );
+
+        // Halving the margin of a long position with leverage 2 doubles the leverage to 4.
+        assert_eq!(liquidation_price(&position, 62_500), 32_000.0);
+
+        let short_position = Position {
+            trader_direction: trade::Direction::Short,
+            ..dummy_position()
+        };
+
+        // Halving the margin of a short position with leverage 2 doubles the leverage to 4.
+        let liquidation_price = liquidation_price(&short_position, 62_500);
+        assert!((liquidation_price - 53_333.33).abs() < 0.01);
+    }
+
     #[test]
     fn test_rollover_expired_position() {
         let expiry_timestamp = OffsetDateTime::now_utc().unix_timestamp() - 10_000;
@@ -453,7 +778,7 @@ mod tests {
                         event_id: format!("btcusd{expiry_timestamp}"),
                     },
                 }],
-                threshold: 0,
+                threshold: 1,
             }],
             counter_party: dummy_pubkey(),
             offer_params: dummy_params(margin_coordinator),
@@ -466,6 +791,30 @@ mod tests {
         }
     }
 
+    fn dummy_position() -> Position {
+        Position {
+            id: 0,
+            contract_symbol: ContractSymbol::BtcUsd,
+            trader_leverage: 2.0,
+            quantity: 100.0,
+            trader_direction: trade::Direction::Long,
+            average_entry_price: 40_000.0,
+            trader_liquidation_price: 26_666.0,
+            position_state: PositionState::Open,
+            coordinator_margin: 125_000,
+            creation_timestamp: OffsetDateTime::now_utc(),
+            expiry_timestamp: OffsetDateTime::now_utc(),
+            update_timestamp: OffsetDateTime::now_utc(),
+            trader: dummy_pubkey(),
+            coordinator_leverage: 2.0,
+            temporary_contract_id: None,
+            closing_price: None,
+            trader_margin: 125_000,
+            stable: false,
+            trader_realized_pnl_sat: None,
+        }
+    }
+
     fn dummy_pubkey() -> PublicKey {
         PublicKey::from_str("02bd998ebd176715fe92b7467cf6b1df8023950a4dd911db4c94dfc89cc9f5a655")
             .expect("valid pubkey")
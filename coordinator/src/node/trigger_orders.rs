@@ -0,0 +1,146 @@
+use crate::db;
+use crate::node::expired_positions::close_position;
+use crate::node::expired_positions::CloseOutcome;
+use crate::orderbook::db::trigger_orders;
+use crate::orderbook::trading::NewOrderMessage;
+use crate::price::PriceService;
+use anyhow::Result;
+use commons::OrderReason;
+use commons::TriggerOrderState;
+use diesel::r2d2::ConnectionManager;
+use diesel::r2d2::Pool;
+use diesel::PgConnection;
+use futures::future::RemoteHandle;
+use futures::FutureExt;
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tokio::task::spawn_blocking;
+use uuid::Uuid;
+
+/// The minimum time between two attempts to execute the same trigger order.
+const TRIGGER_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often trigger orders are evaluated.
+///
+/// Matches the interval at which the [`PriceService`] updates the mark price.
+const TRIGGER_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Periodically closes every open position whose stop-loss or take-profit got triggered by the
+/// mark price.
+pub fn monitor(
+    pool: Pool<ConnectionManager<PgConnection>>,
+    price_service: PriceService,
+    trading_sender: mpsc::Sender<NewOrderMessage>,
+) -> RemoteHandle<()> {
+    let (fut, remote_handle) = async move {
+        let mut last_attempts = HashMap::new();
+
+        loop {
+            tokio::time::sleep(TRIGGER_CHECK_INTERVAL).await;
+
+            if let Err(e) = execute_trigger_orders(
+                pool.clone(),
+                &price_service,
+                &trading_sender,
+                &mut last_attempts,
+            )
+            .await
+            {
+                tracing::error!("Failed to execute trigger orders. Error: {e:#}");
+            }
+        }
+    }
+    .remote_handle();
+
+    tokio::spawn(fut);
+
+    remote_handle
+}
+
+/// Closes all open positions with a trigger order which got triggered at the current mark price.
+///
+/// Trigger orders of positions which have been closed otherwise are cancelled.
+async fn execute_trigger_orders(
+    pool: Pool<ConnectionManager<PgConnection>>,
+    price_service: &PriceService,
+    trading_sender: &mpsc::Sender<NewOrderMessage>,
+    last_attempts: &mut HashMap<Uuid, Instant>,
+) -> Result<()> {
+    let mut conn = spawn_blocking(move || pool.get())
+        .await
+        .expect("task to complete")?;
+
+    let cancelled = trigger_orders::cancel_all_of_closed_positions(&mut conn)?;
+    if cancelled > 0 {
+        tracing::debug!(%cancelled, "Cancelled trigger orders of closed positions");
+    }
+
+    let trigger_orders = trigger_orders::get_all_active(&mut conn)?;
+    if trigger_orders.is_empty() {
+        return Ok(());
+    }
+
+    let positions = db::positions::Position::get_all_open_positions(&mut conn)?;
+
+    last_attempts.retain(|_, attempt| attempt.elapsed() < TRIGGER_RETRY_INTERVAL);
+
+    for trigger_order in trigger_orders {
+        // Positions which are currently being resized or rolled over are not open. We
+        // re-evaluate their trigger orders once they are open again.
+        let position = match positions
+            .iter()
+            .find(|position| position.id == trigger_order.position_id)
+        {
+            Some(position) => position,
+            None => continue,
+        };
+
+        let mark_price = match price_service.get_prices(trigger_order.contract_symbol) {
+            Ok(prices) => prices.mark_price,
+            Err(e) => {
+                tracing::warn!(trigger_order_id=%trigger_order.id, "Not evaluating trigger order: {e:#}");
+                continue;
+            }
+        };
+
+        if !trigger_order.is_triggered(position.trader_direction, mark_price)
+            || last_attempts.contains_key(&trigger_order.id)
+        {
+            continue;
+        }
+
+        tracing::info!(
+            trader_pk=%position.trader,
+            trigger_order_id=%trigger_order.id,
+            trigger_type=?trigger_order.trigger_type,
+            trigger_price=%trigger_order.trigger_price,
+            %mark_price,
+            "Attempting to close position of triggered order"
+        );
+
+        last_attempts.insert(trigger_order.id, Instant::now());
+
+        match close_position(&mut conn, position, OrderReason::Triggered, trading_sender).await {
+            Ok(CloseOutcome::Submitted) => {}
+            Ok(CloseOutcome::Skipped) => {
+                // The trigger order stays active, so that we try again once the pending order of
+                // the trader has been executed or has expired.
+                tracing::debug!(trader_pk=%position.trader, trigger_order_id=%trigger_order.id, "Did not close position of triggered order as the trader has a pending order");
+                continue;
+            }
+            Err(e) => {
+                tracing::error!(trader_pk=%position.trader, trigger_order_id=%trigger_order.id, "Failed to close position of triggered order. Error: {e:#}");
+                continue;
+            }
+        }
+
+        trigger_orders::set_state(&mut conn, trigger_order.id, TriggerOrderState::Triggered)?;
+
+        // The other trigger order of the position is obsolete as the position is being closed.
+        trigger_orders::cancel_all_by_position(&mut conn, position.id)?;
+    }
+
+    Ok(())
+}
@@ -1,28 +1,30 @@
 use crate::db;
 use crate::node::Node;
 use crate::position::models::Position;
+use crate::price::PriceService;
 use anyhow::Context;
 use anyhow::Result;
 use diesel::r2d2::ConnectionManager;
 use diesel::r2d2::PooledConnection;
 use diesel::PgConnection;
-use time::OffsetDateTime;
-use trade::bitmex_client::BitmexClient;
-use trade::bitmex_client::Quote;
+use rust_decimal::Decimal;
 
-pub async fn sync(node: Node) -> Result<()> {
+pub async fn sync(node: Node, price_service: &PriceService) -> Result<()> {
     let mut conn = node.pool.get()?;
 
     let positions = db::positions::Position::get_all_open_or_closing_positions(&mut conn)?;
 
-    // TODO(holzeis): we should not use the bitmex quote here, but rather our own orderbook.
-    let current_quote = BitmexClient::get_quote(&node.inner.network, &OffsetDateTime::now_utc())
-        .await
-        .context("Failed to fetch quote from BitMEX")?;
-
     for position in positions.iter() {
-        if let Err(e) = sync_position(&mut conn, position, current_quote.clone()) {
-            tracing::error!(position_id=%position.id, ?current_quote, "Failed to update position's unrealized pnl in database: {e:#}")
+        let mark_price = match price_service.get_prices(position.contract_symbol) {
+            Ok(prices) => prices.mark_price,
+            Err(e) => {
+                tracing::warn!(position_id=%position.id, "Failed to get mark price: {e:#}");
+                continue;
+            }
+        };
+
+        if let Err(e) = sync_position(&mut conn, position, mark_price) {
+            tracing::error!(position_id=%position.id, %mark_price, "Failed to update position's unrealized pnl in database: {e:#}")
         }
     }
 
@@ -32,9 +34,9 @@ pub async fn sync(node: Node) -> Result<()> {
 fn sync_position(
     conn: &mut PooledConnection<ConnectionManager<PgConnection>>,
     position: &Position,
-    quote: Quote,
+    mark_price: Decimal,
 ) -> Result<()> {
-    let coordinator_pnl = position.calculate_coordinator_pnl(quote)?;
+    let coordinator_pnl = position.calculate_coordinator_pnl(mark_price)?;
     let trader_pnl = -coordinator_pnl;
     db::positions::Position::update_unrealized_pnl(conn, position.id, trader_pnl)
         .context("Failed to update unrealized pnl in db")?;
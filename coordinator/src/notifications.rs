@@ -11,7 +11,10 @@ pub enum NotificationKind {
     RolloverWindowOpen,
     PositionSoonToExpire,
     PositionExpired,
+    PositionLiquidated,
+    TriggerOrderExecuted,
     CollaborativeRevert,
+    LimitOrderFilled,
 }
 
 impl Display for NotificationKind {
@@ -19,8 +22,11 @@ impl Display for NotificationKind {
         match self {
             NotificationKind::PositionSoonToExpire => write!(f, "PositionSoonToExpire"),
             NotificationKind::PositionExpired => write!(f, "PositionExpired"),
+            NotificationKind::PositionLiquidated => write!(f, "PositionLiquidated"),
+            NotificationKind::TriggerOrderExecuted => write!(f, "TriggerOrderExecuted"),
             NotificationKind::RolloverWindowOpen => write!(f, "RolloverWindowOpen"),
             NotificationKind::CollaborativeRevert => write!(f, "CollaborativeRevertPending"),
+            NotificationKind::LimitOrderFilled => write!(f, "LimitOrderFilled"),
         }
     }
 }
@@ -105,6 +111,14 @@ fn build_notification<'a>(kind: NotificationKind) -> fcm::Notification<'a> {
             notification_builder.title("Your position has expired");
             notification_builder.body("Close your position.");
         }
+        NotificationKind::PositionLiquidated => {
+            notification_builder.title("Your position has been liquidated");
+            notification_builder.body("Open your app to close your position.");
+        }
+        NotificationKind::TriggerOrderExecuted => {
+            notification_builder.title("Your stop-loss or take-profit has been triggered");
+            notification_builder.body("Open your app to close your position.");
+        }
         NotificationKind::RolloverWindowOpen => {
             notification_builder.title("Rollover window is open");
             notification_builder.body("Rollover your position for the next cycle.");
@@ -113,6 +127,10 @@ fn build_notification<'a>(kind: NotificationKind) -> fcm::Notification<'a> {
             notification_builder.title("Error detected");
             notification_builder.body("Please open your app to recover your funds.");
         }
+        NotificationKind::LimitOrderFilled => {
+            notification_builder.title("Your limit order has been filled");
+            notification_builder.body("Open your app to execute the trade.");
+        }
     }
     notification_builder.finalize()
 }
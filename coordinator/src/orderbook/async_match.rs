@@ -6,13 +6,14 @@ use anyhow::ensure;
 use anyhow::Result;
 use bitcoin::secp256k1::PublicKey;
 use bitcoin::Network;
-use bitcoin::XOnlyPublicKey;
 use commons::FilledWith;
 use commons::Match;
 use commons::Matches;
 use commons::Message;
+use commons::OracleParams;
 use commons::OrderReason;
 use commons::OrderState;
+use commons::OrderType;
 use diesel::r2d2::ConnectionManager;
 use diesel::r2d2::Pool;
 use diesel::PgConnection;
@@ -23,13 +24,14 @@ use tokio::sync::broadcast;
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::mpsc;
 use tokio::task::spawn_blocking;
+use uuid::Uuid;
 
 pub fn monitor(
     pool: Pool<ConnectionManager<PgConnection>>,
     tx_user_feed: broadcast::Sender<NewUserMessage>,
     notifier: mpsc::Sender<OrderbookMessage>,
     network: Network,
-    oracle_pk: XOnlyPublicKey,
+    oracle_params: OracleParams,
 ) -> RemoteHandle<()> {
     let mut user_feed = tx_user_feed.subscribe();
     let (fut, remote_handle) = async move {
@@ -39,6 +41,7 @@ pub fn monitor(
                     tokio::spawn({
                         let notifier = notifier.clone();
                         let pool = pool.clone();
+                        let oracle_params = oracle_params.clone();
                         async move {
                             tracing::debug!(
                                 trader_id=%new_user_msg.new_user,
@@ -49,7 +52,7 @@ pub fn monitor(
                                 notifier,
                                 new_user_msg.new_user,
                                 network,
-                                oracle_pk,
+                                oracle_params,
                             )
                             .await
                             {
@@ -84,7 +87,7 @@ async fn process_pending_match(
     notifier: mpsc::Sender<OrderbookMessage>,
     trader_id: PublicKey,
     network: Network,
-    oracle_pk: XOnlyPublicKey,
+    oracle_params: OracleParams,
 ) -> Result<()> {
     let mut conn = spawn_blocking(move || pool.get())
         .await
@@ -96,11 +99,18 @@ async fn process_pending_match(
         tracing::debug!(%trader_id, order_id=%order.id, "Notifying trader about pending match");
 
         let matches = matches::get_matches_by_order_id(&mut conn, order.id)?;
-        let filled_with = get_filled_with_from_matches(matches, network, oracle_pk)?;
+        let parent_order_id = orders::get_parent_order_id(&mut conn, order.id)?;
+        let filled_with =
+            get_filled_with_from_matches(matches, parent_order_id, network, oracle_params)?;
 
-        let message = match order.order_reason {
-            OrderReason::Manual => Message::Match(filled_with),
-            OrderReason::Expired => Message::AsyncMatch { order, filled_with },
+        // Only market orders are filled while the trader is waiting for the match. Resting limit
+        // orders and orders generated by the coordinator have to be executed asynchronously.
+        let message = match (order.order_type, &order.order_reason) {
+            (OrderType::Market, OrderReason::Manual) => Message::Match(filled_with),
+            (OrderType::Limit, OrderReason::Manual)
+            | (_, OrderReason::Expired)
+            | (_, OrderReason::Liquidated)
+            | (_, OrderReason::Triggered) => Message::AsyncMatch { order, filled_with },
         };
 
         // Sending no optional push notification as this is only executed if the user just
@@ -121,8 +131,9 @@ async fn process_pending_match(
 
 fn get_filled_with_from_matches(
     matches: Vec<Matches>,
+    parent_order_id: Option<Uuid>,
     network: Network,
-    oracle_pk: XOnlyPublicKey,
+    oracle_params: OracleParams,
 ) -> Result<FilledWith> {
     ensure!(
         !matches.is_empty(),
@@ -139,7 +150,7 @@ fn get_filled_with_from_matches(
     Ok(FilledWith {
         order_id,
         expiry_timestamp,
-        oracle_pk,
+        oracle_params,
         matches: matches
             .iter()
             .map(|m| Match {
@@ -150,5 +161,6 @@ fn get_filled_with_from_matches(
                 execution_price: m.execution_price,
             })
             .collect(),
+        parent_order_id,
     })
 }
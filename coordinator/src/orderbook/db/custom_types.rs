@@ -3,6 +3,9 @@ use crate::schema::sql_types::MatchStateType;
 use crate::schema::sql_types::OrderReasonType;
 use crate::schema::sql_types::OrderStateType;
 use crate::schema::sql_types::OrderTypeType;
+use crate::schema::sql_types::TimeInForceType;
+use crate::schema::sql_types::TriggerOrderStateType;
+use crate::schema::sql_types::TriggerTypeType;
 use diesel::deserialize;
 use diesel::deserialize::FromSql;
 use diesel::pg::Pg;
@@ -100,6 +103,8 @@ pub(crate) enum OrderState {
     Taken,
     /// The order failed, e.g. expired or for some other technical reason.
     Failed,
+    /// The order has been cancelled by the trader.
+    Deleted,
 }
 
 impl QueryId for OrderStateType {
@@ -118,6 +123,7 @@ impl ToSql<OrderStateType, Pg> for OrderState {
             OrderState::Matched => out.write_all(b"Matched")?,
             OrderState::Taken => out.write_all(b"Taken")?,
             OrderState::Failed => out.write_all(b"Failed")?,
+            OrderState::Deleted => out.write_all(b"Deleted")?,
         }
         Ok(IsNull::No)
     }
@@ -130,6 +136,7 @@ impl FromSql<OrderStateType, Pg> for OrderState {
             b"Matched" => Ok(OrderState::Matched),
             b"Taken" => Ok(OrderState::Taken),
             b"Failed" => Ok(OrderState::Failed),
+            b"Deleted" => Ok(OrderState::Deleted),
             _ => Err("Unrecognized enum variant".into()),
         }
     }
@@ -142,6 +149,10 @@ pub(crate) enum OrderReason {
     Manual,
     /// The order has been create automatically as the position expired.
     Expired,
+    /// The order has been created automatically as the position got liquidated.
+    Liquidated,
+    /// The order has been created automatically as a stop-loss or take-profit got triggered.
+    Triggered,
 }
 
 impl QueryId for OrderReasonType {
@@ -158,6 +169,8 @@ impl ToSql<OrderReasonType, Pg> for OrderReason {
         match *self {
             OrderReason::Manual => out.write_all(b"Manual")?,
             OrderReason::Expired => out.write_all(b"Expired")?,
+            OrderReason::Liquidated => out.write_all(b"Liquidated")?,
+            OrderReason::Triggered => out.write_all(b"Triggered")?,
         }
         Ok(IsNull::No)
     }
@@ -168,6 +181,50 @@ impl FromSql<OrderReasonType, Pg> for OrderReason {
         match bytes.as_bytes() {
             b"Manual" => Ok(OrderReason::Manual),
             b"Expired" => Ok(OrderReason::Expired),
+            b"Liquidated" => Ok(OrderReason::Liquidated),
+            b"Triggered" => Ok(OrderReason::Triggered),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, FromSqlRow, AsExpression)]
+#[diesel(sql_type = TimeInForceType)]
+pub(crate) enum TimeInForce {
+    ImmediateOrCancel,
+    FillOrKill,
+    GoodTillCancelled,
+    GoodTillTime,
+}
+
+impl QueryId for TimeInForceType {
+    type QueryId = TimeInForceType;
+    const HAS_STATIC_QUERY_ID: bool = false;
+
+    fn query_id() -> Option<TypeId> {
+        None
+    }
+}
+
+impl ToSql<TimeInForceType, Pg> for TimeInForce {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            TimeInForce::ImmediateOrCancel => out.write_all(b"ImmediateOrCancel")?,
+            TimeInForce::FillOrKill => out.write_all(b"FillOrKill")?,
+            TimeInForce::GoodTillCancelled => out.write_all(b"GoodTillCancelled")?,
+            TimeInForce::GoodTillTime => out.write_all(b"GoodTillTime")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<TimeInForceType, Pg> for TimeInForce {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"ImmediateOrCancel" => Ok(TimeInForce::ImmediateOrCancel),
+            b"FillOrKill" => Ok(TimeInForce::FillOrKill),
+            b"GoodTillCancelled" => Ok(TimeInForce::GoodTillCancelled),
+            b"GoodTillTime" => Ok(TimeInForce::GoodTillTime),
             _ => Err("Unrecognized enum variant".into()),
         }
     }
@@ -211,3 +268,78 @@ impl FromSql<MatchStateType, Pg> for MatchState {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, FromSqlRow, AsExpression)]
+#[diesel(sql_type = TriggerTypeType)]
+pub(crate) enum TriggerType {
+    StopLoss,
+    TakeProfit,
+}
+
+impl QueryId for TriggerTypeType {
+    type QueryId = TriggerTypeType;
+    const HAS_STATIC_QUERY_ID: bool = false;
+
+    fn query_id() -> Option<TypeId> {
+        None
+    }
+}
+
+impl ToSql<TriggerTypeType, Pg> for TriggerType {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            TriggerType::StopLoss => out.write_all(b"StopLoss")?,
+            TriggerType::TakeProfit => out.write_all(b"TakeProfit")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<TriggerTypeType, Pg> for TriggerType {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"StopLoss" => Ok(TriggerType::StopLoss),
+            b"TakeProfit" => Ok(TriggerType::TakeProfit),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, FromSqlRow, AsExpression)]
+#[diesel(sql_type = TriggerOrderStateType)]
+pub(crate) enum TriggerOrderState {
+    Active,
+    Triggered,
+    Cancelled,
+}
+
+impl QueryId for TriggerOrderStateType {
+    type QueryId = TriggerOrderStateType;
+    const HAS_STATIC_QUERY_ID: bool = false;
+
+    fn query_id() -> Option<TypeId> {
+        None
+    }
+}
+
+impl ToSql<TriggerOrderStateType, Pg> for TriggerOrderState {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            TriggerOrderState::Active => out.write_all(b"Active")?,
+            TriggerOrderState::Triggered => out.write_all(b"Triggered")?,
+            TriggerOrderState::Cancelled => out.write_all(b"Cancelled")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<TriggerOrderStateType, Pg> for TriggerOrderState {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"Active" => Ok(TriggerOrderState::Active),
+            b"Triggered" => Ok(TriggerOrderState::Triggered),
+            b"Cancelled" => Ok(TriggerOrderState::Cancelled),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
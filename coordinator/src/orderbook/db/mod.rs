@@ -1,3 +1,4 @@
 pub mod custom_types;
 pub mod matches;
 pub mod orders;
+pub mod trigger_orders;
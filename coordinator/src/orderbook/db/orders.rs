@@ -4,20 +4,24 @@ use crate::orderbook::db::custom_types::MatchState;
 use crate::orderbook::db::custom_types::OrderReason;
 use crate::orderbook::db::custom_types::OrderState;
 use crate::orderbook::db::custom_types::OrderType;
+use crate::orderbook::db::custom_types::TimeInForce;
 use crate::schema::matches;
 use crate::schema::orders;
 use bitcoin::secp256k1::PublicKey;
+use commons::LimitOrderFilledMatch;
 use commons::NewOrder as OrderbookNewOrder;
 use commons::Order as OrderbookOrder;
 use commons::OrderReason as OrderBookOrderReason;
 use commons::OrderState as OrderBookOrderState;
 use commons::OrderType as OrderBookOrderType;
+use commons::TimeInForce as OrderBookTimeInForce;
 use diesel::prelude::*;
 use diesel::result::QueryResult;
 use diesel::PgConnection;
 use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
+use std::collections::HashSet;
 use time::OffsetDateTime;
 use trade::Direction as OrderbookDirection;
 use uuid::Uuid;
@@ -58,6 +62,28 @@ impl From<OrderBookOrderType> for OrderType {
     }
 }
 
+impl From<TimeInForce> for OrderBookTimeInForce {
+    fn from(value: TimeInForce) -> Self {
+        match value {
+            TimeInForce::ImmediateOrCancel => OrderBookTimeInForce::ImmediateOrCancel,
+            TimeInForce::FillOrKill => OrderBookTimeInForce::FillOrKill,
+            TimeInForce::GoodTillCancelled => OrderBookTimeInForce::GoodTillCancelled,
+            TimeInForce::GoodTillTime => OrderBookTimeInForce::GoodTillTime,
+        }
+    }
+}
+
+impl From<OrderBookTimeInForce> for TimeInForce {
+    fn from(value: OrderBookTimeInForce) -> Self {
+        match value {
+            OrderBookTimeInForce::ImmediateOrCancel => TimeInForce::ImmediateOrCancel,
+            OrderBookTimeInForce::FillOrKill => TimeInForce::FillOrKill,
+            OrderBookTimeInForce::GoodTillCancelled => TimeInForce::GoodTillCancelled,
+            OrderBookTimeInForce::GoodTillTime => TimeInForce::GoodTillTime,
+        }
+    }
+}
+
 impl From<OrderState> for OrderBookOrderState {
     fn from(value: OrderState) -> Self {
         match value {
@@ -65,6 +91,7 @@ impl From<OrderState> for OrderBookOrderState {
             OrderState::Matched => OrderBookOrderState::Matched,
             OrderState::Taken => OrderBookOrderState::Taken,
             OrderState::Failed => OrderBookOrderState::Failed,
+            OrderState::Deleted => OrderBookOrderState::Deleted,
         }
     }
 }
@@ -76,6 +103,7 @@ impl From<OrderBookOrderState> for OrderState {
             OrderBookOrderState::Matched => OrderState::Matched,
             OrderBookOrderState::Taken => OrderState::Taken,
             OrderBookOrderState::Failed => OrderState::Failed,
+            OrderBookOrderState::Deleted => OrderState::Deleted,
         }
     }
 }
@@ -98,6 +126,9 @@ struct Order {
     pub leverage: f32,
     pub order_reason: OrderReason,
     pub stable: bool,
+    pub time_in_force: TimeInForce,
+    pub post_only: bool,
+    pub parent_order_id: Option<Uuid>,
 }
 
 impl From<Order> for OrderbookOrder {
@@ -117,6 +148,8 @@ impl From<Order> for OrderbookOrder {
             order_state: value.order_state.into(),
             order_reason: value.order_reason.into(),
             stable: value.stable,
+            time_in_force: value.time_in_force.into(),
+            post_only: value.post_only,
         }
     }
 }
@@ -126,6 +159,8 @@ impl From<OrderReason> for OrderBookOrderReason {
         match value {
             OrderReason::Manual => OrderBookOrderReason::Manual,
             OrderReason::Expired => OrderBookOrderReason::Expired,
+            OrderReason::Liquidated => OrderBookOrderReason::Liquidated,
+            OrderReason::Triggered => OrderBookOrderReason::Triggered,
         }
     }
 }
@@ -135,6 +170,8 @@ impl From<OrderBookOrderReason> for OrderReason {
         match value {
             OrderBookOrderReason::Manual => OrderReason::Manual,
             OrderBookOrderReason::Expired => OrderReason::Expired,
+            OrderBookOrderReason::Liquidated => OrderReason::Liquidated,
+            OrderBookOrderReason::Triggered => OrderReason::Triggered,
         }
     }
 }
@@ -153,6 +190,8 @@ struct NewOrder {
     pub contract_symbol: ContractSymbol,
     pub leverage: f32,
     pub stable: bool,
+    pub time_in_force: TimeInForce,
+    pub post_only: bool,
 }
 
 impl From<OrderbookNewOrder> for NewOrder {
@@ -177,6 +216,8 @@ impl From<OrderbookNewOrder> for NewOrder {
             contract_symbol: value.contract_symbol.into(),
             leverage: value.leverage,
             stable: value.stable,
+            time_in_force: value.time_in_force.into(),
+            post_only: value.post_only,
         }
     }
 }
@@ -184,8 +225,13 @@ impl From<OrderbookNewOrder> for NewOrder {
 pub fn all_limit_orders(conn: &mut PgConnection) -> QueryResult<Vec<OrderbookOrder>> {
     let orders = orders::table
         .filter(orders::order_type.eq(OrderType::Limit))
-        .filter(orders::expiry.gt(OffsetDateTime::now_utc()))
+        .filter(
+            orders::expiry
+                .gt(OffsetDateTime::now_utc())
+                .or(orders::time_in_force.eq(TimeInForce::GoodTillCancelled)),
+        )
         .filter(orders::order_state.ne(OrderState::Failed))
+        .filter(orders::order_state.ne(OrderState::Deleted))
         .load::<Order>(conn)?;
 
     Ok(orders.into_iter().map(OrderbookOrder::from).collect())
@@ -205,7 +251,11 @@ pub fn all_by_direction_and_type(
 
     let orders: Vec<Order> = if filter_expired {
         filters
-            .filter(orders::expiry.gt(OffsetDateTime::now_utc()))
+            .filter(
+                orders::expiry
+                    .gt(OffsetDateTime::now_utc())
+                    .or(orders::time_in_force.eq(TimeInForce::GoodTillCancelled)),
+            )
             .load::<Order>(conn)?
     } else {
         filters.load::<Order>(conn)?
@@ -225,7 +275,11 @@ pub fn get_all_orders(
         .filter(orders::order_type.eq(OrderType::from(order_type)));
     let orders: Vec<Order> = if filter_expired {
         filters
-            .filter(orders::expiry.gt(OffsetDateTime::now_utc()))
+            .filter(
+                orders::expiry
+                    .gt(OffsetDateTime::now_utc())
+                    .or(orders::time_in_force.eq(TimeInForce::GoodTillCancelled)),
+            )
             .load::<Order>(conn)?
     } else {
         filters.load::<Order>(conn)?
@@ -278,12 +332,197 @@ pub fn set_order_state(
     Ok(OrderbookOrder::from(order))
 }
 
+/// Splits the `filled_quantity` off the order identified by `id` into a new order identified by
+/// `fill_order_id`.
+///
+/// The new order inherits all properties of the original order, including its timestamp, and
+/// references the original order as its parent. As the new order has been matched, it never rests
+/// in the orderbook. The original order keeps the remaining quantity.
+///
+/// Returns the original order with the remaining quantity.
+pub fn split(
+    conn: &mut PgConnection,
+    id: Uuid,
+    fill_order_id: Uuid,
+    filled_quantity: Decimal,
+) -> QueryResult<OrderbookOrder> {
+    conn.transaction(|conn| {
+        let order: Order = orders::table
+            .filter(orders::trader_order_id.eq(id))
+            .first(conn)?;
+
+        let filled_quantity = filled_quantity
+            .round_dp(2)
+            .to_f32()
+            .expect("To be able to convert decimal to f32");
+
+        diesel::insert_into(orders::table)
+            .values((
+                orders::trader_order_id.eq(fill_order_id),
+                orders::price.eq(order.price),
+                orders::trader_id.eq(&order.trader_id),
+                orders::direction.eq(order.direction),
+                orders::quantity.eq(filled_quantity),
+                orders::timestamp.eq(order.timestamp),
+                orders::order_type.eq(order.order_type),
+                orders::expiry.eq(order.expiry),
+                orders::order_state.eq(OrderState::Matched),
+                orders::contract_symbol.eq(order.contract_symbol),
+                orders::leverage.eq(order.leverage),
+                orders::order_reason.eq(order.order_reason),
+                orders::stable.eq(order.stable),
+                orders::time_in_force.eq(order.time_in_force),
+                orders::post_only.eq(order.post_only),
+                orders::parent_order_id.eq(id),
+            ))
+            .execute(conn)?;
+
+        let order: Order = diesel::update(orders::table)
+            .filter(orders::trader_order_id.eq(id))
+            .set(orders::quantity.eq(order.quantity - filled_quantity))
+            .get_result(conn)?;
+
+        Ok(OrderbookOrder::from(order))
+    })
+}
+
+/// Returns the id of the order the order identified by `id` has been split off from, if any.
+pub fn get_parent_order_id(conn: &mut PgConnection, id: Uuid) -> QueryResult<Option<Uuid>> {
+    orders::table
+        .filter(orders::trader_order_id.eq(id))
+        .select(orders::parent_order_id)
+        .first(conn)
+}
+
+/// Sets the open limit order identified by `id` to [`OrderState::Deleted`].
+///
+/// Returns `None` if the trader has no open limit order with the given id.
+pub fn delete(
+    conn: &mut PgConnection,
+    id: Uuid,
+    trader_id: PublicKey,
+) -> QueryResult<Option<OrderbookOrder>> {
+    let order: Option<Order> = diesel::update(orders::table)
+        .filter(orders::trader_order_id.eq(id))
+        .filter(orders::trader_id.eq(trader_id.to_string()))
+        .filter(orders::order_type.eq(OrderType::Limit))
+        .filter(orders::order_state.eq(OrderState::Open))
+        .set(orders::order_state.eq(OrderState::Deleted))
+        .get_result(conn)
+        .optional()?;
+
+    Ok(order.map(OrderbookOrder::from))
+}
+
+/// Sets all open limit orders of the trader to [`OrderState::Deleted`].
+///
+/// Returns the deleted orders.
+pub fn delete_all_by_trader(
+    conn: &mut PgConnection,
+    trader_id: PublicKey,
+) -> QueryResult<Vec<OrderbookOrder>> {
+    let orders: Vec<Order> = diesel::update(orders::table)
+        .filter(orders::trader_id.eq(trader_id.to_string()))
+        .filter(orders::order_type.eq(OrderType::Limit))
+        .filter(orders::order_state.eq(OrderState::Open))
+        .set(orders::order_state.eq(OrderState::Deleted))
+        .get_results(conn)?;
+
+    Ok(orders.into_iter().map(OrderbookOrder::from).collect())
+}
+
+/// Replaces the price and the quantity of the open limit order identified by `id`.
+///
+/// The order only keeps its time priority if its size is reduced. Any change of the price or
+/// increase of the quantity moves it to the back of the queue, as if it had been posted anew.
+///
+/// Returns `None` if the trader has no open limit order with the given id.
+pub fn amend(
+    conn: &mut PgConnection,
+    id: Uuid,
+    trader_id: PublicKey,
+    price: Decimal,
+    quantity: Decimal,
+) -> QueryResult<Option<OrderbookOrder>> {
+    let price = price
+        .round_dp(2)
+        .to_f32()
+        .expect("To be able to convert decimal to f32");
+    let quantity = quantity
+        .round_dp(2)
+        .to_f32()
+        .expect("To be able to convert decimal to f32");
+
+    conn.transaction(|conn| {
+        let order: Option<Order> = orders::table
+            .filter(orders::trader_order_id.eq(id))
+            .filter(orders::trader_id.eq(trader_id.to_string()))
+            .filter(orders::order_type.eq(OrderType::Limit))
+            .filter(orders::order_state.eq(OrderState::Open))
+            .for_update()
+            .first(conn)
+            .optional()?;
+        let order = match order {
+            Some(order) => order,
+            None => return Ok(None),
+        };
+
+        let timestamp = if keeps_time_priority(&order, price, quantity) {
+            order.timestamp
+        } else {
+            OffsetDateTime::now_utc()
+        };
+
+        let order: Order = diesel::update(orders::table)
+            .filter(orders::trader_order_id.eq(id))
+            .set((
+                orders::price.eq(price),
+                orders::quantity.eq(quantity),
+                orders::timestamp.eq(timestamp),
+            ))
+            .get_result(conn)?;
+
+        Ok(Some(OrderbookOrder::from(order)))
+    })
+}
+
+/// Whether an order amended to the given price and quantity keeps its place in the queue.
+fn keeps_time_priority(order: &Order, price: f32, quantity: f32) -> bool {
+    order.price == price && quantity <= order.quantity
+}
+
+/// Reduces the quantity of the order identified by `id` to the quantity which has been filled.
+///
+/// Used for orders which are [`OrderBookTimeInForce::ImmediateOrCancel`], whose remaining quantity
+/// is cancelled.
+pub fn set_filled_quantity(
+    conn: &mut PgConnection,
+    id: Uuid,
+    filled_quantity: Decimal,
+) -> QueryResult<OrderbookOrder> {
+    let order: Order = diesel::update(orders::table)
+        .filter(orders::trader_order_id.eq(id))
+        .set(
+            orders::quantity.eq(filled_quantity
+                .round_dp(2)
+                .to_f32()
+                .expect("To be able to convert decimal to f32")),
+        )
+        .get_result(conn)?;
+
+    Ok(OrderbookOrder::from(order))
+}
+
+/// Sets all open limit orders which passed their expiry to [`OrderState::Failed`].
+///
+/// Orders which are [`OrderBookTimeInForce::GoodTillCancelled`] never expire.
 pub fn set_expired_limit_orders_to_failed(
     conn: &mut PgConnection,
 ) -> QueryResult<Vec<OrderbookOrder>> {
     let expired_limit_orders: Vec<Order> = diesel::update(orders::table)
         .filter(orders::order_state.eq(OrderState::Open))
         .filter(orders::order_type.eq(OrderType::Limit))
+        .filter(orders::time_in_force.ne(TimeInForce::GoodTillCancelled))
         .filter(orders::expiry.lt(OffsetDateTime::now_utc()))
         .set(orders::order_state.eq(OrderState::Failed))
         .get_results(conn)?;
@@ -317,6 +556,23 @@ pub fn get_by_trader_id_and_state(
         .optional()
 }
 
+/// Returns the ids of all traders with at least one order in the given state.
+pub fn get_trader_ids_by_state(
+    conn: &mut PgConnection,
+    order_state: commons::OrderState,
+) -> QueryResult<HashSet<PublicKey>> {
+    let trader_ids: Vec<String> = orders::table
+        .filter(orders::order_state.eq(OrderState::from(order_state)))
+        .select(orders::trader_id)
+        .distinct()
+        .load(conn)?;
+
+    Ok(trader_ids
+        .into_iter()
+        .map(|trader_id| trader_id.parse().expect("to have a valid pubkey"))
+        .collect())
+}
+
 /// Get all the filled matches for all the limit orders generated by `trader_id`.
 ///
 /// This can be used to calculate the implicit position of the maker, assuming that all the filled
@@ -324,7 +580,7 @@ pub fn get_by_trader_id_and_state(
 pub fn get_all_limit_order_filled_matches(
     conn: &mut PgConnection,
     trader_id: PublicKey,
-) -> QueryResult<Vec<(Uuid, Decimal)>> {
+) -> QueryResult<Vec<LimitOrderFilledMatch>> {
     let orders = orders::table
         // We use `matches::match_order_id` so that we can verify that the corresponding app trader
         // order is in `match_state` _`Filled`_. The maker's match remains in `Pending` (since the
@@ -333,9 +589,11 @@ pub fn get_all_limit_order_filled_matches(
         .filter(
             orders::trader_id
                 .eq(trader_id.to_string())
-                // Looking for `Matched`, `Limit` orders only, corresponding to the maker.
+                // Looking for `Limit` orders only, corresponding to the maker. The filled part of a
+                // partially filled limit order is split off into its own order, so only `Matched`
+                // and `Taken` orders can have been filled.
                 .and(orders::order_type.eq(OrderType::Limit))
-                .and(orders::order_state.eq(OrderState::Matched))
+                .and(orders::order_state.eq_any([OrderState::Matched, OrderState::Taken]))
                 // The corresponding app trader match is `Filled`.
                 .and(matches::match_state.eq(MatchState::Filled)),
         )
@@ -343,23 +601,36 @@ pub fn get_all_limit_order_filled_matches(
             // We use the order ID of the _match_ so that we get a unique order ID even if the same
             // limit order is partially filled more than once.
             matches::order_id,
+            orders::contract_symbol,
             matches::quantity,
             orders::direction,
+            matches::execution_price,
+            // The app trader match is updated when it is filled.
+            matches::updated_at,
         ))
-        .load::<(Uuid, f32, Direction)>(conn)?;
+        .load::<(Uuid, ContractSymbol, f32, Direction, f32, OffsetDateTime)>(conn)?;
 
     let filled_matches = orders
         .into_iter()
-        .map(|(order_id, quantity, direction_maker)| {
-            let quantity = Decimal::from_f32(quantity).expect("to fit into Decimal");
-
-            let quantity = match direction_maker {
-                Direction::Long => quantity,
-                Direction::Short => -quantity,
-            };
-
-            (order_id, quantity)
-        })
+        .map(
+            |(order_id, contract_symbol, quantity, direction_maker, execution_price, timestamp)| {
+                let quantity = Decimal::from_f32(quantity).expect("to fit into Decimal");
+
+                let quantity = match direction_maker {
+                    Direction::Long => quantity,
+                    Direction::Short => -quantity,
+                };
+
+                LimitOrderFilledMatch {
+                    order_id,
+                    contract_symbol: contract_symbol.into(),
+                    quantity,
+                    execution_price: Decimal::from_f32(execution_price)
+                        .expect("to fit into Decimal"),
+                    timestamp,
+                }
+            },
+        )
         .collect();
 
     Ok(filled_matches)
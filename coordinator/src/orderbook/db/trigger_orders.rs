@@ -0,0 +1,263 @@
+use crate::db::positions::ContractSymbol;
+use crate::db::positions::PositionState;
+use crate::orderbook::db::custom_types::TriggerOrderState;
+use crate::orderbook::db::custom_types::TriggerType;
+use crate::schema::positions;
+use crate::schema::trigger_orders;
+use bitcoin::secp256k1::PublicKey;
+use commons::NewTriggerOrder;
+use commons::TriggerOrder as OrderbookTriggerOrder;
+use commons::TriggerOrderState as OrderbookTriggerOrderState;
+use commons::TriggerType as OrderbookTriggerType;
+use diesel::prelude::*;
+use diesel::result::QueryResult;
+use diesel::PgConnection;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+impl From<TriggerType> for OrderbookTriggerType {
+    fn from(value: TriggerType) -> Self {
+        match value {
+            TriggerType::StopLoss => OrderbookTriggerType::StopLoss,
+            TriggerType::TakeProfit => OrderbookTriggerType::TakeProfit,
+        }
+    }
+}
+
+impl From<OrderbookTriggerType> for TriggerType {
+    fn from(value: OrderbookTriggerType) -> Self {
+        match value {
+            OrderbookTriggerType::StopLoss => TriggerType::StopLoss,
+            OrderbookTriggerType::TakeProfit => TriggerType::TakeProfit,
+        }
+    }
+}
+
+impl From<TriggerOrderState> for OrderbookTriggerOrderState {
+    fn from(value: TriggerOrderState) -> Self {
+        match value {
+            TriggerOrderState::Active => OrderbookTriggerOrderState::Active,
+            TriggerOrderState::Triggered => OrderbookTriggerOrderState::Triggered,
+            TriggerOrderState::Cancelled => OrderbookTriggerOrderState::Cancelled,
+        }
+    }
+}
+
+impl From<OrderbookTriggerOrderState> for TriggerOrderState {
+    fn from(value: OrderbookTriggerOrderState) -> Self {
+        match value {
+            OrderbookTriggerOrderState::Active => TriggerOrderState::Active,
+            OrderbookTriggerOrderState::Triggered => TriggerOrderState::Triggered,
+            OrderbookTriggerOrderState::Cancelled => TriggerOrderState::Cancelled,
+        }
+    }
+}
+
+#[derive(Queryable, Debug, Clone)]
+struct TriggerOrder {
+    // this id is only internally but needs to be here or diesel complains
+    #[allow(dead_code)]
+    pub id: i32,
+    pub trigger_order_id: Uuid,
+    pub position_id: i32,
+    pub trader_pubkey: String,
+    pub contract_symbol: ContractSymbol,
+    pub trigger_type: TriggerType,
+    pub trigger_price: f32,
+    pub trigger_order_state: TriggerOrderState,
+    #[allow(dead_code)]
+    pub creation_timestamp: OffsetDateTime,
+    pub update_timestamp: OffsetDateTime,
+}
+
+impl From<TriggerOrder> for OrderbookTriggerOrder {
+    fn from(value: TriggerOrder) -> Self {
+        OrderbookTriggerOrder {
+            id: value.trigger_order_id,
+            trader_id: value.trader_pubkey.parse().expect("to have a valid pubkey"),
+            position_id: value.position_id,
+            contract_symbol: value.contract_symbol.into(),
+            trigger_type: value.trigger_type.into(),
+            trigger_price: Decimal::from_f32(value.trigger_price)
+                .expect("To be able to convert f32 to decimal"),
+            state: value.trigger_order_state.into(),
+            timestamp: value.update_timestamp,
+        }
+    }
+}
+
+#[derive(Insertable, Debug, PartialEq)]
+#[diesel(table_name = trigger_orders)]
+struct NewTriggerOrderRow {
+    pub trigger_order_id: Uuid,
+    pub position_id: i32,
+    pub trader_pubkey: String,
+    pub contract_symbol: ContractSymbol,
+    pub trigger_type: TriggerType,
+    pub trigger_price: f32,
+    pub trigger_order_state: TriggerOrderState,
+}
+
+/// Inserts a new active trigger order attached to the position with the given id.
+pub fn insert(
+    conn: &mut PgConnection,
+    trigger_order: NewTriggerOrder,
+    position_id: i32,
+) -> QueryResult<OrderbookTriggerOrder> {
+    let trigger_order: TriggerOrder = diesel::insert_into(trigger_orders::table)
+        .values(NewTriggerOrderRow {
+            trigger_order_id: trigger_order.id,
+            position_id,
+            trader_pubkey: trigger_order.trader_id.to_string(),
+            contract_symbol: trigger_order.contract_symbol.into(),
+            trigger_type: trigger_order.trigger_type.into(),
+            trigger_price: trigger_order
+                .trigger_price
+                .to_f32()
+                .expect("To be able to convert decimal to f32"),
+            trigger_order_state: TriggerOrderState::Active,
+        })
+        .get_result(conn)?;
+
+    Ok(OrderbookTriggerOrder::from(trigger_order))
+}
+
+pub fn get_with_id(
+    conn: &mut PgConnection,
+    trigger_order_id: Uuid,
+) -> QueryResult<Option<OrderbookTriggerOrder>> {
+    let trigger_order = trigger_orders::table
+        .filter(trigger_orders::trigger_order_id.eq(trigger_order_id))
+        .first::<TriggerOrder>(conn)
+        .optional()?;
+
+    Ok(trigger_order.map(OrderbookTriggerOrder::from))
+}
+
+/// Loads all active trigger orders of the given trader.
+pub fn get_active_by_trader(
+    conn: &mut PgConnection,
+    trader_id: PublicKey,
+) -> QueryResult<Vec<OrderbookTriggerOrder>> {
+    let trigger_orders = trigger_orders::table
+        .filter(trigger_orders::trader_pubkey.eq(trader_id.to_string()))
+        .filter(trigger_orders::trigger_order_state.eq(TriggerOrderState::Active))
+        .load::<TriggerOrder>(conn)?;
+
+    Ok(trigger_orders
+        .into_iter()
+        .map(OrderbookTriggerOrder::from)
+        .collect())
+}
+
+/// Loads all active trigger orders.
+pub fn get_all_active(conn: &mut PgConnection) -> QueryResult<Vec<OrderbookTriggerOrder>> {
+    let trigger_orders = trigger_orders::table
+        .filter(trigger_orders::trigger_order_state.eq(TriggerOrderState::Active))
+        .load::<TriggerOrder>(conn)?;
+
+    Ok(trigger_orders
+        .into_iter()
+        .map(OrderbookTriggerOrder::from)
+        .collect())
+}
+
+/// Updates the trigger price of the given trigger order if it is still active.
+///
+/// Returns `None` if the trader has no active trigger order with the given id.
+pub fn set_trigger_price(
+    conn: &mut PgConnection,
+    trigger_order_id: Uuid,
+    trader_id: PublicKey,
+    trigger_price: Decimal,
+) -> QueryResult<Option<OrderbookTriggerOrder>> {
+    let trigger_order = diesel::update(trigger_orders::table)
+        .filter(trigger_orders::trigger_order_id.eq(trigger_order_id))
+        .filter(trigger_orders::trader_pubkey.eq(trader_id.to_string()))
+        .filter(trigger_orders::trigger_order_state.eq(TriggerOrderState::Active))
+        .set((
+            trigger_orders::trigger_price.eq(trigger_price
+                .to_f32()
+                .expect("To be able to convert decimal to f32")),
+            trigger_orders::update_timestamp.eq(OffsetDateTime::now_utc()),
+        ))
+        .get_result::<TriggerOrder>(conn)
+        .optional()?;
+
+    Ok(trigger_order.map(OrderbookTriggerOrder::from))
+}
+
+/// Moves the given trigger order from `Active` into the given state.
+///
+/// Returns `None` if no active trigger order with the given id exists.
+pub fn set_state(
+    conn: &mut PgConnection,
+    trigger_order_id: Uuid,
+    state: OrderbookTriggerOrderState,
+) -> QueryResult<Option<OrderbookTriggerOrder>> {
+    let trigger_order = diesel::update(trigger_orders::table)
+        .filter(trigger_orders::trigger_order_id.eq(trigger_order_id))
+        .filter(trigger_orders::trigger_order_state.eq(TriggerOrderState::Active))
+        .set((
+            trigger_orders::trigger_order_state.eq(TriggerOrderState::from(state)),
+            trigger_orders::update_timestamp.eq(OffsetDateTime::now_utc()),
+        ))
+        .get_result::<TriggerOrder>(conn)
+        .optional()?;
+
+    Ok(trigger_order.map(OrderbookTriggerOrder::from))
+}
+
+/// Cancels the given trigger order if it is still active.
+///
+/// Returns `None` if the trader has no active trigger order with the given id.
+pub fn cancel(
+    conn: &mut PgConnection,
+    trigger_order_id: Uuid,
+    trader_id: PublicKey,
+) -> QueryResult<Option<OrderbookTriggerOrder>> {
+    let trigger_order = diesel::update(trigger_orders::table)
+        .filter(trigger_orders::trigger_order_id.eq(trigger_order_id))
+        .filter(trigger_orders::trader_pubkey.eq(trader_id.to_string()))
+        .filter(trigger_orders::trigger_order_state.eq(TriggerOrderState::Active))
+        .set((
+            trigger_orders::trigger_order_state.eq(TriggerOrderState::Cancelled),
+            trigger_orders::update_timestamp.eq(OffsetDateTime::now_utc()),
+        ))
+        .get_result::<TriggerOrder>(conn)
+        .optional()?;
+
+    Ok(trigger_order.map(OrderbookTriggerOrder::from))
+}
+
+/// Cancels all active trigger orders attached to the given position.
+pub fn cancel_all_by_position(conn: &mut PgConnection, position_id: i32) -> QueryResult<usize> {
+    diesel::update(trigger_orders::table)
+        .filter(trigger_orders::position_id.eq(position_id))
+        .filter(trigger_orders::trigger_order_state.eq(TriggerOrderState::Active))
+        .set((
+            trigger_orders::trigger_order_state.eq(TriggerOrderState::Cancelled),
+            trigger_orders::update_timestamp.eq(OffsetDateTime::now_utc()),
+        ))
+        .execute(conn)
+}
+
+/// Cancels all active trigger orders attached to positions which have been closed in the meantime,
+/// e.g. manually by the trader or because they expired.
+pub fn cancel_all_of_closed_positions(conn: &mut PgConnection) -> QueryResult<usize> {
+    let closed_positions = positions::table
+        .filter(positions::position_state.eq(PositionState::Closed))
+        .select(positions::id);
+
+    diesel::update(trigger_orders::table)
+        .filter(trigger_orders::position_id.eq_any(closed_positions))
+        .filter(trigger_orders::trigger_order_state.eq(TriggerOrderState::Active))
+        .set((
+            trigger_orders::trigger_order_state.eq(TriggerOrderState::Cancelled),
+            trigger_orders::update_timestamp.eq(OffsetDateTime::now_utc()),
+        ))
+        .execute(conn)
+}
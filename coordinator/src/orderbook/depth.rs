@@ -0,0 +1,192 @@
+use crate::orderbook::order_cache::load_orders;
+use crate::orderbook::order_cache::update_orders;
+use commons::Depth;
+use commons::DepthSnapshot;
+use commons::DepthUpdate;
+use commons::Message;
+use commons::Order;
+use diesel::r2d2::ConnectionManager;
+use diesel::r2d2::Pool;
+use diesel::PgConnection;
+use futures::future::RemoteHandle;
+use futures::FutureExt;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use trade::ContractSymbol;
+
+/// How often the depth is recomputed even if no order changed, so that expired orders are removed
+/// from the depth.
+const EXPIRED_ORDERS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The aggregated depth of the orderbook of every contract.
+///
+/// Clones share the same depth, so that the websocket can hand out snapshots of the depth kept up
+/// to date by the [`monitor`].
+#[derive(Clone, Default)]
+pub struct OrderbookDepth {
+    snapshots: Arc<RwLock<HashMap<ContractSymbol, DepthSnapshot>>>,
+}
+
+impl OrderbookDepth {
+    pub fn snapshot(&self, contract_symbol: ContractSymbol) -> DepthSnapshot {
+        self.snapshots
+            .read()
+            .get(&contract_symbol)
+            .cloned()
+            .unwrap_or(DepthSnapshot {
+                contract_symbol,
+                sequence: 0,
+                depth: Depth::default(),
+            })
+    }
+
+    /// Recomputes the depth of every contract from the given orders.
+    ///
+    /// Returns an update for every contract whose depth changed.
+    fn update(&self, orders: &[Order]) -> Vec<DepthUpdate> {
+        let mut snapshots = self.snapshots.write();
+
+        let mut updates = vec![];
+        for contract_symbol in ContractSymbol::ALL {
+            let depth = Depth::from_orders(orders, contract_symbol);
+
+            let snapshot = snapshots
+                .entry(contract_symbol)
+                .or_insert_with(|| DepthSnapshot {
+                    contract_symbol,
+                    sequence: 0,
+                    depth: Depth::default(),
+                });
+
+            let changes = snapshot.depth.changes(&depth);
+            if changes.is_empty() {
+                continue;
+            }
+
+            snapshot.sequence += 1;
+            snapshot.depth = depth;
+
+            updates.push(DepthUpdate {
+                contract_symbol,
+                sequence: snapshot.sequence,
+                changes,
+            });
+        }
+
+        updates
+    }
+}
+
+/// Keeps the depth up to date with the orders on the price feed and publishes every change as a
+/// [`DepthUpdate`].
+pub fn monitor(
+    pool: Pool<ConnectionManager<PgConnection>>,
+    tx_price_feed: broadcast::Sender<Message>,
+    depth: OrderbookDepth,
+) -> RemoteHandle<()> {
+    let mut price_feed = tx_price_feed.subscribe();
+    let (fut, remote_handle) = async move {
+        let mut orders = load_orders(pool.clone()).await;
+        let mut interval = tokio::time::interval(EXPIRED_ORDERS_INTERVAL);
+
+        loop {
+            tokio::select! {
+                message = price_feed.recv() => match message {
+                    Ok(message) => {
+                        if !update_orders(&mut orders, message) {
+                            continue;
+                        }
+                    }
+                    Err(RecvError::Closed) => {
+                        tracing::error!("Price feed sender died! Channel closed.");
+                        break;
+                    }
+                    Err(RecvError::Lagged(skip)) => {
+                        tracing::warn!(%skip, "Lagging behind on price feed. Reloading orders.");
+                        orders = load_orders(pool.clone()).await;
+                    }
+                },
+                _ = interval.tick() => {}
+            }
+
+            for update in depth.update(&orders) {
+                // An error only means that no client is connected at the moment.
+                let _ = tx_price_feed.send(Message::DepthUpdate(update));
+            }
+        }
+    }
+    .remote_handle();
+
+    tokio::spawn(fut);
+
+    remote_handle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::secp256k1::PublicKey;
+    use commons::OrderReason;
+    use commons::OrderState;
+    use commons::OrderType;
+    use commons::PriceLevel;
+    use commons::TimeInForce;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use std::str::FromStr;
+    use time::OffsetDateTime;
+    use trade::Direction;
+    use uuid::Uuid;
+
+    fn order(price: Decimal, quantity: Decimal) -> Order {
+        Order {
+            id: Uuid::new_v4(),
+            price,
+            leverage: 2.0,
+            contract_symbol: ContractSymbol::BtcUsd,
+            trader_id: PublicKey::from_str(
+                "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
+            )
+            .unwrap(),
+            direction: Direction::Short,
+            quantity,
+            order_type: OrderType::Limit,
+            timestamp: OffsetDateTime::now_utc(),
+            expiry: OffsetDateTime::now_utc() + time::Duration::minutes(1),
+            order_state: OrderState::Open,
+            order_reason: OrderReason::Manual,
+            stable: false,
+            time_in_force: TimeInForce::GoodTillTime,
+            post_only: false,
+        }
+    }
+
+    #[test]
+    fn only_changes_increment_the_sequence() {
+        let depth = OrderbookDepth::default();
+        let orders = vec![order(dec!(40_000), dec!(100))];
+
+        let updates = depth.update(&orders);
+        assert_eq!(
+            updates,
+            vec![DepthUpdate {
+                contract_symbol: ContractSymbol::BtcUsd,
+                sequence: 1,
+                changes: Depth {
+                    bids: vec![],
+                    asks: vec![PriceLevel {
+                        price: dec!(40_000),
+                        quantity: dec!(100),
+                    }],
+                },
+            }]
+        );
+
+        assert!(depth.update(&orders).is_empty());
+        assert_eq!(depth.snapshot(ContractSymbol::BtcUsd).sequence, 1);
+    }
+}
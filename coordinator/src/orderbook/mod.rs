@@ -1,6 +1,8 @@
 pub mod async_match;
 pub mod collaborative_revert;
 pub mod db;
+pub mod depth;
+pub mod order_cache;
 pub mod routes;
 pub mod trading;
 pub mod websocket;
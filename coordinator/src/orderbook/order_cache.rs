@@ -0,0 +1,44 @@
+use crate::orderbook::db::orders;
+use commons::Message;
+use commons::Order;
+use diesel::r2d2::ConnectionManager;
+use diesel::r2d2::Pool;
+use diesel::PgConnection;
+use tokio::task::spawn_blocking;
+
+/// Applies the given price feed message to the in-memory copy of the limit orders in the
+/// orderbook. Returns true if the orders changed.
+///
+/// Monitors which have to react to changes of the orderbook keep such a copy, to avoid hitting the
+/// database on every message of the price feed.
+pub fn update_orders(orders: &mut Vec<Order>, message: Message) -> bool {
+    match message {
+        Message::AllOrders(all_orders) => *orders = all_orders,
+        Message::NewOrder(order) => orders.push(order),
+        Message::Update(order) => match orders.iter_mut().find(|o| o.id == order.id) {
+            Some(existing) => *existing = order,
+            None => orders.push(order),
+        },
+        Message::DeleteOrder(order_id) => orders.retain(|o| o.id != order_id),
+        _ => return false,
+    }
+
+    true
+}
+
+/// Loads the limit orders in the orderbook from the database, e.g. to initialise or to resync the
+/// in-memory copy of the orderbook.
+pub async fn load_orders(pool: Pool<ConnectionManager<PgConnection>>) -> Vec<Order> {
+    let orders = spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        let orders = orders::all_limit_orders(&mut conn)?;
+        anyhow::Ok(orders)
+    })
+    .await
+    .expect("task to complete");
+
+    orders.unwrap_or_else(|e| {
+        tracing::error!("Failed to load limit orders. Error: {e:#}");
+        vec![]
+    })
+}
@@ -1,7 +1,10 @@
+use crate::db;
 use crate::orderbook;
+use crate::orderbook::trading::amended_order_crosses_book;
 use crate::orderbook::trading::NewOrderMessage;
 use crate::orderbook::trading::TradingError;
 use crate::orderbook::websocket::websocket_connection;
+use crate::position::models::PositionState;
 use crate::routes::AppState;
 use crate::AppError;
 use anyhow::Context;
@@ -11,18 +14,33 @@ use axum::extract::Path;
 use axum::extract::State;
 use axum::response::IntoResponse;
 use axum::Json;
+use bitcoin::secp256k1::PublicKey;
+use commons::AmendOrder;
+use commons::AmendTriggerOrder;
+use commons::CancelAllOrders;
+use commons::CancelOrder;
+use commons::CancelTriggerOrder;
+use commons::GetTriggerOrders;
 use commons::Message;
 use commons::NewOrder;
+use commons::NewTriggerOrder;
 use commons::Order;
 use commons::OrderReason;
 use commons::OrderState;
 use commons::OrderType;
+use commons::SignedRequest;
+use commons::SignedRequestType;
+use commons::TriggerOrder;
+use commons::VerifiedRequest;
 use diesel::r2d2::ConnectionManager;
 use diesel::r2d2::PooledConnection;
 use diesel::PgConnection;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde::Serialize;
 use std::sync::Arc;
+use time::OffsetDateTime;
 use tokio::sync::broadcast::Sender;
 use tokio::sync::mpsc;
 use tracing::instrument;
@@ -63,11 +81,41 @@ pub async fn get_orders(State(state): State<Arc<AppState>>) -> Result<Json<Vec<O
     Ok(Json(orders))
 }
 
+/// Verifies the signature of the request and that it has not been used before.
+///
+/// Returns the trader who signed the request and the request itself.
+fn verify_signed_request<T: Serialize + DeserializeOwned + SignedRequestType>(
+    state: &AppState,
+    signed_request: &SignedRequest<T>,
+) -> Result<(PublicKey, T), AppError> {
+    let VerifiedRequest {
+        trader_id, request, ..
+    } = state
+        .nonces
+        .verify(signed_request, OffsetDateTime::now_utc())
+        .map_err(|e| {
+            tracing::warn!("Rejecting signed request: {e:#}");
+            AppError::Unauthorized
+        })?;
+
+    Ok((trader_id, request))
+}
+
 #[instrument(skip_all, err(Debug))]
 pub async fn post_order(
     State(state): State<Arc<AppState>>,
-    Json(new_order): Json<NewOrder>,
+    Json(signed_new_order): Json<SignedRequest<NewOrder>>,
 ) -> Result<Json<Order>, AppError> {
+    let (trader_id, new_order) = verify_signed_request(&state, &signed_new_order)?;
+    if trader_id != new_order.trader_id {
+        tracing::warn!(
+            %trader_id,
+            order_trader_id = %new_order.trader_id,
+            "Rejecting order signed for another trader"
+        );
+        return Err(AppError::Unauthorized);
+    }
+
     let (sender, mut receiver) = mpsc::channel::<Result<Order>>(1);
 
     let message = NewOrderMessage {
@@ -88,6 +136,7 @@ pub async fn post_order(
     let order = result.map_err(|e| match e.downcast_ref() {
         Some(TradingError::InvalidOrder(reason)) => AppError::InvalidOrder(reason.to_string()),
         Some(TradingError::NoMatchFound(message)) => AppError::NoMatchFound(message.to_string()),
+        Some(TradingError::OrderInExecution(reason)) => AppError::InvalidOrder(reason.to_string()),
         _ => AppError::InternalServerError(format!("Failed to post order. Error: {e:#}")),
     })?;
 
@@ -110,6 +159,9 @@ pub struct UpdateOrder {
     pub taken: bool,
 }
 
+/// Sets whether an order has been taken. Taken orders are not matched anymore.
+///
+/// The request is not signed by the owner of the order, hence this is an admin route.
 #[instrument(skip_all, err(Debug))]
 pub async fn put_order(
     Path(order_id): Path<Uuid>,
@@ -125,6 +177,288 @@ pub async fn put_order(
     Ok(Json(order))
 }
 
+/// Cancels an open limit order of the signing trader.
+#[instrument(skip_all, err(Debug))]
+pub async fn delete_order(
+    Path(order_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(signed_cancel_order): Json<SignedRequest<CancelOrder>>,
+) -> Result<Json<Order>, AppError> {
+    let (trader_id, cancel_order) = verify_signed_request(&state, &signed_cancel_order)?;
+    if cancel_order.order_id != order_id {
+        return Err(AppError::BadRequest(format!(
+            "Cancel request is for order {}, not {order_id}",
+            cancel_order.order_id
+        )));
+    }
+
+    let mut conn = get_db_connection(&state)?;
+    let order = orderbook::db::orders::delete(&mut conn, order_id, trader_id)
+        .map_err(|e| AppError::InternalServerError(format!("Failed to cancel order: {e:#}")))?
+        .ok_or_else(|| AppError::BadRequest(format!("No open limit order found {order_id}")))?;
+
+    tracing::info!(%trader_id, %order_id, "Cancelled limit order");
+
+    update_pricefeed(Message::DeleteOrder(order.id), state.tx_price_feed.clone());
+
+    Ok(Json(order))
+}
+
+/// Cancels all open limit orders of the signing trader.
+#[instrument(skip_all, err(Debug))]
+pub async fn delete_orders(
+    State(state): State<Arc<AppState>>,
+    Json(signed_cancel_all_orders): Json<SignedRequest<CancelAllOrders>>,
+) -> Result<Json<Vec<Order>>, AppError> {
+    let (trader_id, cancel_all_orders) = verify_signed_request(&state, &signed_cancel_all_orders)?;
+    if trader_id != cancel_all_orders.trader_id {
+        return Err(AppError::Unauthorized);
+    }
+
+    let mut conn = get_db_connection(&state)?;
+    let orders = orderbook::db::orders::delete_all_by_trader(&mut conn, trader_id)
+        .map_err(|e| AppError::InternalServerError(format!("Failed to cancel orders: {e:#}")))?;
+
+    tracing::info!(%trader_id, "Cancelled {} limit orders", orders.len());
+
+    for order in orders.iter() {
+        update_pricefeed(Message::DeleteOrder(order.id), state.tx_price_feed.clone());
+    }
+
+    Ok(Json(orders))
+}
+
+/// Replaces the price and the quantity of an open limit order of the signing trader.
+///
+/// The amended order is not matched, hence it must not cross the orders of other traders. Such an
+/// order has to be cancelled and posted again instead. The order only keeps its time priority if it
+/// is reduced in size.
+#[instrument(skip_all, err(Debug))]
+pub async fn patch_order(
+    Path(order_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(signed_amend_order): Json<SignedRequest<AmendOrder>>,
+) -> Result<Json<Order>, AppError> {
+    let (trader_id, amend_order) = verify_signed_request(&state, &signed_amend_order)?;
+    if amend_order.order_id != order_id {
+        return Err(AppError::BadRequest(format!(
+            "Amend request is for order {}, not {order_id}",
+            amend_order.order_id
+        )));
+    }
+
+    if amend_order.price <= Decimal::ZERO || amend_order.quantity <= Decimal::ZERO {
+        return Err(AppError::InvalidOrder(
+            "Price and quantity must be positive".to_string(),
+        ));
+    }
+
+    let mut conn = get_db_connection(&state)?;
+    let order = orderbook::db::orders::get_with_id(&mut conn, order_id)
+        .map_err(|e| AppError::InternalServerError(format!("Failed to load order: {e:#}")))?
+        .ok_or_else(|| AppError::BadRequest(format!("No open limit order found {order_id}")))?;
+
+    let opposite_orders = orderbook::db::orders::all_by_direction_and_type(
+        &mut conn,
+        order.direction.opposite(),
+        OrderType::Limit,
+        true,
+    )
+    .map_err(|e| AppError::InternalServerError(format!("Failed to load orders: {e:#}")))?;
+
+    if amended_order_crosses_book(&order, amend_order.price, &opposite_orders) {
+        return Err(AppError::InvalidOrder(format!(
+            "Amended price {} would cross the book",
+            amend_order.price
+        )));
+    }
+
+    let order = orderbook::db::orders::amend(
+        &mut conn,
+        order_id,
+        trader_id,
+        amend_order.price,
+        amend_order.quantity,
+    )
+    .map_err(|e| AppError::InternalServerError(format!("Failed to amend order: {e:#}")))?
+    .ok_or_else(|| AppError::BadRequest(format!("No open limit order found {order_id}")))?;
+
+    tracing::info!(
+        %trader_id,
+        %order_id,
+        price = %order.price,
+        quantity = %order.quantity,
+        "Amended limit order"
+    );
+
+    update_pricefeed(Message::Update(order.clone()), state.tx_price_feed.clone());
+
+    Ok(Json(order))
+}
+
+/// Loads the active trigger orders of the signing trader.
+///
+/// This is a `POST` request, as the signed request is sent in the body, which is not reliably
+/// forwarded for `GET` requests.
+#[instrument(skip_all, err(Debug))]
+pub async fn get_trigger_orders(
+    State(state): State<Arc<AppState>>,
+    Json(signed_get_trigger_orders): Json<SignedRequest<GetTriggerOrders>>,
+) -> Result<Json<Vec<TriggerOrder>>, AppError> {
+    let (trader_id, get_trigger_orders) =
+        verify_signed_request(&state, &signed_get_trigger_orders)?;
+    if trader_id != get_trigger_orders.trader_id {
+        return Err(AppError::Unauthorized);
+    }
+
+    let mut conn = get_db_connection(&state)?;
+    let trigger_orders = orderbook::db::trigger_orders::get_active_by_trader(&mut conn, trader_id)
+        .map_err(|e| {
+            AppError::InternalServerError(format!("Failed to load trigger orders: {e:#}"))
+        })?;
+
+    Ok(Json(trigger_orders))
+}
+
+/// Attaches a stop-loss or take-profit order to the open position of the signing trader.
+///
+/// A position can have at most one active trigger order of each type.
+#[instrument(skip_all, err(Debug))]
+pub async fn post_trigger_order(
+    State(state): State<Arc<AppState>>,
+    Json(signed_new_trigger_order): Json<SignedRequest<NewTriggerOrder>>,
+) -> Result<Json<TriggerOrder>, AppError> {
+    let (trader_id, new_trigger_order) = verify_signed_request(&state, &signed_new_trigger_order)?;
+    if trader_id != new_trigger_order.trader_id {
+        tracing::warn!(
+            %trader_id,
+            order_trader_id = %new_trigger_order.trader_id,
+            "Rejecting trigger order signed for another trader"
+        );
+        return Err(AppError::Unauthorized);
+    }
+
+    if new_trigger_order.trigger_price <= Decimal::ZERO {
+        return Err(AppError::InvalidOrder(
+            "Trigger price must be positive".to_string(),
+        ));
+    }
+
+    let mut conn = get_db_connection(&state)?;
+
+    let position = db::positions::Position::get_position_by_trader(
+        &mut conn,
+        trader_id,
+        vec![PositionState::Open],
+    )
+    .map_err(|e| AppError::InternalServerError(format!("Failed to load position: {e:#}")))?
+    .ok_or_else(|| AppError::InvalidOrder("Trader has no open position".to_string()))?;
+
+    if position.contract_symbol != new_trigger_order.contract_symbol {
+        return Err(AppError::InvalidOrder(format!(
+            "Trader has no open {} position",
+            new_trigger_order.contract_symbol
+        )));
+    }
+
+    let trigger_orders = orderbook::db::trigger_orders::get_active_by_trader(&mut conn, trader_id)
+        .map_err(|e| {
+            AppError::InternalServerError(format!("Failed to load trigger orders: {e:#}"))
+        })?;
+
+    if trigger_orders.iter().any(|trigger_order| {
+        trigger_order.position_id == position.id
+            && trigger_order.trigger_type == new_trigger_order.trigger_type
+    }) {
+        return Err(AppError::InvalidOrder(format!(
+            "Position already has an active {:?} order",
+            new_trigger_order.trigger_type
+        )));
+    }
+
+    let trigger_order =
+        orderbook::db::trigger_orders::insert(&mut conn, new_trigger_order, position.id).map_err(
+            |e| AppError::InternalServerError(format!("Failed to insert trigger order: {e:#}")),
+        )?;
+
+    Ok(Json(trigger_order))
+}
+
+/// Replaces the trigger price of an active trigger order of the signing trader.
+#[instrument(skip_all, err(Debug))]
+pub async fn put_trigger_order(
+    Path(trigger_order_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(signed_amend_trigger_order): Json<SignedRequest<AmendTriggerOrder>>,
+) -> Result<Json<TriggerOrder>, AppError> {
+    let (trader_id, amend_trigger_order) =
+        verify_signed_request(&state, &signed_amend_trigger_order)?;
+    if amend_trigger_order.trigger_order_id != trigger_order_id {
+        return Err(AppError::BadRequest(format!(
+            "Amend request is for trigger order {}, not {trigger_order_id}",
+            amend_trigger_order.trigger_order_id
+        )));
+    }
+
+    if amend_trigger_order.trigger_price <= Decimal::ZERO {
+        return Err(AppError::InvalidOrder(
+            "Trigger price must be positive".to_string(),
+        ));
+    }
+
+    let mut conn = get_db_connection(&state)?;
+    let trigger_order = orderbook::db::trigger_orders::set_trigger_price(
+        &mut conn,
+        trigger_order_id,
+        trader_id,
+        amend_trigger_order.trigger_price,
+    )
+    .map_err(|e| AppError::InternalServerError(format!("Failed to amend trigger order: {e:#}")))?
+    .ok_or_else(|| {
+        AppError::BadRequest(format!("No active trigger order found {trigger_order_id}"))
+    })?;
+
+    tracing::info!(
+        %trader_id,
+        %trigger_order_id,
+        trigger_price = %trigger_order.trigger_price,
+        "Amended trigger order"
+    );
+
+    Ok(Json(trigger_order))
+}
+
+/// Cancels an active trigger order of the signing trader.
+#[instrument(skip_all, err(Debug))]
+pub async fn delete_trigger_order(
+    Path(trigger_order_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(signed_cancel_trigger_order): Json<SignedRequest<CancelTriggerOrder>>,
+) -> Result<Json<TriggerOrder>, AppError> {
+    let (trader_id, cancel_trigger_order) =
+        verify_signed_request(&state, &signed_cancel_trigger_order)?;
+    if cancel_trigger_order.trigger_order_id != trigger_order_id {
+        return Err(AppError::BadRequest(format!(
+            "Cancel request is for trigger order {}, not {trigger_order_id}",
+            cancel_trigger_order.trigger_order_id
+        )));
+    }
+
+    let mut conn = get_db_connection(&state)?;
+    let trigger_order =
+        orderbook::db::trigger_orders::cancel(&mut conn, trigger_order_id, trader_id)
+            .map_err(|e| {
+                AppError::InternalServerError(format!("Failed to cancel trigger order: {e:#}"))
+            })?
+            .ok_or_else(|| {
+                AppError::BadRequest(format!("No active trigger order found {trigger_order_id}"))
+            })?;
+
+    tracing::info!(%trader_id, %trigger_order_id, "Cancelled trigger order");
+
+    Ok(Json(trigger_order))
+}
+
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
@@ -7,6 +7,7 @@ use commons::NewOrder;
 use commons::OrderReason;
 use commons::OrderState;
 use commons::OrderType;
+use commons::TimeInForce;
 use rust_decimal_macros::dec;
 use std::str::FromStr;
 use testcontainers::clients::Cli;
@@ -85,6 +86,117 @@ async fn test_all_limit_orders() {
     assert_eq!(orders.len(), 1);
 }
 
+#[tokio::test]
+async fn test_good_till_cancelled_limit_orders_do_not_expire() {
+    init_tracing_for_test();
+
+    let docker = Cli::default();
+    let (_container, conn_spec) = start_postgres(&docker).unwrap();
+
+    let mut conn = setup_db(conn_spec);
+
+    let expired = OffsetDateTime::now_utc() - Duration::minutes(1);
+
+    let good_till_time_order = orders::insert(
+        &mut conn,
+        dummy_order(expired, OrderType::Limit),
+        OrderReason::Manual,
+    )
+    .unwrap();
+
+    let good_till_cancelled_order = orders::insert(
+        &mut conn,
+        NewOrder {
+            time_in_force: TimeInForce::GoodTillCancelled,
+            ..dummy_order(expired, OrderType::Limit)
+        },
+        OrderReason::Manual,
+    )
+    .unwrap();
+
+    let expired_orders = orders::set_expired_limit_orders_to_failed(&mut conn).unwrap();
+    assert_eq!(expired_orders.len(), 1);
+    assert_eq!(expired_orders[0].id, good_till_time_order.id);
+
+    let orders = orders::all_limit_orders(&mut conn).unwrap();
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].id, good_till_cancelled_order.id);
+    assert_eq!(orders[0].time_in_force, TimeInForce::GoodTillCancelled);
+}
+
+#[tokio::test]
+async fn reducing_the_size_of_an_order_keeps_its_time_priority() {
+    init_tracing_for_test();
+
+    let docker = Cli::default();
+    let (_container, conn_spec) = start_postgres(&docker).unwrap();
+
+    let mut conn = setup_db(conn_spec);
+
+    let order = orders::insert(
+        &mut conn,
+        dummy_order(
+            OffsetDateTime::now_utc() + Duration::minutes(1),
+            OrderType::Limit,
+        ),
+        OrderReason::Manual,
+    )
+    .unwrap();
+
+    let amended = orders::amend(&mut conn, order.id, order.trader_id, order.price, dec!(50))
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(amended.quantity, dec!(50));
+    assert_eq!(amended.timestamp, order.timestamp);
+}
+
+#[tokio::test]
+async fn changing_the_price_or_increasing_the_size_of_an_order_resets_its_time_priority() {
+    init_tracing_for_test();
+
+    let docker = Cli::default();
+    let (_container, conn_spec) = start_postgres(&docker).unwrap();
+
+    let mut conn = setup_db(conn_spec);
+
+    let order = orders::insert(
+        &mut conn,
+        dummy_order(
+            OffsetDateTime::now_utc() + Duration::minutes(1),
+            OrderType::Limit,
+        ),
+        OrderReason::Manual,
+    )
+    .unwrap();
+
+    let repriced = orders::amend(
+        &mut conn,
+        order.id,
+        order.trader_id,
+        dec!(20001),
+        order.quantity,
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(repriced.price, dec!(20001));
+    assert!(repriced.timestamp > order.timestamp);
+
+    let increased = orders::amend(
+        &mut conn,
+        order.id,
+        order.trader_id,
+        repriced.price,
+        dec!(200),
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(increased.quantity, dec!(200));
+    assert!(increased.timestamp > repriced.timestamp);
+}
+
 fn dummy_order(expiry: OffsetDateTime, order_type: OrderType) -> NewOrder {
     NewOrder {
         id: Uuid::new_v4(),
@@ -100,5 +212,10 @@ fn dummy_order(expiry: OffsetDateTime, order_type: OrderType) -> NewOrder {
         contract_symbol: trade::ContractSymbol::BtcUsd,
         leverage: 1.0,
         stable: false,
+        time_in_force: match order_type {
+            OrderType::Market => TimeInForce::FillOrKill,
+            OrderType::Limit => TimeInForce::GoodTillTime,
+        },
+        post_only: false,
     }
 }
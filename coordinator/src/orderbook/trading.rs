@@ -1,3 +1,4 @@
+use crate::db;
 use crate::message::OrderbookMessage;
 use crate::notifications::NotificationKind;
 use crate::orderbook::db::matches;
@@ -8,23 +9,26 @@ use anyhow::Context;
 use anyhow::Result;
 use bitcoin::secp256k1::PublicKey;
 use bitcoin::Network;
-use bitcoin::XOnlyPublicKey;
 use commons::FilledWith;
 use commons::Match;
 use commons::Message;
 use commons::NewOrder;
+use commons::OracleParams;
 use commons::Order;
 use commons::OrderReason;
 use commons::OrderState;
 use commons::OrderType;
+use commons::TimeInForce;
 use commons::TradeParams;
 use diesel::r2d2::ConnectionManager;
 use diesel::r2d2::Pool;
+use diesel::Connection;
 use diesel::PgConnection;
 use futures::future::RemoteHandle;
 use futures::FutureExt;
 use rust_decimal::Decimal;
 use std::cmp::Ordering;
+use std::collections::HashSet;
 use thiserror::Error;
 use time::OffsetDateTime;
 use tokio::sync::broadcast;
@@ -49,12 +53,16 @@ pub enum TradingError {
     InvalidOrder(String),
     #[error("{0}")]
     NoMatchFound(String),
+    /// The trader still has a matched order waiting for execution.
+    #[error("{0}")]
+    OrderInExecution(String),
 }
 
 #[derive(Clone)]
 pub struct MatchParams {
     pub taker_match: TraderMatchParams,
     pub makers_matches: Vec<TraderMatchParams>,
+    pub partial_fills: Vec<PartialFill>,
 }
 
 #[derive(Clone)]
@@ -63,6 +71,17 @@ pub struct TraderMatchParams {
     pub filled_with: FilledWith,
 }
 
+/// A resting limit order which has only been partially consumed by a match.
+///
+/// The filled `quantity` is split off into a new order identified by `fill_order_id`, while the
+/// order identified by `order_id` remains open with the remaining quantity.
+#[derive(Clone)]
+pub struct PartialFill {
+    pub order_id: Uuid,
+    pub fill_order_id: Uuid,
+    pub quantity: Decimal,
+}
+
 /// Spawn a task that processes [`NewOrderMessage`]s.
 ///
 /// To feed messages to this task, the caller can use the corresponding
@@ -72,7 +91,7 @@ pub fn start(
     tx_price_feed: broadcast::Sender<Message>,
     notifier: mpsc::Sender<OrderbookMessage>,
     network: Network,
-    oracle_pk: XOnlyPublicKey,
+    oracle_params: OracleParams,
 ) -> (RemoteHandle<()>, mpsc::Sender<NewOrderMessage>) {
     let (sender, mut receiver) = mpsc::channel::<NewOrderMessage>(NEW_ORDERS_BUFFER_SIZE);
 
@@ -82,6 +101,7 @@ pub fn start(
                 let tx_price_feed = tx_price_feed.clone();
                 let notifier = notifier.clone();
                 let pool = pool.clone();
+                let oracle_params = oracle_params.clone();
                 async move {
                     let result = process_new_order(
                         pool,
@@ -90,7 +110,7 @@ pub fn start(
                         new_order_msg.new_order,
                         new_order_msg.order_reason,
                         network,
-                        oracle_pk,
+                        oracle_params,
                     )
                     .await;
 
@@ -112,7 +132,10 @@ pub fn start(
 
 /// Process a [`NewOrder`].
 ///
-/// If the [`NewOrder`] is of [`OrderType::Limit`]: update the price feed.
+/// If the [`NewOrder`] is of [`OrderType::Limit`]: find a match if the order crosses the book and
+/// notify traders. Add the order, or the quantity which could not be filled, to the book and update
+/// the price feed, unless its [`TimeInForce`] does not allow it to rest in the book. A post-only
+/// limit order is rejected if it crosses the book.
 ///
 /// If the [`NewOrder`] is of [`OrderType::Market`]: find match and notify traders.
 ///
@@ -125,7 +148,7 @@ pub async fn process_new_order(
     new_order: NewOrder,
     order_reason: OrderReason,
     network: Network,
-    oracle_pk: XOnlyPublicKey,
+    oracle_params: OracleParams,
 ) -> Result<Order> {
     tracing::info!(
         trader_id = %new_order.trader_id,
@@ -143,11 +166,10 @@ pub async fn process_new_order(
         ))?;
     }
 
+    validate_time_in_force(&new_order)?;
+
     // Before processing any match we set all expired limit orders to failed, to ensure they do not
     // get matched.
-    //
-    // TODO(holzeis): Orders should probably not have an expiry, but should either be replaced or
-    // deleted if not wanted anymore.
     let expired_limit_orders = orders::set_expired_limit_orders_to_failed(&mut conn)?;
     for expired_limit_order in expired_limit_orders {
         tx_price_feed
@@ -160,215 +182,501 @@ pub async fn process_new_order(
         .map_err(|e| anyhow!(e))
         .context("Failed to insert new order into DB")?;
 
-    if new_order.order_type == OrderType::Limit {
+    // A trader can only execute one trade at a time, so traders with a matched order waiting for
+    // execution are not matched again until that execution is finished.
+    let traders_in_execution = orders::get_trader_ids_by_state(&mut conn, OrderState::Matched)?;
+    let taker_in_execution = traders_in_execution.contains(&order.trader_id);
+
+    if order.order_type == OrderType::Market && taker_in_execution {
+        // Reject new order if there is already a matched order waiting for execution.
+        orders::set_order_state(&mut conn, order.id, OrderState::Failed)?;
+        bail!(order_in_execution(&order));
+    }
+
+    let opposite_direction_limit_orders = orders::all_by_direction_and_type(
+        &mut conn,
+        order.direction.opposite(),
+        OrderType::Limit,
+        true,
+    )?;
+
+    if order.post_only
+        && opposite_direction_limit_orders
+            .iter()
+            .any(|maker_order| crosses(&order, maker_order))
+    {
+        orders::set_order_state(&mut conn, order.id, OrderState::Failed)?;
+        bail!(TradingError::InvalidOrder(format!(
+            "Post-only order {} would cross the book",
+            order.id
+        )));
+    }
+
+    let matched_orders = match match_order(
+        &order,
+        opposite_direction_limit_orders,
+        network,
+        &oracle_params,
+        &traders_in_execution,
+    ) {
+        Ok(Some(_)) if taker_in_execution => {
+            // A limit order which does not cross the book can still rest in it, but one which would
+            // be matched has to wait until the pending execution of the trader is finished.
+            orders::set_order_state(&mut conn, order.id, OrderState::Failed)?;
+            bail!(order_in_execution(&order));
+        }
+        Ok(Some(matched_orders)) => matched_orders,
+        Ok(None) if order.time_in_force.rests_in_book() => {
+            // The limit order does not cross the book, so it rests in the orderbook until it
+            // is matched by another order, cancelled or expires.
+            tx_price_feed
+                .send(Message::NewOrder(order.clone()))
+                .map_err(|e| anyhow!(e))
+                .context("Could not update price feed")?;
+
+            return Ok(order);
+        }
+        Ok(None) => {
+            // TODO(holzeis): Currently we still respond to the user immediately if there
+            // has been a match or not, that's the reason why we also have to set the order
+            // to failed here. But actually we could keep the order until either expired or
+            // a match has been found and then update the state accordingly.
+
+            orders::set_order_state(&mut conn, order.id, OrderState::Failed)?;
+            bail!(TradingError::NoMatchFound(format!(
+                "Could not match order {}",
+                order.id
+            )));
+        }
+        Err(e) => {
+            orders::set_order_state(&mut conn, order.id, OrderState::Failed)?;
+            bail!("Failed to match order: {e:#}")
+        }
+    };
+
+    tracing::info!(
+        trader_id=%order.trader_id,
+        order_id=%order.id,
+        "Found a match with {} makers for new order",
+        matched_orders.taker_match.filled_with.matches.len()
+    );
+
+    // All changes to the orderbook caused by the match are applied atomically, so that the book
+    // never holds the liquidity of a matched order twice.
+    let persisted_match =
+        match conn.transaction(|conn| persist_match(conn, &order, &matched_orders)) {
+            Ok(persisted_match) => persisted_match,
+            Err(e) => {
+                orders::set_order_state(&mut conn, order.id, OrderState::Failed)?;
+                return Err(e.context("Failed to persist match"));
+            }
+        };
+
+    for remaining_order in persisted_match.remaining_orders {
+        // The remainder of a partially filled taker order is new to the book.
+        let message = if remaining_order.id == order.id {
+            Message::NewOrder(remaining_order)
+        } else {
+            Message::Update(remaining_order)
+        };
+
         tx_price_feed
-            .send(Message::NewOrder(order.clone()))
+            .send(message)
             .map_err(|e| anyhow!(e))
             .context("Could not update price feed")?;
-    } else {
-        // Reject new order if there is already a matched order waiting for execution.
-        if let Some(order) =
-            orders::get_by_trader_id_and_state(&mut conn, new_order.trader_id, OrderState::Matched)?
+    }
+
+    let taker_order_id = matched_orders.taker_match.filled_with.order_id;
+    for (match_param, matched_order) in matched_orders
+        .matches()
+        .into_iter()
+        .zip(persisted_match.matched_orders)
+    {
+        let trader_id = match_param.trader_id;
+        let order_id = match_param.filled_with.order_id;
+
+        let is_taker = order_id == taker_order_id;
+
+        tracing::info!(%trader_id, %order_id, "Notifying trader about match");
+
+        let message = match (&matched_order.order_reason, is_taker) {
+            (OrderReason::Manual, true) => Message::Match(match_param.filled_with.clone()),
+            // The owner of a resting limit order is not necessarily online when their order gets
+            // matched. Hence, we ask them to execute the trade asynchronously, which also works if
+            // the message is only delivered once they reconnect.
+            (OrderReason::Manual, false)
+            | (OrderReason::Expired, _)
+            | (OrderReason::Liquidated, _)
+            | (OrderReason::Triggered, _) => Message::AsyncMatch {
+                order: matched_order.clone(),
+                filled_with: match_param.filled_with.clone(),
+            },
+        };
+
+        let notification = match (&matched_order.order_reason, is_taker) {
+            (OrderReason::Expired, _) => Some(NotificationKind::PositionExpired),
+            (OrderReason::Liquidated, _) => Some(NotificationKind::PositionLiquidated),
+            (OrderReason::Triggered, _) => Some(NotificationKind::TriggerOrderExecuted),
+            (OrderReason::Manual, false) => Some(NotificationKind::LimitOrderFilled),
+            (OrderReason::Manual, true) => None,
+        };
+
+        let msg = OrderbookMessage::TraderMessage {
+            trader_id,
+            message,
+            notification,
+        };
+
+        let matched_order = match notifier.send(msg).await {
+            Ok(()) => {
+                tracing::debug!(%trader_id, %order_id, "Successfully notified trader");
+                matched_order
+            }
+            Err(e) => {
+                tracing::warn!(%trader_id, %order_id, "Failed to send trader message: {e:#}");
+
+                if matched_order.order_type == OrderType::Limit {
+                    // FIXME: The maker is currently not connected to the WebSocket so we can't
+                    // notify him about a trade. However, trades are always accepted by the
+                    // maker at the moment so in order to not have all limit orders in order
+                    // state `Match` we are setting the order to `Taken` even if we couldn't
+                    // notify the maker.
+
+                    tracing::debug!(%trader_id, %order_id, "Updating the order state to Taken");
+
+                    orders::set_order_state(&mut conn, order_id, OrderState::Taken)?
+                } else {
+                    matched_order
+                }
+            }
+        };
+
+        // Resting limit orders which have been filled completely have to be removed from the book.
+        // The split-off part of a partially filled order was never part of the book.
+        if !is_taker && !matched_orders.is_partial_fill(order_id) {
+            tx_price_feed
+                .send(Message::Update(matched_order))
+                .map_err(|e| anyhow!(e))
+                .context("Could not update price feed")?;
+        }
+    }
+
+    Ok(persisted_match.order)
+}
+
+/// The error for a new [`Order`] of a trader who still has a matched order waiting for execution.
+fn order_in_execution(order: &Order) -> TradingError {
+    TradingError::OrderInExecution(format!(
+        "trader_id={}, order_id={}. Order is currently in execution. \
+         Can't accept new orders until the order execution is finished",
+        order.trader_id, order.id
+    ))
+}
+
+/// The state of the orders affected by a match, after the match has been persisted.
+struct PersistedMatch {
+    /// The taker order.
+    order: Order,
+    /// The partially filled orders with their remaining quantity.
+    remaining_orders: Vec<Order>,
+    /// The matched orders, in the same order as [`MatchParams::matches`].
+    matched_orders: Vec<Order>,
+}
+
+/// Persists the [`MatchParams`] found for the taker `order`.
+///
+/// Cancels the unfilled quantity of an [`TimeInForce::ImmediateOrCancel`] order, splits partially
+/// filled orders (including a taker order resting in the book), inserts the matches and sets all
+/// matched orders to [`OrderState::Matched`].
+///
+/// Expired, liquidated and triggered positions are closed by concurrent tasks, each of which may
+/// submit a closing order for the same position. The position is locked while checking for a
+/// pending execution and matching the closing order, so that only one closing order is matched.
+/// Otherwise, a second one would flip the position to the other side.
+///
+/// Must be called within a DB transaction, so that an error does not leave a partially persisted
+/// match behind.
+fn persist_match(
+    conn: &mut PgConnection,
+    order: &Order,
+    match_params: &MatchParams,
+) -> Result<PersistedMatch> {
+    if order.order_reason != OrderReason::Manual {
+        if db::positions::Position::lock_open_position(conn, order.trader_id)?.is_none() {
+            bail!(
+                "trader_id={} has no open position to close",
+                order.trader_id
+            );
+        }
+
+        if orders::get_by_trader_id_and_state(conn, order.trader_id, OrderState::Matched)?.is_some()
         {
-            bail!(TradingError::InvalidOrder(format!(
-                "trader_id={}, order_id={}. Order is currently in execution. \
-                 Can't accept new orders until the order execution is finished",
-                new_order.trader_id, order.id
-            )));
+            bail!(order_in_execution(order));
         }
+    }
 
-        let opposite_direction_limit_orders = orders::all_by_direction_and_type(
-            &mut conn,
-            order.direction.opposite(),
-            OrderType::Limit,
-            true,
+    let filled_quantity = match_params.taker_match.filled_with.quantity();
+    let order = if filled_quantity < order.quantity
+        && order.time_in_force == TimeInForce::ImmediateOrCancel
+    {
+        tracing::debug!(
+            order_id = %order.id,
+            %filled_quantity,
+            remaining_quantity = %(order.quantity - filled_quantity),
+            "Cancelling the remaining quantity of immediate-or-cancel order"
+        );
+
+        orders::set_filled_quantity(conn, order.id, filled_quantity)?
+    } else {
+        order.clone()
+    };
+
+    let mut remaining_orders = vec![];
+    for partial_fill in match_params.partial_fills.iter() {
+        let remaining_order = orders::split(
+            conn,
+            partial_fill.order_id,
+            partial_fill.fill_order_id,
+            partial_fill.quantity,
         )?;
 
-        let matched_orders =
-            match match_order(&order, opposite_direction_limit_orders, network, oracle_pk) {
-                Ok(Some(matched_orders)) => matched_orders,
-                Ok(None) => {
-                    // TODO(holzeis): Currently we still respond to the user immediately if there
-                    // has been a match or not, that's the reason why we also have to set the order
-                    // to failed here. But actually we could keep the order until either expired or
-                    // a match has been found and then update the state accordingly.
-
-                    orders::set_order_state(&mut conn, order.id, OrderState::Failed)?;
-                    bail!(TradingError::NoMatchFound(format!(
-                        "Could not match order {}",
-                        order.id
-                    )));
-                }
-                Err(e) => {
-                    orders::set_order_state(&mut conn, order.id, OrderState::Failed)?;
-                    bail!("Failed to match order: {e:#}")
-                }
-            };
-
-        tracing::info!(
-            trader_id=%order.trader_id,
-            order_id=%order.id,
-            "Found a match with {} makers for new order",
-            matched_orders.taker_match.filled_with.matches.len()
-        );
-
-        for match_param in matched_orders.matches() {
-            matches::insert(&mut conn, match_param)?;
-
-            let trader_id = match_param.trader_id;
-            let order_id = match_param.filled_with.order_id.to_string();
-
-            tracing::info!(%trader_id, order_id, "Notifying trader about match");
-
-            let message = match &order.order_reason {
-                OrderReason::Manual => Message::Match(match_param.filled_with.clone()),
-                OrderReason::Expired => Message::AsyncMatch {
-                    order: order.clone(),
-                    filled_with: match_param.filled_with.clone(),
-                },
-            };
-
-            let notification = match &order.order_reason {
-                OrderReason::Expired => Some(NotificationKind::PositionExpired),
-                OrderReason::Manual => None,
-            };
-
-            let msg = OrderbookMessage::TraderMessage {
-                trader_id,
-                message,
-                notification,
-            };
-
-            let order_state = match notifier.send(msg).await {
-                Ok(()) => {
-                    tracing::debug!(%trader_id, order_id, "Successfully notified trader");
-                    OrderState::Matched
-                }
-                Err(e) => {
-                    tracing::warn!(%trader_id, order_id, "Failed to send trader message: {e:#}");
-
-                    if order.order_type == OrderType::Limit {
-                        // FIXME: The maker is currently not connected to the WebSocket so we can't
-                        // notify him about a trade. However, trades are always accepted by the
-                        // maker at the moment so in order to not have all limit orders in order
-                        // state `Match` we are setting the order to `Taken` even if we couldn't
-                        // notify the maker.
-
-                        OrderState::Taken
-                    } else {
-                        OrderState::Matched
-                    }
-                }
-            };
+        tracing::debug!(
+            order_id = %partial_fill.order_id,
+            fill_order_id = %partial_fill.fill_order_id,
+            filled_quantity = %partial_fill.quantity,
+            remaining_quantity = %remaining_order.quantity,
+            "Limit order has been partially filled"
+        );
 
-            tracing::debug!(%trader_id, order_id, "Updating the order state to {order_state:?}");
+        remaining_orders.push(remaining_order);
+    }
 
-            orders::set_order_state(&mut conn, match_param.filled_with.order_id, order_state)?;
-        }
+    // The taker order keeps the remaining quantity if it has been partially filled.
+    let order = remaining_orders
+        .iter()
+        .find(|remaining_order| remaining_order.id == order.id)
+        .cloned()
+        .unwrap_or(order);
+
+    let mut matched_orders = vec![];
+    for match_param in match_params.matches() {
+        matches::insert(conn, match_param)?;
+
+        let order_id = match_param.filled_with.order_id;
+        let matched_order = orders::set_order_state(conn, order_id, OrderState::Matched)?;
+
+        matched_orders.push(matched_order);
     }
 
-    Ok(order)
+    Ok(PersistedMatch {
+        order,
+        remaining_orders,
+        matched_orders,
+    })
 }
 
-/// Matches an [`Order`] of [`OrderType::Market`] with a list of [`Order`]s of [`OrderType::Limit`].
+/// Matches a taker [`Order`] with a list of resting [`Order`]s of [`OrderType::Limit`].
 ///
 /// The caller is expected to provide a list of `opposite_direction_orders` of [`OrderType::Limit`]
-/// and opposite [`Direction`] to the `market_order`. We nevertheless ensure that this is the case
-/// to be on the safe side.
-
+/// and opposite [`Direction`] to the `taker_order`. We nevertheless ensure that this is the case
+/// to be on the safe side. Orders of the same trader are never matched with each other, and limit
+/// orders of `traders_in_execution` are skipped, as their owners still have a matched order waiting
+/// for execution.
+///
+/// The limit orders are consumed in price-time priority (see [`sort_orders`]) until the quantity of
+/// the taker order is filled, producing one [`Match`] per limit order at that order's price. The
+/// last limit order may only be partially consumed. If the book does not hold enough liquidity to
+/// fill the whole taker order, the available liquidity is matched if the taker order is
+/// [`TimeInForce::ImmediateOrCancel`] or rests in the book. Otherwise, no match is returned.
+///
+/// If the taker order is of [`OrderType::Limit`], only limit orders crossing its price are
+/// considered, i.e. limit orders at the same or a better price.
 fn match_order(
-    market_order: &Order,
+    taker_order: &Order,
     opposite_direction_orders: Vec<Order>,
     network: Network,
-    oracle_pk: XOnlyPublicKey,
+    oracle_params: &OracleParams,
+    traders_in_execution: &HashSet<PublicKey>,
 ) -> Result<Option<MatchParams>> {
-    if market_order.order_type == OrderType::Limit {
-        // We don't match limit orders with other limit orders at the moment.
-        return Ok(None);
-    }
-
     let opposite_direction_orders = opposite_direction_orders
         .into_iter()
-        .filter(|o| !o.direction.eq(&market_order.direction))
+        .filter(|o| crosses(taker_order, o))
+        .filter(|o| !traders_in_execution.contains(&o.trader_id))
         .collect();
 
-    let mut orders = sort_orders(opposite_direction_orders, market_order.direction);
+    let orders = sort_orders(opposite_direction_orders, taker_order.direction);
 
-    let mut remaining_quantity = market_order.quantity;
+    let mut remaining_quantity = taker_order.quantity;
     let mut matched_orders = vec![];
-    while !orders.is_empty() {
-        let matched_order = orders.remove(0);
-        remaining_quantity -= matched_order.quantity;
-        matched_orders.push(matched_order);
-
+    for maker_order in orders {
         if remaining_quantity <= Decimal::ZERO {
             break;
         }
-    }
 
-    // For the time being we do not want to support multi-matches.
-    if matched_orders.len() > 1 {
-        bail!("More than one matched order, please reduce order quantity");
+        let quantity = remaining_quantity.min(maker_order.quantity);
+        remaining_quantity -= quantity;
+        matched_orders.push((maker_order, quantity));
     }
 
     if matched_orders.is_empty() {
         return Ok(None);
     }
 
-    let expiry_timestamp = commons::calculate_next_expiry(OffsetDateTime::now_utc(), network);
+    let mut partial_fills = vec![];
+
+    // The filled part of a partially filled taker order which rests in the book is split off into
+    // a new order, like for partially consumed limit orders.
+    let (taker_order_id, taker_parent_order_id) = if remaining_quantity > Decimal::ZERO {
+        match taker_order.time_in_force {
+            // The remaining quantity is cancelled.
+            TimeInForce::ImmediateOrCancel => (taker_order.id, None),
+            // The remaining quantity rests in the book.
+            TimeInForce::GoodTillCancelled | TimeInForce::GoodTillTime => {
+                let fill_order_id = Uuid::new_v4();
+                partial_fills.push(PartialFill {
+                    order_id: taker_order.id,
+                    fill_order_id,
+                    quantity: taker_order.quantity - remaining_quantity,
+                });
+
+                (fill_order_id, Some(taker_order.id))
+            }
+            TimeInForce::FillOrKill => {
+                tracing::debug!(
+                    order_id = %taker_order.id,
+                    %remaining_quantity,
+                    "Not enough liquidity in the orderbook to fill order"
+                );
+                return Ok(None);
+            }
+        }
+    } else {
+        (taker_order.id, None)
+    };
 
-    let matches = matched_orders
-        .iter()
-        .map(|maker_order| {
-            (
-                TraderMatchParams {
-                    trader_id: maker_order.trader_id,
-                    filled_with: FilledWith {
-                        order_id: maker_order.id,
-                        expiry_timestamp,
-                        oracle_pk,
-                        matches: vec![Match {
-                            id: Uuid::new_v4(),
-                            order_id: market_order.id,
-                            quantity: market_order.quantity,
-                            pubkey: market_order.trader_id,
-                            execution_price: maker_order.price,
-                        }],
-                    },
-                },
-                Match {
-                    id: Uuid::new_v4(),
-                    order_id: maker_order.id,
-                    quantity: market_order.quantity,
-                    pubkey: maker_order.trader_id,
-                    execution_price: maker_order.price,
-                },
-            )
-        })
-        .collect::<Vec<(TraderMatchParams, Match)>>();
+    let expiry_timestamp = commons::calculate_next_expiry(OffsetDateTime::now_utc(), network);
 
     let mut maker_matches = vec![];
     let mut taker_matches = vec![];
 
-    for (mm, taker_match) in matches {
-        maker_matches.push(mm);
-        taker_matches.push(taker_match);
+    for (maker_order, quantity) in matched_orders {
+        // The filled part of a partially consumed limit order is split off into a new order, so
+        // that every order is filled by exactly one trade.
+        let (maker_order_id, parent_order_id) = if quantity < maker_order.quantity {
+            let fill_order_id = Uuid::new_v4();
+            partial_fills.push(PartialFill {
+                order_id: maker_order.id,
+                fill_order_id,
+                quantity,
+            });
+
+            (fill_order_id, Some(maker_order.id))
+        } else {
+            (maker_order.id, None)
+        };
+
+        maker_matches.push(TraderMatchParams {
+            trader_id: maker_order.trader_id,
+            filled_with: FilledWith {
+                order_id: maker_order_id,
+                expiry_timestamp,
+                oracle_params: oracle_params.clone(),
+                matches: vec![Match {
+                    id: Uuid::new_v4(),
+                    order_id: taker_order_id,
+                    quantity,
+                    pubkey: taker_order.trader_id,
+                    execution_price: maker_order.price,
+                }],
+                parent_order_id,
+            },
+        });
+
+        taker_matches.push(Match {
+            id: Uuid::new_v4(),
+            order_id: maker_order_id,
+            quantity,
+            pubkey: maker_order.trader_id,
+            execution_price: maker_order.price,
+        });
     }
 
     Ok(Some(MatchParams {
         taker_match: TraderMatchParams {
-            trader_id: market_order.trader_id,
+            trader_id: taker_order.trader_id,
             filled_with: FilledWith {
-                order_id: market_order.id,
+                order_id: taker_order_id,
                 expiry_timestamp,
-                oracle_pk,
+                oracle_params: oracle_params.clone(),
                 matches: taker_matches,
+                parent_order_id: taker_parent_order_id,
             },
         },
         makers_matches: maker_matches,
+        partial_fills,
     }))
 }
 
+/// Whether the taker [`Order`] can be matched with the given resting limit [`Order`].
+///
+/// Orders of the same trader are never matched with each other. A taker order of
+/// [`OrderType::Limit`] only crosses limit orders at the same or a better price.
+fn crosses(taker_order: &Order, maker_order: &Order) -> bool {
+    let crosses_price = match taker_order.order_type {
+        OrderType::Market => true,
+        OrderType::Limit => match taker_order.direction {
+            Direction::Long => maker_order.price <= taker_order.price,
+            Direction::Short => maker_order.price >= taker_order.price,
+        },
+    };
+
+    maker_order.direction != taker_order.direction
+        && maker_order.contract_symbol == taker_order.contract_symbol
+        && maker_order.trader_id != taker_order.trader_id
+        && crosses_price
+}
+
+/// Whether the resting limit [`Order`] would cross the book if its price was amended to `price`.
+///
+/// Like when matching, the orders of the same trader are not considered.
+pub(crate) fn amended_order_crosses_book(
+    order: &Order,
+    price: Decimal,
+    opposite_direction_orders: &[Order],
+) -> bool {
+    let amended_order = Order {
+        price,
+        ..order.clone()
+    };
+
+    opposite_direction_orders
+        .iter()
+        .any(|opposite_order| crosses(&amended_order, opposite_order))
+}
+
+/// Ensures that the [`TimeInForce`] and the post-only flag of the [`NewOrder`] fit its
+/// [`OrderType`].
+///
+/// Market orders never rest in the orderbook, and post-only orders have to.
+fn validate_time_in_force(new_order: &NewOrder) -> Result<(), TradingError> {
+    let rests_in_book = new_order.time_in_force.rests_in_book();
+
+    if new_order.order_type == OrderType::Market && rests_in_book {
+        return Err(TradingError::InvalidOrder(format!(
+            "Market orders cannot be {:?}",
+            new_order.time_in_force
+        )));
+    }
+
+    if new_order.post_only && (new_order.order_type == OrderType::Market || !rests_in_book) {
+        return Err(TradingError::InvalidOrder(
+            "Only limit orders resting in the orderbook can be post-only".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 /// Sort the provided list of limit [`Order`]s based on the [`Direction`] of the market order to be
 /// matched.
 ///
@@ -410,6 +718,12 @@ impl MatchParams {
             .chain(self.makers_matches.iter())
             .collect()
     }
+
+    fn is_partial_fill(&self, order_id: Uuid) -> bool {
+        self.partial_fills
+            .iter()
+            .any(|partial_fill| partial_fill.fill_order_id == order_id)
+    }
 }
 
 impl From<&TradeParams> for TraderMatchParams {
@@ -424,6 +738,7 @@ impl From<&TradeParams> for TraderMatchParams {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bitcoin::XOnlyPublicKey;
     use rust_decimal_macros::dec;
     use std::str::FromStr;
     use time::Duration;
@@ -567,13 +882,16 @@ mod tests {
             order_state: OrderState::Open,
             order_reason: OrderReason::Manual,
             stable: false,
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
         };
 
         let matched_orders = match_order(
             &order,
             all_orders,
             Network::Bitcoin,
-            get_oracle_public_key(),
+            &get_oracle_params(),
+            &HashSet::new(),
         )
         .unwrap()
         .unwrap();
@@ -603,34 +921,42 @@ mod tests {
         );
     }
 
-    /// This test is for safety reasons only. Once we want multiple matches we should update it
     #[test]
-    fn given_limit_and_market_with_smaller_amount_then_error() {
-        let order1 = dummy_long_order(
-            dec!(20_000),
-            Uuid::new_v4(),
-            dec!(400),
-            Duration::seconds(0),
-        );
-        let order2 = dummy_long_order(
-            dec!(21_000),
-            Uuid::new_v4(),
-            dec!(200),
-            Duration::seconds(0),
-        );
+    fn given_limit_orders_and_bigger_market_order_then_match_multiple_makers() {
+        let maker_1 = PublicKey::from_str(
+            "03f75f318471d32d39be3c86c622e2c51bd5731bf95f98aaa3ed5d6e1c0025927f",
+        )
+        .unwrap();
+        let maker_2 = PublicKey::from_str(
+            "02d5aa8fce495f6301b466594af056a46104dcdc6d735ec4793aa43108854cbd4a",
+        )
+        .unwrap();
+
+        let order1 = Order {
+            trader_id: maker_1,
+            ..dummy_long_order(
+                dec!(20_000),
+                Uuid::new_v4(),
+                dec!(100),
+                Duration::seconds(0),
+            )
+        };
+        let order2 = Order {
+            trader_id: maker_2,
+            ..dummy_long_order(
+                dec!(21_000),
+                Uuid::new_v4(),
+                dec!(200),
+                Duration::seconds(0),
+            )
+        };
         let order3 = dummy_long_order(
             dec!(22_000),
             Uuid::new_v4(),
             dec!(100),
             Duration::seconds(0),
         );
-        let order4 = dummy_long_order(
-            dec!(20_000),
-            Uuid::new_v4(),
-            dec!(300),
-            Duration::seconds(0),
-        );
-        let all_orders = vec![order1, order2, order3, order4];
+        let all_orders = vec![order1.clone(), order2.clone(), order3.clone()];
 
         let order = Order {
             id: Uuid::new_v4(),
@@ -642,26 +968,72 @@ mod tests {
             direction: Direction::Short,
             leverage: 1.0,
             contract_symbol: ContractSymbol::BtcUsd,
-            quantity: dec!(200),
+            quantity: dec!(250),
             order_type: OrderType::Market,
             timestamp: OffsetDateTime::now_utc(),
             expiry: OffsetDateTime::now_utc() + Duration::minutes(1),
             order_state: OrderState::Open,
             order_reason: OrderReason::Manual,
             stable: false,
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
         };
 
-        assert!(match_order(
+        let matched_orders = match_order(
             &order,
             all_orders,
             Network::Bitcoin,
-            get_oracle_public_key()
+            &get_oracle_params(),
+            &HashSet::new(),
         )
-        .is_err());
+        .unwrap()
+        .unwrap();
+
+        // The short market order consumes the highest bids first: `order3` is filled completely
+        // and `order2` partially. `order1` is not touched.
+        assert_eq!(matched_orders.makers_matches.len(), 2);
+
+        let maker_match_1 = matched_orders.makers_matches.get(0).unwrap();
+        assert_eq!(maker_match_1.filled_with.order_id, order3.id);
+        assert_eq!(filled_quantity(maker_match_1), dec!(100));
+        assert_eq!(
+            maker_match_1.filled_with.matches[0].execution_price,
+            dec!(22_000)
+        );
+
+        // The filled part of `order2` is split off into a new order.
+        assert_eq!(matched_orders.partial_fills.len(), 1);
+        let partial_fill = matched_orders.partial_fills.get(0).unwrap();
+        assert_eq!(partial_fill.order_id, order2.id);
+        assert_eq!(partial_fill.quantity, dec!(150));
+
+        let maker_match_2 = matched_orders.makers_matches.get(1).unwrap();
+        assert_eq!(maker_match_2.trader_id, maker_2);
+        assert_eq!(
+            maker_match_2.filled_with.order_id,
+            partial_fill.fill_order_id
+        );
+        assert_eq!(filled_quantity(maker_match_2), dec!(150));
+        assert_eq!(
+            maker_match_2.filled_with.matches[0].execution_price,
+            dec!(21_000)
+        );
+
+        let taker_match = &matched_orders.taker_match;
+        assert_eq!(taker_match.filled_with.order_id, order.id);
+        assert_eq!(taker_match.filled_with.matches.len(), 2);
+        assert_eq!(filled_quantity(taker_match), order.quantity);
+        assert_eq!(
+            taker_match
+                .filled_with
+                .average_execution_price()
+                .round_dp(2),
+            dec!(21_388.89)
+        );
     }
 
     #[test]
-    fn given_long_when_needed_short_direction_then_no_match() {
+    fn given_not_enough_liquidity_then_no_match() {
         let all_orders = vec![
             dummy_long_order(
                 dec!(20_000),
@@ -675,18 +1047,6 @@ mod tests {
                 dec!(200),
                 Duration::seconds(0),
             ),
-            dummy_long_order(
-                dec!(22_000),
-                Uuid::new_v4(),
-                dec!(400),
-                Duration::seconds(0),
-            ),
-            dummy_long_order(
-                dec!(20_000),
-                Uuid::new_v4(),
-                dec!(300),
-                Duration::seconds(0),
-            ),
         ];
 
         let order = Order {
@@ -696,57 +1056,605 @@ mod tests {
                 "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
             )
             .unwrap(),
-            direction: Direction::Long,
+            direction: Direction::Short,
             leverage: 1.0,
             contract_symbol: ContractSymbol::BtcUsd,
-            quantity: dec!(200),
+            quantity: dec!(301),
             order_type: OrderType::Market,
             timestamp: OffsetDateTime::now_utc(),
             expiry: OffsetDateTime::now_utc() + Duration::minutes(1),
             order_state: OrderState::Open,
             order_reason: OrderReason::Manual,
             stable: false,
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
         };
 
         let matched_orders = match_order(
             &order,
             all_orders,
             Network::Bitcoin,
-            get_oracle_public_key(),
+            &get_oracle_params(),
+            &HashSet::new(),
         )
         .unwrap();
 
         assert!(matched_orders.is_none());
     }
 
-    fn dummy_long_order(
-        price: Decimal,
-        id: Uuid,
-        quantity: Decimal,
-        timestamp_delay: Duration,
-    ) -> Order {
-        Order {
-            id,
-            price,
+    #[test]
+    fn given_crossing_limit_order_then_match_at_resting_price() {
+        let order1 = dummy_long_order(
+            dec!(20_000),
+            Uuid::new_v4(),
+            dec!(100),
+            Duration::seconds(0),
+        );
+        let order2 = dummy_long_order(
+            dec!(21_000),
+            Uuid::new_v4(),
+            dec!(100),
+            Duration::seconds(0),
+        );
+        let all_orders = vec![order1, order2.clone()];
+
+        let order = Order {
+            id: Uuid::new_v4(),
+            price: dec!(20_500),
             trader_id: PublicKey::from_str(
                 "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
             )
             .unwrap(),
-            direction: Direction::Long,
+            direction: Direction::Short,
             leverage: 1.0,
             contract_symbol: ContractSymbol::BtcUsd,
-            quantity,
+            quantity: dec!(100),
             order_type: OrderType::Limit,
-            timestamp: OffsetDateTime::now_utc() + timestamp_delay,
+            timestamp: OffsetDateTime::now_utc(),
             expiry: OffsetDateTime::now_utc() + Duration::minutes(1),
             order_state: OrderState::Open,
             order_reason: OrderReason::Manual,
             stable: false,
-        }
+            time_in_force: TimeInForce::GoodTillTime,
+            post_only: false,
+        };
+
+        let matched_orders = match_order(
+            &order,
+            all_orders,
+            Network::Bitcoin,
+            &get_oracle_params(),
+            &HashSet::new(),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(matched_orders.makers_matches.len(), 1);
+        assert_eq!(
+            matched_orders.makers_matches[0].filled_with.order_id,
+            order2.id
+        );
+        assert_eq!(
+            matched_orders.taker_match.filled_with.matches[0].execution_price,
+            dec!(21_000)
+        );
     }
 
-    fn get_oracle_public_key() -> XOnlyPublicKey {
-        XOnlyPublicKey::from_str("16f88cf7d21e6c0f46bcbc983a4e3b19726c6c98858cc31c83551a88fde171c0")
-            .unwrap()
+    #[test]
+    fn given_limit_order_not_crossing_the_book_then_no_match() {
+        let all_orders = vec![
+            dummy_long_order(
+                dec!(20_000),
+                Uuid::new_v4(),
+                dec!(100),
+                Duration::seconds(0),
+            ),
+            dummy_long_order(
+                dec!(21_000),
+                Uuid::new_v4(),
+                dec!(100),
+                Duration::seconds(0),
+            ),
+        ];
+
+        // No contracts are offered at or above the limit price.
+        let order = Order {
+            id: Uuid::new_v4(),
+            price: dec!(21_500),
+            trader_id: PublicKey::from_str(
+                "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
+            )
+            .unwrap(),
+            direction: Direction::Short,
+            leverage: 1.0,
+            contract_symbol: ContractSymbol::BtcUsd,
+            quantity: dec!(200),
+            order_type: OrderType::Limit,
+            timestamp: OffsetDateTime::now_utc(),
+            expiry: OffsetDateTime::now_utc() + Duration::minutes(1),
+            order_state: OrderState::Open,
+            order_reason: OrderReason::Manual,
+            stable: false,
+            time_in_force: TimeInForce::GoodTillTime,
+            post_only: false,
+        };
+
+        let matched_orders = match_order(
+            &order,
+            all_orders,
+            Network::Bitcoin,
+            &get_oracle_params(),
+            &HashSet::new(),
+        )
+        .unwrap();
+
+        assert!(matched_orders.is_none());
+    }
+
+    #[test]
+    fn given_limit_order_partially_crossing_the_book_then_remainder_rests() {
+        let order1 = dummy_long_order(
+            dec!(20_000),
+            Uuid::new_v4(),
+            dec!(100),
+            Duration::seconds(0),
+        );
+        let order2 = dummy_long_order(
+            dec!(21_000),
+            Uuid::new_v4(),
+            dec!(100),
+            Duration::seconds(0),
+        );
+        let all_orders = vec![order1, order2.clone()];
+
+        // Only 100 contracts are offered at or above the limit price.
+        let order = Order {
+            id: Uuid::new_v4(),
+            price: dec!(20_500),
+            trader_id: PublicKey::from_str(
+                "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
+            )
+            .unwrap(),
+            direction: Direction::Short,
+            leverage: 1.0,
+            contract_symbol: ContractSymbol::BtcUsd,
+            quantity: dec!(250),
+            order_type: OrderType::Limit,
+            timestamp: OffsetDateTime::now_utc(),
+            expiry: OffsetDateTime::now_utc() + Duration::minutes(1),
+            order_state: OrderState::Open,
+            order_reason: OrderReason::Manual,
+            stable: false,
+            time_in_force: TimeInForce::GoodTillTime,
+            post_only: false,
+        };
+
+        let matched_orders = match_order(
+            &order,
+            all_orders,
+            Network::Bitcoin,
+            &get_oracle_params(),
+            &HashSet::new(),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(matched_orders.makers_matches.len(), 1);
+        assert_eq!(
+            matched_orders.makers_matches[0].filled_with.order_id,
+            order2.id
+        );
+
+        // The crossing quantity is split off the limit order, the remainder rests in the book.
+        let taker_filled_with = &matched_orders.taker_match.filled_with;
+        assert_ne!(taker_filled_with.order_id, order.id);
+        assert_eq!(taker_filled_with.parent_order_id, Some(order.id));
+        assert_eq!(taker_filled_with.quantity(), dec!(100));
+        assert_eq!(taker_filled_with.matches[0].execution_price, dec!(21_000));
+        assert_eq!(
+            matched_orders.makers_matches[0].filled_with.matches[0].order_id,
+            taker_filled_with.order_id
+        );
+
+        assert_eq!(matched_orders.partial_fills.len(), 1);
+        assert_eq!(matched_orders.partial_fills[0].order_id, order.id);
+        assert_eq!(
+            matched_orders.partial_fills[0].fill_order_id,
+            taker_filled_with.order_id
+        );
+        assert_eq!(matched_orders.partial_fills[0].quantity, dec!(100));
+    }
+
+    #[test]
+    fn given_own_limit_order_then_no_match() {
+        let maker_order = dummy_long_order(
+            dec!(20_000),
+            Uuid::new_v4(),
+            dec!(100),
+            Duration::seconds(0),
+        );
+
+        let order = Order {
+            id: Uuid::new_v4(),
+            price: Default::default(),
+            trader_id: maker_order.trader_id,
+            direction: Direction::Short,
+            leverage: 1.0,
+            contract_symbol: ContractSymbol::BtcUsd,
+            quantity: dec!(100),
+            order_type: OrderType::Market,
+            timestamp: OffsetDateTime::now_utc(),
+            expiry: OffsetDateTime::now_utc() + Duration::minutes(1),
+            order_state: OrderState::Open,
+            order_reason: OrderReason::Manual,
+            stable: false,
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
+        };
+
+        let matched_orders = match_order(
+            &order,
+            vec![maker_order],
+            Network::Bitcoin,
+            &get_oracle_params(),
+            &HashSet::new(),
+        )
+        .unwrap();
+
+        assert!(matched_orders.is_none());
+    }
+
+    #[test]
+    fn given_limit_order_of_trader_in_execution_then_skip_it() {
+        let order_in_execution = dummy_long_order(
+            dec!(21_000),
+            Uuid::new_v4(),
+            dec!(100),
+            Duration::seconds(0),
+        );
+        let other_order = Order {
+            trader_id: PublicKey::from_str(
+                "03f75f318471d32d39be3c86c622e2c51bd5731bf95f98aaa3ed5d6e1c0025927f",
+            )
+            .unwrap(),
+            ..dummy_long_order(
+                dec!(20_000),
+                Uuid::new_v4(),
+                dec!(100),
+                Duration::seconds(0),
+            )
+        };
+
+        let order = Order {
+            id: Uuid::new_v4(),
+            price: Default::default(),
+            trader_id: PublicKey::from_str(
+                "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
+            )
+            .unwrap(),
+            direction: Direction::Short,
+            leverage: 1.0,
+            contract_symbol: ContractSymbol::BtcUsd,
+            quantity: dec!(100),
+            order_type: OrderType::Market,
+            timestamp: OffsetDateTime::now_utc(),
+            expiry: OffsetDateTime::now_utc() + Duration::minutes(1),
+            order_state: OrderState::Open,
+            order_reason: OrderReason::Manual,
+            stable: false,
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
+        };
+
+        let matched_orders = match_order(
+            &order,
+            vec![order_in_execution.clone(), other_order.clone()],
+            Network::Bitcoin,
+            &get_oracle_params(),
+            &HashSet::from([order_in_execution.trader_id]),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(matched_orders.makers_matches.len(), 1);
+        assert_eq!(
+            matched_orders.makers_matches[0].trader_id,
+            other_order.trader_id
+        );
+        assert_eq!(
+            matched_orders.taker_match.filled_with.matches[0].execution_price,
+            dec!(20_000)
+        );
+    }
+
+    #[test]
+    fn given_long_when_needed_short_direction_then_no_match() {
+        let all_orders = vec![
+            dummy_long_order(
+                dec!(20_000),
+                Uuid::new_v4(),
+                dec!(100),
+                Duration::seconds(0),
+            ),
+            dummy_long_order(
+                dec!(21_000),
+                Uuid::new_v4(),
+                dec!(200),
+                Duration::seconds(0),
+            ),
+            dummy_long_order(
+                dec!(22_000),
+                Uuid::new_v4(),
+                dec!(400),
+                Duration::seconds(0),
+            ),
+            dummy_long_order(
+                dec!(20_000),
+                Uuid::new_v4(),
+                dec!(300),
+                Duration::seconds(0),
+            ),
+        ];
+
+        let order = Order {
+            id: Uuid::new_v4(),
+            price: Default::default(),
+            trader_id: PublicKey::from_str(
+                "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
+            )
+            .unwrap(),
+            direction: Direction::Long,
+            leverage: 1.0,
+            contract_symbol: ContractSymbol::BtcUsd,
+            quantity: dec!(200),
+            order_type: OrderType::Market,
+            timestamp: OffsetDateTime::now_utc(),
+            expiry: OffsetDateTime::now_utc() + Duration::minutes(1),
+            order_state: OrderState::Open,
+            order_reason: OrderReason::Manual,
+            stable: false,
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
+        };
+
+        let matched_orders = match_order(
+            &order,
+            all_orders,
+            Network::Bitcoin,
+            &get_oracle_params(),
+            &HashSet::new(),
+        )
+        .unwrap();
+
+        assert!(matched_orders.is_none());
+    }
+
+    #[test]
+    fn given_not_enough_liquidity_and_immediate_or_cancel_then_partial_match() {
+        let all_orders = vec![
+            dummy_long_order(
+                dec!(20_000),
+                Uuid::new_v4(),
+                dec!(100),
+                Duration::seconds(0),
+            ),
+            dummy_long_order(
+                dec!(21_000),
+                Uuid::new_v4(),
+                dec!(200),
+                Duration::seconds(0),
+            ),
+        ];
+
+        let order = Order {
+            id: Uuid::new_v4(),
+            price: Default::default(),
+            trader_id: PublicKey::from_str(
+                "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
+            )
+            .unwrap(),
+            direction: Direction::Short,
+            leverage: 1.0,
+            contract_symbol: ContractSymbol::BtcUsd,
+            quantity: dec!(301),
+            order_type: OrderType::Market,
+            timestamp: OffsetDateTime::now_utc(),
+            expiry: OffsetDateTime::now_utc() + Duration::minutes(1),
+            order_state: OrderState::Open,
+            order_reason: OrderReason::Manual,
+            stable: false,
+            time_in_force: TimeInForce::ImmediateOrCancel,
+            post_only: false,
+        };
+
+        let matched_orders = match_order(
+            &order,
+            all_orders,
+            Network::Bitcoin,
+            &get_oracle_params(),
+            &HashSet::new(),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(matched_orders.makers_matches.len(), 2);
+        assert_eq!(filled_quantity(&matched_orders.taker_match), dec!(300));
+        assert_eq!(matched_orders.taker_match.filled_with.quantity(), dec!(300));
+    }
+
+    #[test]
+    fn given_post_only_limit_order_then_crosses_detects_crossing_the_book() {
+        let resting_order = dummy_long_order(
+            dec!(20_000),
+            Uuid::new_v4(),
+            dec!(100),
+            Duration::seconds(0),
+        );
+
+        let order = |price| Order {
+            id: Uuid::new_v4(),
+            price,
+            trader_id: PublicKey::from_str(
+                "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
+            )
+            .unwrap(),
+            direction: Direction::Short,
+            leverage: 1.0,
+            contract_symbol: ContractSymbol::BtcUsd,
+            quantity: dec!(100),
+            order_type: OrderType::Limit,
+            timestamp: OffsetDateTime::now_utc(),
+            expiry: OffsetDateTime::now_utc() + Duration::minutes(1),
+            order_state: OrderState::Open,
+            order_reason: OrderReason::Manual,
+            stable: false,
+            time_in_force: TimeInForce::GoodTillCancelled,
+            post_only: true,
+        };
+
+        assert!(crosses(&order(dec!(19_900)), &resting_order));
+        assert!(crosses(&order(dec!(20_000)), &resting_order));
+        assert!(!crosses(&order(dec!(20_100)), &resting_order));
+    }
+
+    #[test]
+    fn given_time_in_force_not_fitting_order_type_then_invalid_order() {
+        let new_order = |order_type, time_in_force, post_only| NewOrder {
+            id: Uuid::new_v4(),
+            contract_symbol: ContractSymbol::BtcUsd,
+            price: dec!(20_000),
+            quantity: dec!(100),
+            trader_id: PublicKey::from_str(
+                "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
+            )
+            .unwrap(),
+            direction: Direction::Long,
+            leverage: 1.0,
+            order_type,
+            expiry: OffsetDateTime::now_utc() + Duration::minutes(1),
+            stable: false,
+            time_in_force,
+            post_only,
+        };
+
+        assert!(validate_time_in_force(&new_order(
+            OrderType::Market,
+            TimeInForce::FillOrKill,
+            false
+        ))
+        .is_ok());
+        assert!(validate_time_in_force(&new_order(
+            OrderType::Limit,
+            TimeInForce::GoodTillCancelled,
+            true
+        ))
+        .is_ok());
+
+        assert!(validate_time_in_force(&new_order(
+            OrderType::Market,
+            TimeInForce::GoodTillCancelled,
+            false
+        ))
+        .is_err());
+        assert!(validate_time_in_force(&new_order(
+            OrderType::Market,
+            TimeInForce::ImmediateOrCancel,
+            true
+        ))
+        .is_err());
+        assert!(validate_time_in_force(&new_order(
+            OrderType::Limit,
+            TimeInForce::FillOrKill,
+            true
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn given_amended_price_crossing_only_own_orders_then_does_not_cross_book() {
+        let own_order = dummy_long_order(
+            dec!(20_000),
+            Uuid::new_v4(),
+            dec!(100),
+            Duration::seconds(0),
+        );
+        let order = Order {
+            direction: Direction::Short,
+            ..dummy_long_order(
+                dec!(21_000),
+                Uuid::new_v4(),
+                dec!(100),
+                Duration::seconds(0),
+            )
+        };
+        let other_order = Order {
+            trader_id: PublicKey::from_str(
+                "03f75f318471d32d39be3c86c622e2c51bd5731bf95f98aaa3ed5d6e1c0025927f",
+            )
+            .unwrap(),
+            ..dummy_long_order(
+                dec!(19_000),
+                Uuid::new_v4(),
+                dec!(100),
+                Duration::seconds(0),
+            )
+        };
+        let opposite_direction_orders = vec![own_order, other_order];
+
+        assert!(!amended_order_crosses_book(
+            &order,
+            dec!(20_000),
+            &opposite_direction_orders
+        ));
+        assert!(amended_order_crosses_book(
+            &order,
+            dec!(19_000),
+            &opposite_direction_orders
+        ));
+    }
+
+    fn filled_quantity(match_params: &TraderMatchParams) -> Decimal {
+        match_params
+            .filled_with
+            .matches
+            .iter()
+            .fold(Decimal::ZERO, |acc, m| acc + m.quantity)
+    }
+
+    fn dummy_long_order(
+        price: Decimal,
+        id: Uuid,
+        quantity: Decimal,
+        timestamp_delay: Duration,
+    ) -> Order {
+        Order {
+            id,
+            price,
+            trader_id: PublicKey::from_str(
+                "02bd998ebd176715fe92b7467cf6b1df8023950a4dd911db4c94dfc89cc9f5a655",
+            )
+            .unwrap(),
+            direction: Direction::Long,
+            leverage: 1.0,
+            contract_symbol: ContractSymbol::BtcUsd,
+            quantity,
+            order_type: OrderType::Limit,
+            timestamp: OffsetDateTime::now_utc() + timestamp_delay,
+            expiry: OffsetDateTime::now_utc() + Duration::minutes(1),
+            order_state: OrderState::Open,
+            order_reason: OrderReason::Manual,
+            stable: false,
+            time_in_force: TimeInForce::GoodTillTime,
+            post_only: false,
+        }
+    }
+
+    fn get_oracle_params() -> OracleParams {
+        OracleParams::single(
+            XOnlyPublicKey::from_str(
+                "16f88cf7d21e6c0f46bcbc983a4e3b19726c6c98858cc31c83551a88fde171c0",
+            )
+            .unwrap(),
+        )
     }
 }
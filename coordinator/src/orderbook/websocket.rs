@@ -12,10 +12,13 @@ use commons::OrderbookRequest;
 use commons::AUTH_SIGN_MESSAGE;
 use futures::SinkExt;
 use futures::StreamExt;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::mpsc;
+use trade::ContractSymbol;
 
 const WEBSOCKET_SEND_TIMEOUT: Duration = Duration::from_secs(5);
 
@@ -32,6 +35,21 @@ pub async fn websocket_connection(stream: WebSocket, state: Arc<AppState>) {
 
     let (local_sender, mut local_receiver) = mpsc::channel::<Message>(100);
 
+    // Individual orders are only forwarded if the client is allowed to see them. Every client
+    // receives the aggregated depth instead.
+    let receives_orders = {
+        let settings = state.settings.read().await;
+        Arc::new(AtomicBool::new(settings.order_feed.receives_orders(None)))
+    };
+
+    for contract_symbol in ContractSymbol::ALL {
+        let snapshot = state.depth.snapshot(contract_symbol);
+        if let Err(e) = local_sender.send(Message::DepthSnapshot(snapshot)).await {
+            tracing::error!("Failed to send depth snapshot: {e:#}");
+            return;
+        }
+    }
+
     let mut local_recv_task = tokio::spawn(async move {
         while let Some(local_msg) = local_receiver.recv().await {
             match serde_json::to_string(&local_msg) {
@@ -57,10 +75,15 @@ pub async fn websocket_connection(stream: WebSocket, state: Arc<AppState>) {
     // messages over the websocket to our client.
     let mut send_task = {
         let local_sender = local_sender.clone();
+        let receives_orders = receives_orders.clone();
         tokio::spawn(async move {
             loop {
                 match price_feed.recv().await {
                     Ok(st) => {
+                        if is_order_message(&st) && !receives_orders.load(Ordering::Relaxed) {
+                            continue;
+                        }
+
                         if let Err(error) = local_sender.send(st).await {
                             tracing::error!("Could not send message {error:#}");
                             return;
@@ -154,9 +177,19 @@ pub async fn websocket_connection(stream: WebSocket, state: Arc<AppState>) {
                                 return;
                             }
 
-                            let orders = orders::all_limit_orders(&mut conn).unwrap_or_default();
-                            if let Err(e) = local_sender.send(Message::AllOrders(orders)).await {
-                                tracing::error!(%trader_id, "Failed to send all orders to user {e:#}");
+                            let is_maker = {
+                                let settings = state.settings.read().await;
+                                settings.order_feed.receives_orders(Some(trader_id))
+                            };
+                            receives_orders.store(is_maker, Ordering::Relaxed);
+
+                            if is_maker {
+                                let orders =
+                                    orders::all_limit_orders(&mut conn).unwrap_or_default();
+                                if let Err(e) = local_sender.send(Message::AllOrders(orders)).await
+                                {
+                                    tracing::error!(%trader_id, "Failed to send all orders to user {e:#}");
+                                }
                             }
 
                             let token = fcm_token.unwrap_or("unavailable".to_string());
@@ -188,6 +221,12 @@ pub async fn websocket_connection(stream: WebSocket, state: Arc<AppState>) {
                         }
                     }
                 }
+                Ok(OrderbookRequest::DepthSnapshot { contract_symbol }) => {
+                    let snapshot = state.depth.snapshot(contract_symbol);
+                    if let Err(e) = local_sender.send(Message::DepthSnapshot(snapshot)).await {
+                        tracing::error!(%contract_symbol, "Failed to send depth snapshot: {e:#}");
+                    }
+                }
                 Err(err) => {
                     tracing::trace!("Could not deserialize msg: {text} {err:#}");
                 }
@@ -211,3 +250,11 @@ pub async fn websocket_connection(stream: WebSocket, state: Arc<AppState>) {
         },
     };
 }
+
+/// Whether the message reveals individual orders.
+fn is_order_message(message: &Message) -> bool {
+    matches!(
+        message,
+        Message::AllOrders(_) | Message::NewOrder(_) | Message::DeleteOrder(_) | Message::Update(_)
+    )
+}
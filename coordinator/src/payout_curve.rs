@@ -1,7 +1,8 @@
-use anyhow::ensure;
 use anyhow::Context;
 use anyhow::Result;
 use bitcoin::Amount;
+use commons::OracleParams;
+use dlc_manager::contract::numerical_descriptor::DifferenceParams;
 use dlc_manager::contract::numerical_descriptor::NumericalDescriptor;
 use dlc_manager::contract::ContractDescriptor;
 use dlc_manager::payout_curve::PayoutFunction;
@@ -10,19 +11,22 @@ use dlc_manager::payout_curve::PayoutPoint;
 use dlc_manager::payout_curve::PolynomialPayoutCurvePiece;
 use dlc_manager::payout_curve::RoundingInterval;
 use dlc_manager::payout_curve::RoundingIntervals;
-use payout_curve::ROUNDING_PERCENT;
+use payout_curve::DiscretizationParams;
 use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::Decimal;
 use tracing::instrument;
 use trade::cfd::calculate_long_liquidation_price;
 use trade::cfd::calculate_short_liquidation_price;
-use trade::cfd::BTCUSD_MAX_PRICE;
 use trade::ContractSymbol;
 use trade::Direction;
+use trade::PayoutType;
 
 /// Builds the contract descriptor from the point of view of the coordinator.
 ///
 /// It's the direction of the coordinator because the coordinator is always proposing.
+///
+/// The descriptor expects one attestation per oracle in `oracle_params`, all of them attesting to
+/// the price with the number of digits of the given `symbol`.
 #[instrument]
 #[allow(clippy::too_many_arguments)]
 pub fn build_contract_descriptor(
@@ -36,34 +40,38 @@ pub fn build_contract_descriptor(
     trader_collateral_reserve: u64,
     quantity: f32,
     symbol: ContractSymbol,
+    oracle_params: &OracleParams,
 ) -> Result<ContractDescriptor> {
-    ensure!(
-        symbol == ContractSymbol::BtcUsd,
-        "We only support BTCUSD at the moment. \
-         For other symbols we will need a different payout curve"
-    );
-
     tracing::info!("Building contract descriptor");
 
-    let (payout_function, rounding_intervals) = build_inverse_payout_function(
-        coordinator_margin,
-        trader_margin,
-        initial_price,
-        leverage_trader,
-        leverage_coordinator,
-        coordinator_collateral_reserve,
-        trader_collateral_reserve,
-        coordinator_direction,
-        quantity,
-    )?;
+    let (payout_function, rounding_intervals) = match symbol.payout_type() {
+        PayoutType::Inverse => build_inverse_payout_function(
+            coordinator_margin,
+            trader_margin,
+            initial_price,
+            leverage_trader,
+            leverage_coordinator,
+            coordinator_collateral_reserve,
+            trader_collateral_reserve,
+            coordinator_direction,
+            quantity,
+            symbol,
+        )?,
+    };
 
     Ok(ContractDescriptor::Numerical(NumericalDescriptor {
         payout_function,
         rounding_intervals,
-        difference_params: None,
+        difference_params: oracle_params
+            .difference_params
+            .map(|params| DifferenceParams {
+                max_error_exp: params.max_error_exp,
+                min_support_exp: params.min_support_exp,
+                maximize_coverage: params.maximize_coverage,
+            }),
         oracle_numeric_infos: dlc_trie::OracleNumericInfo {
             base: 2,
-            nb_digits: vec![20],
+            nb_digits: vec![symbol.oracle_nb_digits(); oracle_params.pubkeys.len()],
         },
     }))
 }
@@ -83,6 +91,7 @@ fn build_inverse_payout_function(
     trader_collateral_reserve: u64,
     coordinator_direction: Direction,
     quantity: f32,
+    symbol: ContractSymbol,
 ) -> Result<(PayoutFunction, RoundingIntervals)> {
     let leverage_coordinator =
         Decimal::from_f32(leverage_coordinator).expect("to fit into decimal");
@@ -100,7 +109,8 @@ fn build_inverse_payout_function(
         Direction::Short => (trader_liquidation_price, coordinator_liquidation_price),
     };
 
-    let price_params = payout_curve::PriceParams::new_btc_usd(
+    let price_params = payout_curve::PriceParams::new_for_contract(
+        symbol,
         initial_price,
         long_liquidation_price,
         short_liquidation_price,
@@ -159,9 +169,11 @@ fn build_inverse_payout_function(
         let total_margin = coordinator_margin + trader_margin;
 
         create_rounding_intervals(
+            initial_price,
             total_margin,
             adjusted_long_liquidation_price,
             adjusted_short_liquidation_price,
+            symbol.max_price(),
         )
     };
 
@@ -189,58 +201,39 @@ fn get_liquidation_prices(
 }
 
 pub fn create_rounding_intervals(
+    initial_price: Decimal,
     total_margin: u64,
     long_liquidation_price: u64,
     short_liquidation_price: u64,
+    max_price: u64,
 ) -> RoundingIntervals {
-    let liquidation_diff = short_liquidation_price
-        .checked_sub(long_liquidation_price)
-        .expect("short liquidation to be higher than long liquidation");
-    let low_price = long_liquidation_price + liquidation_diff / 10;
-    let high_price = short_liquidation_price - liquidation_diff / 10;
-
-    let mut intervals = vec![
-        RoundingInterval {
-            begin_interval: 0,
-            // No rounding.
-            rounding_mod: 1,
-        },
-        // HACK: We decrease the rounding here to prevent `rust-dlc` from rounding under the long
-        // liquidation price _payout_.
-        RoundingInterval {
-            begin_interval: long_liquidation_price,
-            rounding_mod: (total_margin as f32 * ROUNDING_PERCENT * 0.1) as u64,
-        },
-        RoundingInterval {
-            begin_interval: low_price,
-            rounding_mod: (total_margin as f32 * ROUNDING_PERCENT) as u64,
-        },
-    ];
-
-    if short_liquidation_price < BTCUSD_MAX_PRICE {
-        intervals.push(
-            // HACK: We decrease the rounding here to prevent `rust-dlc` from rounding over the
-            // short liquidation price _payout_.
-            RoundingInterval {
-                begin_interval: high_price,
-                rounding_mod: (total_margin as f32 * ROUNDING_PERCENT * 0.1) as u64,
-            },
-        );
-        intervals.push(RoundingInterval {
-            begin_interval: short_liquidation_price,
-            // No rounding.
-            rounding_mod: 1,
-        })
-    }
+    let intervals = payout_curve::build_rounding_intervals(
+        initial_price,
+        total_margin,
+        long_liquidation_price,
+        short_liquidation_price,
+        max_price,
+        DiscretizationParams::default(),
+    );
 
-    RoundingIntervals { intervals }
+    RoundingIntervals {
+        intervals: intervals
+            .into_iter()
+            .map(|interval| RoundingInterval {
+                begin_interval: interval.begin_interval,
+                rounding_mod: interval.rounding_mod,
+            })
+            .collect(),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bitcoin::XOnlyPublicKey;
     use commons::order_matching_fee_taker;
     use rust_decimal_macros::dec;
+    use std::str::FromStr;
     use trade::cfd::calculate_margin;
 
     #[test]
@@ -274,6 +267,7 @@ mod tests {
             trader_collateral_reserve,
             quantity,
             symbol,
+            &dummy_oracle_params(),
         )
         .unwrap();
 
@@ -284,7 +278,7 @@ mod tests {
             }
         };
 
-        let max_price = 2usize.pow(20);
+        let max_price = 2usize.pow(symbol.oracle_nb_digits() as u32);
 
         for range_payout in &range_payouts {
             assert!(
@@ -362,7 +356,61 @@ mod tests {
             trader_collateral_reserve,
             quantity,
             symbol,
+            &dummy_oracle_params(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn build_contract_descriptor_with_multiple_oracles() {
+        let oracle_params = OracleParams {
+            pubkeys: vec![dummy_oracle_pk(); 3],
+            threshold: 2,
+            difference_params: Some(commons::DifferenceParams {
+                max_error_exp: 8,
+                min_support_exp: 4,
+                maximize_coverage: false,
+            }),
+        };
+
+        let descriptor = build_contract_descriptor(
+            dec!(36404.5),
+            18_313,
+            27_469,
+            2.0,
+            3.0,
+            Direction::Short,
+            0,
+            0,
+            20.0,
+            ContractSymbol::BtcUsd,
+            &oracle_params,
         )
         .unwrap();
+
+        let numerical = match descriptor {
+            ContractDescriptor::Enum(_) => unreachable!(),
+            ContractDescriptor::Numerical(numerical) => numerical,
+        };
+
+        assert_eq!(
+            numerical.oracle_numeric_infos.nb_digits,
+            vec![ContractSymbol::BtcUsd.oracle_nb_digits(); 3]
+        );
+        assert_eq!(
+            numerical
+                .difference_params
+                .map(|params| params.max_error_exp),
+            Some(8)
+        );
+    }
+
+    fn dummy_oracle_params() -> OracleParams {
+        OracleParams::single(dummy_oracle_pk())
+    }
+
+    fn dummy_oracle_pk() -> XOnlyPublicKey {
+        XOnlyPublicKey::from_str("16f88cf7d21e6c0f46bcbc983a4e3b19726c6c98858cc31c83551a88fde171c0")
+            .unwrap()
     }
 }
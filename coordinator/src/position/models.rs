@@ -16,7 +16,6 @@ use rust_decimal::prelude::Signed;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use time::OffsetDateTime;
-use trade::bitmex_client::Quote;
 use trade::cfd::calculate_margin;
 use trade::cfd::calculate_pnl;
 use trade::ContractSymbol;
@@ -108,10 +107,22 @@ impl Position {
         OffsetDateTime::now_utc() >= self.expiry_timestamp
     }
 
+    /// Returns true if the given mark price crossed the traders liquidation price.
+    pub fn is_liquidated(&self, mark_price: Decimal) -> bool {
+        let liquidation_price = decimal_from_f32(self.trader_liquidation_price);
+
+        match self.trader_direction {
+            Direction::Long => mark_price <= liquidation_price,
+            Direction::Short => mark_price >= liquidation_price,
+        }
+    }
+
     /// Calculates the profit and loss for the coordinator in satoshis
-    pub fn calculate_coordinator_pnl(&self, quote: Quote) -> Result<i64> {
+    ///
+    /// Open positions are valued at the given mark price, closing positions at their closing price.
+    pub fn calculate_coordinator_pnl(&self, mark_price: Decimal) -> Result<i64> {
         let closing_price = match self.closing_price {
-            None => quote.get_price_for_direction(self.trader_direction.opposite()),
+            None => mark_price,
             Some(closing_price) => {
                 Decimal::try_from(closing_price).expect("f32 closing price to fit into decimal")
             }
@@ -454,6 +465,28 @@ mod tests {
     use rust_decimal_macros::dec;
     use std::str::FromStr;
 
+    #[test]
+    fn given_mark_price_below_liquidation_price_then_long_position_is_liquidated() {
+        let position = Position::dummy()
+            .with_direction(Direction::Long)
+            .with_liquidation_price(20_000.0);
+
+        assert!(position.is_liquidated(dec!(19_999)));
+        assert!(position.is_liquidated(dec!(20_000)));
+        assert!(!position.is_liquidated(dec!(20_001)));
+    }
+
+    #[test]
+    fn given_mark_price_above_liquidation_price_then_short_position_is_liquidated() {
+        let position = Position::dummy()
+            .with_direction(Direction::Short)
+            .with_liquidation_price(20_000.0);
+
+        assert!(position.is_liquidated(dec!(20_001)));
+        assert!(position.is_liquidated(dec!(20_000)));
+        assert!(!position.is_liquidated(dec!(19_999)));
+    }
+
     #[test]
     fn position_calculate_coordinator_settlement_amount() {
         let position = Position {
@@ -751,31 +784,31 @@ mod tests {
     }
 
     #[test]
-    fn given_trader_long_position_when_no_bid_price_change_then_zero_coordinator_pnl() {
+    fn given_trader_long_position_when_no_mark_price_change_then_zero_coordinator_pnl() {
         let position = Position::dummy()
             .with_leverage(2.0)
             .with_quantity(1.0)
             .with_average_entry_price(1000.0)
             .with_direction(Direction::Long);
 
-        let quote = dummy_quote(1000, 0);
+        let mark_price = Decimal::from(1000);
 
-        let coordinator_pnl = position.calculate_coordinator_pnl(quote).unwrap();
+        let coordinator_pnl = position.calculate_coordinator_pnl(mark_price).unwrap();
 
         assert_eq!(coordinator_pnl, 0);
     }
 
     #[test]
-    fn given_trader_short_position_when_no_ask_price_change_then_zero_coordinator_pnl() {
+    fn given_trader_short_position_when_no_mark_price_change_then_zero_coordinator_pnl() {
         let position = Position::dummy()
             .with_leverage(2.0)
             .with_quantity(1.0)
             .with_average_entry_price(1000.0)
             .with_direction(Direction::Short);
 
-        let quote = dummy_quote(0, 1000);
+        let mark_price = Decimal::from(1000);
 
-        let coordinator_pnl = position.calculate_coordinator_pnl(quote).unwrap();
+        let coordinator_pnl = position.calculate_coordinator_pnl(mark_price).unwrap();
 
         assert_eq!(coordinator_pnl, 0);
     }
@@ -795,64 +828,64 @@ mod tests {
     ///
     /// See also: `given_long_position_when_price_10_pc_up_then_18pc_profit` test in `trade::cfd`
     #[test]
-    fn given_trader_long_position_when_bid_price_10pc_up_then_coordinator_9pc_loss() {
+    fn given_trader_long_position_when_mark_price_10pc_up_then_coordinator_9pc_loss() {
         let position = Position::dummy()
             .with_leverage(2.0)
             .with_quantity(20000.0)
             .with_average_entry_price(20000.0)
             .with_direction(Direction::Long);
 
-        let quote = dummy_quote(22000, 0);
+        let mark_price = Decimal::from(22000);
 
-        let coordinator_pnl = position.calculate_coordinator_pnl(quote).unwrap();
+        let coordinator_pnl = position.calculate_coordinator_pnl(mark_price).unwrap();
 
         assert_eq!(coordinator_pnl, -9_090_909);
     }
 
     /// See also: `given_short_position_when_price_10_pc_up_then_18pc_loss` test in `trade::cfd`
     #[test]
-    fn given_trader_short_position_when_ask_price_10pc_up_then_coordinator_9pc_profit() {
+    fn given_trader_short_position_when_mark_price_10pc_up_then_coordinator_9pc_profit() {
         let position = Position::dummy()
             .with_leverage(2.0)
             .with_quantity(20000.0)
             .with_average_entry_price(20000.0)
             .with_direction(Direction::Short);
 
-        let quote = dummy_quote(0, 22000);
+        let mark_price = Decimal::from(22000);
 
-        let coordinator_pnl = position.calculate_coordinator_pnl(quote).unwrap();
+        let coordinator_pnl = position.calculate_coordinator_pnl(mark_price).unwrap();
 
         assert_eq!(coordinator_pnl, 9_090_909);
     }
 
     /// See also: `given_long_position_when_price_10_pc_down_then_22pc_loss` test in `trade::cfd`
     #[test]
-    fn given_trader_long_position_when_bid_price_10pc_down_then_coordinator_11pc_profit() {
+    fn given_trader_long_position_when_mark_price_10pc_down_then_coordinator_11pc_profit() {
         let position = Position::dummy()
             .with_leverage(2.0)
             .with_quantity(20000.0)
             .with_average_entry_price(20000.0)
             .with_direction(Direction::Long);
 
-        let quote = dummy_quote(18000, 0);
+        let mark_price = Decimal::from(18000);
 
-        let coordinator_pnl = position.calculate_coordinator_pnl(quote).unwrap();
+        let coordinator_pnl = position.calculate_coordinator_pnl(mark_price).unwrap();
 
         assert_eq!(coordinator_pnl, 11_111_111);
     }
 
     /// See also: `given_short_position_when_price_10_pc_down_then_22pc_profit` test in `trade::cfd`
     #[test]
-    fn given_trader_short_position_when_ask_price_10pc_down_then_coordinator_11pc_loss() {
+    fn given_trader_short_position_when_mark_price_10pc_down_then_coordinator_11pc_loss() {
         let position = Position::dummy()
             .with_leverage(2.0)
             .with_quantity(20000.0)
             .with_average_entry_price(20000.0)
             .with_direction(Direction::Short);
 
-        let quote = dummy_quote(0, 18000);
+        let mark_price = Decimal::from(18000);
 
-        let coordinator_pnl = position.calculate_coordinator_pnl(quote).unwrap();
+        let coordinator_pnl = position.calculate_coordinator_pnl(mark_price).unwrap();
 
         assert_eq!(coordinator_pnl, -11_111_111);
     }
@@ -952,17 +985,6 @@ mod tests {
         );
     }
 
-    fn dummy_quote(bid: u64, ask: u64) -> Quote {
-        Quote {
-            bid_size: 0,
-            ask_size: 0,
-            bid_price: Decimal::from(bid),
-            ask_price: Decimal::from(ask),
-            symbol: "".to_string(),
-            timestamp: OffsetDateTime::now_utc(),
-        }
-    }
-
     impl Position {
         fn dummy() -> Self {
             Position {
@@ -1010,5 +1032,10 @@ mod tests {
             self.trader_direction = direction;
             self
         }
+
+        fn with_liquidation_price(mut self, liquidation_price: f32) -> Self {
+            self.trader_liquidation_price = liquidation_price;
+            self
+        }
     }
 }
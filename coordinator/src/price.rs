@@ -0,0 +1,440 @@
+use crate::db;
+use crate::settings::PriceFeedSource;
+use crate::settings::PriceSettings;
+use anyhow::ensure;
+use anyhow::Context;
+use anyhow::Result;
+use async_trait::async_trait;
+use bitcoin::Network;
+use diesel::r2d2::ConnectionManager;
+use diesel::r2d2::Pool;
+use diesel::PgConnection;
+use futures::future::join_all;
+use futures::future::RemoteHandle;
+use futures::FutureExt;
+use parking_lot::RwLock;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::task::spawn_blocking;
+use trade::ContractSymbol;
+
+pub mod feeds;
+
+/// How often the index and mark prices are updated.
+const PRICE_UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Prices older than this are considered stale and are not handed out anymore.
+const MAX_PRICE_AGE: time::Duration = time::Duration::minutes(2);
+
+/// A source for the price of a contract.
+#[async_trait]
+pub trait PriceFeed: Send + Sync {
+    /// The name of the feed, used for logging.
+    fn name(&self) -> &'static str;
+
+    /// The current mid price of the given contract.
+    async fn get_price(&self, contract_symbol: ContractSymbol) -> Result<Decimal>;
+}
+
+/// The index and mark price of a contract.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketPrices {
+    /// The median of the prices of all feeds, ignoring outliers.
+    pub index_price: Decimal,
+    /// The exponential moving average of the index price.
+    ///
+    /// Used to value positions, as it is less prone to short-lived spikes than the index price.
+    pub mark_price: Decimal,
+    pub timestamp: OffsetDateTime,
+}
+
+/// Computes the index and mark prices of all contracts from the configured price feeds.
+#[derive(Clone)]
+pub struct PriceService {
+    feeds: Arc<Vec<Box<dyn PriceFeed>>>,
+    max_feed_deviation: Decimal,
+    ema_alpha: Decimal,
+    /// The period the mark price is averaged over.
+    ema_window: time::Duration,
+    prices: Arc<RwLock<HashMap<ContractSymbol, MarketPrices>>>,
+}
+
+impl PriceService {
+    pub fn new(feeds: Vec<Box<dyn PriceFeed>>, settings: &PriceSettings) -> Self {
+        let max_feed_deviation =
+            Decimal::from_f32(settings.max_feed_deviation).expect("to fit into decimal");
+        let ema_samples = settings.mark_price_ema_samples.max(1);
+        let ema_alpha = Decimal::TWO / Decimal::from(ema_samples + 1);
+        let ema_window = PRICE_UPDATE_INTERVAL * ema_samples;
+
+        Self {
+            feeds: Arc::new(feeds),
+            max_feed_deviation,
+            ema_alpha,
+            ema_window: time::Duration::try_from(ema_window).expect("to fit into duration"),
+            prices: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Creates a price service using the feeds configured in the given settings.
+    pub fn from_settings(
+        settings: &PriceSettings,
+        network: Network,
+        pool: Pool<ConnectionManager<PgConnection>>,
+    ) -> Self {
+        let feeds = settings
+            .feeds
+            .iter()
+            .map(|feed| match feed {
+                PriceFeedSource::Bitmex => {
+                    Box::new(feeds::BitmexFeed::new(network)) as Box<dyn PriceFeed>
+                }
+                PriceFeedSource::Orderbook => Box::new(feeds::OrderbookFeed::new(pool.clone())),
+            })
+            .collect();
+
+        Self::new(feeds, settings)
+    }
+
+    /// The current index and mark price of the given contract.
+    ///
+    /// Fails if the prices have not been updated recently, e.g. because all feeds are down.
+    pub fn get_prices(&self, contract_symbol: ContractSymbol) -> Result<MarketPrices> {
+        let prices = self
+            .prices
+            .read()
+            .get(&contract_symbol)
+            .copied()
+            .with_context(|| format!("No price available for {contract_symbol}"))?;
+
+        ensure!(
+            OffsetDateTime::now_utc() - prices.timestamp <= MAX_PRICE_AGE,
+            "Price of {contract_symbol} is outdated: {}",
+            prices.timestamp
+        );
+
+        Ok(prices)
+    }
+
+    /// Fetches the price of the given contract from all feeds and updates its index and mark
+    /// price.
+    pub async fn update(&self, contract_symbol: ContractSymbol) -> Result<MarketPrices> {
+        let results = join_all(
+            self.feeds
+                .iter()
+                .map(|feed| feed.get_price(contract_symbol)),
+        )
+        .await;
+
+        let mut feed_prices = vec![];
+        for (feed, result) in self.feeds.iter().zip(results) {
+            match result {
+                Ok(price) => feed_prices.push(price),
+                Err(e) => {
+                    tracing::warn!(feed = feed.name(), %contract_symbol, "Failed to get price from feed: {e:#}")
+                }
+            }
+        }
+
+        let index_price = robust_median(&feed_prices, self.max_feed_deviation)
+            .with_context(|| format!("Price feeds for {contract_symbol} do not agree"))?;
+
+        let mut prices = self.prices.write();
+
+        let mark_price = match prices.get(&contract_symbol) {
+            Some(previous) => {
+                previous.mark_price + self.ema_alpha * (index_price - previous.mark_price)
+            }
+            None => index_price,
+        };
+
+        let market_prices = MarketPrices {
+            index_price,
+            mark_price,
+            timestamp: OffsetDateTime::now_utc(),
+        };
+        prices.insert(contract_symbol, market_prices);
+
+        Ok(market_prices)
+    }
+
+    /// Restores the last known prices, so that the moving average of the mark price survives a
+    /// restart.
+    ///
+    /// Prices older than the period the mark price is averaged over are discarded, so that the
+    /// mark price restarts from the index price instead of lagging behind the market after a
+    /// longer downtime.
+    fn restore(&self, contract_symbol: ContractSymbol, prices: MarketPrices) {
+        let age = OffsetDateTime::now_utc() - prices.timestamp;
+        if age > self.ema_window {
+            tracing::debug!(%contract_symbol, timestamp = %prices.timestamp, "Not restoring outdated prices");
+            return;
+        }
+
+        self.prices.write().entry(contract_symbol).or_insert(prices);
+    }
+}
+
+/// Periodically updates the prices of all contracts and persists them in the price history.
+pub fn monitor(
+    pool: Pool<ConnectionManager<PgConnection>>,
+    price_service: PriceService,
+) -> RemoteHandle<()> {
+    let (fut, remote_handle) = async move {
+        if let Err(e) = restore_prices(pool.clone(), &price_service).await {
+            tracing::error!("Failed to restore prices from price history: {e:#}");
+        }
+
+        loop {
+            for contract_symbol in ContractSymbol::ALL {
+                match price_service.update(contract_symbol).await {
+                    Ok(prices) => {
+                        if let Err(e) = persist_prices(pool.clone(), contract_symbol, prices).await
+                        {
+                            tracing::error!(%contract_symbol, "Failed to persist prices: {e:#}");
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!(%contract_symbol, "Failed to update prices: {e:#}");
+                    }
+                }
+            }
+
+            tokio::time::sleep(PRICE_UPDATE_INTERVAL).await;
+        }
+    }
+    .remote_handle();
+
+    tokio::spawn(fut);
+
+    remote_handle
+}
+
+async fn restore_prices(
+    pool: Pool<ConnectionManager<PgConnection>>,
+    price_service: &PriceService,
+) -> Result<()> {
+    let latest_prices = spawn_blocking(move || {
+        let mut conn = pool.get()?;
+
+        let mut latest_prices = vec![];
+        for contract_symbol in ContractSymbol::ALL {
+            if let Some(price) = db::price_history::get_latest(&mut conn, contract_symbol)? {
+                latest_prices.push((contract_symbol, price));
+            }
+        }
+
+        anyhow::Ok(latest_prices)
+    })
+    .await
+    .expect("task to complete")?;
+
+    for (contract_symbol, price) in latest_prices {
+        price_service.restore(
+            contract_symbol,
+            MarketPrices {
+                index_price: price.index_price,
+                mark_price: price.mark_price,
+                timestamp: price.timestamp,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+async fn persist_prices(
+    pool: Pool<ConnectionManager<PgConnection>>,
+    contract_symbol: ContractSymbol,
+    prices: MarketPrices,
+) -> Result<()> {
+    spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        db::price_history::insert(
+            &mut conn,
+            contract_symbol,
+            prices.index_price,
+            prices.mark_price,
+            prices.timestamp,
+        )?;
+
+        anyhow::Ok(())
+    })
+    .await
+    .expect("task to complete")
+}
+
+/// The median of the given prices, ignoring prices which deviate from the median by more than
+/// `max_deviation` (relative to the median).
+///
+/// Returns `None` if there are no prices or if all prices are outliers, e.g. if two feeds
+/// disagree.
+fn robust_median(prices: &[Decimal], max_deviation: Decimal) -> Option<Decimal> {
+    let median = median(prices)?;
+    if median.is_zero() {
+        return None;
+    }
+
+    let inliers = prices
+        .iter()
+        .copied()
+        .filter(|price| ((price - median) / median).abs() <= max_deviation)
+        .collect::<Vec<_>>();
+
+    self::median(&inliers)
+}
+
+fn median(prices: &[Decimal]) -> Option<Decimal> {
+    if prices.is_empty() {
+        return None;
+    }
+
+    let mut prices = prices.to_vec();
+    prices.sort();
+
+    let mid = prices.len() / 2;
+    let median = if prices.len() % 2 == 0 {
+        (prices[mid - 1] + prices[mid]) / Decimal::TWO
+    } else {
+        prices[mid]
+    };
+
+    Some(median)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::bail;
+    use rust_decimal_macros::dec;
+
+    struct FixedFeed(Option<Decimal>);
+
+    #[async_trait]
+    impl PriceFeed for FixedFeed {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        async fn get_price(&self, _: ContractSymbol) -> Result<Decimal> {
+            match self.0 {
+                Some(price) => Ok(price),
+                None => bail!("No price"),
+            }
+        }
+    }
+
+    fn price_service(prices: &[Option<Decimal>]) -> PriceService {
+        let feeds = prices
+            .iter()
+            .map(|price| Box::new(FixedFeed(*price)) as Box<dyn PriceFeed>)
+            .collect();
+
+        PriceService::new(
+            feeds,
+            &PriceSettings {
+                feeds: vec![],
+                max_feed_deviation: 0.05,
+                mark_price_ema_samples: 3,
+            },
+        )
+    }
+
+    #[test]
+    fn outliers_are_ignored_in_median() {
+        let median = robust_median(
+            &[dec!(40_000), dec!(40_100), dec!(39_900), dec!(60_000)],
+            dec!(0.05),
+        );
+
+        assert_eq!(median, Some(dec!(40_000)));
+    }
+
+    #[test]
+    fn disagreeing_feeds_have_no_median() {
+        let median = robust_median(&[dec!(40_000), dec!(60_000)], dec!(0.05));
+
+        assert_eq!(median, None);
+    }
+
+    #[tokio::test]
+    async fn failing_feeds_are_ignored() {
+        let price_service = price_service(&[Some(dec!(40_000)), None]);
+
+        let prices = price_service.update(ContractSymbol::BtcUsd).await.unwrap();
+
+        assert_eq!(prices.index_price, dec!(40_000));
+        assert_eq!(prices.mark_price, dec!(40_000));
+    }
+
+    #[tokio::test]
+    async fn mark_price_follows_index_price_gradually() {
+        let price_service = price_service(&[Some(dec!(40_000))]);
+        price_service.update(ContractSymbol::BtcUsd).await.unwrap();
+
+        let price_service = PriceService {
+            feeds: Arc::new(vec![Box::new(FixedFeed(Some(dec!(42_000))))]),
+            ..price_service
+        };
+        let prices = price_service.update(ContractSymbol::BtcUsd).await.unwrap();
+
+        // With 3 samples the weight of the new index price is 2 / (3 + 1).
+        assert_eq!(prices.index_price, dec!(42_000));
+        assert_eq!(prices.mark_price, dec!(41_000));
+    }
+
+    #[tokio::test]
+    async fn restored_mark_price_is_averaged_with_index_price() {
+        let price_service = price_service(&[Some(dec!(42_000))]);
+        price_service.restore(
+            ContractSymbol::BtcUsd,
+            MarketPrices {
+                index_price: dec!(40_000),
+                mark_price: dec!(40_000),
+                timestamp: OffsetDateTime::now_utc() - time::Duration::seconds(10),
+            },
+        );
+
+        let prices = price_service.update(ContractSymbol::BtcUsd).await.unwrap();
+
+        assert_eq!(prices.mark_price, dec!(41_000));
+    }
+
+    #[tokio::test]
+    async fn outdated_mark_price_is_not_restored() {
+        let price_service = price_service(&[Some(dec!(42_000))]);
+        price_service.restore(
+            ContractSymbol::BtcUsd,
+            MarketPrices {
+                index_price: dec!(40_000),
+                mark_price: dec!(40_000),
+                timestamp: OffsetDateTime::now_utc()
+                    - price_service.ema_window
+                    - time::Duration::seconds(1),
+            },
+        );
+
+        let prices = price_service.update(ContractSymbol::BtcUsd).await.unwrap();
+
+        assert_eq!(prices.mark_price, dec!(42_000));
+    }
+
+    #[test]
+    fn stale_prices_are_not_handed_out() {
+        let price_service = price_service(&[]);
+        price_service.prices.write().insert(
+            ContractSymbol::BtcUsd,
+            MarketPrices {
+                index_price: dec!(40_000),
+                mark_price: dec!(40_000),
+                timestamp: OffsetDateTime::now_utc() - MAX_PRICE_AGE - time::Duration::seconds(1),
+            },
+        );
+
+        assert!(price_service.get_prices(ContractSymbol::BtcUsd).is_err());
+    }
+}
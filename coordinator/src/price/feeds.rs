@@ -0,0 +1,75 @@
+use crate::orderbook;
+use crate::price::PriceFeed;
+use anyhow::Context;
+use anyhow::Result;
+use async_trait::async_trait;
+use bitcoin::Network;
+use diesel::r2d2::ConnectionManager;
+use diesel::r2d2::Pool;
+use diesel::PgConnection;
+use rust_decimal::Decimal;
+use time::OffsetDateTime;
+use tokio::task::spawn_blocking;
+use trade::bitmex_client::BitmexClient;
+use trade::ContractSymbol;
+
+/// The mid price of the BitMEX order book.
+pub struct BitmexFeed {
+    network: Network,
+}
+
+impl BitmexFeed {
+    pub fn new(network: Network) -> Self {
+        Self { network }
+    }
+}
+
+#[async_trait]
+impl PriceFeed for BitmexFeed {
+    fn name(&self) -> &'static str {
+        "bitmex"
+    }
+
+    async fn get_price(&self, contract_symbol: ContractSymbol) -> Result<Decimal> {
+        let quote =
+            BitmexClient::get_quote(&self.network, &OffsetDateTime::now_utc(), contract_symbol)
+                .await
+                .context("Failed to fetch quote from BitMEX")?;
+
+        Ok((quote.bid_price + quote.ask_price) / Decimal::TWO)
+    }
+}
+
+/// The mid price of our own orderbook.
+pub struct OrderbookFeed {
+    pool: Pool<ConnectionManager<PgConnection>>,
+}
+
+impl OrderbookFeed {
+    pub fn new(pool: Pool<ConnectionManager<PgConnection>>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PriceFeed for OrderbookFeed {
+    fn name(&self) -> &'static str {
+        "orderbook"
+    }
+
+    async fn get_price(&self, contract_symbol: ContractSymbol) -> Result<Decimal> {
+        let pool = self.pool.clone();
+        let orders = spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            let orders = orderbook::db::orders::all_limit_orders(&mut conn)?;
+            anyhow::Ok(orders)
+        })
+        .await
+        .expect("task to complete")?;
+
+        commons::best_current_price(&orders)
+            .get(&contract_symbol)
+            .and_then(|price| price.mid())
+            .with_context(|| format!("Orderbook has no bid and ask for {contract_symbol}"))
+    }
+}
@@ -1,18 +1,28 @@
+use crate::admin::abandon_psbt;
+use crate::admin::broadcast_psbt;
+use crate::admin::bump_fee;
 use crate::admin::close_channel;
 use crate::admin::close_ln_dlc_channel;
 use crate::admin::collaborative_revert;
 use crate::admin::connect_to_peer;
+use crate::admin::consolidate_utxos;
+use crate::admin::create_psbt;
 use crate::admin::delete_dlc_channels;
 use crate::admin::get_balance;
 use crate::admin::get_fee_rate_estimation;
 use crate::admin::get_utxos;
+use crate::admin::get_watch_only_address;
+use crate::admin::get_watch_only_balance;
 use crate::admin::is_connected;
 use crate::admin::legacy_collaborative_revert;
 use crate::admin::list_channels;
 use crate::admin::list_dlc_channels;
+use crate::admin::list_funding_psbts;
 use crate::admin::list_on_chain_transactions;
 use crate::admin::list_peers;
 use crate::admin::open_channel;
+use crate::admin::put_utxo;
+use crate::admin::send_on_chain;
 use crate::admin::send_payment;
 use crate::admin::sign_message;
 use crate::backup::SledBackup;
@@ -26,19 +36,31 @@ use crate::leaderboard::generate_leader_board;
 use crate::leaderboard::LeaderBoard;
 use crate::leaderboard::LeaderBoardCategory;
 use crate::leaderboard::LeaderBoardQueryParams;
+use crate::market_data::candle_time_range;
+use crate::market_data::get_candles;
+use crate::market_data::CandleQueryParams;
 use crate::message::NewUserMessage;
 use crate::message::OrderbookMessage;
 use crate::node::Node;
+use crate::orderbook::depth::OrderbookDepth;
+use crate::orderbook::routes::delete_order;
+use crate::orderbook::routes::delete_orders;
+use crate::orderbook::routes::delete_trigger_order;
 use crate::orderbook::routes::get_order;
 use crate::orderbook::routes::get_orders;
+use crate::orderbook::routes::get_trigger_orders;
+use crate::orderbook::routes::patch_order;
 use crate::orderbook::routes::post_order;
+use crate::orderbook::routes::post_trigger_order;
 use crate::orderbook::routes::put_order;
+use crate::orderbook::routes::put_trigger_order;
 use crate::orderbook::routes::websocket_handler;
 use crate::orderbook::trading::NewOrderMessage;
 use crate::parse_channel_id;
 use crate::parse_dlc_channel_id;
 use crate::settings::Settings;
 use crate::settings::SettingsFile;
+use crate::signed_request::NonceCache;
 use crate::AppError;
 use axum::extract::DefaultBodyLimit;
 use axum::extract::Path;
@@ -49,6 +71,7 @@ use axum::response::IntoResponse;
 use axum::routing::delete;
 use axum::routing::get;
 use axum::routing::post;
+use axum::routing::put;
 use axum::Json;
 use axum::Router;
 use bitcoin::consensus::encode::serialize_hex;
@@ -56,6 +79,7 @@ use bitcoin::hashes::hex::ToHex;
 use bitcoin::secp256k1::ecdsa::Signature;
 use bitcoin::secp256k1::PublicKey;
 use commons::Backup;
+use commons::Candle;
 use commons::CollaborativeRevertTraderResponse;
 use commons::DeleteBackup;
 use commons::Message;
@@ -64,8 +88,11 @@ use commons::Poll;
 use commons::PollAnswers;
 use commons::RegisterParams;
 use commons::Restore;
+use commons::RolloverResponse;
 use commons::RouteHintHop;
+use commons::SignedRequest;
 use commons::TradeParams;
+use commons::VerifiedRequest;
 use diesel::r2d2::ConnectionManager;
 use diesel::r2d2::Pool;
 use diesel::PgConnection;
@@ -86,11 +113,13 @@ use serde::Deserialize;
 use serde::Serialize;
 use std::str::FromStr;
 use std::sync::Arc;
+use time::OffsetDateTime;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc;
 use tokio::sync::RwLock;
 use tokio::task::spawn_blocking;
 use tracing::instrument;
+use trade::ContractSymbol;
 
 pub struct AppState {
     pub node: Node,
@@ -105,6 +134,8 @@ pub struct AppState {
     pub node_alias: String,
     pub auth_users_notifier: mpsc::Sender<OrderbookMessage>,
     pub user_backup: SledBackup,
+    pub depth: OrderbookDepth,
+    pub nonces: NonceCache,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -120,6 +151,7 @@ pub fn router(
     tx_user_feed: broadcast::Sender<NewUserMessage>,
     auth_users_notifier: mpsc::Sender<OrderbookMessage>,
     user_backup: SledBackup,
+    depth: OrderbookDepth,
 ) -> Router {
     let app_state = Arc::new(AppState {
         node,
@@ -133,6 +165,8 @@ pub fn router(
         node_alias: node_alias.to_string(),
         auth_users_notifier,
         user_backup,
+        depth,
+        nonces: NonceCache::default(),
     });
 
     Router::new()
@@ -152,10 +186,22 @@ pub fn router(
         .route("/api/newaddress", get(get_unused_address))
         .route("/api/node", get(get_node_info))
         .route("/api/invoice", get(get_invoice))
-        .route("/api/orderbook/orders", get(get_orders).post(post_order))
+        .route(
+            "/api/orderbook/orders",
+            get(get_orders).post(post_order).delete(delete_orders),
+        )
         .route(
             "/api/orderbook/orders/:order_id",
-            get(get_order).put(put_order),
+            get(get_order).patch(patch_order).delete(delete_order),
+        )
+        .route("/api/orderbook/trigger-orders", post(post_trigger_order))
+        .route(
+            "/api/orderbook/trigger-orders/query",
+            post(get_trigger_orders),
+        )
+        .route(
+            "/api/orderbook/trigger-orders/:trigger_order_id",
+            put(put_trigger_order).delete(delete_trigger_order),
         )
         .route("/api/orderbook/websocket", get(websocket_handler))
         .route("/api/trade", post(post_trade))
@@ -163,6 +209,23 @@ pub fn router(
         .route("/api/register", post(post_register))
         .route("/api/admin/wallet/balance", get(get_balance))
         .route("/api/admin/wallet/utxos", get(get_utxos))
+        .route("/api/admin/wallet/utxos/:outpoint", put(put_utxo))
+        .route("/api/admin/wallet/send", post(send_on_chain))
+        .route("/api/admin/wallet/consolidate", post(consolidate_utxos))
+        .route("/api/admin/wallet/bump-fee", post(bump_fee))
+        .route(
+            "/api/admin/wallet/watch-only/balance",
+            get(get_watch_only_balance),
+        )
+        .route(
+            "/api/admin/wallet/watch-only/address",
+            get(get_watch_only_address),
+        )
+        .route("/api/admin/orderbook/orders/:order_id", put(put_order))
+        .route("/api/admin/psbt", post(create_psbt))
+        .route("/api/admin/psbt/channel-fundings", get(list_funding_psbts))
+        .route("/api/admin/psbt/broadcast", post(broadcast_psbt))
+        .route("/api/admin/psbt/:txid", delete(abandon_psbt))
         .route("/api/admin/channels", get(list_channels).post(open_channel))
         .route("/api/admin/channels/:channel_id", delete(close_channel))
         .route(
@@ -206,6 +269,7 @@ pub fn router(
         .route("/metrics", get(get_metrics))
         .route("/health", get(get_health))
         .route("/api/leaderboard", get(get_leaderboard))
+        .route("/api/market/candles/:symbol", get(get_market_candles))
         .layer(DefaultBodyLimit::disable())
         .layer(DefaultBodyLimit::max(50 * 1024))
         .with_state(app_state)
@@ -356,9 +420,30 @@ pub async fn get_invoice(
 
 pub async fn post_trade(
     State(state): State<Arc<AppState>>,
-    trade_params: Json<TradeParams>,
+    Json(signed_trade_params): Json<SignedRequest<TradeParams>>,
 ) -> Result<(), AppError> {
-    state.node.trade(&trade_params.0).await.map_err(|e| {
+    let VerifiedRequest {
+        trader_id,
+        request: trade_params,
+        ..
+    } = state
+        .nonces
+        .verify(&signed_trade_params, OffsetDateTime::now_utc())
+        .map_err(|e| {
+            tracing::warn!("Rejecting trade request: {e:#}");
+            AppError::Unauthorized
+        })?;
+
+    if trader_id != trade_params.pubkey {
+        tracing::warn!(
+            %trader_id,
+            pubkey = %trade_params.pubkey,
+            "Rejecting trade request signed for another trader"
+        );
+        return Err(AppError::Unauthorized);
+    }
+
+    state.node.trade(&trade_params).await.map_err(|e| {
         AppError::InternalServerError(format!("Could not handle trade request: {e:#}"))
     })
 }
@@ -368,14 +453,14 @@ pub async fn post_trade(
 pub async fn rollover(
     State(state): State<Arc<AppState>>,
     Path(dlc_channel_id): Path<String>,
-) -> Result<(), AppError> {
+) -> Result<Json<RolloverResponse>, AppError> {
     let dlc_channel_id = DlcChannelId::from_hex(dlc_channel_id.clone()).map_err(|e| {
         AppError::InternalServerError(format!(
             "Could not decode dlc channel id from {dlc_channel_id}: {e:#}"
         ))
     })?;
 
-    state
+    let response = state
         .node
         .propose_rollover(&dlc_channel_id, state.node.inner.network)
         .await
@@ -386,7 +471,7 @@ pub async fn rollover(
             ))
         })?;
 
-    Ok(())
+    Ok(Json(response))
 }
 
 #[instrument(skip_all, err(Debug))]
@@ -729,3 +814,26 @@ pub async fn get_leaderboard(
         entries: leader_board,
     }))
 }
+
+#[instrument(skip_all, err(Debug))]
+pub async fn get_market_candles(
+    State(state): State<Arc<AppState>>,
+    Path(symbol): Path<String>,
+    Query(params): Query<CandleQueryParams>,
+) -> Result<Json<Vec<Candle>>, AppError> {
+    let contract_symbol = ContractSymbol::from_str(&symbol)
+        .map_err(|e| AppError::BadRequest(format!("Invalid contract symbol: {e:#}")))?;
+
+    let (from, to) = candle_time_range(&params, OffsetDateTime::now_utc())
+        .map_err(|e| AppError::BadRequest(format!("Invalid time range: {e:#}")))?;
+
+    let candles = spawn_blocking(move || {
+        let mut conn = state.pool.get()?;
+        get_candles(&mut conn, contract_symbol, params.resolution, from, to)
+    })
+    .await
+    .expect("task to complete")
+    .map_err(|e| AppError::InternalServerError(format!("Could not get candles: {e:#}")))?;
+
+    Ok(Json(candles))
+}
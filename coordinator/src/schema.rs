@@ -48,6 +48,18 @@ pub mod sql_types {
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "PositionState_Type"))]
     pub struct PositionStateType;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "TimeInForce_Type"))]
+    pub struct TimeInForceType;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "TriggerOrderState_Type"))]
+    pub struct TriggerOrderStateType;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "TriggerType_Type"))]
+    pub struct TriggerTypeType;
 }
 
 diesel::table! {
@@ -113,6 +125,22 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    funding_fee_events (id) {
+        id -> Int4,
+        position_id -> Int4,
+        trader_pubkey -> Text,
+        amount_sats -> Int8,
+        funding_rate -> Float4,
+        mark_price -> Float4,
+        index_price -> Float4,
+        due_date -> Timestamptz,
+        paid_date -> Nullable<Timestamptz>,
+        timestamp -> Timestamptz,
+        rollover_date -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     last_outbound_dlc_messages (peer_id) {
         peer_id -> Text,
@@ -190,6 +218,7 @@ diesel::table! {
     use super::sql_types::OrderStateType;
     use super::sql_types::ContractSymbolType;
     use super::sql_types::OrderReasonType;
+    use super::sql_types::TimeInForceType;
 
     orders (id) {
         id -> Int4,
@@ -206,6 +235,9 @@ diesel::table! {
         leverage -> Float4,
         order_reason -> OrderReasonType,
         stable -> Bool,
+        time_in_force -> TimeInForceType,
+        post_only -> Bool,
+        parent_order_id -> Nullable<Uuid>,
     }
 }
 
@@ -274,6 +306,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ContractSymbolType;
+
+    price_history (id) {
+        id -> Int4,
+        contract_symbol -> ContractSymbolType,
+        index_price -> Float4,
+        mark_price -> Float4,
+        timestamp -> Timestamptz,
+    }
+}
+
 diesel::table! {
     routing_fees (id) {
         id -> Int4,
@@ -321,6 +366,27 @@ diesel::table! {
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         raw -> Text,
+        replaced_by -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ContractSymbolType;
+    use super::sql_types::TriggerTypeType;
+    use super::sql_types::TriggerOrderStateType;
+
+    trigger_orders (id) {
+        id -> Int4,
+        trigger_order_id -> Uuid,
+        position_id -> Int4,
+        trader_pubkey -> Text,
+        contract_symbol -> ContractSymbolType,
+        trigger_type -> TriggerTypeType,
+        trigger_price -> Float4,
+        trigger_order_state -> TriggerOrderStateType,
+        creation_timestamp -> Timestamptz,
+        update_timestamp -> Timestamptz,
     }
 }
 
@@ -338,9 +404,11 @@ diesel::table! {
 
 diesel::joinable!(answers -> choices (choice_id));
 diesel::joinable!(choices -> polls (poll_id));
+diesel::joinable!(funding_fee_events -> positions (position_id));
 diesel::joinable!(last_outbound_dlc_messages -> dlc_messages (message_hash));
 diesel::joinable!(liquidity_request_logs -> liquidity_options (liquidity_option));
 diesel::joinable!(trades -> positions (position_id));
+diesel::joinable!(trigger_orders -> positions (position_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     answers,
@@ -348,6 +416,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     choices,
     collaborative_reverts,
     dlc_messages,
+    funding_fee_events,
     last_outbound_dlc_messages,
     legacy_collaborative_reverts,
     liquidity_options,
@@ -357,9 +426,11 @@ diesel::allow_tables_to_appear_in_same_query!(
     payments,
     polls,
     positions,
+    price_history,
     routing_fees,
     spendable_outputs,
     trades,
     transactions,
+    trigger_orders,
     users,
 );
@@ -1,6 +1,11 @@
 use crate::node::NodeSettings;
+use anyhow::ensure;
 use anyhow::Context;
 use anyhow::Result;
+use bitcoin::secp256k1::PublicKey;
+use bitcoin::XOnlyPublicKey;
+use commons::DifferenceParams;
+use commons::OracleParams;
 use lightning::util::config::UserConfig;
 use ln_dlc_node::node::LnDlcNodeSettings;
 use serde::Deserialize;
@@ -59,6 +64,15 @@ pub struct Settings {
     /// Min balance to keep in on-chain wallet at all times
     pub min_liquidity_threshold_sats: u64,
 
+    /// The oracles new contracts are built on
+    pub oracle: OracleSettings,
+
+    /// How the index and mark prices are computed
+    pub price: PriceSettings,
+
+    /// Who receives the individual orders on the orderbook websocket
+    pub order_feed: OrderFeedSettings,
+
     // Location of the settings file in the file system.
     path: PathBuf,
 }
@@ -133,6 +147,9 @@ impl Settings {
             rollover_window_close_scheduler: file.rollover_window_close_scheduler,
             close_expired_position_scheduler: file.close_expired_position_scheduler,
             min_liquidity_threshold_sats: file.min_liquidity_threshold_sats,
+            oracle: file.oracle,
+            price: file.price,
+            order_feed: file.order_feed,
             path,
         }
     }
@@ -157,6 +174,138 @@ pub struct SettingsFile {
     close_expired_position_scheduler: String,
 
     min_liquidity_threshold_sats: u64,
+
+    #[serde(default)]
+    oracle: OracleSettings,
+
+    #[serde(default)]
+    price: PriceSettings,
+
+    #[serde(default)]
+    order_feed: OrderFeedSettings,
+}
+
+/// Settings for the oracles referenced by new contracts.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct OracleSettings {
+    /// The public keys of the oracles to be used
+    ///
+    /// Every public key has to be one of the oracles passed via `--oracle`. If empty, only the
+    /// oracle given by `--oracle-pubkey` is used.
+    #[serde(default)]
+    pub public_keys: Vec<XOnlyPublicKey>,
+
+    /// The number of oracles which have to attest for a contract to be closed
+    pub threshold: u16,
+
+    /// The allowed disagreement between the oracles
+    ///
+    /// If not set, the oracles have to attest to the exact same price.
+    pub difference_params: Option<DifferenceParams>,
+}
+
+impl Default for OracleSettings {
+    fn default() -> Self {
+        Self {
+            public_keys: vec![],
+            threshold: 1,
+            difference_params: None,
+        }
+    }
+}
+
+impl OracleSettings {
+    /// The oracle parameters for new contracts.
+    ///
+    /// Falls back to `default_oracle` if no oracles are configured. Fails if any of the configured
+    /// oracles is not one of the `available_oracles` or if the threshold cannot be reached.
+    pub fn to_oracle_params(
+        &self,
+        default_oracle: XOnlyPublicKey,
+        available_oracles: &[XOnlyPublicKey],
+    ) -> Result<OracleParams> {
+        let pubkeys = if self.public_keys.is_empty() {
+            vec![default_oracle]
+        } else {
+            self.public_keys.clone()
+        };
+
+        for pubkey in pubkeys.iter() {
+            ensure!(
+                available_oracles.contains(pubkey),
+                "Oracle {pubkey} is not configured"
+            );
+        }
+
+        ensure!(
+            self.threshold > 0 && self.threshold as usize <= pubkeys.len(),
+            "Invalid oracle threshold {} for {} oracles",
+            self.threshold,
+            pubkeys.len()
+        );
+
+        Ok(OracleParams {
+            pubkeys,
+            threshold: self.threshold,
+            difference_params: self.difference_params,
+        })
+    }
+}
+
+/// Settings for the price service.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct PriceSettings {
+    /// The feeds the index price is built from
+    pub feeds: Vec<PriceFeedSource>,
+
+    /// The maximum relative deviation of a feed from the median of all feeds, e.g. 0.05 for 5%
+    ///
+    /// Feeds deviating further are ignored when computing the index price.
+    pub max_feed_deviation: f32,
+
+    /// The number of index prices the mark price is averaged over
+    pub mark_price_ema_samples: u32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceFeedSource {
+    /// The mid price of the corresponding BitMEX contract
+    Bitmex,
+    /// The mid price of our own orderbook
+    Orderbook,
+}
+
+impl Default for PriceSettings {
+    fn default() -> Self {
+        Self {
+            feeds: vec![PriceFeedSource::Bitmex],
+            max_feed_deviation: 0.05,
+            mark_price_ema_samples: 30,
+        }
+    }
+}
+
+/// Settings for the feed of individual orders on the orderbook websocket.
+///
+/// Every client receives the aggregated depth of the orderbook. The individual orders reveal the
+/// trader behind every order and can be restricted to authenticated makers.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct OrderFeedSettings {
+    /// If set, only the `makers` receive individual orders
+    pub restrict_to_makers: bool,
+
+    /// The makers receiving individual orders once they authenticated
+    #[serde(default)]
+    pub makers: Vec<PublicKey>,
+}
+
+impl OrderFeedSettings {
+    /// Whether the given trader (if authenticated) receives individual orders.
+    pub fn receives_orders(&self, trader_id: Option<PublicKey>) -> bool {
+        !self.restrict_to_makers
+            || trader_id.is_some_and(|trader_id| self.makers.contains(&trader_id))
+    }
 }
 
 impl From<Settings> for SettingsFile {
@@ -174,6 +323,9 @@ impl From<Settings> for SettingsFile {
             rollover_window_close_scheduler: value.rollover_window_close_scheduler,
             close_expired_position_scheduler: value.close_expired_position_scheduler,
             min_liquidity_threshold_sats: value.min_liquidity_threshold_sats,
+            oracle: value.oracle,
+            price: value.price,
+            order_feed: value.order_feed,
         }
     }
 }
@@ -181,7 +333,12 @@ impl From<Settings> for SettingsFile {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ln_dlc_node::node::ChainSourceConfig;
     use ln_dlc_node::node::GossipSourceConfig;
+    use ln_dlc_node::FeeRateEstimatorConfig;
+    use ln_dlc_node::FeeRatePolicy;
+    use ln_dlc_node::FeeRateSourceConfig;
+    use std::str::FromStr;
 
     #[test]
     fn toml_serde_roundtrip() {
@@ -205,11 +362,47 @@ mod tests {
                 gossip_source_config: GossipSourceConfig::RapidGossipSync {
                     server_url: "foo".to_string(),
                 },
+                chain_source_config: ChainSourceConfig::Bitcoind {
+                    rpc_url: "http://localhost:18443".to_string(),
+                    rpc_username: "foo".to_string(),
+                    rpc_password: "bar".to_string(),
+                },
+                fee_rate_estimator_config: FeeRateEstimatorConfig {
+                    sources: vec![FeeRateSourceConfig::Mempool, FeeRateSourceConfig::Esplora],
+                    policy: FeeRatePolicy::Median,
+                    min_fee_rate_sats_per_vbyte: 1.0,
+                    max_fee_rate_sats_per_vbyte: 500.0,
+                    max_age: std::time::Duration::from_secs(600),
+                },
             },
             rollover_window_open_scheduler: "foo".to_string(),
             rollover_window_close_scheduler: "bar".to_string(),
             close_expired_position_scheduler: "baz".to_string(),
             min_liquidity_threshold_sats: 2,
+            oracle: OracleSettings {
+                public_keys: vec![XOnlyPublicKey::from_str(
+                    "16f88cf7d21e6c0f46bcbc983a4e3b19726c6c98858cc31c83551a88fde171c0",
+                )
+                .unwrap()],
+                threshold: 1,
+                difference_params: Some(DifferenceParams {
+                    max_error_exp: 8,
+                    min_support_exp: 4,
+                    maximize_coverage: false,
+                }),
+            },
+            price: PriceSettings {
+                feeds: vec![PriceFeedSource::Bitmex, PriceFeedSource::Orderbook],
+                max_feed_deviation: 0.1,
+                mark_price_ema_samples: 10,
+            },
+            order_feed: OrderFeedSettings {
+                restrict_to_makers: true,
+                makers: vec![PublicKey::from_str(
+                    "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
+                )
+                .unwrap()],
+            },
         };
 
         let serialized = toml::to_string_pretty(&original).unwrap();
@@ -218,4 +411,56 @@ mod tests {
 
         assert_eq!(original, deserialized);
     }
+
+    #[test]
+    fn oracle_threshold_has_to_be_reachable() {
+        let oracle = XOnlyPublicKey::from_str(
+            "16f88cf7d21e6c0f46bcbc983a4e3b19726c6c98858cc31c83551a88fde171c0",
+        )
+        .unwrap();
+
+        let settings = OracleSettings {
+            public_keys: vec![oracle],
+            threshold: 2,
+            difference_params: None,
+        };
+
+        assert!(settings.to_oracle_params(oracle, &[oracle]).is_err());
+    }
+
+    #[test]
+    fn oracle_params_fall_back_to_default_oracle() {
+        let oracle = XOnlyPublicKey::from_str(
+            "16f88cf7d21e6c0f46bcbc983a4e3b19726c6c98858cc31c83551a88fde171c0",
+        )
+        .unwrap();
+
+        let oracle_params = OracleSettings::default()
+            .to_oracle_params(oracle, &[oracle])
+            .unwrap();
+
+        assert_eq!(oracle_params, OracleParams::single(oracle));
+    }
+
+    #[test]
+    fn order_feed_can_be_restricted_to_makers() {
+        let maker = PublicKey::from_str(
+            "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
+        )
+        .unwrap();
+        let trader = PublicKey::from_str(
+            "02bd998ebd176715fe92b7467cf6b1df8023950a4dd911db4c94dfc89cc9f5a655",
+        )
+        .unwrap();
+
+        let settings = OrderFeedSettings {
+            restrict_to_makers: true,
+            makers: vec![maker],
+        };
+
+        assert!(settings.receives_orders(Some(maker)));
+        assert!(!settings.receives_orders(Some(trader)));
+        assert!(!settings.receives_orders(None));
+        assert!(OrderFeedSettings::default().receives_orders(None));
+    }
 }
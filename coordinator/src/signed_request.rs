@@ -0,0 +1,96 @@
+use anyhow::ensure;
+use anyhow::Result;
+use commons::SignedRequest;
+use commons::SignedRequestType;
+use commons::VerifiedRequest;
+use commons::SIGNED_REQUEST_VALIDITY;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Remembers the nonces of the signed requests accepted within the [`SIGNED_REQUEST_VALIDITY`],
+/// so that a request cannot be replayed.
+#[derive(Default)]
+pub struct NonceCache {
+    nonces: Mutex<HashMap<Uuid, OffsetDateTime>>,
+}
+
+impl NonceCache {
+    /// Verifies the signed request, failing if it has been accepted before.
+    pub fn verify<T: Serialize + DeserializeOwned + SignedRequestType>(
+        &self,
+        request: &SignedRequest<T>,
+        now: OffsetDateTime,
+    ) -> Result<VerifiedRequest<T>> {
+        let mut nonces = self.nonces.lock();
+
+        // Requests signed before the validity period are rejected anyway.
+        nonces.retain(|_, timestamp| now - *timestamp <= SIGNED_REQUEST_VALIDITY);
+
+        let request = request.verify(now)?;
+        ensure!(
+            nonces.insert(request.nonce, request.timestamp).is_none(),
+            "Request with nonce {} has already been used",
+            request.nonce
+        );
+
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::secp256k1::Message;
+    use bitcoin::secp256k1::SecretKey;
+    use bitcoin::secp256k1::SECP256K1;
+    use commons::CancelOrder;
+    use commons::Signature;
+
+    fn sign(message: Message) -> Signature {
+        let secret_key = SecretKey::from_slice(&[1; 32]).unwrap();
+        Signature {
+            pubkey: secret_key.public_key(SECP256K1),
+            signature: secret_key.sign_ecdsa(message),
+        }
+    }
+
+    fn cancel_order() -> CancelOrder {
+        CancelOrder {
+            order_id: Uuid::new_v4(),
+        }
+    }
+
+    #[test]
+    fn replayed_request_is_rejected() {
+        let nonces = NonceCache::default();
+        let request = SignedRequest::new(cancel_order(), sign).unwrap();
+
+        let now = OffsetDateTime::now_utc();
+        assert!(nonces.verify(&request, now).is_ok());
+        assert!(nonces.verify(&request, now).is_err());
+
+        let other_request = SignedRequest::new(cancel_order(), sign).unwrap();
+        assert!(nonces.verify(&other_request, now).is_ok());
+    }
+
+    #[test]
+    fn nonces_are_forgotten_after_validity() {
+        let nonces = NonceCache::default();
+        nonces
+            .verify(
+                &SignedRequest::new(cancel_order(), sign).unwrap(),
+                OffsetDateTime::now_utc(),
+            )
+            .unwrap();
+
+        let later = OffsetDateTime::now_utc() + SIGNED_REQUEST_VALIDITY + time::Duration::SECOND;
+        let request = SignedRequest::new(cancel_order(), sign).unwrap();
+        assert!(nonces.verify(&request, later).is_err());
+
+        assert!(nonces.nonces.lock().is_empty());
+    }
+}
@@ -0,0 +1,323 @@
+use crate::order::Order;
+use crate::order::OrderState;
+use crate::order::OrderType;
+use crate::price::Price;
+use anyhow::ensure;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
+use trade::ContractSymbol;
+use trade::Direction;
+
+/// The aggregated quantity of all orders at one price.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PriceLevel {
+    #[serde(with = "rust_decimal::serde::float")]
+    pub price: Decimal,
+    /// The quantity in contracts. In a [`DepthUpdate`] a quantity of zero means that the price
+    /// level has been removed.
+    #[serde(with = "rust_decimal::serde::float")]
+    pub quantity: Decimal,
+}
+
+/// The price levels of both sides of the orderbook of a contract.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Depth {
+    /// The price levels of the buy orders, best (highest) price first.
+    pub bids: Vec<PriceLevel>,
+    /// The price levels of the sell orders, best (lowest) price first.
+    pub asks: Vec<PriceLevel>,
+}
+
+/// The complete depth of the orderbook of a contract.
+///
+/// Every change to the depth increments the sequence number. A client applies the
+/// [`DepthUpdate`]s following the snapshot and requests a new snapshot if it misses an update.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DepthSnapshot {
+    pub contract_symbol: ContractSymbol,
+    pub sequence: u64,
+    pub depth: Depth,
+}
+
+/// The price levels which changed since the previous sequence number.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DepthUpdate {
+    pub contract_symbol: ContractSymbol,
+    pub sequence: u64,
+    pub changes: Depth,
+}
+
+impl Depth {
+    /// Aggregates the open, non-expired limit orders of the given contract into price levels.
+    pub fn from_orders(orders: &[Order], contract_symbol: ContractSymbol) -> Self {
+        let now = OffsetDateTime::now_utc();
+
+        let mut bids = BTreeMap::new();
+        let mut asks = BTreeMap::new();
+        for order in orders.iter().filter(|order| {
+            order.contract_symbol == contract_symbol
+                && order.order_type == OrderType::Limit
+                && order.order_state == OrderState::Open
+                && !order.is_expired(now)
+        }) {
+            let levels = match order.direction {
+                Direction::Long => &mut bids,
+                Direction::Short => &mut asks,
+            };
+            *levels.entry(order.price).or_insert(Decimal::ZERO) += order.quantity;
+        }
+
+        Self {
+            bids: bids.into_iter().rev().map(PriceLevel::from).collect(),
+            asks: asks.into_iter().map(PriceLevel::from).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+
+    /// The best bid and ask price.
+    pub fn best_price(&self) -> Price {
+        Price {
+            bid: self.bids.first().map(|level| level.price),
+            ask: self.asks.first().map(|level| level.price),
+        }
+    }
+
+    /// The price levels which have to be applied to `self` to arrive at `other`.
+    pub fn changes(&self, other: &Depth) -> Depth {
+        Depth {
+            bids: level_changes(&self.bids, &other.bids),
+            asks: level_changes(&self.asks, &other.asks),
+        }
+    }
+
+    /// Applies the changed price levels of a [`DepthUpdate`].
+    pub fn apply(&mut self, changes: &Depth) {
+        apply_level_changes(&mut self.bids, &changes.bids, Direction::Long);
+        apply_level_changes(&mut self.asks, &changes.asks, Direction::Short);
+    }
+}
+
+impl DepthSnapshot {
+    /// Applies the given update, ignoring updates which are already part of the snapshot.
+    ///
+    /// Fails if updates have been missed, in which case a new snapshot has to be requested.
+    pub fn apply(&mut self, update: &DepthUpdate) -> Result<()> {
+        ensure!(
+            self.contract_symbol == update.contract_symbol,
+            "Cannot apply depth update of {} to depth of {}",
+            update.contract_symbol,
+            self.contract_symbol
+        );
+
+        if update.sequence <= self.sequence {
+            return Ok(());
+        }
+
+        ensure!(
+            update.sequence == self.sequence + 1,
+            "Missed depth updates between {} and {}",
+            self.sequence,
+            update.sequence
+        );
+
+        self.depth.apply(&update.changes);
+        self.sequence = update.sequence;
+
+        Ok(())
+    }
+}
+
+impl From<(Decimal, Decimal)> for PriceLevel {
+    fn from((price, quantity): (Decimal, Decimal)) -> Self {
+        PriceLevel { price, quantity }
+    }
+}
+
+fn level_changes(old: &[PriceLevel], new: &[PriceLevel]) -> Vec<PriceLevel> {
+    let old = old
+        .iter()
+        .map(|level| (level.price, level.quantity))
+        .collect::<BTreeMap<_, _>>();
+    let new = new
+        .iter()
+        .map(|level| (level.price, level.quantity))
+        .collect::<BTreeMap<_, _>>();
+
+    let removed = old
+        .keys()
+        .filter(|price| !new.contains_key(price))
+        .map(|price| PriceLevel {
+            price: *price,
+            quantity: Decimal::ZERO,
+        });
+
+    let changed = new
+        .iter()
+        .filter(|(price, quantity)| old.get(price) != Some(quantity))
+        .map(|(price, quantity)| PriceLevel {
+            price: *price,
+            quantity: *quantity,
+        });
+
+    removed.chain(changed).collect()
+}
+
+fn apply_level_changes(levels: &mut Vec<PriceLevel>, changes: &[PriceLevel], side: Direction) {
+    for change in changes {
+        levels.retain(|level| level.price != change.price);
+        if !change.quantity.is_zero() {
+            levels.push(*change);
+        }
+    }
+
+    match side {
+        Direction::Long => levels.sort_by(|a, b| b.price.cmp(&a.price)),
+        Direction::Short => levels.sort_by(|a, b| a.price.cmp(&b.price)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::OrderReason;
+    use crate::order::TimeInForce;
+    use rust_decimal_macros::dec;
+    use secp256k1::PublicKey;
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    fn order(direction: Direction, price: Decimal, quantity: Decimal) -> Order {
+        Order {
+            id: Uuid::new_v4(),
+            price,
+            leverage: 2.0,
+            contract_symbol: ContractSymbol::BtcUsd,
+            trader_id: PublicKey::from_str(
+                "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
+            )
+            .unwrap(),
+            direction,
+            quantity,
+            order_type: OrderType::Limit,
+            timestamp: OffsetDateTime::now_utc(),
+            expiry: OffsetDateTime::now_utc() + time::Duration::minutes(1),
+            order_state: OrderState::Open,
+            order_reason: OrderReason::Manual,
+            stable: false,
+            time_in_force: TimeInForce::GoodTillTime,
+            post_only: false,
+        }
+    }
+
+    fn level(price: Decimal, quantity: Decimal) -> PriceLevel {
+        PriceLevel { price, quantity }
+    }
+
+    #[test]
+    fn orders_are_aggregated_into_sorted_price_levels() {
+        let mut taken = order(Direction::Long, dec!(40_100), dec!(500));
+        taken.order_state = OrderState::Taken;
+
+        let orders = [
+            order(Direction::Long, dec!(39_900), dec!(100)),
+            order(Direction::Long, dec!(40_000), dec!(100)),
+            order(Direction::Long, dec!(40_000), dec!(50)),
+            order(Direction::Short, dec!(40_300), dec!(100)),
+            order(Direction::Short, dec!(40_200), dec!(20)),
+            taken,
+        ];
+
+        let depth = Depth::from_orders(&orders, ContractSymbol::BtcUsd);
+
+        assert_eq!(
+            depth,
+            Depth {
+                bids: vec![
+                    level(dec!(40_000), dec!(150)),
+                    level(dec!(39_900), dec!(100))
+                ],
+                asks: vec![
+                    level(dec!(40_200), dec!(20)),
+                    level(dec!(40_300), dec!(100))
+                ],
+            }
+        );
+        assert_eq!(
+            depth.best_price(),
+            Price {
+                bid: Some(dec!(40_000)),
+                ask: Some(dec!(40_200)),
+            }
+        );
+    }
+
+    #[test]
+    fn applying_changes_yields_new_depth() {
+        let old = Depth {
+            bids: vec![
+                level(dec!(40_000), dec!(150)),
+                level(dec!(39_900), dec!(100)),
+            ],
+            asks: vec![level(dec!(40_200), dec!(20))],
+        };
+        let new = Depth {
+            bids: vec![
+                level(dec!(40_050), dec!(10)),
+                level(dec!(40_000), dec!(100)),
+            ],
+            asks: vec![level(dec!(40_200), dec!(20))],
+        };
+
+        let changes = old.changes(&new);
+
+        assert_eq!(
+            changes,
+            Depth {
+                bids: vec![
+                    level(dec!(39_900), dec!(0)),
+                    level(dec!(40_000), dec!(100)),
+                    level(dec!(40_050), dec!(10)),
+                ],
+                asks: vec![],
+            }
+        );
+
+        let mut depth = old;
+        depth.apply(&changes);
+        assert_eq!(depth, new);
+    }
+
+    #[test]
+    fn missed_depth_update_is_detected() {
+        let mut snapshot = DepthSnapshot {
+            contract_symbol: ContractSymbol::BtcUsd,
+            sequence: 5,
+            depth: Depth::default(),
+        };
+        let update = |sequence| DepthUpdate {
+            contract_symbol: ContractSymbol::BtcUsd,
+            sequence,
+            changes: Depth {
+                bids: vec![level(dec!(40_000), dec!(100))],
+                asks: vec![],
+            },
+        };
+
+        // Updates which are already part of the snapshot are ignored.
+        snapshot.apply(&update(5)).unwrap();
+        assert!(snapshot.depth.is_empty());
+
+        snapshot.apply(&update(6)).unwrap();
+        assert_eq!(snapshot.sequence, 6);
+        assert_eq!(snapshot.depth.bids, vec![level(dec!(40_000), dec!(100))]);
+
+        assert!(snapshot.apply(&update(8)).is_err());
+    }
+}
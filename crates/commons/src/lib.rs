@@ -5,8 +5,11 @@ use serde::Serialize;
 
 mod backup;
 mod collab_revert;
+mod depth;
 mod liquidity_option;
+mod market_data;
 mod message;
+mod oracle;
 mod order;
 mod order_matching_fee;
 mod polls;
@@ -15,11 +18,15 @@ mod rollover;
 mod route;
 mod signature;
 mod trade;
+mod trigger_order;
 
 pub use crate::backup::*;
 pub use crate::collab_revert::*;
+pub use crate::depth::*;
 pub use crate::liquidity_option::*;
+pub use crate::market_data::*;
 pub use crate::message::*;
+pub use crate::oracle::*;
 pub use crate::order::*;
 pub use crate::order_matching_fee::order_matching_fee_taker;
 pub use crate::polls::*;
@@ -30,6 +37,7 @@ pub use crate::rollover::*;
 pub use crate::route::*;
 pub use crate::signature::*;
 pub use crate::trade::*;
+pub use crate::trigger_order::*;
 
 pub const AUTH_SIGN_MESSAGE: &[u8; 19] = b"Hello it's me Mario";
 
@@ -0,0 +1,250 @@
+use anyhow::bail;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+use time::Duration;
+use time::OffsetDateTime;
+use trade::ContractSymbol;
+use trade::Direction;
+
+/// The time interval covered by a single [`Candle`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CandleResolution {
+    #[serde(rename = "1m")]
+    OneMinute,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "15m")]
+    FifteenMinutes,
+    #[serde(rename = "1h")]
+    OneHour,
+    #[serde(rename = "4h")]
+    FourHours,
+    #[serde(rename = "1d")]
+    OneDay,
+}
+
+impl CandleResolution {
+    pub const ALL: [CandleResolution; 6] = [
+        CandleResolution::OneMinute,
+        CandleResolution::FiveMinutes,
+        CandleResolution::FifteenMinutes,
+        CandleResolution::OneHour,
+        CandleResolution::FourHours,
+        CandleResolution::OneDay,
+    ];
+
+    pub fn duration(self) -> Duration {
+        match self {
+            CandleResolution::OneMinute => Duration::minutes(1),
+            CandleResolution::FiveMinutes => Duration::minutes(5),
+            CandleResolution::FifteenMinutes => Duration::minutes(15),
+            CandleResolution::OneHour => Duration::hours(1),
+            CandleResolution::FourHours => Duration::hours(4),
+            CandleResolution::OneDay => Duration::days(1),
+        }
+    }
+
+    /// The start of the candle the given timestamp falls into.
+    ///
+    /// Candles are aligned to the unix epoch, i.e. daily candles start at midnight UTC.
+    pub fn open_time(self, timestamp: OffsetDateTime) -> OffsetDateTime {
+        let interval = self.duration().whole_seconds();
+        let start = timestamp.unix_timestamp() - timestamp.unix_timestamp().rem_euclid(interval);
+
+        OffsetDateTime::from_unix_timestamp(start).expect("to be a valid timestamp")
+    }
+}
+
+impl FromStr for CandleResolution {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let resolution = match value {
+            "1m" => CandleResolution::OneMinute,
+            "5m" => CandleResolution::FiveMinutes,
+            "15m" => CandleResolution::FifteenMinutes,
+            "1h" => CandleResolution::OneHour,
+            "4h" => CandleResolution::FourHours,
+            "1d" => CandleResolution::OneDay,
+            unknown => bail!("Unknown candle resolution {unknown}"),
+        };
+
+        Ok(resolution)
+    }
+}
+
+impl fmt::Display for CandleResolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let resolution = match self {
+            CandleResolution::OneMinute => "1m",
+            CandleResolution::FiveMinutes => "5m",
+            CandleResolution::FifteenMinutes => "15m",
+            CandleResolution::OneHour => "1h",
+            CandleResolution::FourHours => "4h",
+            CandleResolution::OneDay => "1d",
+        };
+        resolution.fmt(f)
+    }
+}
+
+/// A trade executed on the 10101 market, without any information about the trader.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MarketTrade {
+    pub contract_symbol: ContractSymbol,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub price: Decimal,
+    /// The quantity in contracts.
+    #[serde(with = "rust_decimal::serde::float")]
+    pub quantity: Decimal,
+    /// The direction of the taker, i.e. of the trader.
+    pub direction: Direction,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
+
+/// The open, high, low and close price and the traded volume of a contract within one interval.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Candle {
+    pub contract_symbol: ContractSymbol,
+    pub resolution: CandleResolution,
+    #[serde(with = "time::serde::rfc3339")]
+    pub open_time: OffsetDateTime,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub open: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub high: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub low: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub close: Decimal,
+    /// The traded volume in contracts.
+    #[serde(with = "rust_decimal::serde::float")]
+    pub volume: Decimal,
+}
+
+impl Candle {
+    fn new(trade: &MarketTrade, resolution: CandleResolution) -> Self {
+        Self {
+            contract_symbol: trade.contract_symbol,
+            resolution,
+            open_time: resolution.open_time(trade.timestamp),
+            open: trade.price,
+            high: trade.price,
+            low: trade.price,
+            close: trade.price,
+            volume: trade.quantity,
+        }
+    }
+
+    fn add(&mut self, trade: &MarketTrade) {
+        self.high = self.high.max(trade.price);
+        self.low = self.low.min(trade.price);
+        self.close = trade.price;
+        self.volume += trade.quantity;
+    }
+}
+
+/// Aggregates the given trades into candles of the given resolution.
+///
+/// The trades have to be of the same contract symbol and ordered by their timestamp. Intervals
+/// without any trades do not have a candle.
+pub fn aggregate_candles(trades: &[MarketTrade], resolution: CandleResolution) -> Vec<Candle> {
+    let mut candles: Vec<Candle> = vec![];
+
+    for trade in trades {
+        match candles.last_mut() {
+            Some(candle) if candle.open_time == resolution.open_time(trade.timestamp) => {
+                candle.add(trade)
+            }
+            _ => candles.push(Candle::new(trade, resolution)),
+        }
+    }
+
+    candles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use time::macros::datetime;
+
+    fn trade(price: Decimal, quantity: Decimal, timestamp: OffsetDateTime) -> MarketTrade {
+        MarketTrade {
+            contract_symbol: ContractSymbol::BtcUsd,
+            price,
+            quantity,
+            direction: Direction::Long,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn trades_are_aggregated_into_candles() {
+        let trades = [
+            trade(dec!(40_000), dec!(100), datetime!(2024-02-15 10:00:05 UTC)),
+            trade(dec!(40_500), dec!(50), datetime!(2024-02-15 10:00:30 UTC)),
+            trade(dec!(39_800), dec!(20), datetime!(2024-02-15 10:00:59 UTC)),
+            trade(dec!(40_100), dec!(10), datetime!(2024-02-15 10:03:00 UTC)),
+        ];
+
+        let candles = aggregate_candles(&trades, CandleResolution::OneMinute);
+
+        assert_eq!(
+            candles,
+            vec![
+                Candle {
+                    contract_symbol: ContractSymbol::BtcUsd,
+                    resolution: CandleResolution::OneMinute,
+                    open_time: datetime!(2024-02-15 10:00:00 UTC),
+                    open: dec!(40_000),
+                    high: dec!(40_500),
+                    low: dec!(39_800),
+                    close: dec!(39_800),
+                    volume: dec!(170),
+                },
+                Candle {
+                    contract_symbol: ContractSymbol::BtcUsd,
+                    resolution: CandleResolution::OneMinute,
+                    open_time: datetime!(2024-02-15 10:03:00 UTC),
+                    open: dec!(40_100),
+                    high: dec!(40_100),
+                    low: dec!(40_100),
+                    close: dec!(40_100),
+                    volume: dec!(10),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn candles_are_aligned_to_resolution() {
+        let timestamp = datetime!(2024-02-15 10:17:42 UTC);
+
+        assert_eq!(
+            CandleResolution::FifteenMinutes.open_time(timestamp),
+            datetime!(2024-02-15 10:15:00 UTC)
+        );
+        assert_eq!(
+            CandleResolution::FourHours.open_time(timestamp),
+            datetime!(2024-02-15 08:00:00 UTC)
+        );
+        assert_eq!(
+            CandleResolution::OneDay.open_time(timestamp),
+            datetime!(2024-02-15 00:00:00 UTC)
+        );
+    }
+
+    #[test]
+    fn candle_resolution_roundtrips_through_string() {
+        for resolution in CandleResolution::ALL {
+            assert_eq!(
+                CandleResolution::from_str(&resolution.to_string()).unwrap(),
+                resolution
+            );
+        }
+    }
+}
@@ -1,3 +1,7 @@
+use crate::depth::DepthSnapshot;
+use crate::depth::DepthUpdate;
+use crate::market_data::Candle;
+use crate::market_data::MarketTrade;
 use crate::order::Order;
 use crate::signature::Signature;
 use crate::trade::FilledWith;
@@ -11,7 +15,9 @@ use secp256k1::PublicKey;
 use serde::Deserialize;
 use serde::Serialize;
 use std::fmt::Display;
+use time::OffsetDateTime;
 use tokio_tungstenite::tungstenite;
+use trade::ContractSymbol;
 use uuid::Uuid;
 
 pub type ChannelId = [u8; 32];
@@ -22,7 +28,7 @@ pub enum Message {
     AllOrders(Vec<Order>),
     LimitOrderFilledMatches {
         trader_id: PublicKey,
-        matches: Vec<(Uuid, Decimal)>,
+        matches: Vec<LimitOrderFilledMatch>,
     },
     NewOrder(Order),
     DeleteOrder(Uuid),
@@ -58,6 +64,29 @@ pub enum Message {
         #[serde(with = "rust_decimal::serde::float")]
         execution_price: Decimal,
     },
+    /// A trade has been executed on the market.
+    MarketTrade(MarketTrade),
+    /// The current candle of a contract has been updated by a new trade.
+    Candle(Candle),
+    /// The aggregated depth of the orderbook of a contract.
+    DepthSnapshot(DepthSnapshot),
+    /// The changes to the depth of the orderbook of a contract since the previous update.
+    DepthUpdate(DepthUpdate),
+}
+
+/// A filled match of a limit order, i.e. a fill of the maker.
+#[derive(Serialize, Clone, Deserialize, Debug, PartialEq)]
+pub struct LimitOrderFilledMatch {
+    /// The ID of the match, which is unique even if the same limit order is filled more than once.
+    pub order_id: Uuid,
+    pub contract_symbol: ContractSymbol,
+    /// The number of contracts filled.
+    ///
+    /// The sign determines the direction of the limit order: positive is long; negative is short.
+    pub quantity: Decimal,
+    pub execution_price: Decimal,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
 }
 
 #[derive(Serialize, Clone, Deserialize, Debug)]
@@ -77,6 +106,10 @@ pub enum OrderbookRequest {
     LimitOrderFilledMatches {
         trader_id: PublicKey,
     },
+    /// Requests a new [`DepthSnapshot`] of the given contract, e.g. after missing an update.
+    DepthSnapshot {
+        contract_symbol: ContractSymbol,
+    },
 }
 
 impl TryFrom<OrderbookRequest> for tungstenite::Message {
@@ -127,6 +160,18 @@ impl Display for Message {
             Message::CollaborativeRevert { .. } => {
                 write!(f, "LegacyCollaborativeRevert")
             }
+            Message::MarketTrade(_) => {
+                write!(f, "MarketTrade")
+            }
+            Message::Candle(_) => {
+                write!(f, "Candle")
+            }
+            Message::DepthSnapshot(_) => {
+                write!(f, "DepthSnapshot")
+            }
+            Message::DepthUpdate(_) => {
+                write!(f, "DepthUpdate")
+            }
         }
     }
 }
@@ -0,0 +1,48 @@
+use secp256k1::XOnlyPublicKey;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The oracles a contract is built on
+///
+/// A contract can reference multiple oracles, out of which `threshold` have to attest to the same
+/// outcome (or to outcomes within the bounds given by `difference_params`) for the contract to be
+/// closed. This way a single oracle outage or misbehaving oracle does not decide the outcome of a
+/// contract.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OracleParams {
+    /// The public keys of the oracles to be used
+    ///
+    /// Every public key has to correspond to an oracle configured in the dlc-manager.
+    pub pubkeys: Vec<XOnlyPublicKey>,
+
+    /// The number of oracles which have to attest for the contract to be closed
+    pub threshold: u16,
+
+    /// Bounds on the disagreement allowed between the attested outcomes of the oracles
+    ///
+    /// If `None`, the oracles have to attest to the exact same outcome.
+    pub difference_params: Option<DifferenceParams>,
+}
+
+/// Allowed disagreement between oracles attesting to numerical outcomes
+///
+/// Mirrors the `DifferenceParams` of rust-dlc: two attested outcomes are considered to agree if
+/// their difference is smaller than `2^max_error_exp` and are guaranteed to agree if their
+/// difference is smaller than `2^min_support_exp`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DifferenceParams {
+    pub max_error_exp: usize,
+    pub min_support_exp: usize,
+    pub maximize_coverage: bool,
+}
+
+impl OracleParams {
+    /// Oracle parameters for a contract relying on a single oracle.
+    pub fn single(pubkey: XOnlyPublicKey) -> Self {
+        Self {
+            pubkeys: vec![pubkey],
+            threshold: 1,
+            difference_params: None,
+        }
+    }
+}
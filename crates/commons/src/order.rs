@@ -1,3 +1,4 @@
+use crate::SignedRequestType;
 use rust_decimal::Decimal;
 use secp256k1::PublicKey;
 use serde::Deserialize;
@@ -8,6 +9,7 @@ use trade::Direction;
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Clone)]
+#[serde(from = "NewOrderMessage")]
 pub struct NewOrder {
     pub id: Uuid,
     pub contract_symbol: ContractSymbol,
@@ -21,6 +23,62 @@ pub struct NewOrder {
     pub order_type: OrderType,
     pub expiry: OffsetDateTime,
     pub stable: bool,
+    pub time_in_force: TimeInForce,
+    /// A post-only limit order is rejected instead of being matched if it would cross the book.
+    pub post_only: bool,
+}
+
+impl SignedRequestType for NewOrder {
+    const REQUEST_TYPE: &'static str = "NewOrder";
+}
+
+/// The deserialised form of [`NewOrder`].
+///
+/// Apps and makers released before orders had a [`TimeInForce`] and a post-only flag do not send
+/// them. Their limit orders rest in the orderbook until they expire, while their market orders are
+/// either filled completely or not at all.
+#[derive(Deserialize)]
+struct NewOrderMessage {
+    id: Uuid,
+    contract_symbol: ContractSymbol,
+    #[serde(with = "rust_decimal::serde::float")]
+    price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    quantity: Decimal,
+    trader_id: PublicKey,
+    direction: Direction,
+    leverage: f32,
+    order_type: OrderType,
+    expiry: OffsetDateTime,
+    stable: bool,
+    #[serde(default)]
+    time_in_force: Option<TimeInForce>,
+    #[serde(default)]
+    post_only: bool,
+}
+
+impl From<NewOrderMessage> for NewOrder {
+    fn from(value: NewOrderMessage) -> Self {
+        let time_in_force = value.time_in_force.unwrap_or(match value.order_type {
+            OrderType::Market => TimeInForce::FillOrKill,
+            OrderType::Limit => TimeInForce::GoodTillTime,
+        });
+
+        NewOrder {
+            id: value.id,
+            contract_symbol: value.contract_symbol,
+            price: value.price,
+            quantity: value.quantity,
+            trader_id: value.trader_id,
+            direction: value.direction,
+            leverage: value.leverage,
+            order_type: value.order_type,
+            expiry: value.expiry,
+            stable: value.stable,
+            time_in_force,
+            post_only: value.post_only,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -30,6 +88,30 @@ pub enum OrderType {
     Limit,
 }
 
+/// Defines how long an order remains in the orderbook.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum TimeInForce {
+    /// The order is matched with the liquidity available right away, even if it is not enough to
+    /// fill the whole order. The remaining quantity is cancelled.
+    ImmediateOrCancel,
+    /// The order is either filled completely right away or not at all.
+    FillOrKill,
+    /// The order rests in the orderbook until it is filled or cancelled, ignoring its expiry.
+    GoodTillCancelled,
+    /// The order rests in the orderbook until it is filled, cancelled or expired.
+    GoodTillTime,
+}
+
+impl TimeInForce {
+    /// Whether an order which could not be filled completely rests in the orderbook.
+    pub fn rests_in_book(&self) -> bool {
+        match self {
+            TimeInForce::ImmediateOrCancel | TimeInForce::FillOrKill => false,
+            TimeInForce::GoodTillCancelled | TimeInForce::GoodTillTime => true,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct OrderResponse {
     pub id: Uuid,
@@ -48,12 +130,17 @@ pub enum OrderState {
     Matched,
     Taken,
     Failed,
+    Deleted,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum OrderReason {
     Manual,
     Expired,
+    Liquidated,
+    /// The order has been created as a stop-loss or take-profit order of the position got
+    /// triggered.
+    Triggered,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -75,4 +162,83 @@ pub struct Order {
     pub order_state: OrderState,
     pub order_reason: OrderReason,
     pub stable: bool,
+    pub time_in_force: TimeInForce,
+    pub post_only: bool,
+}
+
+impl Order {
+    /// Whether the order has passed its expiry. Orders which are good till cancelled never expire.
+    pub fn is_expired(&self, now: OffsetDateTime) -> bool {
+        self.time_in_force != TimeInForce::GoodTillCancelled && self.expiry < now
+    }
+}
+
+/// Cancels an open limit order of the signing trader.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CancelOrder {
+    pub order_id: Uuid,
+}
+
+impl SignedRequestType for CancelOrder {
+    const REQUEST_TYPE: &'static str = "CancelOrder";
+}
+
+/// Cancels all open limit orders of the trader.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CancelAllOrders {
+    pub trader_id: PublicKey,
+}
+
+impl SignedRequestType for CancelAllOrders {
+    const REQUEST_TYPE: &'static str = "CancelAllOrders";
+}
+
+/// Replaces the price and the quantity of an open limit order of the signing trader.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AmendOrder {
+    pub order_id: Uuid,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub price: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub quantity: Decimal,
+}
+
+impl SignedRequestType for AmendOrder {
+    const REQUEST_TYPE: &'static str = "AmendOrder";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn deserialise_orders_without_time_in_force_and_post_only() {
+        let trader_id = PublicKey::from_str(
+            "02bd998ebd176715fe92b7467cf6b1df8023950a4dd911db4c94dfc89cc9f5a655",
+        )
+        .unwrap();
+        let order = |order_type: OrderType| {
+            serde_json::json!({
+                "id": Uuid::new_v4(),
+                "contract_symbol": ContractSymbol::BtcUsd,
+                "price": 20_000.0,
+                "quantity": 100.0,
+                "trader_id": trader_id,
+                "direction": Direction::Long,
+                "leverage": 2.0,
+                "order_type": order_type,
+                "expiry": OffsetDateTime::now_utc(),
+                "stable": false,
+            })
+        };
+
+        let limit_order: NewOrder = serde_json::from_value(order(OrderType::Limit)).unwrap();
+        assert_eq!(limit_order.time_in_force, TimeInForce::GoodTillTime);
+        assert!(!limit_order.post_only);
+
+        let market_order: NewOrder = serde_json::from_value(order(OrderType::Market)).unwrap();
+        assert_eq!(market_order.time_in_force, TimeInForce::FillOrKill);
+        assert!(!market_order.post_only);
+    }
 }
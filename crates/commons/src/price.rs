@@ -14,23 +14,32 @@ pub struct Price {
     pub ask: Option<Decimal>,
 }
 
+impl Price {
+    /// The mid price between the best bid and the best ask, if both are available.
+    pub fn mid(&self) -> Option<Decimal> {
+        let bid = self.bid?;
+        let ask = self.ask?;
+
+        Some((bid + ask) / Decimal::TWO)
+    }
+}
+
 pub type Prices = HashMap<ContractSymbol, Price>;
 
-/// Best prices across all current orders for given ContractSymbol in the orderbook
+/// Best prices across all current orders for every ContractSymbol in the orderbook
 /// Taken orders are not included in the average
 pub fn best_current_price(current_orders: &[Order]) -> Prices {
-    let mut prices = HashMap::new();
-    let mut add_price_for_symbol = |symbol| {
-        prices.insert(
-            symbol,
-            Price {
+    ContractSymbol::ALL
+        .into_iter()
+        .map(|symbol| {
+            let price = Price {
                 bid: best_bid_price(current_orders, symbol),
                 ask: best_ask_price(current_orders, symbol),
-            },
-        );
-    };
-    add_price_for_symbol(ContractSymbol::BtcUsd);
-    prices
+            };
+
+            (symbol, price)
+        })
+        .collect()
 }
 
 /// Best price (highest) of all long (buy) orders in the orderbook
@@ -48,15 +57,14 @@ fn best_price_for(
     direction: Direction,
     symbol: ContractSymbol,
 ) -> Option<Decimal> {
-    assert_eq!(
-        symbol,
-        ContractSymbol::BtcUsd,
-        "only btcusd supported for now"
-    );
     let use_max = direction == Direction::Long;
     current_orders
         .iter()
-        .filter(|order| order.order_state == OrderState::Open && order.direction == direction)
+        .filter(|order| {
+            order.order_state == OrderState::Open
+                && order.direction == direction
+                && order.contract_symbol == symbol
+        })
         .map(|order| order.price.to_f64().expect("to represent decimal as f64"))
         // get the best price
         .fold(None, |acc, x| match acc {
@@ -73,6 +81,7 @@ mod test {
     use crate::order::OrderReason;
     use crate::order::OrderState;
     use crate::order::OrderType;
+    use crate::order::TimeInForce;
     use crate::price::best_ask_price;
     use crate::price::best_bid_price;
     use rust_decimal::Decimal;
@@ -105,6 +114,8 @@ mod test {
             order_state,
             order_reason: OrderReason::Manual,
             stable: false,
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
         }
     }
 
@@ -1,9 +1,27 @@
 use bitcoin::Network;
+use bitcoin::SignedAmount;
+use serde::Deserialize;
+use serde::Serialize;
 use time::macros::time;
 use time::Duration;
 use time::OffsetDateTime;
 use time::Weekday;
 
+/// The response of the coordinator to a rollover request.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RolloverResponse {
+    /// The total funding fee of the position, including the funding fee settled with the
+    /// requested rollover. A positive amount is paid by the trader, a negative amount by the
+    /// coordinator.
+    #[serde(with = "bitcoin::util::amount::serde::as_sat")]
+    pub funding_fee: SignedAmount,
+    /// The margin of the trader in the renewed contract. It is only reduced if the funding fee
+    /// paid by the trader exceeds their collateral reserve.
+    pub trader_margin: u64,
+    /// The liquidation price of the trader in the renewed contract.
+    pub trader_liquidation_price: f32,
+}
+
 /// Calculates the next expiry timestamp based on the given timestamp and the network.
 pub fn calculate_next_expiry(timestamp: OffsetDateTime, network: Network) -> OffsetDateTime {
     match network {
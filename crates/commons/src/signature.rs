@@ -1,10 +1,24 @@
+use anyhow::ensure;
+use anyhow::Context;
+use anyhow::Result;
 use secp256k1::Message as SecpMessage;
 use secp256k1::PublicKey;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde::Serialize;
 use sha2::digest::FixedOutput;
 use sha2::Digest;
 use sha2::Sha256;
+use std::marker::PhantomData;
+use time::Duration;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// How long a [`SignedRequest`] is accepted after it has been signed.
+///
+/// The coordinator only has to remember the nonces of the requests within this period to reject
+/// replayed requests.
+pub const SIGNED_REQUEST_VALIDITY: Duration = Duration::minutes(1);
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Signature {
@@ -20,11 +34,110 @@ pub fn create_sign_message(message: Vec<u8>) -> SecpMessage {
     msg
 }
 
+/// A request signed with the key of the trader it is issued for.
+///
+/// The request is serialised together with a nonce and the time of signing, and the signature is
+/// created over exactly these bytes. Hence, the coordinator verifies the signature before
+/// deserialising the request and does not depend on serialising the request identically.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignedRequest<T> {
+    payload: String,
+    signature: Signature,
+    #[serde(skip)]
+    request: PhantomData<T>,
+}
+
+/// A request which can be sent as a [`SignedRequest`].
+pub trait SignedRequestType {
+    /// Identifies the type of the request in the signed payload.
+    ///
+    /// Without it, a signed request could be replayed against any other endpoint which accepts a
+    /// request of the same shape. Hence, the value has to be unique across all request types.
+    const REQUEST_TYPE: &'static str;
+}
+
+#[derive(Serialize, Deserialize)]
+struct Payload<T> {
+    request_type: String,
+    nonce: Uuid,
+    #[serde(with = "time::serde::rfc3339")]
+    timestamp: OffsetDateTime,
+    request: T,
+}
+
+/// A [`SignedRequest`] whose signature has been verified.
+#[derive(Debug)]
+pub struct VerifiedRequest<T> {
+    /// The key the request has been signed with.
+    pub trader_id: PublicKey,
+    pub nonce: Uuid,
+    pub timestamp: OffsetDateTime,
+    pub request: T,
+}
+
+impl<T: Serialize + DeserializeOwned + SignedRequestType> SignedRequest<T> {
+    pub fn new(request: T, sign: impl Fn(SecpMessage) -> Signature) -> Result<Self> {
+        let payload = serde_json::to_string(&Payload {
+            request_type: T::REQUEST_TYPE.to_string(),
+            nonce: Uuid::new_v4(),
+            timestamp: OffsetDateTime::now_utc(),
+            request,
+        })
+        .context("Failed to serialise request")?;
+
+        let signature = sign(create_sign_message(payload.as_bytes().to_vec()));
+
+        Ok(Self {
+            payload,
+            signature,
+            request: PhantomData,
+        })
+    }
+
+    /// Verifies the signature of the request, that it has been signed for the request type `T` and
+    /// that it has been signed within the [`SIGNED_REQUEST_VALIDITY`].
+    ///
+    /// It is up to the caller to check that the nonce has not been used before and that the
+    /// signing key is allowed to issue the request.
+    pub fn verify(&self, now: OffsetDateTime) -> Result<VerifiedRequest<T>> {
+        let message = create_sign_message(self.payload.as_bytes().to_vec());
+        self.signature
+            .signature
+            .verify(&message, &self.signature.pubkey)
+            .context("Invalid signature")?;
+
+        let payload: Payload<T> =
+            serde_json::from_str(&self.payload).context("Failed to deserialise request")?;
+
+        ensure!(
+            payload.request_type == T::REQUEST_TYPE,
+            "Request signed for {} cannot be used as {}",
+            payload.request_type,
+            T::REQUEST_TYPE
+        );
+
+        ensure!(
+            (now - payload.timestamp).abs() <= SIGNED_REQUEST_VALIDITY,
+            "Request signed at {} has expired",
+            payload.timestamp
+        );
+
+        Ok(VerifiedRequest {
+            trader_id: self.signature.pubkey,
+            nonce: payload.nonce,
+            timestamp: payload.timestamp,
+            request: payload.request,
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::signature::Signature;
-    use secp256k1::PublicKey;
+    use super::*;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
     use secp256k1::SecretKey;
+    use secp256k1::SECP256K1;
     use std::str::FromStr;
 
     fn dummy_public_key() -> PublicKey {
@@ -64,4 +177,85 @@ mod test {
 
         assert_eq!(serialized, signature);
     }
+
+    fn secret_key() -> SecretKey {
+        SecretKey::from_slice(&[
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23,
+            24, 25, 26, 27, 27, 29, 30, 31,
+        ])
+        .unwrap()
+    }
+
+    impl SignedRequestType for Decimal {
+        const REQUEST_TYPE: &'static str = "Decimal";
+    }
+
+    impl SignedRequestType for i32 {
+        const REQUEST_TYPE: &'static str = "i32";
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct RequestA {
+        id: u32,
+    }
+
+    impl SignedRequestType for RequestA {
+        const REQUEST_TYPE: &'static str = "RequestA";
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct RequestB {
+        id: u32,
+    }
+
+    impl SignedRequestType for RequestB {
+        const REQUEST_TYPE: &'static str = "RequestB";
+    }
+
+    fn sign(message: SecpMessage) -> Signature {
+        Signature {
+            pubkey: secret_key().public_key(SECP256K1),
+            signature: secret_key().sign_ecdsa(message),
+        }
+    }
+
+    #[test]
+    fn signed_request_survives_serialisation() {
+        let request = SignedRequest::new(dec!(40_000.50), sign).unwrap();
+
+        let serialized = serde_json::to_string(&request).unwrap();
+        let request: SignedRequest<Decimal> = serde_json::from_str(&serialized).unwrap();
+
+        let verified = request.verify(OffsetDateTime::now_utc()).unwrap();
+        assert_eq!(verified.trader_id, secret_key().public_key(SECP256K1));
+        assert_eq!(verified.request, dec!(40_000.50));
+    }
+
+    #[test]
+    fn tampered_signed_request_is_rejected() {
+        let mut request = SignedRequest::new(100, sign).unwrap();
+        request.payload = request
+            .payload
+            .replace(r#""request":100"#, r#""request":200"#);
+
+        assert!(request.verify(OffsetDateTime::now_utc()).is_err());
+    }
+
+    #[test]
+    fn signed_request_for_another_request_type_is_rejected() {
+        let request = SignedRequest::new(RequestA { id: 1 }, sign).unwrap();
+
+        let serialized = serde_json::to_string(&request).unwrap();
+        let request: SignedRequest<RequestB> = serde_json::from_str(&serialized).unwrap();
+
+        assert!(request.verify(OffsetDateTime::now_utc()).is_err());
+    }
+
+    #[test]
+    fn expired_signed_request_is_rejected() {
+        let request = SignedRequest::new(100, sign).unwrap();
+
+        let later = OffsetDateTime::now_utc() + SIGNED_REQUEST_VALIDITY + Duration::SECOND;
+        assert!(request.verify(later).is_err());
+    }
 }
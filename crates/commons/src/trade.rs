@@ -1,3 +1,5 @@
+use crate::OracleParams;
+use crate::SignedRequestType;
 use rust_decimal::Decimal;
 use secp256k1::PublicKey;
 use secp256k1::XOnlyPublicKey;
@@ -45,6 +47,10 @@ pub struct TradeParams {
     pub filled_with: FilledWith,
 }
 
+impl SignedRequestType for TradeParams {
+    const REQUEST_TYPE: &'static str = "TradeParams";
+}
+
 impl TradeParams {
     pub fn average_execution_price(&self) -> Decimal {
         self.filled_with.average_execution_price()
@@ -100,6 +106,7 @@ impl From<Matches> for Match {
 /// the trader submitted to the orderbook. The matches define how this order was filled.
 /// This information is used to request trade execution with the coordinator.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(try_from = "FilledWithMessage", into = "FilledWithMessage")]
 pub struct FilledWith {
     /// The id of the order defined by the orderbook
     ///
@@ -117,26 +124,86 @@ pub struct FilledWith {
     /// The oracle event-id is defined by contract symbol and the expiry timestamp.
     pub expiry_timestamp: OffsetDateTime,
 
-    /// The public key of the oracle to be used
+    /// The oracles to be used
     ///
     /// The orderbook decides this when matching orders.
-    /// The oracle_pk is used to define what oracle is to be used in the contract.
-    /// This `oracle_pk` must correspond to one `oracle_pk` configured in the dlc-manager.
-    /// It is possible to configure multiple oracles in the dlc-manager; this
-    /// `oracle_pk` has to match one of them. This allows us to configure the dlc-managers
-    /// using two oracles, where one oracles can be used as backup if the other oracle is not
-    /// available. Eventually this can be changed to be a list of oracle PKs and a threshold of
-    /// how many oracle have to agree on the attestation.
-    pub oracle_pk: XOnlyPublicKey,
+    /// The contract references all oracles in `oracle_params`, out of which `threshold` have to
+    /// attest to the outcome. Every public key must correspond to an oracle configured in the
+    /// dlc-manager.
+    pub oracle_params: OracleParams,
 
     /// The matches for the order
     pub matches: Vec<Match>,
+
+    /// The id of the order the filled order has been split off from
+    ///
+    /// If an order is only partially filled, the orderbook splits the filled quantity off into a
+    /// new order identified by `order_id`, while the original order keeps the remaining quantity.
+    #[serde(default)]
+    pub parent_order_id: Option<Uuid>,
+}
+
+/// The serialised form of [`FilledWith`].
+///
+/// Apps released before contracts could reference multiple oracles only know about a single
+/// `oracle_pk`. Hence, the first oracle is still sent as `oracle_pk`, so that these apps can
+/// deserialise their matches. Messages from these apps lack the `oracle_params`, in which case the
+/// `oracle_pk` is the only oracle.
+#[derive(Serialize, Deserialize)]
+struct FilledWithMessage {
+    order_id: Uuid,
+    expiry_timestamp: OffsetDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    oracle_pk: Option<XOnlyPublicKey>,
+    #[serde(default)]
+    oracle_params: Option<OracleParams>,
+    matches: Vec<Match>,
+    #[serde(default)]
+    parent_order_id: Option<Uuid>,
+}
+
+impl TryFrom<FilledWithMessage> for FilledWith {
+    type Error = String;
+
+    fn try_from(value: FilledWithMessage) -> Result<Self, Self::Error> {
+        let oracle_params = match (value.oracle_params, value.oracle_pk) {
+            (Some(oracle_params), _) => oracle_params,
+            (None, Some(oracle_pk)) => OracleParams::single(oracle_pk),
+            (None, None) => return Err("Missing oracle_params and oracle_pk".to_string()),
+        };
+
+        Ok(FilledWith {
+            order_id: value.order_id,
+            expiry_timestamp: value.expiry_timestamp,
+            oracle_params,
+            matches: value.matches,
+            parent_order_id: value.parent_order_id,
+        })
+    }
+}
+
+impl From<FilledWith> for FilledWithMessage {
+    fn from(value: FilledWith) -> Self {
+        FilledWithMessage {
+            order_id: value.order_id,
+            expiry_timestamp: value.expiry_timestamp,
+            oracle_pk: value.oracle_params.pubkeys.first().copied(),
+            oracle_params: Some(value.oracle_params),
+            matches: value.matches,
+            parent_order_id: value.parent_order_id,
+        }
+    }
 }
 
 impl FilledWith {
     pub fn average_execution_price(&self) -> Decimal {
         average_execution_price(self.matches.clone())
     }
+
+    /// The total quantity of all matches.
+    pub fn quantity(&self) -> Decimal {
+        self.matches.iter().map(|m| m.quantity).sum()
+    }
 }
 
 /// calculates the average execution price for inverse contracts
@@ -187,6 +254,7 @@ mod test {
 
     use crate::trade::FilledWith;
     use crate::trade::Match;
+    use crate::OracleParams;
     use rust_decimal_macros::dec;
     use secp256k1::PublicKey;
     use secp256k1::XOnlyPublicKey;
@@ -203,10 +271,12 @@ mod test {
         let filled = FilledWith {
             order_id: Default::default(),
             expiry_timestamp: OffsetDateTime::now_utc(),
-            oracle_pk: XOnlyPublicKey::from_str(
-                "16f88cf7d21e6c0f46bcbc983a4e3b19726c6c98858cc31c83551a88fde171c0",
-            )
-            .expect("To be a valid pubkey"),
+            oracle_params: OracleParams::single(
+                XOnlyPublicKey::from_str(
+                    "16f88cf7d21e6c0f46bcbc983a4e3b19726c6c98858cc31c83551a88fde171c0",
+                )
+                .expect("To be a valid pubkey"),
+            ),
             matches: vec![
                 Match {
                     id: Uuid::new_v4(),
@@ -223,10 +293,54 @@ mod test {
                     execution_price: match_1_price,
                 },
             ],
+            parent_order_id: None,
         };
 
         let average_execution_price = filled.average_execution_price();
 
         assert_eq!(average_execution_price.round_dp(2), dec!(11250.00));
     }
+
+    #[test]
+    fn deserialise_filled_with_of_app_without_oracle_params() {
+        let oracle_pk = XOnlyPublicKey::from_str(
+            "16f88cf7d21e6c0f46bcbc983a4e3b19726c6c98858cc31c83551a88fde171c0",
+        )
+        .unwrap();
+
+        let filled_with = serde_json::json!({
+            "order_id": Uuid::new_v4(),
+            "expiry_timestamp": OffsetDateTime::now_utc(),
+            "oracle_pk": oracle_pk,
+            "matches": [],
+        });
+
+        let filled_with: FilledWith = serde_json::from_value(filled_with).unwrap();
+
+        assert_eq!(filled_with.oracle_params, OracleParams::single(oracle_pk));
+        assert_eq!(filled_with.parent_order_id, None);
+    }
+
+    #[test]
+    fn serialise_filled_with_for_app_without_oracle_params() {
+        let oracle_pk = XOnlyPublicKey::from_str(
+            "16f88cf7d21e6c0f46bcbc983a4e3b19726c6c98858cc31c83551a88fde171c0",
+        )
+        .unwrap();
+        let filled_with = FilledWith {
+            order_id: Uuid::new_v4(),
+            expiry_timestamp: OffsetDateTime::now_utc(),
+            oracle_params: OracleParams::single(oracle_pk),
+            matches: vec![],
+            parent_order_id: None,
+        };
+
+        let value = serde_json::to_value(&filled_with).unwrap();
+
+        assert_eq!(value["oracle_pk"], serde_json::json!(oracle_pk));
+        assert_eq!(
+            serde_json::from_value::<FilledWith>(value).unwrap(),
+            filled_with
+        );
+    }
 }
@@ -0,0 +1,162 @@
+use crate::SignedRequestType;
+use rust_decimal::Decimal;
+use secp256k1::PublicKey;
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+use trade::ContractSymbol;
+use trade::Direction;
+use uuid::Uuid;
+
+/// The kind of a trigger order, i.e. of a conditional exit attached to a position.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerType {
+    /// Closes the position once the price moved against the trader beyond the trigger price.
+    StopLoss,
+    /// Closes the position once the price moved in favour of the trader beyond the trigger price.
+    TakeProfit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerOrderState {
+    /// The trigger order is evaluated against the price feed.
+    Active,
+    /// The trigger price has been reached and the position is being closed.
+    Triggered,
+    /// The trigger order has been cancelled by the trader or because the position has been
+    /// closed otherwise.
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewTriggerOrder {
+    pub id: Uuid,
+    pub trader_id: PublicKey,
+    pub contract_symbol: ContractSymbol,
+    pub trigger_type: TriggerType,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub trigger_price: Decimal,
+}
+
+impl SignedRequestType for NewTriggerOrder {
+    const REQUEST_TYPE: &'static str = "NewTriggerOrder";
+}
+
+/// Loads the active trigger orders of the signing trader.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetTriggerOrders {
+    pub trader_id: PublicKey,
+}
+
+impl SignedRequestType for GetTriggerOrders {
+    const REQUEST_TYPE: &'static str = "GetTriggerOrders";
+}
+
+/// Replaces the trigger price of an active trigger order of the signing trader.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AmendTriggerOrder {
+    pub trigger_order_id: Uuid,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub trigger_price: Decimal,
+}
+
+impl SignedRequestType for AmendTriggerOrder {
+    const REQUEST_TYPE: &'static str = "AmendTriggerOrder";
+}
+
+/// Cancels an active trigger order of the signing trader.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CancelTriggerOrder {
+    pub trigger_order_id: Uuid,
+}
+
+impl SignedRequestType for CancelTriggerOrder {
+    const REQUEST_TYPE: &'static str = "CancelTriggerOrder";
+}
+
+/// A stop-loss or take-profit order attached to the position of a trader.
+///
+/// Once the trigger price is reached, the coordinator closes the position on behalf of the trader.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TriggerOrder {
+    pub id: Uuid,
+    pub trader_id: PublicKey,
+    pub position_id: i32,
+    pub contract_symbol: ContractSymbol,
+    pub trigger_type: TriggerType,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub trigger_price: Decimal,
+    pub state: TriggerOrderState,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
+
+impl TriggerOrder {
+    /// Returns true if the given mark price reached the trigger price of this order.
+    ///
+    /// We do not evaluate trigger orders against the best bid and ask of the orderbook, as a
+    /// single resting limit order on a thin book would otherwise be enough to trigger them.
+    pub fn is_triggered(&self, position_direction: Direction, mark_price: Decimal) -> bool {
+        match (self.trigger_type, position_direction) {
+            (TriggerType::StopLoss, Direction::Long)
+            | (TriggerType::TakeProfit, Direction::Short) => mark_price <= self.trigger_price,
+            (TriggerType::StopLoss, Direction::Short)
+            | (TriggerType::TakeProfit, Direction::Long) => mark_price >= self.trigger_price,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::str::FromStr;
+
+    #[test]
+    fn stop_loss_of_long_position_triggers_if_mark_price_falls_below_trigger_price() {
+        let order = dummy_trigger_order(TriggerType::StopLoss, dec!(30_000));
+
+        assert!(order.is_triggered(Direction::Long, dec!(29_999)));
+        assert!(!order.is_triggered(Direction::Long, dec!(30_001)));
+    }
+
+    #[test]
+    fn stop_loss_of_short_position_triggers_if_mark_price_rises_above_trigger_price() {
+        let order = dummy_trigger_order(TriggerType::StopLoss, dec!(30_000));
+
+        assert!(order.is_triggered(Direction::Short, dec!(30_001)));
+        assert!(!order.is_triggered(Direction::Short, dec!(29_999)));
+    }
+
+    #[test]
+    fn take_profit_of_long_position_triggers_if_mark_price_rises_above_trigger_price() {
+        let order = dummy_trigger_order(TriggerType::TakeProfit, dec!(30_000));
+
+        assert!(order.is_triggered(Direction::Long, dec!(30_000)));
+        assert!(!order.is_triggered(Direction::Long, dec!(29_999)));
+    }
+
+    #[test]
+    fn take_profit_of_short_position_triggers_if_mark_price_falls_below_trigger_price() {
+        let order = dummy_trigger_order(TriggerType::TakeProfit, dec!(30_000));
+
+        assert!(order.is_triggered(Direction::Short, dec!(29_999)));
+        assert!(!order.is_triggered(Direction::Short, dec!(30_001)));
+    }
+
+    fn dummy_trigger_order(trigger_type: TriggerType, trigger_price: Decimal) -> TriggerOrder {
+        TriggerOrder {
+            id: Uuid::new_v4(),
+            trader_id: PublicKey::from_str(
+                "027f31ebc5462c1fdce1b737ecff52d37d75dea43ce11c74d25aa297165faa2007",
+            )
+            .unwrap(),
+            position_id: 1,
+            contract_symbol: ContractSymbol::BtcUsd,
+            trigger_type,
+            trigger_price,
+            state: TriggerOrderState::Active,
+            timestamp: OffsetDateTime::now_utc(),
+        }
+    }
+}
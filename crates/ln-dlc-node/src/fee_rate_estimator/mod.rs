@@ -0,0 +1,465 @@
+use anyhow::bail;
+use anyhow::Result;
+use bdk::FeeRate;
+use bitcoin::Network;
+use futures::future::join_all;
+use lightning::chain::chaininterface::ConfirmationTarget;
+use lightning::chain::chaininterface::FeeEstimator;
+use lightning::chain::chaininterface::FEERATE_FLOOR_SATS_PER_KW;
+use parking_lot::RwLock;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_with::serde_as;
+use serde_with::DurationSeconds;
+use std::collections::HashMap;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+mod source;
+
+pub(crate) use source::FeeRateSource;
+pub use source::FeeRateSourceConfig;
+
+/// Default values used when constructing the [`FeeRateEstimator`] if the fee rate sever cannot give
+/// us up-to-date values.
+///
+/// In sats/kwu.
+const FEE_RATE_DEFAULTS: [(ConfirmationTarget, u32); 4] = [
+    (ConfirmationTarget::MempoolMinimum, 1000),
+    (ConfirmationTarget::Background, 2000),
+    (ConfirmationTarget::Normal, 3000),
+    (ConfirmationTarget::HighPriority, 4000),
+];
+
+/// Which fee rate sources to ask and how to combine their estimates.
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct FeeRateEstimatorConfig {
+    pub sources: Vec<FeeRateSourceConfig>,
+    pub policy: FeeRatePolicy,
+    /// Estimates below this fee rate are discarded as bogus.
+    pub min_fee_rate_sats_per_vbyte: f32,
+    /// Estimates above this fee rate are discarded as bogus.
+    pub max_fee_rate_sats_per_vbyte: f32,
+    /// How long the estimates of a source are used after the last successful update.
+    #[serde_as(as = "DurationSeconds")]
+    pub max_age: Duration,
+}
+
+impl Default for FeeRateEstimatorConfig {
+    fn default() -> Self {
+        Self {
+            sources: vec![FeeRateSourceConfig::Mempool, FeeRateSourceConfig::Esplora],
+            policy: FeeRatePolicy::PriorityOrder,
+            min_fee_rate_sats_per_vbyte: 1.0,
+            max_fee_rate_sats_per_vbyte: 1_000.0,
+            max_age: Duration::from_secs(600),
+        }
+    }
+}
+
+/// How the estimates of several fee rate sources are combined.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+pub enum FeeRatePolicy {
+    /// Take the estimate of the first source in the configured order.
+    PriorityOrder,
+    /// Take the median of all estimates.
+    Median,
+    /// Take the highest estimate.
+    Max,
+}
+
+impl FeeRatePolicy {
+    /// Combine the `fee_rates` of the sources, given in the configured order.
+    fn combine(&self, fee_rates: &[FeeRate]) -> Option<FeeRate> {
+        match self {
+            FeeRatePolicy::PriorityOrder => fee_rates.first().copied(),
+            FeeRatePolicy::Max => fee_rates
+                .iter()
+                .copied()
+                .max_by(|a, b| a.as_sat_per_vb().total_cmp(&b.as_sat_per_vb())),
+            FeeRatePolicy::Median => {
+                let mut fee_rates = fee_rates
+                    .iter()
+                    .map(|fee_rate| fee_rate.as_sat_per_vb())
+                    .collect::<Vec<_>>();
+                fee_rates.sort_by(f32::total_cmp);
+
+                let middle = fee_rates.len() / 2;
+                let median = match fee_rates.len() {
+                    0 => return None,
+                    n if n % 2 == 0 => (fee_rates[middle - 1] + fee_rates[middle]) / 2.0,
+                    _ => fee_rates[middle],
+                };
+
+                Some(FeeRate::from_sat_per_vb(median))
+            }
+        }
+    }
+}
+
+/// The latest estimates of a fee rate source, as exposed in metrics.
+#[derive(Debug, Clone)]
+pub struct FeeRateSourceStatus {
+    pub name: &'static str,
+    pub estimates: HashMap<ConfirmationTarget, FeeRate>,
+    /// When the source was last updated successfully.
+    pub last_update: Option<OffsetDateTime>,
+    /// Whether the estimates are too old to be used.
+    pub is_stale: bool,
+}
+
+struct FeeRateSample {
+    estimates: HashMap<ConfirmationTarget, FeeRate>,
+    timestamp: OffsetDateTime,
+}
+
+pub struct FeeRateEstimator {
+    sources: Vec<Box<dyn FeeRateSource + Send + Sync>>,
+    /// The latest estimates of each of the `sources`, in the same order.
+    samples: RwLock<Vec<Option<FeeRateSample>>>,
+    config: FeeRateEstimatorConfig,
+    fee_rate_cache: RwLock<HashMap<ConfirmationTarget, FeeRate>>,
+    last_update: RwLock<Option<OffsetDateTime>>,
+}
+
+pub trait EstimateFeeRate {
+    fn estimate(&self, target: ConfirmationTarget) -> FeeRate;
+}
+
+impl EstimateFeeRate for FeeRateEstimator {
+    fn estimate(&self, target: ConfirmationTarget) -> FeeRate {
+        self.get(target)
+    }
+}
+
+impl FeeRateEstimator {
+    /// Constructor for the [`FeeRateEstimator`].
+    pub fn new(
+        network: Network,
+        config: FeeRateEstimatorConfig,
+        esplora_server_url: &str,
+    ) -> Result<Self> {
+        let mut sources = Vec::new();
+        for source_config in config.sources.iter() {
+            if let Some(source) = source::build(source_config, network, esplora_server_url)? {
+                sources.push(source);
+            }
+        }
+
+        if sources.is_empty() {
+            tracing::warn!(?config, "No fee rate sources available");
+        }
+
+        Ok(Self::from_sources(sources, config))
+    }
+
+    fn from_sources(
+        sources: Vec<Box<dyn FeeRateSource + Send + Sync>>,
+        config: FeeRateEstimatorConfig,
+    ) -> Self {
+        tracing::warn!(defaults = ?FEE_RATE_DEFAULTS, "Initializing fee rate cache with default values.");
+
+        let initial_fee_rates = HashMap::from_iter(
+            FEE_RATE_DEFAULTS
+                .into_iter()
+                .map(|(target, fee_rate)| (target, FeeRate::from_sat_per_kwu(fee_rate as f32))),
+        );
+
+        let fee_rate_cache = RwLock::new(initial_fee_rates);
+
+        Self {
+            samples: RwLock::new(sources.iter().map(|_| None).collect()),
+            sources,
+            config,
+            fee_rate_cache,
+            last_update: RwLock::new(None),
+        }
+    }
+
+    pub fn get(&self, target: ConfirmationTarget) -> FeeRate {
+        self.fee_rate_cache
+            .read()
+            .get(&target)
+            .copied()
+            .expect("to have entries for all confirmation targets")
+    }
+
+    /// When the fee rate estimates were last updated. `None` if we are still using the defaults.
+    pub fn last_update(&self) -> Option<OffsetDateTime> {
+        *self.last_update.read()
+    }
+
+    /// Whether none of the sources have given us usable estimates for longer than the configured
+    /// maximum age.
+    pub fn is_stale(&self) -> bool {
+        self.last_update()
+            .map_or(true, |last_update| self.is_older_than_max_age(last_update))
+    }
+
+    pub fn source_statuses(&self) -> Vec<FeeRateSourceStatus> {
+        let samples = self.samples.read();
+
+        self.sources
+            .iter()
+            .zip(samples.iter())
+            .map(|(source, sample)| FeeRateSourceStatus {
+                name: source.name(),
+                estimates: sample
+                    .as_ref()
+                    .map(|sample| sample.estimates.clone())
+                    .unwrap_or_default(),
+                last_update: sample.as_ref().map(|sample| sample.timestamp),
+                is_stale: sample
+                    .as_ref()
+                    .map_or(true, |sample| self.is_older_than_max_age(sample.timestamp)),
+            })
+            .collect()
+    }
+
+    pub(crate) async fn update(&self) -> Result<()> {
+        let results = join_all(self.sources.iter().map(|source| source.fetch())).await;
+
+        let now = OffsetDateTime::now_utc();
+        {
+            let mut samples = self.samples.write();
+            for ((source, result), sample) in
+                self.sources.iter().zip(results).zip(samples.iter_mut())
+            {
+                match result {
+                    Ok(estimates) => {
+                        *sample = Some(FeeRateSample {
+                            estimates: self.within_bounds(source.name(), estimates),
+                            timestamp: now,
+                        });
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            source = source.name(),
+                            "Failed to fetch fee rate estimates: {e:#}"
+                        );
+                    }
+                }
+            }
+        }
+
+        let fee_rates = self.combine_fresh_estimates();
+        if fee_rates.is_empty() {
+            bail!("No fresh fee rate estimates from any source");
+        }
+
+        self.fee_rate_cache.write().extend(fee_rates);
+
+        let newest_sample = self
+            .samples
+            .read()
+            .iter()
+            .flatten()
+            .map(|sample| sample.timestamp)
+            .max();
+        *self.last_update.write() = newest_sample;
+
+        Ok(())
+    }
+
+    /// Discard the `estimates` of the source outside of the configured sanity bounds.
+    fn within_bounds(
+        &self,
+        source: &str,
+        estimates: HashMap<ConfirmationTarget, FeeRate>,
+    ) -> HashMap<ConfirmationTarget, FeeRate> {
+        let min = self.config.min_fee_rate_sats_per_vbyte;
+        let max = self.config.max_fee_rate_sats_per_vbyte;
+
+        estimates
+            .into_iter()
+            .filter(|(target, fee_rate)| {
+                let sats_per_vbyte = fee_rate.as_sat_per_vb();
+                let within_bounds = (min..=max).contains(&sats_per_vbyte);
+                if !within_bounds {
+                    tracing::warn!(
+                        %source,
+                        ?target,
+                        %sats_per_vbyte,
+                        %min,
+                        %max,
+                        "Discarding fee rate estimate outside of sanity bounds"
+                    );
+                }
+
+                within_bounds
+            })
+            .collect()
+    }
+
+    /// Combine the estimates of all sources which are not stale, according to the configured
+    /// [`FeeRatePolicy`].
+    fn combine_fresh_estimates(&self) -> HashMap<ConfirmationTarget, FeeRate> {
+        let samples = self.samples.read();
+        let fresh_samples = samples
+            .iter()
+            .flatten()
+            .filter(|sample| !self.is_older_than_max_age(sample.timestamp))
+            .collect::<Vec<_>>();
+
+        FEE_RATE_DEFAULTS
+            .iter()
+            .filter_map(|(target, _)| {
+                let fee_rates = fresh_samples
+                    .iter()
+                    .filter_map(|sample| sample.estimates.get(target).copied())
+                    .collect::<Vec<_>>();
+
+                let fee_rate = self.config.policy.combine(&fee_rates)?;
+                Some((*target, fee_rate))
+            })
+            .collect()
+    }
+
+    fn is_older_than_max_age(&self, timestamp: OffsetDateTime) -> bool {
+        OffsetDateTime::now_utc() - timestamp >= self.config.max_age
+    }
+}
+
+impl FeeEstimator for FeeRateEstimator {
+    fn get_est_sat_per_1000_weight(&self, confirmation_target: ConfirmationTarget) -> u32 {
+        (self.estimate(confirmation_target).fee_wu(1000) as u32).max(FEERATE_FLOOR_SATS_PER_KW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    #[test]
+    fn median_of_even_number_of_fee_rates_is_mean_of_middle_two() {
+        let fee_rates = [2.0, 10.0, 4.0, 6.0].map(FeeRate::from_sat_per_vb);
+
+        let median = FeeRatePolicy::Median.combine(&fee_rates).unwrap();
+
+        assert_eq!(median, FeeRate::from_sat_per_vb(5.0));
+    }
+
+    #[test]
+    fn policies_combine_fee_rates() {
+        let fee_rates = [4.0, 10.0, 2.0].map(FeeRate::from_sat_per_vb);
+
+        assert_eq!(
+            FeeRatePolicy::PriorityOrder.combine(&fee_rates),
+            Some(FeeRate::from_sat_per_vb(4.0))
+        );
+        assert_eq!(
+            FeeRatePolicy::Median.combine(&fee_rates),
+            Some(FeeRate::from_sat_per_vb(4.0))
+        );
+        assert_eq!(
+            FeeRatePolicy::Max.combine(&fee_rates),
+            Some(FeeRate::from_sat_per_vb(10.0))
+        );
+        assert_eq!(FeeRatePolicy::Median.combine(&[]), None);
+    }
+
+    #[tokio::test]
+    async fn skips_failing_sources_and_estimates_out_of_bounds() {
+        let estimator = FeeRateEstimator::from_sources(
+            vec![
+                DummyFeeRateSource::new_boxed(None),
+                DummyFeeRateSource::new_boxed(Some(5_000.0)),
+                DummyFeeRateSource::new_boxed(Some(3.0)),
+            ],
+            FeeRateEstimatorConfig {
+                sources: vec![],
+                ..Default::default()
+            },
+        );
+
+        assert!(estimator.is_stale());
+
+        estimator.update().await.unwrap();
+
+        assert_eq!(
+            estimator.get(ConfirmationTarget::Normal),
+            FeeRate::from_sat_per_vb(3.0)
+        );
+        assert!(!estimator.is_stale());
+
+        let statuses = estimator.source_statuses();
+        assert!(statuses[0].last_update.is_none());
+        assert!(statuses[0].is_stale);
+        assert!(statuses[1].estimates.is_empty());
+        assert!(!statuses[2].is_stale);
+    }
+
+    #[tokio::test]
+    async fn keeps_last_estimates_if_all_sources_fail() {
+        let fee_rate = Arc::new(Mutex::new(Some(3.0)));
+        let estimator = FeeRateEstimator::from_sources(
+            vec![Box::new(DummyFeeRateSource {
+                fee_rate: fee_rate.clone(),
+            })],
+            FeeRateEstimatorConfig::default(),
+        );
+
+        estimator.update().await.unwrap();
+
+        *fee_rate.lock() = None;
+        estimator.update().await.unwrap();
+
+        assert_eq!(
+            estimator.get(ConfirmationTarget::Normal),
+            FeeRate::from_sat_per_vb(3.0)
+        );
+    }
+
+    #[tokio::test]
+    async fn stale_estimates_are_not_used() {
+        let estimator = FeeRateEstimator::from_sources(
+            vec![DummyFeeRateSource::new_boxed(Some(3.0))],
+            FeeRateEstimatorConfig {
+                max_age: Duration::ZERO,
+                ..Default::default()
+            },
+        );
+
+        assert!(estimator.update().await.is_err());
+        assert_eq!(
+            estimator.get(ConfirmationTarget::Normal),
+            FeeRate::from_sat_per_kwu(3000.0)
+        );
+    }
+
+    /// Estimates the same fee rate for all targets, or fails if it has none.
+    struct DummyFeeRateSource {
+        fee_rate: Arc<Mutex<Option<f32>>>,
+    }
+
+    impl DummyFeeRateSource {
+        fn new_boxed(fee_rate: Option<f32>) -> Box<dyn FeeRateSource + Send + Sync> {
+            Box::new(Self {
+                fee_rate: Arc::new(Mutex::new(fee_rate)),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl FeeRateSource for DummyFeeRateSource {
+        fn name(&self) -> &'static str {
+            "dummy"
+        }
+
+        async fn fetch(&self) -> Result<HashMap<ConfirmationTarget, FeeRate>> {
+            let fee_rate = match *self.fee_rate.lock() {
+                Some(fee_rate) => FeeRate::from_sat_per_vb(fee_rate),
+                None => bail!("No fee rate"),
+            };
+
+            Ok(FEE_RATE_DEFAULTS
+                .iter()
+                .map(|(target, _)| (*target, fee_rate))
+                .collect())
+        }
+    }
+}
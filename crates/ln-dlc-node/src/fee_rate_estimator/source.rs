@@ -0,0 +1,298 @@
+use anyhow::bail;
+use anyhow::Context;
+use anyhow::Result;
+use async_trait::async_trait;
+use bdk::bitcoincore_rpc;
+use bdk::bitcoincore_rpc::RpcApi;
+use bdk::electrum_client;
+use bdk::electrum_client::ElectrumApi;
+use bdk::FeeRate;
+use bitcoin::Network;
+use lightning::chain::chaininterface::ConfirmationTarget;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The number of blocks within which we want a transaction to confirm, per
+/// [`ConfirmationTarget`].
+///
+/// Used for the sources which estimate fee rates for a number of blocks.
+const CONFIRMATION_BLOCKS: [(ConfirmationTarget, u16); 4] = [
+    (ConfirmationTarget::MempoolMinimum, 1008),
+    (ConfirmationTarget::Background, 144),
+    (ConfirmationTarget::Normal, 6),
+    (ConfirmationTarget::HighPriority, 1),
+];
+
+/// A service we can ask for fee rate estimates.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub enum FeeRateSourceConfig {
+    /// The mempool.space API. Not available on regtest.
+    Mempool,
+    /// The `estimatesmartfee` RPC of a bitcoind node.
+    Bitcoind {
+        rpc_url: String,
+        rpc_username: String,
+        rpc_password: String,
+    },
+    /// The `/fee-estimates` endpoint of the Esplora server the node is started with.
+    Esplora,
+    /// The `blockchain.estimatefee` method of an Electrum server.
+    Electrum { server_url: String },
+}
+
+#[async_trait]
+pub trait FeeRateSource {
+    /// The name of the source, used in logs and metrics.
+    fn name(&self) -> &'static str;
+
+    /// Fetch the current fee rate estimates of the source.
+    ///
+    /// Sources may not have an estimate for every [`ConfirmationTarget`].
+    async fn fetch(&self) -> Result<HashMap<ConfirmationTarget, FeeRate>>;
+}
+
+/// Build the [`FeeRateSource`] described by `config`.
+///
+/// Returns `None` if the source is not available on the `network`.
+pub(crate) fn build(
+    config: &FeeRateSourceConfig,
+    network: Network,
+    esplora_server_url: &str,
+) -> Result<Option<Box<dyn FeeRateSource + Send + Sync>>> {
+    let source: Box<dyn FeeRateSource + Send + Sync> = match config {
+        FeeRateSourceConfig::Mempool => {
+            let network = match network {
+                Network::Bitcoin => mempool::Network::Mainnet,
+                Network::Testnet => mempool::Network::Testnet,
+                Network::Signet => mempool::Network::Signet,
+                Network::Regtest => {
+                    tracing::warn!(
+                        "mempool.space does not support regtest, ignoring fee rate source"
+                    );
+                    return Ok(None);
+                }
+            };
+
+            Box::new(MempoolFeeRateSource {
+                client: mempool::MempoolFeeRateEstimator::new(network),
+            })
+        }
+        FeeRateSourceConfig::Bitcoind {
+            rpc_url,
+            rpc_username,
+            rpc_password,
+        } => {
+            let client = bitcoincore_rpc::Client::new(
+                rpc_url,
+                bitcoincore_rpc::Auth::UserPass(rpc_username.clone(), rpc_password.clone()),
+            )
+            .with_context(|| format!("Failed to create bitcoind RPC client for {rpc_url}"))?;
+
+            Box::new(BitcoindFeeRateSource {
+                client: Arc::new(client),
+            })
+        }
+        FeeRateSourceConfig::Esplora => Box::new(EsploraFeeRateSource {
+            url: esplora_server_url.trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+        }),
+        FeeRateSourceConfig::Electrum { server_url } => {
+            let client = electrum_client::Client::new(server_url)
+                .with_context(|| format!("Failed to connect to Electrum server {server_url}"))?;
+
+            Box::new(ElectrumFeeRateSource {
+                client: Arc::new(client),
+            })
+        }
+    };
+
+    Ok(Some(source))
+}
+
+struct MempoolFeeRateSource {
+    client: mempool::MempoolFeeRateEstimator,
+}
+
+#[async_trait]
+impl FeeRateSource for MempoolFeeRateSource {
+    fn name(&self) -> &'static str {
+        "mempool"
+    }
+
+    async fn fetch(&self) -> Result<HashMap<ConfirmationTarget, FeeRate>> {
+        let estimates = self.client.fetch_fee().await?;
+
+        Ok(HashMap::from([
+            (
+                ConfirmationTarget::MempoolMinimum,
+                FeeRate::from_sat_per_vb(estimates.minimum_fee as f32),
+            ),
+            (
+                ConfirmationTarget::Background,
+                FeeRate::from_sat_per_vb(estimates.economy_fee as f32),
+            ),
+            (
+                ConfirmationTarget::Normal,
+                FeeRate::from_sat_per_vb(estimates.hour_fee as f32),
+            ),
+            (
+                ConfirmationTarget::HighPriority,
+                FeeRate::from_sat_per_vb(estimates.fastest_fee as f32),
+            ),
+        ]))
+    }
+}
+
+struct BitcoindFeeRateSource {
+    client: Arc<bitcoincore_rpc::Client>,
+}
+
+#[async_trait]
+impl FeeRateSource for BitcoindFeeRateSource {
+    fn name(&self) -> &'static str {
+        "bitcoind"
+    }
+
+    async fn fetch(&self) -> Result<HashMap<ConfirmationTarget, FeeRate>> {
+        let client = self.client.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let mut estimates = HashMap::new();
+            let mut errors = Vec::new();
+            for (target, blocks) in CONFIRMATION_BLOCKS {
+                let estimate = client
+                    .estimate_smart_fee(blocks, None)
+                    .context("Failed to call estimatesmartfee")?;
+
+                match estimate.fee_rate {
+                    // In BTC/kvB.
+                    Some(fee_rate) => {
+                        let fee_rate = FeeRate::from_sat_per_vb(fee_rate.to_sat() as f32 / 1000.0);
+                        estimates.insert(target, fee_rate);
+                    }
+                    None => errors.extend(estimate.errors.unwrap_or_default()),
+                }
+            }
+
+            if estimates.is_empty() {
+                bail!("bitcoind has no fee rate estimates: {}", errors.join(", "));
+            }
+
+            Ok(estimates)
+        })
+        .await?
+    }
+}
+
+struct EsploraFeeRateSource {
+    url: String,
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl FeeRateSource for EsploraFeeRateSource {
+    fn name(&self) -> &'static str {
+        "esplora"
+    }
+
+    async fn fetch(&self) -> Result<HashMap<ConfirmationTarget, FeeRate>> {
+        let response = self
+            .client
+            .get(format!("{}/fee-estimates", self.url))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        // Maps the number of blocks to a fee rate in sats/vbyte.
+        let estimates: HashMap<String, f64> = response.json().await?;
+        let estimates = estimates
+            .into_iter()
+            .filter_map(|(blocks, fee_rate)| Some((blocks.parse().ok()?, fee_rate)))
+            .collect::<Vec<(u16, f64)>>();
+
+        let estimates = CONFIRMATION_BLOCKS
+            .into_iter()
+            .filter_map(|(target, blocks)| {
+                let fee_rate = fee_rate_for_blocks(&estimates, blocks)?;
+                Some((target, FeeRate::from_sat_per_vb(fee_rate as f32)))
+            })
+            .collect::<HashMap<_, _>>();
+
+        if estimates.is_empty() {
+            bail!("Esplora has no fee rate estimates");
+        }
+
+        Ok(estimates)
+    }
+}
+
+struct ElectrumFeeRateSource {
+    client: Arc<electrum_client::Client>,
+}
+
+#[async_trait]
+impl FeeRateSource for ElectrumFeeRateSource {
+    fn name(&self) -> &'static str {
+        "electrum"
+    }
+
+    async fn fetch(&self) -> Result<HashMap<ConfirmationTarget, FeeRate>> {
+        let client = self.client.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let mut estimates = HashMap::new();
+            for (target, blocks) in CONFIRMATION_BLOCKS {
+                let fee_rate = client
+                    .estimate_fee(usize::from(blocks))
+                    .context("Failed to call blockchain.estimatefee")?;
+
+                // In BTC/kvB. The server responds with -1 if it has no estimate.
+                if fee_rate > 0.0 {
+                    let fee_rate = FeeRate::from_sat_per_vb((fee_rate * 100_000.0) as f32);
+                    estimates.insert(target, fee_rate);
+                }
+            }
+
+            if estimates.is_empty() {
+                bail!("Electrum server has no fee rate estimates");
+            }
+
+            Ok(estimates)
+        })
+        .await?
+    }
+}
+
+/// Pick the estimate for the largest number of blocks which does not exceed `blocks`, i.e. the
+/// cheapest fee rate with which we still expect to confirm in time.
+fn fee_rate_for_blocks(estimates: &[(u16, f64)], blocks: u16) -> Option<f64> {
+    estimates
+        .iter()
+        .filter(|(estimate_blocks, _)| *estimate_blocks <= blocks)
+        .max_by_key(|(estimate_blocks, _)| *estimate_blocks)
+        .map(|(_, fee_rate)| *fee_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_estimate_for_largest_number_of_blocks_within_target() {
+        let estimates = [(1, 20.0), (2, 15.0), (6, 10.0), (144, 2.0)];
+
+        assert_eq!(fee_rate_for_blocks(&estimates, 1), Some(20.0));
+        assert_eq!(fee_rate_for_blocks(&estimates, 5), Some(15.0));
+        assert_eq!(fee_rate_for_blocks(&estimates, 6), Some(10.0));
+        assert_eq!(fee_rate_for_blocks(&estimates, 1008), Some(2.0));
+    }
+
+    #[test]
+    fn no_estimate_if_all_estimates_are_for_more_blocks() {
+        let estimates = [(2, 15.0), (6, 10.0)];
+
+        assert_eq!(fee_rate_for_blocks(&estimates, 1), None);
+    }
+}
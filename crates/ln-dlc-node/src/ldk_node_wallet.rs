@@ -3,11 +3,13 @@ use crate::node::Fee;
 use crate::node::Storage;
 use anyhow::anyhow;
 use anyhow::bail;
+use anyhow::ensure;
 use anyhow::Context;
 use anyhow::Result;
 use bdk::blockchain::Blockchain;
 use bdk::blockchain::GetBlockHash;
 use bdk::blockchain::GetHeight;
+use bdk::blockchain::GetTx;
 use bdk::database::BatchDatabase;
 use bdk::psbt::PsbtUtils;
 use bdk::wallet::AddressIndex;
@@ -37,13 +39,20 @@ use lightning::chain::chaininterface::BroadcasterInterface;
 use lightning::chain::chaininterface::ConfirmationTarget;
 use parking_lot::Mutex;
 use parking_lot::MutexGuard;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 use std::time::Instant;
 use tokio::sync::RwLock;
 
 /// Taken from mempool.space
 const AVG_SEGWIT_TX_WEIGHT_VB: usize = 140;
 
+/// How long the inputs of an unsigned PSBT are reserved for it, if it is neither broadcast nor
+/// abandoned.
+const PSBT_RESERVATION_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
 pub struct Wallet<D, B, F, N>
 where
     D: BatchDatabase,
@@ -59,9 +68,22 @@ where
     // Only cleared upon restart. This means that if a locked outpoint ends up unspent, it will
     // remain locked until the binary is restarted.
     locked_outpoints: Mutex<Vec<OutPoint>>,
+    // Frozen outpoints are only spent if they are selected explicitly. They are persisted by the
+    // owner of the wallet.
+    frozen_outpoints: Mutex<HashSet<OutPoint>>,
+    // The inputs of unsigned PSBTs by the txid of the PSBT. Unlike locked outpoints, they are not
+    // released on sync, because signing a PSBT externally may take a while.
+    psbt_reservations: Mutex<HashMap<Txid, PsbtReservation>>,
     node_storage: Arc<N>,
 }
 
+/// The inputs of an unsigned PSBT, which are not spent by any other transaction until the PSBT is
+/// broadcast or abandoned, or the reservation expires.
+struct PsbtReservation {
+    outpoints: Vec<OutPoint>,
+    expiry: Instant,
+}
+
 #[derive(Clone, Debug)]
 pub struct WalletSettings {
     pub max_allowed_tx_fee_rate_when_opening_channel: Option<u32>,
@@ -100,6 +122,8 @@ where
             settings,
             fee_rate_estimator,
             locked_outpoints: Mutex::new(vec![]),
+            frozen_outpoints: Mutex::new(HashSet::new()),
+            psbt_reservations: Mutex::new(HashMap::new()),
             node_storage,
         }
     }
@@ -156,6 +180,7 @@ where
             value_sats,
             Fee::FeeRate(fee_rate),
             locked_utxos.clone(),
+            &[],
         )?;
 
         let transaction = psbt.extract_tx();
@@ -216,10 +241,15 @@ where
 
         let utxos = self.get_utxos()?;
 
-        // Filter out reserved and spent UTXOs to prevent double-spending attempts.
+        // Filter out reserved and spent UTXOs to prevent double-spending attempts. Frozen UTXOs
+        // are never used to fund DLCs.
+        let frozen_outpoints = self.frozen_outpoints.lock();
+        let psbt_outpoints = self.psbt_outpoints();
         let utxos = utxos
             .iter()
             .filter(|utxo| !reserved_outpoints.contains(&utxo.outpoint))
+            .filter(|utxo| !psbt_outpoints.contains(&utxo.outpoint))
+            .filter(|utxo| !frozen_outpoints.contains(&utxo.outpoint))
             .filter(|utxo| !utxo.is_spent)
             .collect::<Vec<_>>();
 
@@ -319,28 +349,87 @@ where
         amount_sat_or_drain: u64,
         fee: Fee,
         locked_utxos: Vec<OutPoint>,
+        inputs: &[OutPoint],
     ) -> Result<PartiallySignedTransaction> {
         let locked_wallet = self.bdk_lock();
+        let mut psbt = self.build_unsigned_psbt(
+            &locked_wallet,
+            recipient,
+            amount_sat_or_drain,
+            fee,
+            locked_utxos,
+            inputs,
+        )?;
+
+        match locked_wallet.sign(&mut psbt, SignOptions::default()) {
+            Ok(finalized) => {
+                if !finalized {
+                    bail!("On chain creation failed");
+                }
+            }
+            Err(err) => {
+                bail!(err)
+            }
+        }
+
+        Ok(psbt)
+    }
+
+    /// Build the PSBT for sending funds to a given script without signing it
+    ///
+    /// If `inputs` are given, exactly these UTXOs are spent. Otherwise the inputs are selected
+    /// among the UTXOs which are neither locked nor frozen.
+    fn build_unsigned_psbt(
+        &self,
+        locked_wallet: &bdk::Wallet<D>,
+        recipient: Script,
+        amount_sat_or_drain: u64,
+        fee: Fee,
+        locked_utxos: Vec<OutPoint>,
+        inputs: &[OutPoint],
+    ) -> Result<PartiallySignedTransaction> {
         let mut tx_builder = locked_wallet.build_tx();
 
-        for outpoint in locked_utxos.iter() {
-            tx_builder.add_unspendable(*outpoint);
+        let psbt_outpoints = self.psbt_outpoints();
+
+        if inputs.is_empty() {
+            for outpoint in locked_utxos
+                .iter()
+                .chain(psbt_outpoints.iter())
+                .chain(self.frozen_outpoints.lock().iter())
+            {
+                tx_builder.add_unspendable(*outpoint);
+            }
+        } else {
+            for outpoint in inputs {
+                ensure!(
+                    !locked_utxos.contains(outpoint),
+                    "UTXO {outpoint} is already spent by another transaction"
+                );
+                ensure!(
+                    !psbt_outpoints.contains(outpoint),
+                    "UTXO {outpoint} is reserved for an unsigned PSBT"
+                );
+
+                tx_builder.add_utxo(*outpoint)?;
+            }
+
+            tx_builder.manually_selected_only();
         }
 
         if amount_sat_or_drain > 0 {
             tx_builder
                 .add_recipient(recipient, amount_sat_or_drain)
                 .enable_rbf();
-        } else {
+        } else if inputs.is_empty() {
             tx_builder.drain_wallet().drain_to(recipient).enable_rbf();
+        } else {
+            tx_builder.drain_to(recipient).enable_rbf();
         }
 
-        match fee {
-            Fee::Priority(target) => tx_builder.fee_rate(self.fee_rate_estimator.estimate(target)),
-            Fee::FeeRate(fee_rate) => tx_builder.fee_rate(fee_rate),
-        };
+        tx_builder.fee_rate(self.resolve_fee_rate(fee));
 
-        let mut psbt = match tx_builder.finish() {
+        let psbt = match tx_builder.finish() {
             Ok((psbt, _)) => {
                 tracing::trace!("Created PSBT: {:?}", psbt);
                 psbt
@@ -350,20 +439,78 @@ where
             }
         };
 
-        match locked_wallet.sign(&mut psbt, SignOptions::default()) {
-            Ok(finalized) => {
-                if !finalized {
-                    bail!("On chain creation failed");
-                }
-            }
-            Err(err) => {
-                bail!(err)
-            }
-        }
+        Ok(psbt)
+    }
+
+    /// Create an unsigned PSBT sending funds to a given script, to be signed externally.
+    ///
+    /// If `amount_sat_or_drain` is `0` the wallet will be drained, i.e., all available funds
+    /// will be spent. The inputs of the PSBT are reserved until it is broadcast or abandoned, or
+    /// for the [`PSBT_RESERVATION_TIMEOUT`], so that they are not spent by another transaction in
+    /// the meantime.
+    pub(crate) fn create_unsigned_psbt(
+        &self,
+        recipient: Script,
+        amount_sat_or_drain: u64,
+        fee: Fee,
+    ) -> Result<PartiallySignedTransaction> {
+        let locked_utxos = self.locked_outpoints.lock();
+        let psbt = self.build_unsigned_psbt(
+            &self.bdk_lock(),
+            recipient,
+            amount_sat_or_drain,
+            fee,
+            locked_utxos.clone(),
+            &[],
+        )?;
+
+        let txid = psbt.unsigned_tx.txid();
+        let outpoints = psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .map(|input| input.previous_output)
+            .collect();
+
+        self.psbt_reservations.lock().insert(
+            txid,
+            PsbtReservation {
+                outpoints,
+                expiry: Instant::now() + PSBT_RESERVATION_TIMEOUT,
+            },
+        );
 
         Ok(psbt)
     }
 
+    /// Release the inputs reserved for the unsigned PSBT with the given `txid`, e.g. because it
+    /// has been abandoned.
+    pub(crate) fn release_psbt_reservation(&self, txid: &Txid) {
+        if self.psbt_reservations.lock().remove(txid).is_some() {
+            tracing::info!(%txid, "Released inputs of PSBT");
+        }
+    }
+
+    /// The inputs reserved for unsigned PSBTs whose reservation has not expired.
+    fn psbt_outpoints(&self) -> HashSet<OutPoint> {
+        let mut reservations = self.psbt_reservations.lock();
+
+        let now = Instant::now();
+        reservations.retain(|txid, reservation| {
+            let is_expired = reservation.expiry <= now;
+            if is_expired {
+                tracing::info!(%txid, "Reservation of the inputs of PSBT expired");
+            }
+
+            !is_expired
+        });
+
+        reservations
+            .values()
+            .flat_map(|reservation| reservation.outpoints.iter().copied())
+            .collect()
+    }
+
     /// Estimate the fee for sending funds to a given address
     pub(crate) fn calculate_fee(
         &self,
@@ -377,6 +524,7 @@ where
             amount_sat_or_drain,
             Fee::Priority(confirmation_target),
             locked_utxos.clone(),
+            &[],
         );
 
         let fee_sat = match psbt {
@@ -395,12 +543,14 @@ where
     /// Send funds to the given address.
     ///
     /// If `amount_sat_or_drain` is `0` the wallet will be drained, i.e., all available funds
-    /// will be spent.
+    /// will be spent. If `inputs` are given, exactly these UTXOs are spent, even if they are
+    /// frozen, and draining only spends them.
     pub(crate) fn send_to_address(
         &self,
         address: &Address,
         amount_sat_or_drain: u64,
         fee: Fee,
+        inputs: &[OutPoint],
     ) -> Result<Txid> {
         let mut locked_utxos = self.locked_outpoints.lock();
         let tx = self
@@ -409,6 +559,7 @@ where
                 amount_sat_or_drain,
                 fee,
                 locked_utxos.clone(),
+                inputs,
             )?
             .extract_tx();
 
@@ -440,6 +591,257 @@ where
         Ok(txid)
     }
 
+    /// Spend the given `inputs`, or all UTXOs which are neither locked nor frozen if none are
+    /// given, to a single output of this wallet.
+    pub(crate) fn consolidate_utxos(&self, inputs: &[OutPoint], fee: Fee) -> Result<Txid> {
+        let inputs = match inputs.is_empty() {
+            true => {
+                let locked_utxos = self.locked_outpoints.lock();
+                let frozen_utxos = self.frozen_outpoints.lock();
+                let psbt_utxos = self.psbt_outpoints();
+
+                self.get_utxos()?
+                    .into_iter()
+                    .filter(|utxo| !utxo.is_spent)
+                    .map(|utxo| utxo.outpoint)
+                    .filter(|outpoint| {
+                        !locked_utxos.contains(outpoint)
+                            && !frozen_utxos.contains(outpoint)
+                            && !psbt_utxos.contains(outpoint)
+                    })
+                    .collect()
+            }
+            false => inputs.to_vec(),
+        };
+
+        ensure!(inputs.len() > 1, "Need at least two UTXOs to consolidate");
+
+        let address = self
+            .bdk_lock()
+            .get_internal_address(AddressIndex::New)?
+            .address;
+
+        let txid = self.send_to_address(&address, 0, fee, &inputs)?;
+
+        tracing::info!(%txid, inputs = inputs.len(), "Consolidated UTXOs");
+
+        Ok(txid)
+    }
+
+    /// Replace the set of frozen UTXOs, e.g. with the one loaded from storage.
+    pub(crate) fn set_frozen_utxos(&self, outpoints: impl IntoIterator<Item = OutPoint>) {
+        *self.frozen_outpoints.lock() = outpoints.into_iter().collect();
+    }
+
+    /// Exclude the UTXO from automatic coin selection, until it is unfrozen.
+    pub(crate) fn freeze_utxo(&self, outpoint: OutPoint) {
+        self.frozen_outpoints.lock().insert(outpoint);
+    }
+
+    pub(crate) fn unfreeze_utxo(&self, outpoint: &OutPoint) {
+        self.frozen_outpoints.lock().remove(outpoint);
+    }
+
+    /// Replace the unconfirmed transaction `txid`, which was sent from this wallet, with a
+    /// transaction paying the higher `fee` (RBF).
+    ///
+    /// The replacement may spend additional inputs if the change output of the original
+    /// transaction cannot cover the higher fee. Frozen UTXOs are never added.
+    pub(crate) fn replace_by_fee(&self, txid: &Txid, fee: Fee) -> Result<Txid> {
+        let mut locked_utxos = self.locked_outpoints.lock();
+
+        let tx = {
+            let locked_wallet = self.bdk_lock();
+
+            let original = locked_wallet
+                .get_tx(txid, true)?
+                .with_context(|| format!("Unknown transaction {txid}"))?;
+            ensure!(
+                original.confirmation_time.is_none(),
+                "Transaction {txid} is already confirmed"
+            );
+            let original_inputs = original
+                .transaction
+                .context("Missing raw transaction")?
+                .input
+                .into_iter()
+                .map(|input| input.previous_output)
+                .collect::<Vec<_>>();
+
+            let mut tx_builder = locked_wallet.build_fee_bump(*txid)?;
+
+            // The inputs of the original transaction are locked too, but they must be spent by
+            // the replacement, even if they have been frozen since.
+            for outpoint in locked_utxos
+                .iter()
+                .chain(self.psbt_outpoints().iter())
+                .chain(self.frozen_outpoints.lock().iter())
+                .filter(|outpoint| !original_inputs.contains(outpoint))
+            {
+                tx_builder.add_unspendable(*outpoint);
+            }
+
+            tx_builder.fee_rate(self.resolve_fee_rate(fee)).enable_rbf();
+
+            let (mut psbt, _) = tx_builder.finish()?;
+
+            if !locked_wallet.sign(&mut psbt, SignOptions::default())? {
+                bail!("Failed to sign replacement of transaction {txid}");
+            }
+
+            psbt.extract_tx()
+        };
+
+        locked_utxos.extend(tx.input.iter().map(|input| input.previous_output));
+
+        let replacement = self.broadcast_transaction(&tx)?;
+
+        tracing::info!(%txid, %replacement, "Replaced transaction by fee");
+
+        match self.node_storage.get_transaction(&txid.to_string()) {
+            Ok(Some(transaction)) => {
+                if let Err(e) = self
+                    .node_storage
+                    .upsert_transaction(transaction.with_replaced_by(replacement))
+                {
+                    tracing::error!("Failed to mark transaction {txid} as replaced. Error: {e:#}");
+                }
+            }
+            Ok(None) => {
+                tracing::warn!(%txid, "Replaced transaction is unknown to the node storage");
+            }
+            Err(e) => {
+                tracing::error!("Failed to load transaction {txid}. Error: {e:#}");
+            }
+        }
+
+        Ok(replacement)
+    }
+
+    /// Accelerate the unconfirmed transaction `txid` by spending its outputs which belong to this
+    /// wallet back to the wallet, paying a fee so that parent and child together reach the `fee`
+    /// rate (CPFP).
+    ///
+    /// Unlike [`Self::replace_by_fee`], this also works for transactions sent to this wallet.
+    /// Outputs which have been frozen are not spent by the child.
+    pub(crate) fn child_pays_for_parent(&self, txid: &Txid, fee: Fee) -> Result<Txid> {
+        let fee_rate = self.resolve_fee_rate(fee);
+
+        let parent = self
+            .bdk_lock()
+            .get_tx(txid, true)?
+            .with_context(|| format!("Unknown transaction {txid}"))?;
+        ensure!(
+            parent.confirmation_time.is_none(),
+            "Transaction {txid} is already confirmed"
+        );
+
+        let parent_tx = match parent.transaction {
+            Some(tx) => tx,
+            None => self
+                .blockchain
+                .get_tx(txid)?
+                .with_context(|| format!("Could not find transaction {txid}"))?,
+        };
+        let parent_fee = match parent.fee {
+            Some(fee) => fee,
+            None => self.fee_of(&parent_tx)?,
+        };
+
+        let mut locked_utxos = self.locked_outpoints.lock();
+        let psbt_utxos = self.psbt_outpoints();
+        let frozen_utxos = self.frozen_outpoints.lock().clone();
+
+        let tx = {
+            let locked_wallet = self.bdk_lock();
+
+            let outpoints = locked_wallet
+                .list_unspent()?
+                .into_iter()
+                .filter(|utxo| {
+                    utxo.outpoint.txid == *txid
+                        && !utxo.is_spent
+                        && !locked_utxos.contains(&utxo.outpoint)
+                        && !psbt_utxos.contains(&utxo.outpoint)
+                        && !frozen_utxos.contains(&utxo.outpoint)
+                })
+                .map(|utxo| utxo.outpoint)
+                .collect::<Vec<_>>();
+            ensure!(
+                !outpoints.is_empty(),
+                "Transaction {txid} has no spendable outputs belonging to this wallet"
+            );
+
+            let drain_script = locked_wallet
+                .get_internal_address(AddressIndex::New)?
+                .script_pubkey();
+
+            // We need the size of the child to know how much fee it has to pay for the package.
+            let child = build_child_transaction(
+                &locked_wallet,
+                &outpoints,
+                drain_script.clone(),
+                ChildFee::Rate(fee_rate),
+            )?;
+
+            let package_fee = fee_rate.fee_vb(parent_tx.vsize() + child.vsize());
+            ensure!(
+                package_fee > parent_fee,
+                "Transaction {txid} already pays at least {} sat/vB",
+                fee_rate.as_sat_per_vb()
+            );
+
+            let child_fee = (package_fee - parent_fee).max(fee_rate.fee_vb(child.vsize()));
+
+            build_child_transaction(
+                &locked_wallet,
+                &outpoints,
+                drain_script,
+                ChildFee::Absolute(child_fee),
+            )?
+        };
+
+        locked_utxos.extend(tx.input.iter().map(|input| input.previous_output));
+
+        let child = self.broadcast_transaction(&tx)?;
+
+        tracing::info!(%txid, %child, "Bumped fee of transaction with child");
+
+        Ok(child)
+    }
+
+    /// The fee of a transaction which was not sent from this wallet, from the values of the
+    /// outputs it spends.
+    fn fee_of(&self, tx: &Transaction) -> Result<u64> {
+        let mut input_value = 0;
+        for input in tx.input.iter() {
+            let outpoint = input.previous_output;
+            let prev_tx = self
+                .blockchain
+                .get_tx(&outpoint.txid)?
+                .with_context(|| format!("Could not find transaction {}", outpoint.txid))?;
+            let prev_output = prev_tx
+                .output
+                .get(outpoint.vout as usize)
+                .with_context(|| format!("Could not find output {outpoint}"))?;
+
+            input_value += prev_output.value;
+        }
+
+        let output_value = tx.output.iter().map(|output| output.value).sum::<u64>();
+
+        input_value
+            .checked_sub(output_value)
+            .context("Transaction spends more than its inputs")
+    }
+
+    fn resolve_fee_rate(&self, fee: Fee) -> FeeRate {
+        match fee {
+            Fee::Priority(target) => self.fee_rate_estimator.estimate(target),
+            Fee::FeeRate(fee_rate) => fee_rate,
+        }
+    }
+
     pub fn tip(&self) -> Result<(u32, BlockHash)> {
         let height = self.blockchain.get_height()?;
         let hash = self.blockchain.get_block_hash(height as u64)?;
@@ -473,10 +875,52 @@ where
             .broadcast(tx)
             .map_err(|e| anyhow!("Failed to broadcast transaction {txid}. {e:#}"))?;
 
+        self.release_psbt_reservation(&txid);
+
         Ok(txid)
     }
 }
 
+enum ChildFee {
+    Rate(FeeRate),
+    Absolute(u64),
+}
+
+/// Build and sign a transaction spending exactly the given `outpoints` to `drain_script`.
+fn build_child_transaction<D>(
+    wallet: &bdk::Wallet<D>,
+    outpoints: &[OutPoint],
+    drain_script: Script,
+    fee: ChildFee,
+) -> Result<Transaction>
+where
+    D: BatchDatabase,
+{
+    let mut tx_builder = wallet.build_tx();
+
+    for outpoint in outpoints {
+        tx_builder.add_utxo(*outpoint)?;
+    }
+
+    tx_builder
+        .manually_selected_only()
+        .drain_to(drain_script)
+        .enable_rbf();
+
+    match fee {
+        ChildFee::Rate(fee_rate) => tx_builder.fee_rate(fee_rate),
+        ChildFee::Absolute(fee) => tx_builder.fee_absolute(fee),
+    };
+
+    let (mut psbt, _) = tx_builder.finish()?;
+
+    if !wallet.sign(&mut psbt, SignOptions::default())? {
+        bail!("Failed to sign child transaction");
+    }
+
+    Ok(psbt.extract_tx())
+}
+
 impl<D, B, F, N> BroadcasterInterface for Wallet<D, B, F, N>
 where
     D: BatchDatabase,
@@ -582,6 +1026,163 @@ mod tests {
             .is_err());
     }
 
+    #[tokio::test]
+    async fn psbt_inputs_stay_reserved_across_syncs() {
+        let mut rng = thread_rng();
+        let test_wallet = new_test_wallet(&mut rng, Amount::from_btc(1.0).unwrap(), 2).unwrap();
+        let wallet = Wallet::new(
+            DummyEsplora,
+            test_wallet,
+            Arc::new(DummyFeeRateEstimator),
+            Arc::new(DummyNodeStorage),
+            WalletSettings::default(),
+        );
+
+        let fee_rate = FeeRate::from_sat_per_vb(10.0);
+        let amount = Amount::from_btc(0.5).unwrap().to_sat();
+
+        wallet
+            .create_unsigned_psbt(Script::new(), amount, Fee::FeeRate(fee_rate))
+            .unwrap();
+
+        // Syncing releases the locked UTXOs, but not the ones reserved for the PSBT.
+        wallet.sync().unwrap();
+
+        let _ = wallet
+            .create_funding_transaction(Script::new(), amount, fee_rate)
+            .await
+            .unwrap();
+        assert!(wallet
+            .create_funding_transaction(Script::new(), amount, fee_rate)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn abandoned_psbt_releases_its_inputs() {
+        let mut rng = thread_rng();
+        let test_wallet = new_test_wallet(&mut rng, Amount::from_btc(1.0).unwrap(), 1).unwrap();
+        let wallet = Wallet::new(
+            DummyEsplora,
+            test_wallet,
+            Arc::new(DummyFeeRateEstimator),
+            Arc::new(DummyNodeStorage),
+            WalletSettings::default(),
+        );
+
+        let fee_rate = FeeRate::from_sat_per_vb(10.0);
+        let amount = Amount::from_btc(0.5).unwrap().to_sat();
+
+        let psbt = wallet
+            .create_unsigned_psbt(Script::new(), amount, Fee::FeeRate(fee_rate))
+            .unwrap();
+        assert!(wallet
+            .create_funding_transaction(Script::new(), amount, fee_rate)
+            .await
+            .is_err());
+
+        wallet.release_psbt_reservation(&psbt.unsigned_tx.txid());
+
+        let _ = wallet
+            .create_funding_transaction(Script::new(), amount, fee_rate)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn expired_psbt_reservation_releases_its_inputs() {
+        let mut rng = thread_rng();
+        let test_wallet = new_test_wallet(&mut rng, Amount::from_btc(1.0).unwrap(), 1).unwrap();
+        let wallet = Wallet::new(
+            DummyEsplora,
+            test_wallet,
+            Arc::new(DummyFeeRateEstimator),
+            Arc::new(DummyNodeStorage),
+            WalletSettings::default(),
+        );
+
+        let fee_rate = FeeRate::from_sat_per_vb(10.0);
+        let amount = Amount::from_btc(0.5).unwrap().to_sat();
+
+        wallet
+            .create_unsigned_psbt(Script::new(), amount, Fee::FeeRate(fee_rate))
+            .unwrap();
+
+        for reservation in wallet.psbt_reservations.lock().values_mut() {
+            reservation.expiry = Instant::now();
+        }
+
+        let _ = wallet
+            .create_funding_transaction(Script::new(), amount, fee_rate)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn frozen_utxos_are_skipped_by_automatic_coin_selection() {
+        let mut rng = thread_rng();
+        let test_wallet = new_test_wallet(&mut rng, Amount::from_btc(1.0).unwrap(), 2).unwrap();
+        let wallet = Wallet::new(
+            DummyEsplora,
+            test_wallet,
+            Arc::new(DummyFeeRateEstimator),
+            Arc::new(DummyNodeStorage),
+            WalletSettings::default(),
+        );
+
+        let frozen = wallet.get_utxos().unwrap()[0].outpoint;
+        wallet.freeze_utxo(frozen);
+
+        let fee_rate = FeeRate::from_sat_per_vb(10.0);
+        let amount = Amount::from_btc(0.5).unwrap().to_sat();
+
+        let tx = wallet
+            .create_funding_transaction(Script::new(), amount, fee_rate)
+            .await
+            .unwrap();
+        assert!(tx.input.iter().all(|input| input.previous_output != frozen));
+
+        assert!(wallet
+            .create_funding_transaction(Script::new(), amount, fee_rate)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn frozen_utxos_are_not_used_to_fund_dlcs() {
+        let mut rng = thread_rng();
+        let test_wallet = new_test_wallet(&mut rng, Amount::from_btc(1.0).unwrap(), 2).unwrap();
+        let wallet = Wallet::new(
+            DummyEsplora,
+            test_wallet,
+            Arc::new(DummyFeeRateEstimator),
+            Arc::new(DummyNodeStorage),
+            WalletSettings::default(),
+        );
+
+        let frozen = wallet.get_utxos().unwrap()[0].outpoint;
+        wallet.freeze_utxo(frozen);
+
+        let utxos = wallet
+            .get_utxos_for_dlc_funding_transaction(
+                Amount::from_btc(0.5).unwrap().to_sat(),
+                Some(10),
+                false,
+            )
+            .unwrap();
+        assert!(!utxos.is_empty());
+        assert!(utxos.iter().all(|utxo| utxo.outpoint != frozen));
+
+        // Both UTXOs would be needed.
+        assert!(wallet
+            .get_utxos_for_dlc_funding_transaction(
+                Amount::from_btc(1.5).unwrap().to_sat(),
+                Some(10),
+                false,
+            )
+            .is_err());
+    }
+
     fn new_test_wallet(
         rng: &mut (impl RngCore + CryptoRng),
         utxo_amount: Amount,
@@ -638,13 +1239,13 @@ mod tests {
             _: &RefCell<D>,
             _: Box<dyn Progress>,
         ) -> std::result::Result<(), Error> {
-            unimplemented!()
+            Ok(())
         }
     }
 
     impl GetHeight for DummyEsplora {
         fn get_height(&self) -> std::result::Result<u32, Error> {
-            unimplemented!()
+            Ok(100)
         }
     }
 
@@ -44,6 +44,10 @@ pub mod transaction;
 pub mod util;
 
 pub use config::CONFIRMATION_TARGET;
+pub use fee_rate_estimator::FeeRateEstimatorConfig;
+pub use fee_rate_estimator::FeeRatePolicy;
+pub use fee_rate_estimator::FeeRateSourceConfig;
+pub use fee_rate_estimator::FeeRateSourceStatus;
 pub use ldk_node_wallet::WalletSettings;
 pub use lightning;
 pub use lightning_invoice;
@@ -211,7 +211,7 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> EventHan
                 channel_id,
                 transaction,
             } => {
-                common_handlers::handle_discard_funding(transaction, channel_id);
+                common_handlers::handle_discard_funding(&self.node, transaction, channel_id);
             }
             Event::ChannelReady {
                 channel_id,
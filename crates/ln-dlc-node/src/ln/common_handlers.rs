@@ -74,7 +74,11 @@ pub fn handle_htlc_handling_failed(
     );
 }
 
-pub fn handle_discard_funding(transaction: bitcoin::Transaction, channel_id: ChannelId) {
+pub fn handle_discard_funding<S: TenTenOneStorage, N: Storage>(
+    node: &Arc<Node<S, N>>,
+    transaction: bitcoin::Transaction,
+    channel_id: ChannelId,
+) {
     let tx_hex = serialize_hex(&transaction);
     tracing::info!(
         channel_id = %channel_id.to_hex(),
@@ -82,6 +86,9 @@ pub fn handle_discard_funding(transaction: bitcoin::Transaction, channel_id: Cha
         "Discarding funding transaction"
     );
 
+    // The funding transaction may have been created from a PSBT.
+    node.release_psbt(&transaction.txid());
+
     // FIXME: Address the comment below
     // A "real" node should probably "lock" the UTXOs spent in funding transactions
     // until the funding transaction either confirms, or this event is
@@ -236,6 +243,9 @@ pub fn handle_channel_closed<S: TenTenOneStorage, N: Storage>(
             "Channel closed",
         );
 
+        // The channel may have been closed while waiting for its funding PSBT to be signed.
+        node.discard_funding_psbt(&channel_id);
+
         if let Some(channel) = node.node_storage.get_channel(&user_channel_id)? {
             let counterparty = channel.counterparty;
 
@@ -397,6 +407,40 @@ pub async fn handle_funding_generation_ready<S: TenTenOneStorage, N: Storage>(
         counterparty_node_id
     );
 
+    let is_funded_externally = node
+        .pending_external_channel_fundings
+        .lock()
+        .remove(&counterparty_node_id);
+
+    if is_funded_externally {
+        match node.create_funding_psbt(
+            temporary_channel_id,
+            counterparty_node_id,
+            output_script,
+            channel_value_satoshis,
+            fee_rate,
+        ) {
+            Ok(funding_txid) => {
+                tracing::info!(
+                    %user_channel_id,
+                    %funding_txid,
+                    "Created funding PSBT, waiting for it to be signed externally"
+                );
+            }
+            Err(err) => {
+                tracing::error!(
+                    "Cannot open channel due to not being able to create funding PSBT: {err:#}"
+                );
+
+                node.channel_manager
+                    .close_channel(&temporary_channel_id, &counterparty_node_id)
+                    .map_err(|e| anyhow!("{e:?}"))?;
+            }
+        }
+
+        return Ok(());
+    }
+
     let funding_tx_result = node
         .wallet
         .ldk_wallet()
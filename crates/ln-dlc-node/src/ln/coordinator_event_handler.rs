@@ -190,7 +190,7 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Send + Sync + 'static> EventHan
                 channel_id,
                 transaction,
             } => {
-                common_handlers::handle_discard_funding(transaction, channel_id);
+                common_handlers::handle_discard_funding(&self.node, transaction, channel_id);
             }
             Event::ProbeSuccessful {
                 payment_id, path, ..
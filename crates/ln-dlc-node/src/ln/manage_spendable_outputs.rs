@@ -1,13 +1,11 @@
 use crate::dlc_custom_signer::CustomKeysManager;
 use crate::fee_rate_estimator::FeeRateEstimator;
 use crate::ln_dlc_wallet::LnDlcWallet;
+use crate::node::ChainSource;
 use crate::node::Storage;
 use crate::storage::TenTenOneStorage;
-use anyhow::bail;
 use anyhow::Context;
 use anyhow::Result;
-use esplora_client::OutputStatus;
-use esplora_client::TxStatus;
 use lightning::chain::chaininterface::BroadcasterInterface;
 use lightning::chain::chaininterface::ConfirmationTarget;
 use lightning::chain::chaininterface::FeeEstimator;
@@ -27,7 +25,7 @@ const REQUIRED_CONFIRMATIONS: u32 = 6;
 /// Determine what to do with a [`SpendableOutputDescriptor`] and do it.
 pub fn manage_spendable_outputs<S: TenTenOneStorage, N: Storage>(
     node_storage: Arc<N>,
-    esplora_client: impl Borrow<esplora_client::BlockingClient>,
+    chain_source: impl Borrow<ChainSource>,
     wallet: impl Borrow<LnDlcWallet<S, N>>,
     fee_rate_estimator: impl Borrow<FeeRateEstimator>,
     keys_manager: impl Borrow<CustomKeysManager<S, N>>,
@@ -36,7 +34,7 @@ pub fn manage_spendable_outputs<S: TenTenOneStorage, N: Storage>(
 
     let spendable_outputs = &node_storage.all_spendable_outputs()?;
     for output in spendable_outputs.iter() {
-        let action = match choose_spendable_output_action(esplora_client.borrow(), output) {
+        let action = match choose_spendable_output_action(chain_source.borrow(), output) {
             Ok(action) => action,
             Err(e) => {
                 tracing::error!(
@@ -89,7 +87,7 @@ enum Action {
 /// Decide on which [`Action`] should be performed based on the characteristics and status of a
 /// [`SpendableOutputDescriptor`].
 fn choose_spendable_output_action(
-    esplora_client: &esplora_client::BlockingClient,
+    chain_source: &ChainSource,
     output: &SpendableOutputDescriptor,
 ) -> Result<Action> {
     use SpendableOutputDescriptor::*;
@@ -100,58 +98,32 @@ fn choose_spendable_output_action(
         StaticOutput { outpoint, .. } => return Ok(Action::Forget(*outpoint)),
     };
 
-    let output_status = esplora_client
-        .get_output_status(&outpoint.txid, outpoint.index.into())
+    let confirmations = chain_source
+        .get_output_spend_confirmations(&outpoint.into_bitcoin_outpoint(), REQUIRED_CONFIRMATIONS)
         .context("Could not get spendable output status")?;
 
-    match output_status {
-        Some(OutputStatus { spent: false, .. }) | None => {
+    match confirmations {
+        None => {
             tracing::debug!(?output, "Spendable output not yet spent");
             Ok(Action::Spend)
         }
-        Some(OutputStatus {
-            status:
-                Some(TxStatus {
-                    confirmed: true,
-                    block_height: Some(confirmation_height),
-                    ..
-                }),
-            ..
-        }) => {
-            let current_height = esplora_client.get_height()?;
-
-            let confirmations = current_height
-                .checked_sub(confirmation_height)
-                .unwrap_or_else(|| {
-                    tracing::warn!(
-                        %confirmation_height,
-                        %current_height,
-                        "Possible re-org detected"
-                    );
-
-                    0
-                });
-
-            if confirmations >= REQUIRED_CONFIRMATIONS {
-                tracing::info!(
-                    %confirmations,
-                    required_confirmations = %REQUIRED_CONFIRMATIONS,
-                    "Spendable output sufficiently confirmed"
-                );
-
-                Ok(Action::Forget(*outpoint))
-            } else {
-                tracing::info!(
-                    %confirmations,
-                    required_confirmations = %REQUIRED_CONFIRMATIONS,
-                    "Spendable output without enough confirmations"
-                );
-
-                Ok(Action::Monitor)
-            }
+        Some(confirmations) if confirmations >= REQUIRED_CONFIRMATIONS => {
+            tracing::info!(
+                %confirmations,
+                required_confirmations = %REQUIRED_CONFIRMATIONS,
+                "Spendable output sufficiently confirmed"
+            );
+
+            Ok(Action::Forget(*outpoint))
         }
-        Some(_) => {
-            bail!("Spendable output in unexpected state: {output:?}");
+        Some(confirmations) => {
+            tracing::info!(
+                %confirmations,
+                required_confirmations = %REQUIRED_CONFIRMATIONS,
+                "Spendable output without enough confirmations"
+            );
+
+            Ok(Action::Monitor)
         }
     }
 }
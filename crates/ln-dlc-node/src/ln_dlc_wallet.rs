@@ -1,13 +1,13 @@
 use crate::fee_rate_estimator::FeeRateEstimator;
 use crate::ldk_node_wallet;
+use crate::node::ChainSource;
 use crate::node::Storage;
 use crate::storage::TenTenOneStorage;
-use crate::TracingLogger;
 use crate::WalletSettings;
 use anyhow::anyhow;
+use anyhow::Context;
 use anyhow::Result;
-use bdk::blockchain::EsploraBlockchain;
-use bdk::esplora_client::TxStatus;
+use bdk::blockchain::AnyBlockchain;
 use bdk::sled;
 use bdk::SignOptions;
 use bdk::TransactionDetails;
@@ -21,6 +21,7 @@ use bitcoin::Block;
 use bitcoin::BlockHash;
 use bitcoin::KeyPair;
 use bitcoin::Network;
+use bitcoin::OutPoint;
 use bitcoin::Script;
 use bitcoin::Transaction;
 use bitcoin::Txid;
@@ -29,17 +30,19 @@ use dlc_manager::Blockchain;
 use dlc_manager::Signer;
 use dlc_manager::Utxo;
 use lightning::chain::chaininterface::BroadcasterInterface;
-use lightning_transaction_sync::EsploraSyncClient;
 use ln_dlc_storage::DlcStorageProvider;
+use ln_dlc_storage::UtxoMetadata;
 use ln_dlc_storage::WalletStorage;
 use parking_lot::RwLock;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// This is a wrapper type introduced to be able to implement traits from `rust-dlc` on the
 /// `ldk_node::LightningWallet`.
 pub struct LnDlcWallet<S, N> {
-    ln_wallet: Arc<ldk_node_wallet::Wallet<sled::Tree, EsploraBlockchain, FeeRateEstimator, N>>,
+    ln_wallet: Arc<ldk_node_wallet::Wallet<sled::Tree, AnyBlockchain, FeeRateEstimator, N>>,
     dlc_storage: Arc<DlcStorageProvider<S>>,
+    chain_source: Arc<ChainSource>,
     secp: Secp256k1<All>,
     network: Network,
     /// Cache for the last unused address according to the latest on-chain sync.
@@ -53,7 +56,7 @@ pub struct LnDlcWallet<S, N> {
 impl<S: TenTenOneStorage, N: Storage> LnDlcWallet<S, N> {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        esplora_client: Arc<EsploraSyncClient<Arc<TracingLogger>>>,
+        chain_source: Arc<ChainSource>,
         on_chain_wallet: bdk::Wallet<bdk::sled::Tree>,
         fee_rate_estimator: Arc<FeeRateEstimator>,
         dlc_storage: Arc<DlcStorageProvider<S>>,
@@ -61,10 +64,14 @@ impl<S: TenTenOneStorage, N: Storage> LnDlcWallet<S, N> {
         bdk_client_stop_gap: usize,
         bdk_client_concurrency: u8,
         settings: WalletSettings,
-    ) -> Self {
-        let blockchain =
-            EsploraBlockchain::from_client(esplora_client.client().clone(), bdk_client_stop_gap)
-                .with_concurrency(bdk_client_concurrency);
+    ) -> Result<Self> {
+        let blockchain = chain_source
+            .bdk_blockchain(
+                &on_chain_wallet,
+                bdk_client_stop_gap,
+                bdk_client_concurrency,
+            )
+            .context("Failed to build on-chain wallet blockchain client")?;
 
         let network = on_chain_wallet.network();
 
@@ -78,20 +85,29 @@ impl<S: TenTenOneStorage, N: Storage> LnDlcWallet<S, N> {
 
         let last_unused_address = wallet
             .get_last_unused_address()
-            .expect("to get the last unused address");
+            .context("Failed to get the last unused address")?;
 
-        Self {
+        let frozen_utxos = dlc_storage
+            .get_utxo_metadata()
+            .context("Failed to get the UTXO metadata")?
+            .into_iter()
+            .filter(|(_, metadata)| metadata.frozen)
+            .map(|(outpoint, _)| outpoint);
+        wallet.set_frozen_utxos(frozen_utxos);
+
+        Ok(Self {
             ln_wallet: wallet,
             dlc_storage,
+            chain_source,
             secp: Secp256k1::new(),
             network,
             address_cache: RwLock::new(last_unused_address),
-        }
+        })
     }
 
     pub fn ldk_wallet(
         &self,
-    ) -> Arc<ldk_node_wallet::Wallet<sled::Tree, EsploraBlockchain, FeeRateEstimator, N>> {
+    ) -> Arc<ldk_node_wallet::Wallet<sled::Tree, AnyBlockchain, FeeRateEstimator, N>> {
         self.ln_wallet.clone()
     }
 
@@ -146,6 +162,42 @@ impl<S: TenTenOneStorage, N: Storage> LnDlcWallet<S, N> {
             .ok_or_else(|| anyhow!("Transaction {txid} not found on-chain"))
     }
 
+    /// The coin control metadata of the UTXOs of the wallet, which may include spent UTXOs.
+    pub fn utxo_metadata(&self) -> Result<HashMap<OutPoint, UtxoMetadata>> {
+        let metadata = self.dlc_storage.get_utxo_metadata()?;
+
+        Ok(metadata.into_iter().collect())
+    }
+
+    /// Exclude the UTXO from automatic coin selection, or include it again.
+    pub fn set_utxo_frozen(&self, outpoint: OutPoint, frozen: bool) -> Result<()> {
+        self.update_utxo_metadata(outpoint, |metadata| metadata.frozen = frozen)?;
+
+        match frozen {
+            true => self.ln_wallet.freeze_utxo(outpoint),
+            false => self.ln_wallet.unfreeze_utxo(&outpoint),
+        }
+
+        Ok(())
+    }
+
+    pub fn set_utxo_label(&self, outpoint: OutPoint, label: Option<String>) -> Result<()> {
+        let label = label.filter(|label| !label.is_empty());
+        self.update_utxo_metadata(outpoint, |metadata| metadata.label = label)
+    }
+
+    fn update_utxo_metadata(
+        &self,
+        outpoint: OutPoint,
+        update: impl FnOnce(&mut UtxoMetadata),
+    ) -> Result<()> {
+        let mut metadata = self.utxo_metadata()?.remove(&outpoint).unwrap_or_default();
+
+        update(&mut metadata);
+
+        self.dlc_storage.upsert_utxo_metadata(&outpoint, &metadata)
+    }
+
     fn update_address_cache(&self) -> Result<()> {
         let address = self.ldk_wallet().get_last_unused_address()?;
         *self.address_cache.write() = address;
@@ -177,22 +229,11 @@ impl<S: TenTenOneStorage, N: Storage> Blockchain for LnDlcWallet<S, N> {
     }
 
     fn get_block_at_height(&self, height: u64) -> Result<Block, Error> {
-        let block_hash = self
-            .ln_wallet
-            .blockchain
-            .get_block_hash(height as u32)
-            .map_err(|e| {
-                Error::BlockchainError(format!("Could not find block at height {height}: {e:#}"))
-            })?;
         let block = self
-            .ln_wallet
-            .blockchain
-            .get_block_by_hash(&block_hash)
+            .chain_source
+            .get_block_at_height(height as u32)
             .map_err(|e| {
                 Error::BlockchainError(format!("Could not find block at height {height}: {e:#}"))
-            })?
-            .ok_or_else(|| {
-                Error::BlockchainError(format!("Could not find block at height {height}"))
             })?;
 
         Ok(block)
@@ -206,23 +247,18 @@ impl<S: TenTenOneStorage, N: Storage> Blockchain for LnDlcWallet<S, N> {
 
     fn get_transaction_confirmations(&self, txid: &Txid) -> Result<u32, Error> {
         let confirmation_height = match self
-            .ln_wallet
-            .blockchain
-            .get_tx_status(txid)
-            .map_err(|e| Error::BlockchainError(e.to_string()))?
+            .chain_source
+            .get_tx_confirmation_height(txid)
+            .map_err(|e| Error::BlockchainError(format!("{e:#}")))?
         {
-            Some(TxStatus {
-                block_height: Some(height),
-                ..
-            }) => height,
-            _ => return Ok(0),
+            Some(height) => height,
+            None => return Ok(0),
         };
 
         let tip = self
-            .ln_wallet
-            .blockchain
+            .chain_source
             .get_height()
-            .map_err(|e| Error::BlockchainError(e.to_string()))?;
+            .map_err(|e| Error::BlockchainError(format!("{e:#}")))?;
         let confirmations = tip.checked_sub(confirmation_height).unwrap_or_default();
 
         Ok(confirmations)
@@ -0,0 +1,851 @@
+use crate::ln::TracingLogger;
+use anyhow::bail;
+use anyhow::ensure;
+use anyhow::Context;
+use anyhow::Result;
+use bdk::bitcoincore_rpc;
+use bdk::bitcoincore_rpc::RpcApi;
+use bdk::blockchain::electrum::ElectrumBlockchainConfig;
+use bdk::blockchain::rpc::Auth;
+use bdk::blockchain::rpc::RpcConfig;
+use bdk::blockchain::AnyBlockchain;
+use bdk::blockchain::ConfigurableBlockchain;
+use bdk::blockchain::ElectrumBlockchain;
+use bdk::blockchain::EsploraBlockchain;
+use bdk::blockchain::RpcBlockchain;
+use bdk::database::BatchDatabase;
+use bdk::electrum_client;
+use bdk::electrum_client::Batch;
+use bdk::electrum_client::ElectrumApi;
+use bdk::electrum_client::GetHistoryRes;
+use bdk::electrum_client::Param;
+use bdk::esplora_client::OutputStatus;
+use bdk::wallet::wallet_name_from_descriptor;
+use bdk::KeychainKind;
+use bitcoin::Block;
+use bitcoin::BlockHash;
+use bitcoin::BlockHeader;
+use bitcoin::OutPoint;
+use bitcoin::Script;
+use bitcoin::Transaction;
+use bitcoin::Txid;
+use lightning::chain::channelmonitor::ANTI_REORG_DELAY;
+use lightning::chain::BestBlock;
+use lightning::chain::Confirm;
+use lightning::chain::Filter;
+use lightning::chain::WatchedOutput;
+use lightning_transaction_sync::EsploraSyncClient;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// The error code bitcoind returns for unknown transactions.
+const RPC_INVALID_ADDRESS_OR_KEY: i32 = -5;
+
+/// How often BDK retries failed requests against the Electrum server.
+const ELECTRUM_RETRIES: u8 = 3;
+
+/// How many requests are sent to the Electrum server at once when fetching the transactions of a
+/// block.
+const ELECTRUM_BATCH_SIZE: usize = 100;
+
+/// An upper bound on the number of transactions in a block.
+///
+/// A block weighs at most 4M weight units, and every transaction weighs at least 240.
+const MAX_BLOCK_TRANSACTIONS: usize = 1 << 15;
+
+/// Where the node learns about the state of the blockchain from.
+///
+/// The chain source feeds both the sync of the on-chain wallet and LDK's [`Confirm`] interface.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub enum ChainSourceConfig {
+    /// The Esplora server the node is started with.
+    #[default]
+    Esplora,
+    /// A bitcoind node, queried via JSON-RPC.
+    ///
+    /// bitcoind has to run with `-txindex`, as we look up transactions which do not belong to its
+    /// wallet. The on-chain wallet is imported into a watch-only wallet of bitcoind.
+    Bitcoind {
+        rpc_url: String,
+        rpc_username: String,
+        rpc_password: String,
+    },
+    /// An Electrum server.
+    ///
+    /// Electrum servers do not serve full blocks, which the [`crate::node::DlcManager`] scans to
+    /// detect on-chain closures of DLC channels. Hence, blocks are assembled from their header and
+    /// all of their transactions, which takes a few requests per block.
+    ///
+    /// Together with [`crate::FeeRateSourceConfig::Electrum`] as fee rate source, the node does not
+    /// depend on the Esplora server it is started with.
+    Electrum { server_url: String },
+}
+
+/// The node's view of the blockchain, see [`ChainSourceConfig`].
+pub enum ChainSource {
+    Esplora(EsploraSyncClient<Arc<TracingLogger>>),
+    Bitcoind(BitcoindChainSource),
+    Electrum(ElectrumChainSource),
+}
+
+pub struct BitcoindChainSource {
+    rpc_url: String,
+    rpc_username: String,
+    rpc_password: String,
+    client: bitcoincore_rpc::Client,
+    watched: Mutex<WatchedItems>,
+}
+
+pub struct ElectrumChainSource {
+    server_url: String,
+    client: electrum_client::Client,
+    watched: Mutex<WatchedItems>,
+}
+
+/// The transactions and outputs LDK registered via [`Filter`].
+#[derive(Default, Clone)]
+struct WatchedItems {
+    transactions: HashMap<Txid, Script>,
+    outputs: HashMap<OutPoint, Script>,
+}
+
+struct ConfirmedTransaction {
+    tx: Transaction,
+    header: BlockHeader,
+    height: u32,
+    pos: usize,
+}
+
+impl ChainSource {
+    pub(crate) fn new(
+        config: &ChainSourceConfig,
+        esplora_server_url: String,
+        logger: Arc<TracingLogger>,
+    ) -> Result<Self> {
+        let chain_source = match config {
+            ChainSourceConfig::Esplora => {
+                ChainSource::Esplora(EsploraSyncClient::new(esplora_server_url, logger))
+            }
+            ChainSourceConfig::Bitcoind {
+                rpc_url,
+                rpc_username,
+                rpc_password,
+            } => {
+                let client = bitcoincore_rpc::Client::new(
+                    rpc_url,
+                    bitcoincore_rpc::Auth::UserPass(rpc_username.clone(), rpc_password.clone()),
+                )
+                .with_context(|| format!("Failed to create bitcoind RPC client for {rpc_url}"))?;
+
+                ChainSource::Bitcoind(BitcoindChainSource {
+                    rpc_url: rpc_url.clone(),
+                    rpc_username: rpc_username.clone(),
+                    rpc_password: rpc_password.clone(),
+                    client,
+                    watched: Mutex::default(),
+                })
+            }
+            ChainSourceConfig::Electrum { server_url } => {
+                let client = electrum_client::Client::new(server_url).with_context(|| {
+                    format!("Failed to connect to Electrum server {server_url}")
+                })?;
+
+                ChainSource::Electrum(ElectrumChainSource {
+                    server_url: server_url.clone(),
+                    client,
+                    watched: Mutex::default(),
+                })
+            }
+        };
+
+        Ok(chain_source)
+    }
+
+    /// Build the BDK blockchain to sync the given on-chain `wallet` with.
+    pub(crate) fn bdk_blockchain<D: BatchDatabase>(
+        &self,
+        wallet: &bdk::Wallet<D>,
+        stop_gap: usize,
+        concurrency: u8,
+    ) -> Result<AnyBlockchain> {
+        let blockchain = match self {
+            ChainSource::Esplora(client) => AnyBlockchain::from(
+                EsploraBlockchain::from_client(client.client().clone(), stop_gap)
+                    .with_concurrency(concurrency),
+            ),
+            ChainSource::Bitcoind(bitcoind) => {
+                let descriptor = wallet
+                    .get_descriptor_for_keychain(KeychainKind::External)
+                    .to_string();
+                let change_descriptor = wallet
+                    .get_descriptor_for_keychain(KeychainKind::Internal)
+                    .to_string();
+                let wallet_name = wallet_name_from_descriptor(
+                    descriptor.as_str(),
+                    Some(change_descriptor.as_str()),
+                    wallet.network(),
+                    wallet.secp_ctx(),
+                )?;
+
+                let config = RpcConfig {
+                    url: bitcoind.rpc_url.clone(),
+                    auth: Auth::UserPass {
+                        username: bitcoind.rpc_username.clone(),
+                        password: bitcoind.rpc_password.clone(),
+                    },
+                    network: wallet.network(),
+                    wallet_name,
+                    sync_params: None,
+                };
+
+                AnyBlockchain::from(
+                    RpcBlockchain::from_config(&config)
+                        .context("Failed to set up bitcoind wallet")?,
+                )
+            }
+            ChainSource::Electrum(electrum) => {
+                let config = ElectrumBlockchainConfig {
+                    url: electrum.server_url.clone(),
+                    socks5: None,
+                    retry: ELECTRUM_RETRIES,
+                    timeout: None,
+                    stop_gap,
+                    validate_domain: true,
+                };
+
+                AnyBlockchain::from(
+                    ElectrumBlockchain::from_config(&config)
+                        .context("Failed to connect to Electrum server")?,
+                )
+            }
+        };
+
+        Ok(blockchain)
+    }
+
+    /// Sync LDK's `confirmables` with the chain.
+    ///
+    /// `best_block` is the block the `confirmables` were last synced to. This is where chain
+    /// sources which scan blocks pick up.
+    pub(crate) fn sync(
+        &self,
+        confirmables: Vec<&(dyn Confirm + Sync + Send)>,
+        best_block: BestBlock,
+    ) -> Result<()> {
+        match self {
+            ChainSource::Esplora(client) => client.sync(confirmables)?,
+            ChainSource::Bitcoind(bitcoind) => bitcoind.sync(&confirmables, best_block)?,
+            ChainSource::Electrum(electrum) => electrum.sync(&confirmables)?,
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn get_height(&self) -> Result<u32> {
+        let height = match self {
+            ChainSource::Esplora(client) => client.client().get_height()?,
+            ChainSource::Bitcoind(bitcoind) => u32::try_from(bitcoind.client.get_block_count()?)?,
+            ChainSource::Electrum(electrum) => {
+                u32::try_from(electrum.client.block_headers_subscribe()?.height)?
+            }
+        };
+
+        Ok(height)
+    }
+
+    pub(crate) fn get_block_hash(&self, height: u32) -> Result<BlockHash> {
+        let block_hash = match self {
+            ChainSource::Esplora(client) => client.client().get_block_hash(height)?,
+            ChainSource::Bitcoind(bitcoind) => bitcoind.client.get_block_hash(u64::from(height))?,
+            ChainSource::Electrum(electrum) => {
+                electrum.client.block_header(height as usize)?.block_hash()
+            }
+        };
+
+        Ok(block_hash)
+    }
+
+    pub(crate) fn get_block_at_height(&self, height: u32) -> Result<Block> {
+        let block = match self {
+            ChainSource::Esplora(client) => {
+                let block_hash = self.get_block_hash(height)?;
+                client
+                    .client()
+                    .get_block_by_hash(&block_hash)?
+                    .with_context(|| format!("Block {block_hash} not found"))?
+            }
+            ChainSource::Bitcoind(bitcoind) => {
+                let block_hash = self.get_block_hash(height)?;
+                bitcoind.client.get_block(&block_hash)?
+            }
+            ChainSource::Electrum(electrum) => electrum.get_block_at_height(height as usize)?,
+        };
+
+        Ok(block)
+    }
+
+    /// The height of the block which includes the transaction `txid`, if it is confirmed.
+    pub(crate) fn get_tx_confirmation_height(&self, txid: &Txid) -> Result<Option<u32>> {
+        match self {
+            ChainSource::Esplora(client) => {
+                let status = client.client().get_tx_status(txid)?;
+                Ok(status.and_then(|status| status.block_height))
+            }
+            ChainSource::Bitcoind(bitcoind) => bitcoind.tx_confirmation_height(txid),
+            ChainSource::Electrum(electrum) => electrum.tx_confirmation_height(txid),
+        }
+    }
+
+    /// The number of confirmations of the transaction spending `outpoint`, capped at
+    /// `max_confirmations`. `None` if the output is not spent or unknown.
+    pub(crate) fn get_output_spend_confirmations(
+        &self,
+        outpoint: &OutPoint,
+        max_confirmations: u32,
+    ) -> Result<Option<u32>> {
+        let confirmations = match self {
+            ChainSource::Esplora(client) => {
+                let client = client.client();
+                match client.get_output_status(&outpoint.txid, u64::from(outpoint.vout))? {
+                    Some(OutputStatus {
+                        spent: true,
+                        status,
+                        ..
+                    }) => match status.and_then(|status| status.block_height) {
+                        Some(height) => Some(confirmations(client.get_height()?, height)),
+                        None => Some(0),
+                    },
+                    _ => None,
+                }
+            }
+            ChainSource::Bitcoind(bitcoind) => {
+                bitcoind.output_spend_confirmations(outpoint, max_confirmations)?
+            }
+            ChainSource::Electrum(electrum) => electrum.output_spend_confirmations(outpoint)?,
+        };
+
+        Ok(confirmations.map(|confirmations| confirmations.min(max_confirmations)))
+    }
+}
+
+impl Filter for ChainSource {
+    fn register_tx(&self, txid: &Txid, script_pubkey: &Script) {
+        match self {
+            ChainSource::Esplora(client) => client.register_tx(txid, script_pubkey),
+            ChainSource::Bitcoind(BitcoindChainSource { watched, .. })
+            | ChainSource::Electrum(ElectrumChainSource { watched, .. }) => {
+                watched
+                    .lock()
+                    .transactions
+                    .insert(*txid, script_pubkey.clone());
+            }
+        }
+    }
+
+    fn register_output(&self, output: WatchedOutput) {
+        match self {
+            ChainSource::Esplora(client) => client.register_output(output),
+            ChainSource::Bitcoind(BitcoindChainSource { watched, .. })
+            | ChainSource::Electrum(ElectrumChainSource { watched, .. }) => {
+                watched.lock().outputs.insert(
+                    output.outpoint.into_bitcoin_outpoint(),
+                    output.script_pubkey,
+                );
+            }
+        }
+    }
+}
+
+impl BitcoindChainSource {
+    /// Connect all blocks since `best_block` to the `confirmables`.
+    fn sync(
+        &self,
+        confirmables: &[&(dyn Confirm + Sync + Send)],
+        best_block: BestBlock,
+    ) -> Result<()> {
+        for confirmable in confirmables {
+            for (txid, block_hash) in confirmable.get_relevant_txids() {
+                if let Some(block_hash) = block_hash {
+                    if self
+                        .client
+                        .get_block_header_info(&block_hash)?
+                        .confirmations
+                        < 0
+                    {
+                        tracing::debug!(%txid, %block_hash, "Transaction was reorged out");
+                        confirmable.transaction_unconfirmed(&txid);
+                    }
+                }
+            }
+        }
+
+        // After a reorg, we pick up at the last block which is still part of the main chain.
+        let mut header = self
+            .client
+            .get_block_header_info(&best_block.block_hash())?;
+        while header.confirmations < 0 {
+            let previous = header
+                .previous_block_hash
+                .context("Stale block without predecessor")?;
+            header = self.client.get_block_header_info(&previous)?;
+        }
+
+        let mut previous_block_hash = header.hash;
+        let tip = self.client.get_block_count()?;
+        for height in (header.height as u64 + 1)..=tip {
+            let block_hash = self.client.get_block_hash(height)?;
+            let block = self.client.get_block(&block_hash)?;
+
+            if block.header.prev_blockhash != previous_block_hash {
+                bail!("Chain tip changed during sync");
+            }
+
+            self.connect_block(confirmables, &block, u32::try_from(height)?);
+
+            previous_block_hash = block_hash;
+        }
+
+        Ok(())
+    }
+
+    fn connect_block(
+        &self,
+        confirmables: &[&(dyn Confirm + Sync + Send)],
+        block: &Block,
+        height: u32,
+    ) {
+        let mut confirmed = HashSet::new();
+
+        // Confirming a transaction can make LDK watch more outputs, e.g. the ones of a commitment
+        // transaction, which may already be spent in the same block.
+        loop {
+            let txdata = {
+                let watched = self.watched.lock();
+                block
+                    .txdata
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, tx)| !confirmed.contains(&tx.txid()) && watched.is_relevant(tx))
+                    .collect::<Vec<_>>()
+            };
+
+            if txdata.is_empty() {
+                break;
+            }
+
+            for confirmable in confirmables {
+                confirmable.transactions_confirmed(&block.header, &txdata, height);
+            }
+
+            confirmed.extend(txdata.iter().map(|(_, tx)| tx.txid()));
+        }
+
+        for confirmable in confirmables {
+            confirmable.best_block_updated(&block.header, height);
+        }
+    }
+
+    fn tx_confirmation_height(&self, txid: &Txid) -> Result<Option<u32>> {
+        let tx = match self.client.get_raw_transaction_info(txid, None) {
+            Ok(tx) => tx,
+            Err(e) if is_unknown_transaction(&e) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let block_hash = match tx.blockhash {
+            Some(block_hash) => block_hash,
+            None => return Ok(None),
+        };
+
+        let header = self.client.get_block_header_info(&block_hash)?;
+        if header.confirmations < 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(u32::try_from(header.height)?))
+    }
+
+    fn output_spend_confirmations(
+        &self,
+        outpoint: &OutPoint,
+        max_confirmations: u32,
+    ) -> Result<Option<u32>> {
+        let tx = match self.client.get_raw_transaction_info(&outpoint.txid, None) {
+            Ok(tx) => tx,
+            Err(e) if is_unknown_transaction(&e) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        if self
+            .client
+            .get_tx_out(&outpoint.txid, outpoint.vout, Some(true))?
+            .is_some()
+        {
+            return Ok(None);
+        }
+
+        let spent_in_mempool = tx.blockhash.is_none()
+            || self
+                .client
+                .get_tx_out(&outpoint.txid, outpoint.vout, Some(false))?
+                .is_some();
+        if spent_in_mempool {
+            return Ok(Some(0));
+        }
+
+        // bitcoind does not index spending transactions, so we look for it in the most recent
+        // blocks. If it is buried deeper, it has at least `max_confirmations`.
+        let mut block_hash = self.client.get_best_block_hash()?;
+        for confirmations in 1..=max_confirmations {
+            let block = self.client.get_block(&block_hash)?;
+
+            let spends_output = block.txdata.iter().any(|tx| {
+                tx.input
+                    .iter()
+                    .any(|input| input.previous_output == *outpoint)
+            });
+            if spends_output {
+                return Ok(Some(confirmations));
+            }
+
+            block_hash = block.header.prev_blockhash;
+        }
+
+        Ok(Some(max_confirmations))
+    }
+}
+
+impl ElectrumChainSource {
+    /// Report the confirmations of all watched transactions and of all transactions spending
+    /// watched outputs to the `confirmables`.
+    fn sync(&self, confirmables: &[&(dyn Confirm + Sync + Send)]) -> Result<()> {
+        let tip = self.client.block_headers_subscribe()?;
+        let tip_height = u32::try_from(tip.height)?;
+
+        // Transactions which are still confirmed in the same block are not reported again.
+        let mut reported = HashSet::new();
+        for confirmable in confirmables {
+            for (txid, block_hash) in confirmable.get_relevant_txids() {
+                if let Some(block_hash) = block_hash {
+                    if self.is_confirmed_in(&txid, &block_hash)? {
+                        reported.insert(txid);
+                    } else {
+                        tracing::debug!(%txid, %block_hash, "Transaction was reorged out");
+                        confirmable.transaction_unconfirmed(&txid);
+                    }
+                }
+            }
+        }
+
+        for confirmable in confirmables {
+            confirmable.best_block_updated(&tip.header, tip_height);
+        }
+
+        // Confirming a transaction can make LDK watch more outputs, which may already be spent.
+        let mut confirmed_txs = Vec::new();
+        loop {
+            let watched = self.watched.lock().clone();
+            let txs = self.confirmed_transactions(&watched, &reported)?;
+
+            if txs.is_empty() {
+                break;
+            }
+
+            for ConfirmedTransaction {
+                tx,
+                header,
+                height,
+                pos,
+            } in txs.iter()
+            {
+                for confirmable in confirmables {
+                    confirmable.transactions_confirmed(header, &[(*pos, tx)], *height);
+                }
+            }
+
+            reported.extend(txs.iter().map(|confirmed| confirmed.tx.txid()));
+            confirmed_txs.extend(txs);
+        }
+
+        // Transactions which are buried deep enough do not have to be looked up anymore.
+        let mut watched = self.watched.lock();
+        for ConfirmedTransaction { tx, height, .. } in confirmed_txs {
+            if confirmations(tip_height, height) >= ANTI_REORG_DELAY {
+                watched.transactions.remove(&tx.txid());
+                for input in tx.input.iter() {
+                    watched.outputs.remove(&input.previous_output);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn confirmed_transactions(
+        &self,
+        watched: &WatchedItems,
+        reported: &HashSet<Txid>,
+    ) -> Result<Vec<ConfirmedTransaction>> {
+        let mut confirmation_heights = HashMap::new();
+
+        for (txid, script) in watched.transactions.iter() {
+            if reported.contains(txid) {
+                continue;
+            }
+
+            let height = self
+                .client
+                .script_get_history(script)?
+                .iter()
+                .filter(|entry| entry.tx_hash == *txid)
+                .find_map(confirmation_height);
+
+            if let Some(height) = height {
+                confirmation_heights.insert(*txid, height);
+            }
+        }
+
+        for (outpoint, script) in watched.outputs.iter() {
+            for entry in self.client.script_get_history(script)? {
+                let txid = entry.tx_hash;
+                if txid == outpoint.txid
+                    || reported.contains(&txid)
+                    || confirmation_heights.contains_key(&txid)
+                {
+                    continue;
+                }
+
+                let height = match confirmation_height(&entry) {
+                    Some(height) => height,
+                    None => continue,
+                };
+
+                let tx = self.client.transaction_get(&txid)?;
+                if tx
+                    .input
+                    .iter()
+                    .any(|input| input.previous_output == *outpoint)
+                {
+                    confirmation_heights.insert(txid, height);
+                }
+            }
+        }
+
+        let mut confirmed_txs = Vec::new();
+        for (txid, height) in confirmation_heights {
+            let tx = self.client.transaction_get(&txid)?;
+            let header = self.client.block_header(height as usize)?;
+            let pos = self
+                .client
+                .transaction_get_merkle(&txid, height as usize)?
+                .pos;
+
+            confirmed_txs.push(ConfirmedTransaction {
+                tx,
+                header,
+                height,
+                pos,
+            });
+        }
+
+        confirmed_txs.sort_by_key(|confirmed| (confirmed.height, confirmed.pos));
+
+        Ok(confirmed_txs)
+    }
+
+    fn is_confirmed_in(&self, txid: &Txid, block_hash: &BlockHash) -> Result<bool> {
+        match self.tx_confirmation_height(txid)? {
+            Some(height) => {
+                Ok(self.client.block_header(height as usize)?.block_hash() == *block_hash)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn tx_confirmation_height(&self, txid: &Txid) -> Result<Option<u32>> {
+        let tx = match self.get_transaction(txid)? {
+            Some(tx) => tx,
+            None => return Ok(None),
+        };
+
+        // Electrum servers index transactions by the scripts they touch.
+        let script = match tx.output.first() {
+            Some(output) => &output.script_pubkey,
+            None => return Ok(None),
+        };
+
+        let height = self
+            .client
+            .script_get_history(script)?
+            .iter()
+            .filter(|entry| entry.tx_hash == *txid)
+            .find_map(confirmation_height);
+
+        Ok(height)
+    }
+
+    fn output_spend_confirmations(&self, outpoint: &OutPoint) -> Result<Option<u32>> {
+        let tx = match self.get_transaction(&outpoint.txid)? {
+            Some(tx) => tx,
+            None => return Ok(None),
+        };
+
+        let script = match tx.output.get(outpoint.vout as usize) {
+            Some(output) => &output.script_pubkey,
+            None => return Ok(None),
+        };
+
+        for entry in self.client.script_get_history(script)? {
+            if entry.tx_hash == outpoint.txid {
+                continue;
+            }
+
+            let tx = self.client.transaction_get(&entry.tx_hash)?;
+            if !tx
+                .input
+                .iter()
+                .any(|input| input.previous_output == *outpoint)
+            {
+                continue;
+            }
+
+            let confirmations = match confirmation_height(&entry) {
+                Some(height) => {
+                    let tip = u32::try_from(self.client.block_headers_subscribe()?.height)?;
+                    confirmations(tip, height)
+                }
+                None => 0,
+            };
+
+            return Ok(Some(confirmations));
+        }
+
+        Ok(None)
+    }
+
+    /// Assemble the block at the given height from its header and all of its transactions.
+    fn get_block_at_height(&self, height: usize) -> Result<Block> {
+        let header = self.client.block_header(height)?;
+
+        let tx_count = self.tx_count(height)?;
+        let positions = (0..tx_count).collect::<Vec<_>>();
+
+        let mut txids = Vec::with_capacity(tx_count);
+        for positions in positions.chunks(ELECTRUM_BATCH_SIZE) {
+            let mut batch = Batch::default();
+            for position in positions {
+                batch.raw(
+                    "blockchain.transaction.id_from_pos".to_string(),
+                    vec![Param::Usize(height), Param::Usize(*position)],
+                );
+            }
+
+            for txid in self.client.batch_call(&batch)? {
+                let txid = txid
+                    .as_str()
+                    .with_context(|| format!("Invalid txid in block at height {height}: {txid}"))?;
+                txids.push(Txid::from_str(txid)?);
+            }
+        }
+
+        let mut txdata = Vec::with_capacity(tx_count);
+        for txids in txids.chunks(ELECTRUM_BATCH_SIZE) {
+            txdata.extend(self.client.batch_transaction_get(txids)?);
+        }
+
+        let block = Block { header, txdata };
+
+        ensure!(
+            block.check_merkle_root(),
+            "Transactions of block at height {height} do not match its merkle root"
+        );
+
+        Ok(block)
+    }
+
+    /// The number of transactions in the block at the given height.
+    ///
+    /// Electrum servers only serve the id of the transaction at a given position of a block, and
+    /// respond with an error for positions beyond the last transaction. Hence, we search for the
+    /// first position without a transaction.
+    fn tx_count(&self, height: usize) -> Result<usize> {
+        // Every block has a coinbase transaction at position 0.
+        let mut with_tx = 0;
+        let mut without_tx = MAX_BLOCK_TRANSACTIONS;
+        while without_tx - with_tx > 1 {
+            let position = with_tx + (without_tx - with_tx) / 2;
+            match self.client.txid_from_pos(height, position) {
+                Ok(_) => with_tx = position,
+                Err(electrum_client::Error::Protocol(_)) => without_tx = position,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(without_tx)
+    }
+
+    fn get_transaction(&self, txid: &Txid) -> Result<Option<Transaction>> {
+        match self.client.transaction_get(txid) {
+            Ok(tx) => Ok(Some(tx)),
+            // The server responds with an error for unknown transactions.
+            Err(electrum_client::Error::Protocol(_)) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl WatchedItems {
+    fn is_relevant(&self, tx: &Transaction) -> bool {
+        self.transactions.contains_key(&tx.txid())
+            || tx
+                .input
+                .iter()
+                .any(|input| self.outputs.contains_key(&input.previous_output))
+    }
+}
+
+fn is_unknown_transaction(error: &bitcoincore_rpc::Error) -> bool {
+    matches!(
+        error,
+        bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::Error::Rpc(e))
+            if e.code == RPC_INVALID_ADDRESS_OR_KEY
+    )
+}
+
+/// The height of the block including the transaction of the Electrum history `entry`. Unconfirmed
+/// transactions have a height of `0` or `-1`.
+fn confirmation_height(entry: &GetHistoryRes) -> Option<u32> {
+    u32::try_from(entry.height)
+        .ok()
+        .filter(|height| *height > 0)
+}
+
+fn confirmations(tip: u32, confirmation_height: u32) -> u32 {
+    (tip + 1).saturating_sub(confirmation_height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirmations_count_the_including_block() {
+        assert_eq!(confirmations(100, 100), 1);
+        assert_eq!(confirmations(105, 100), 6);
+    }
+
+    #[test]
+    fn stale_tip_has_no_confirmations() {
+        assert_eq!(confirmations(99, 100), 0);
+    }
+
+    #[test]
+    fn esplora_is_the_default_chain_source() {
+        assert_eq!(ChainSourceConfig::default(), ChainSourceConfig::Esplora);
+    }
+}
@@ -2,6 +2,7 @@ use crate::dlc_custom_signer::CustomKeysManager;
 use crate::fee_rate_estimator::FeeRateEstimator;
 use crate::ln::TracingLogger;
 use crate::ln_dlc_wallet::LnDlcWallet;
+use crate::node::ChainSource;
 use crate::node::Storage;
 use crate::storage::TenTenOneStorage;
 use crate::ChainMonitor;
@@ -21,7 +22,6 @@ use lightning::util::persist::CHANNEL_MANAGER_PERSISTENCE_KEY;
 use lightning::util::persist::CHANNEL_MANAGER_PERSISTENCE_PRIMARY_NAMESPACE;
 use lightning::util::persist::CHANNEL_MANAGER_PERSISTENCE_SECONDARY_NAMESPACE;
 use lightning::util::ser::ReadableArgs;
-use lightning_transaction_sync::EsploraSyncClient;
 use std::sync::Arc;
 
 pub type ChannelManager<S, N> = lightning::ln::channelmanager::ChannelManager<
@@ -40,7 +40,7 @@ pub(crate) fn build<S: TenTenOneStorage, N: Storage>(
     keys_manager: Arc<CustomKeysManager<S, N>>,
     ln_dlc_wallet: Arc<LnDlcWallet<S, N>>,
     fee_rate_estimator: Arc<FeeRateEstimator>,
-    explora_client: Arc<EsploraSyncClient<Arc<TracingLogger>>>,
+    chain_source: Arc<ChainSource>,
     logger: Arc<TracingLogger>,
     chain_monitor: Arc<ChainMonitor<S, N>>,
     ldk_config: UserConfig,
@@ -112,7 +112,7 @@ pub(crate) fn build<S: TenTenOneStorage, N: Storage>(
     // Make sure our filter is initialized with all the txs and outputs
     // that we need to be watching based on our set of channel monitors
     for (_, monitor) in channelmonitors.iter() {
-        monitor.load_outputs_to_watch(&explora_client.clone());
+        monitor.load_outputs_to_watch(&chain_source.clone());
     }
 
     for (_, monitor) in channelmonitors.drain(..) {
@@ -1,6 +1,7 @@
 use crate::channel::UserChannelId;
 use crate::dlc_custom_signer::CustomKeysManager;
 use crate::fee_rate_estimator::FeeRateEstimator;
+use crate::fee_rate_estimator::FeeRateEstimatorConfig;
 use crate::ln::manage_spendable_outputs;
 use crate::ln::GossipSource;
 use crate::ln::Probes;
@@ -8,6 +9,7 @@ use crate::ln::TracingLogger;
 use crate::ln_dlc_wallet::LnDlcWallet;
 use crate::node::peer_manager::alias_as_bytes;
 use crate::node::peer_manager::broadcast_node_announcement;
+use crate::node::psbt::WatchOnlyWallet;
 use crate::node::sub_channel::sub_channel_manager_periodic_check;
 use crate::on_chain_wallet::OnChainWallet;
 use crate::seed::Bip39Seed;
@@ -28,6 +30,7 @@ use bitcoin::hashes::hex::ToHex;
 use bitcoin::secp256k1::PublicKey;
 use bitcoin::Amount;
 use bitcoin::Network;
+use bitcoin::OutPoint;
 use bitcoin::Txid;
 use bitcoin::XOnlyPublicKey;
 use dlc_messages::message_handler::MessageHandler as DlcMessageHandler;
@@ -47,7 +50,6 @@ use lightning::sign::EntropySource;
 use lightning::sign::KeysManager;
 use lightning::util::config::UserConfig;
 use lightning_background_processor::process_events_async;
-use lightning_transaction_sync::EsploraSyncClient;
 use ln_dlc_storage::DlcStorageProvider;
 use p2pd_oracle_client::P2PDOracleClient;
 use serde::Deserialize;
@@ -55,6 +57,7 @@ use serde::Serialize;
 use serde_with::serde_as;
 use serde_with::DurationSeconds;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
@@ -68,11 +71,13 @@ use std::time::SystemTime;
 use tokio::sync::RwLock;
 use tokio::task::spawn_blocking;
 
+mod chain_source;
 mod channel_manager;
 mod connection;
 mod dlc_manager;
 mod ln_channel;
 mod oracle;
+mod psbt;
 mod storage;
 mod sub_channel_manager;
 mod wallet;
@@ -90,6 +95,8 @@ pub use crate::node::dlc_manager::DlcManager;
 use crate::node::event::NodeEventHandler;
 pub use crate::node::oracle::OracleInfo;
 pub use ::dlc_manager as rust_dlc_manager;
+pub(crate) use chain_source::ChainSource;
+pub use chain_source::ChainSourceConfig;
 pub use channel_manager::ChannelManager;
 pub use invoice::HTLCStatus;
 use lightning::ln::msgs::SocketAddress;
@@ -98,6 +105,8 @@ use lightning::util::persist::NETWORK_GRAPH_PERSISTENCE_KEY;
 use lightning::util::persist::NETWORK_GRAPH_PERSISTENCE_PRIMARY_NAMESPACE;
 use lightning::util::persist::NETWORK_GRAPH_PERSISTENCE_SECONDARY_NAMESPACE;
 use lightning::util::ser::ReadableArgs;
+pub use psbt::PendingFundingPsbt;
+pub use psbt::PsbtWallet;
 pub use storage::InMemoryStore;
 pub use storage::Storage;
 pub use sub_channel::dlc_message_name;
@@ -121,8 +130,6 @@ const RGS_SYNC_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 type Scorer = ProbabilisticScorer<Arc<NetworkGraph>, Arc<TracingLogger>>;
 
-type NodeEsploraClient = EsploraSyncClient<Arc<TracingLogger>>;
-
 type RequestedScid = u64;
 // TODO(holzeis): Move to coordinator
 type FakeChannelPaymentRequests = Arc<parking_lot::Mutex<HashMap<RequestedScid, LiquidityRequest>>>;
@@ -183,9 +190,13 @@ pub struct Node<S: TenTenOneStorage, N: Storage> {
     pub(crate) alias: String,
     pub(crate) announcement_addresses: Vec<SocketAddress>,
     pub scorer: Arc<std::sync::RwLock<Scorer>>,
-    esplora_server_url: String,
-    esplora_client: Arc<NodeEsploraClient>,
+    chain_source: Arc<ChainSource>,
     pub pending_channel_opening_fee_rates: Arc<parking_lot::Mutex<HashMap<PublicKey, FeeRate>>>,
+    /// Counterparties whose next channel is funded with a PSBT from the watch-only wallet.
+    pub(crate) pending_external_channel_fundings: Arc<parking_lot::Mutex<HashSet<PublicKey>>>,
+    /// Unsigned funding PSBTs of channels by the txid of the funding transaction.
+    pub(crate) pending_funding_psbts: Arc<parking_lot::Mutex<HashMap<Txid, PendingFundingPsbt>>>,
+    watch_only_wallet: Arc<parking_lot::RwLock<Option<Arc<WatchOnlyWallet<N>>>>>,
     pub probes: Probes,
 }
 
@@ -246,6 +257,14 @@ pub struct LnDlcNodeSettings {
 
     /// XXX: Requires restart of the node to take effect
     pub gossip_source_config: GossipSourceConfig,
+
+    /// XXX: Requires restart of the node to take effect
+    #[serde(default)]
+    pub chain_source_config: ChainSourceConfig,
+
+    /// XXX: Requires restart of the node to take effect
+    #[serde(default)]
+    pub fee_rate_estimator_config: FeeRateEstimatorConfig,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -297,18 +316,23 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
         let on_chain_wallet =
             OnChainWallet::new(on_chain_dir.as_path(), network, seed.wallet_seed())?;
 
-        let esplora_client = Arc::new(EsploraSyncClient::new(
+        let chain_source = Arc::new(ChainSource::new(
+            &settings.chain_source_config,
             esplora_server_url.clone(),
             logger.clone(),
-        ));
+        )?);
 
         let dlc_storage = Arc::new(DlcStorageProvider::new(storage.clone()));
         let ln_storage = Arc::new(storage);
 
-        let fee_rate_estimator = Arc::new(FeeRateEstimator::new(network));
+        let fee_rate_estimator = Arc::new(FeeRateEstimator::new(
+            network,
+            settings.fee_rate_estimator_config.clone(),
+            &esplora_server_url,
+        )?);
         let ln_dlc_wallet = {
             Arc::new(LnDlcWallet::new(
-                esplora_client.clone(),
+                chain_source.clone(),
                 on_chain_wallet.inner,
                 fee_rate_estimator.clone(),
                 dlc_storage.clone(),
@@ -316,11 +340,11 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
                 settings.bdk_client_stop_gap,
                 settings.bdk_client_concurrency,
                 wallet_settings,
-            ))
+            )?)
         };
 
         let chain_monitor: Arc<ChainMonitor<S, N>> = Arc::new(chainmonitor::ChainMonitor::new(
-            Some(esplora_client.clone()),
+            Some(chain_source.clone()),
             ln_dlc_wallet.clone(),
             logger.clone(),
             fee_rate_estimator.clone(),
@@ -384,7 +408,7 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
             keys_manager.clone(),
             ln_dlc_wallet.clone(),
             fee_rate_estimator.clone(),
-            esplora_client.clone(),
+            chain_source.clone(),
             logger.clone(),
             chain_monitor.clone(),
             *ldk_config.read(),
@@ -503,9 +527,11 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
             alias: alias.to_string(),
             announcement_addresses,
             scorer,
-            esplora_server_url,
-            esplora_client,
+            chain_source,
             pending_channel_opening_fee_rates: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            pending_external_channel_fundings: Arc::new(parking_lot::Mutex::new(HashSet::new())),
+            pending_funding_psbts: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            watch_only_wallet: Arc::new(parking_lot::RwLock::new(None)),
             oracle_pubkey,
             probes: Probes::default(),
             event_handler: node_event_handler,
@@ -536,7 +562,7 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
             self.channel_manager.clone(),
             self.chain_monitor.clone(),
             self.settings.clone(),
-            self.esplora_client.clone(),
+            self.chain_source.clone(),
         ));
 
         tokio::spawn(update_fee_rate_estimates(
@@ -580,7 +606,7 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
         ));
 
         tokio::spawn(manage_spendable_outputs_task(
-            self.esplora_server_url.clone(),
+            self.chain_source.clone(),
             self.node_storage.clone(),
             self.wallet.clone(),
             self.fee_rate_estimator.clone(),
@@ -634,11 +660,19 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
         let handle = tokio::runtime::Handle::current();
         let settings = self.settings.clone();
         let ln_dlc_wallet = self.wallet.clone();
+        let watch_only_wallet = self.watch_only_wallet.clone();
         move || loop {
             if let Err(e) = ln_dlc_wallet.sync_and_update_address_cache() {
                 tracing::error!("Failed on-chain sync: {e:#}");
             }
 
+            let watch_only_wallet = watch_only_wallet.read().clone();
+            if let Some(watch_only_wallet) = watch_only_wallet {
+                if let Err(e) = watch_only_wallet.sync() {
+                    tracing::error!("Failed on-chain sync of watch-only wallet: {e:#}");
+                }
+            }
+
             let interval = handle.block_on(async {
                 let guard = settings.read().await;
                 guard.on_chain_sync_interval
@@ -649,14 +683,15 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
     }
 
     pub fn sync_on_chain_wallet(&self) -> Result<()> {
-        self.wallet.sync_and_update_address_cache()
+        self.wallet.sync_and_update_address_cache()?;
+        self.sync_watch_only_wallet()
     }
 
     pub fn sync_lightning_wallet(&self) -> Result<()> {
         lightning_wallet_sync(
             &self.channel_manager,
             &self.chain_monitor,
-            &self.esplora_client,
+            &self.chain_source,
         )
     }
 
@@ -674,15 +709,38 @@ impl<S: TenTenOneStorage + 'static, N: Storage + Sync + Send + 'static> Node<S,
     }
 
     /// Send the given `amount_sats` sats to the given `address` on-chain.
+    ///
+    /// If `inputs` are given, exactly these UTXOs are spent. Otherwise they are selected
+    /// automatically, leaving out frozen UTXOs.
     pub fn send_to_address(
         &self,
         address: &bitcoin::Address,
         amount_sats: u64,
         fee: Fee,
+        inputs: &[OutPoint],
     ) -> Result<Txid> {
         self.wallet
             .ldk_wallet()
-            .send_to_address(address, amount_sats, fee)
+            .send_to_address(address, amount_sats, fee, inputs)
+    }
+
+    /// Spend the given `inputs`, or all UTXOs which are not frozen if none are given, to a single
+    /// output of the on-chain wallet.
+    pub fn consolidate_utxos(&self, inputs: &[OutPoint], fee: Fee) -> Result<Txid> {
+        self.wallet.ldk_wallet().consolidate_utxos(inputs, fee)
+    }
+
+    /// Replace the unconfirmed on-chain transaction `txid` with one paying the given `fee`.
+    ///
+    /// Only works for transactions sent from this node's wallet.
+    pub fn replace_by_fee(&self, txid: &Txid, fee: Fee) -> Result<Txid> {
+        self.wallet.ldk_wallet().replace_by_fee(txid, fee)
+    }
+
+    /// Accelerate the unconfirmed on-chain transaction `txid` by spending its outputs belonging
+    /// to this node's wallet with a child transaction paying for both at the given `fee`.
+    pub fn child_pays_for_parent(&self, txid: &Txid, fee: Fee) -> Result<Txid> {
+        self.wallet.ldk_wallet().child_pays_for_parent(txid, fee)
     }
 }
 
@@ -748,10 +806,10 @@ async fn periodic_lightning_wallet_sync<S: TenTenOneStorage, N: Storage + Sync +
     channel_manager: Arc<ChannelManager<S, N>>,
     chain_monitor: Arc<ChainMonitor<S, N>>,
     settings: Arc<RwLock<LnDlcNodeSettings>>,
-    esplora_client: Arc<EsploraSyncClient<Arc<TracingLogger>>>,
+    chain_source: Arc<ChainSource>,
 ) {
     loop {
-        if let Err(e) = lightning_wallet_sync(&channel_manager, &chain_monitor, &esplora_client) {
+        if let Err(e) = lightning_wallet_sync(&channel_manager, &chain_monitor, &chain_source) {
             tracing::error!("Background sync of Lightning wallet failed: {e:#}")
         }
 
@@ -766,15 +824,15 @@ async fn periodic_lightning_wallet_sync<S: TenTenOneStorage, N: Storage + Sync +
 fn lightning_wallet_sync<S: TenTenOneStorage, N: Storage + Sync + Send>(
     channel_manager: &ChannelManager<S, N>,
     chain_monitor: &ChainMonitor<S, N>,
-    esplora_client: &EsploraSyncClient<Arc<TracingLogger>>,
+    chain_source: &ChainSource,
 ) -> Result<()> {
     let now = Instant::now();
     let confirmables = vec![
         channel_manager as &(dyn Confirm + Sync + Send),
         chain_monitor as &(dyn Confirm + Sync + Send),
     ];
-    esplora_client
-        .sync(confirmables)
+    chain_source
+        .sync(confirmables, channel_manager.current_best_block())
         .context("Lightning wallet sync failed")?;
 
     tracing::trace!(
@@ -891,19 +949,15 @@ async fn manage_spendable_outputs_task<
     S: TenTenOneStorage + 'static,
     N: Storage + Sync + Send + 'static,
 >(
-    esplora_server_url: String,
+    chain_source: Arc<ChainSource>,
     node_storage: Arc<N>,
     ln_dlc_wallet: Arc<LnDlcWallet<S, N>>,
     fee_rate_estimator: Arc<FeeRateEstimator>,
     keys_manager: Arc<CustomKeysManager<S, N>>,
 ) {
-    let client = Arc::new(esplora_client::BlockingClient::from_agent(
-        esplora_server_url,
-        ureq::agent(),
-    ));
     loop {
         if let Err(e) = spawn_blocking({
-            let client = client.clone();
+            let chain_source = chain_source.clone();
             let node_storage = node_storage.clone();
             let ln_dlc_wallet = ln_dlc_wallet.clone();
             let fee_rate_estimator = fee_rate_estimator.clone();
@@ -911,7 +965,7 @@ async fn manage_spendable_outputs_task<
             move || {
                 manage_spendable_outputs(
                     node_storage,
-                    client,
+                    chain_source,
                     ln_dlc_wallet,
                     fee_rate_estimator,
                     keys_manager,
@@ -0,0 +1,293 @@
+use crate::fee_rate_estimator::FeeRateEstimator;
+use crate::ldk_node_wallet;
+use crate::node::Fee;
+use crate::node::Node;
+use crate::node::Storage;
+use crate::on_chain_wallet::OnChainWallet;
+use crate::storage::TenTenOneStorage;
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::Context;
+use anyhow::Result;
+use bdk::blockchain::AnyBlockchain;
+use bdk::miniscript::psbt::PsbtExt;
+use bdk::sled;
+use bdk::FeeRate;
+use bitcoin::psbt::PartiallySignedTransaction;
+use bitcoin::secp256k1::PublicKey;
+use bitcoin::secp256k1::Secp256k1;
+use bitcoin::Address;
+use bitcoin::Script;
+use bitcoin::Txid;
+use lightning::ln::ChannelId;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A wallet without private keys, e.g. for funds kept in cold storage.
+pub(crate) type WatchOnlyWallet<N> =
+    ldk_node_wallet::Wallet<sled::Tree, AnyBlockchain, FeeRateEstimator, N>;
+
+/// The on-chain wallet of the node to create a PSBT with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PsbtWallet {
+    /// The hot wallet of the node.
+    Hot,
+    /// The watch-only wallet of the node, see [`Node::load_watch_only_wallet`].
+    WatchOnly,
+}
+
+/// The unsigned funding transaction of a channel, waiting to be signed externally.
+#[derive(Debug, Clone)]
+pub struct PendingFundingPsbt {
+    pub temporary_channel_id: ChannelId,
+    pub counterparty_node_id: PublicKey,
+    pub psbt: PartiallySignedTransaction,
+}
+
+impl<S: TenTenOneStorage, N: Storage> Node<S, N> {
+    /// Load a watch-only wallet from the public `descriptor` (and optionally the
+    /// `change_descriptor`) next to the hot wallet of the node.
+    ///
+    /// The watch-only wallet is synced together with the hot wallet. Its funds can only be spent
+    /// with PSBTs which are signed externally.
+    pub async fn load_watch_only_wallet(
+        &self,
+        data_dir: &Path,
+        descriptor: &str,
+        change_descriptor: Option<&str>,
+    ) -> Result<()> {
+        let on_chain_wallet = OnChainWallet::watch_only(
+            data_dir.join("on_chain").as_path(),
+            self.network,
+            descriptor,
+            change_descriptor,
+        )?;
+
+        let (stop_gap, concurrency) = {
+            let settings = self.settings.read().await;
+            (
+                settings.bdk_client_stop_gap,
+                settings.bdk_client_concurrency,
+            )
+        };
+
+        let blockchain =
+            self.chain_source
+                .bdk_blockchain(&on_chain_wallet.inner, stop_gap, concurrency)?;
+
+        let wallet = ldk_node_wallet::Wallet::new(
+            blockchain,
+            on_chain_wallet.inner,
+            self.fee_rate_estimator.clone(),
+            self.node_storage.clone(),
+            self.wallet.ldk_wallet().settings().await,
+        );
+
+        *self.watch_only_wallet.write() = Some(Arc::new(wallet));
+
+        Ok(())
+    }
+
+    pub fn get_watch_only_balance(&self) -> Result<bdk::Balance> {
+        self.watch_only_wallet()?
+            .get_balance()
+            .context("Failed to get watch-only balance")
+    }
+
+    pub fn get_watch_only_address(&self) -> Result<Address> {
+        self.watch_only_wallet()?
+            .get_last_unused_address()
+            .context("Failed to get watch-only address")
+    }
+
+    /// Create an unsigned PSBT sending `amount_sats` (or everything if `0`) to `address` from the
+    /// given `wallet`.
+    pub fn create_unsigned_psbt(
+        &self,
+        wallet: PsbtWallet,
+        address: &Address,
+        amount_sats: u64,
+        fee: Fee,
+    ) -> Result<PartiallySignedTransaction> {
+        let recipient = address.script_pubkey();
+
+        match wallet {
+            PsbtWallet::Hot => {
+                self.wallet
+                    .ldk_wallet()
+                    .create_unsigned_psbt(recipient, amount_sats, fee)
+            }
+            PsbtWallet::WatchOnly => {
+                self.watch_only_wallet()?
+                    .create_unsigned_psbt(recipient, amount_sats, fee)
+            }
+        }
+    }
+
+    /// Fund the next channel opened with `counterparty_node_id` from the watch-only wallet.
+    ///
+    /// Once the counterparty accepts the channel, the unsigned funding PSBT is listed in
+    /// [`Node::pending_funding_psbts`] until it is signed and passed to
+    /// [`Node::broadcast_signed_psbt`]. LDK gives up on channels which are not funded within about
+    /// an hour.
+    pub fn fund_channel_externally(&self, counterparty_node_id: PublicKey) -> Result<()> {
+        self.watch_only_wallet()?;
+
+        self.pending_external_channel_fundings
+            .lock()
+            .insert(counterparty_node_id);
+
+        Ok(())
+    }
+
+    pub fn pending_funding_psbts(&self) -> Vec<PendingFundingPsbt> {
+        self.pending_funding_psbts
+            .lock()
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Finalize and broadcast an externally signed PSBT.
+    ///
+    /// If the PSBT funds a channel, the funding transaction is handed over to LDK, which
+    /// broadcasts it once the counterparty has signed the first commitment transaction.
+    pub fn broadcast_signed_psbt(&self, mut psbt: PartiallySignedTransaction) -> Result<Txid> {
+        let is_finalized = psbt
+            .inputs
+            .iter()
+            .all(|input| input.final_script_sig.is_some() || input.final_script_witness.is_some());
+
+        if !is_finalized {
+            psbt.finalize_mut(&Secp256k1::verification_only())
+                .map_err(|errors| anyhow!("Failed to finalize PSBT: {errors:?}"))?;
+        }
+
+        let tx = psbt.extract_tx();
+        let txid = tx.txid();
+
+        let pending_funding = self.pending_funding_psbts.lock().remove(&txid);
+        match pending_funding {
+            Some(PendingFundingPsbt {
+                temporary_channel_id,
+                counterparty_node_id,
+                ..
+            }) => {
+                self.channel_manager
+                    .funding_transaction_generated(&temporary_channel_id, &counterparty_node_id, tx)
+                    .map_err(|e| anyhow!("{e:?}"))
+                    .with_context(|| {
+                        format!("Failed to fund channel with {counterparty_node_id}")
+                    })?;
+
+                tracing::info!(%txid, %counterparty_node_id, "Funded channel with signed PSBT");
+
+                Ok(txid)
+            }
+            None => {
+                self.wallet.ldk_wallet().broadcast_transaction(&tx)?;
+                self.release_psbt(&txid);
+
+                Ok(txid)
+            }
+        }
+    }
+
+    /// Abandon the unsigned PSBT with the given `txid`, releasing its inputs.
+    ///
+    /// If the PSBT funds a channel, the channel is closed.
+    pub fn abandon_psbt(&self, txid: &Txid) -> Result<()> {
+        let pending_funding = self.pending_funding_psbts.lock().remove(txid);
+        if let Some(PendingFundingPsbt {
+            temporary_channel_id,
+            counterparty_node_id,
+            ..
+        }) = pending_funding
+        {
+            tracing::info!(%txid, %counterparty_node_id, "Abandoning channel funding PSBT");
+
+            if let Err(e) = self
+                .channel_manager
+                .close_channel(&temporary_channel_id, &counterparty_node_id)
+            {
+                // The channel may have been dropped by LDK already.
+                tracing::warn!(%txid, "Failed to close channel of abandoned PSBT: {e:?}");
+            }
+        }
+
+        self.release_psbt(txid);
+
+        Ok(())
+    }
+
+    /// Forget the funding PSBT of the channel which has been closed before it was funded.
+    pub(crate) fn discard_funding_psbt(&self, temporary_channel_id: &ChannelId) {
+        let mut pending_funding_psbts = self.pending_funding_psbts.lock();
+
+        let txids = pending_funding_psbts
+            .iter()
+            .filter(|(_, pending)| pending.temporary_channel_id == *temporary_channel_id)
+            .map(|(txid, _)| *txid)
+            .collect::<Vec<_>>();
+
+        for txid in txids {
+            pending_funding_psbts.remove(&txid);
+
+            tracing::info!(%txid, "Discarded funding PSBT of closed channel");
+
+            self.release_psbt(&txid);
+        }
+    }
+
+    /// Release the inputs reserved for the PSBT with the given `txid` in both wallets.
+    pub(crate) fn release_psbt(&self, txid: &Txid) {
+        self.wallet.ldk_wallet().release_psbt_reservation(txid);
+
+        if let Some(wallet) = self.watch_only_wallet.read().clone() {
+            wallet.release_psbt_reservation(txid);
+        }
+    }
+
+    /// Create the unsigned funding PSBT of a channel from the watch-only wallet.
+    pub(crate) fn create_funding_psbt(
+        &self,
+        temporary_channel_id: ChannelId,
+        counterparty_node_id: PublicKey,
+        output_script: Script,
+        channel_value_satoshis: u64,
+        fee_rate: FeeRate,
+    ) -> Result<Txid> {
+        let psbt = self.watch_only_wallet()?.create_unsigned_psbt(
+            output_script,
+            channel_value_satoshis,
+            Fee::FeeRate(fee_rate),
+        )?;
+
+        let txid = psbt.unsigned_tx.txid();
+
+        self.pending_funding_psbts.lock().insert(
+            txid,
+            PendingFundingPsbt {
+                temporary_channel_id,
+                counterparty_node_id,
+                psbt,
+            },
+        );
+
+        Ok(txid)
+    }
+
+    pub(crate) fn sync_watch_only_wallet(&self) -> Result<()> {
+        match self.watch_only_wallet.read().clone() {
+            Some(wallet) => wallet.sync(),
+            None => Ok(()),
+        }
+    }
+
+    fn watch_only_wallet(&self) -> Result<Arc<WatchOnlyWallet<N>>> {
+        match self.watch_only_wallet.read().clone() {
+            Some(wallet) => Ok(wallet),
+            None => bail!("No watch-only wallet loaded"),
+        }
+    }
+}
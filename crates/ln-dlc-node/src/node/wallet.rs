@@ -9,12 +9,15 @@ use crate::PaymentFlow;
 use crate::ToHex;
 use anyhow::Context;
 use anyhow::Result;
-use bdk::blockchain::EsploraBlockchain;
+use bdk::blockchain::AnyBlockchain;
 use bdk::sled;
 use bitcoin::secp256k1::SecretKey;
 use bitcoin::Address;
+use bitcoin::OutPoint;
 use dlc_manager::Blockchain;
 use lightning::ln::PaymentHash;
+use ln_dlc_storage::UtxoMetadata;
+use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 use time::OffsetDateTime;
@@ -51,7 +54,7 @@ impl<S: TenTenOneStorage, N: Storage> Node<S, N> {
 
     pub fn ldk_wallet(
         &self,
-    ) -> Arc<ldk_node_wallet::Wallet<sled::Tree, EsploraBlockchain, FeeRateEstimator, N>> {
+    ) -> Arc<ldk_node_wallet::Wallet<sled::Tree, AnyBlockchain, FeeRateEstimator, N>> {
         self.wallet.ldk_wallet()
     }
 
@@ -71,6 +74,26 @@ impl<S: TenTenOneStorage, N: Storage> Node<S, N> {
             .context("Failed to get blockchain height")
     }
 
+    /// The coin control metadata of the UTXOs of the on-chain wallet.
+    pub fn get_utxo_metadata(&self) -> Result<HashMap<OutPoint, UtxoMetadata>> {
+        self.wallet
+            .utxo_metadata()
+            .context("Failed to get UTXO metadata")
+    }
+
+    /// Exclude the UTXO from automatic coin selection, or include it again.
+    pub fn set_utxo_frozen(&self, outpoint: OutPoint, frozen: bool) -> Result<()> {
+        self.wallet
+            .set_utxo_frozen(outpoint, frozen)
+            .with_context(|| format!("Failed to update frozen state of UTXO {outpoint}"))
+    }
+
+    pub fn set_utxo_label(&self, outpoint: OutPoint, label: Option<String>) -> Result<()> {
+        self.wallet
+            .set_utxo_label(outpoint, label)
+            .with_context(|| format!("Failed to label UTXO {outpoint}"))
+    }
+
     pub fn get_on_chain_balance(&self) -> Result<bdk::Balance> {
         self.wallet
             .ldk_wallet()
@@ -1,4 +1,5 @@
 use crate::seed::WalletSeed;
+use anyhow::ensure;
 use anyhow::Context;
 use anyhow::Result;
 use bdk::bitcoin::secp256k1::Secp256k1;
@@ -48,4 +49,43 @@ impl OnChainWallet {
 
         Ok(OnChainWallet { inner: bdk_wallet })
     }
+
+    /// Create a wallet which only knows the public `descriptor` (and optionally the
+    /// `change_descriptor`), e.g. of funds kept in cold storage.
+    ///
+    /// The wallet can track its funds and create PSBTs, but they have to be signed externally.
+    pub fn watch_only(
+        data_dir: &Path,
+        network: bitcoin::Network,
+        descriptor: &str,
+        change_descriptor: Option<&str>,
+    ) -> Result<OnChainWallet> {
+        tracing::info!(?network, "Creating the watch-only wallet");
+
+        let data_dir = data_dir.join(network.to_string());
+        if !data_dir.exists() {
+            std::fs::create_dir_all(&data_dir).context(format!(
+                "Could not create data dir ({data_dir:?}) for {network}"
+            ))?;
+        }
+
+        let wallet_name =
+            wallet_name_from_descriptor(descriptor, change_descriptor, network, &Secp256k1::new())?;
+
+        let db = bdk::sled::open(data_dir.join("watch_only_wallet"))?;
+        let db = db.open_tree(wallet_name)?;
+
+        let bdk_wallet = bdk::Wallet::new(descriptor, change_descriptor, network, db)?;
+
+        let has_private_keys = !bdk_wallet
+            .get_signers(KeychainKind::External)
+            .signers()
+            .is_empty();
+        ensure!(
+            !has_private_keys,
+            "The descriptor of a watch-only wallet must not contain private keys"
+        );
+
+        Ok(OnChainWallet { inner: bdk_wallet })
+    }
 }
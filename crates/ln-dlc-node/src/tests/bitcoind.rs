@@ -1,6 +1,10 @@
 use crate::tests;
 use anyhow::bail;
+use anyhow::Context;
 use anyhow::Result;
+use bitcoin::Address;
+use bitcoin::Amount;
+use bitcoin::Txid;
 use reqwest::Response;
 use serde::Deserialize;
 use std::time::Duration;
@@ -20,6 +24,14 @@ pub async fn fund(address: String, amount: bitcoin::Amount) -> Result<Response>
     .await
 }
 
+/// Instructs `bitcoind` to send `amount` to `address`, returning the ID of the transaction.
+pub async fn send_to_address(address: &Address, amount: Amount) -> Result<Txid> {
+    let response = fund(address.to_string(), amount).await?;
+    let response: BitcoindResponse = response.json().await?;
+
+    response.result.parse().context("Invalid txid")
+}
+
 /// Instructs `bitcoind` to generate to address.
 pub async fn mine(n_blocks: u16) -> Result<()> {
     tracing::debug!(n_blocks, "Mining");
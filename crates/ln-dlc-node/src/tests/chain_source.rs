@@ -0,0 +1,179 @@
+use crate::ln::TracingLogger;
+use crate::node::ChainSource;
+use crate::node::ChainSourceConfig;
+use crate::node::Node;
+use crate::tests::bitcoind;
+use crate::tests::init_tracing;
+use crate::tests::ln_dlc_node_settings_app;
+use crate::tests::oracle_info;
+use crate::tests::random_address;
+use crate::tests::BITCOIND_RPC_ORIGIN;
+use crate::tests::BITCOIND_RPC_PASSWORD;
+use crate::tests::BITCOIND_RPC_USERNAME;
+use crate::tests::ELECTRUM_ORIGIN;
+use crate::tests::ESPLORA_ORIGIN;
+use bitcoin::Amount;
+use bitcoin::BlockHash;
+use bitcoin::BlockHeader;
+use bitcoin::Txid;
+use dlc_manager::Blockchain;
+use lightning::chain::transaction::TransactionData;
+use lightning::chain::BestBlock;
+use lightning::chain::Confirm;
+use lightning::chain::Filter;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use tokio::task::block_in_place;
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn node_with_bitcoind_chain_source_can_be_funded() {
+    init_tracing();
+
+    let settings = {
+        let mut settings = ln_dlc_node_settings_app();
+        settings.chain_source_config = bitcoind_chain_source_config();
+        settings
+    };
+
+    let (node, _running) =
+        Node::start_test_app_internal("app", settings, oracle_info().unwrap()).unwrap();
+
+    node.fund(Amount::from_btc(0.1).unwrap()).await.unwrap();
+
+    // One more block, so that the funding transactions have a confirmation according to
+    // `get_transaction_confirmations`
+    bitcoind::mine(1).await.unwrap();
+
+    let txs = node.wallet.on_chain_transactions().unwrap();
+    assert!(!txs.is_empty());
+
+    for tx in txs {
+        let confirmations = block_in_place(|| {
+            Blockchain::get_transaction_confirmations(node.wallet.as_ref(), &tx.txid)
+        })
+        .unwrap();
+
+        assert!(confirmations >= 1);
+    }
+
+    let height = block_in_place(|| node.wallet.get_blockchain_height()).unwrap();
+    let block = block_in_place(|| node.wallet.get_block_at_height(height)).unwrap();
+    let tip = block_in_place(|| node.chain_source.get_block_hash(height as u32)).unwrap();
+
+    assert_eq!(block.block_hash(), tip);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn node_with_electrum_chain_source_can_get_blocks() {
+    init_tracing();
+
+    let settings = {
+        let mut settings = ln_dlc_node_settings_app();
+        settings.chain_source_config = ChainSourceConfig::Electrum {
+            server_url: ELECTRUM_ORIGIN.to_string(),
+        };
+        settings
+    };
+
+    let (node, _running) =
+        Node::start_test_app_internal("app", settings, oracle_info().unwrap()).unwrap();
+
+    node.fund(Amount::from_btc(0.1).unwrap()).await.unwrap();
+
+    // The `DlcManager` scans full blocks, which are assembled from the transactions served by the
+    // Electrum server.
+    let height = block_in_place(|| node.wallet.get_blockchain_height()).unwrap();
+    let block = block_in_place(|| node.wallet.get_block_at_height(height)).unwrap();
+    let tip = block_in_place(|| node.chain_source.get_block_hash(height as u32)).unwrap();
+
+    assert_eq!(block.block_hash(), tip);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn bitcoind_chain_source_confirms_registered_transaction() {
+    init_tracing();
+
+    let chain_source = ChainSource::new(
+        &bitcoind_chain_source_config(),
+        ESPLORA_ORIGIN.to_string(),
+        Arc::new(TracingLogger {
+            alias: "chain_source".to_string(),
+        }),
+    )
+    .unwrap();
+
+    let best_block = block_in_place(|| {
+        let height = chain_source.get_height()?;
+        let block_hash = chain_source.get_block_hash(height)?;
+
+        anyhow::Ok(BestBlock::new(block_hash, height))
+    })
+    .unwrap();
+
+    let address = random_address();
+    let txid = bitcoind::send_to_address(&address, Amount::from_sat(100_000))
+        .await
+        .unwrap();
+
+    chain_source.register_tx(&txid, &address.script_pubkey());
+
+    bitcoind::mine(1).await.unwrap();
+
+    let confirmable = RecordingConfirm::default();
+    block_in_place(|| chain_source.sync(vec![&confirmable], best_block)).unwrap();
+
+    let confirmation_height = block_in_place(|| chain_source.get_tx_confirmation_height(&txid))
+        .unwrap()
+        .expect("transaction to be confirmed");
+    assert!(confirmation_height > best_block.height());
+
+    let confirmed = confirmable.confirmed.lock().clone();
+    assert_eq!(confirmed, vec![(txid, confirmation_height)]);
+
+    let best_height = confirmable
+        .best_height
+        .lock()
+        .expect("best block to be updated");
+    assert!(best_height >= confirmation_height);
+}
+
+fn bitcoind_chain_source_config() -> ChainSourceConfig {
+    ChainSourceConfig::Bitcoind {
+        rpc_url: BITCOIND_RPC_ORIGIN.to_string(),
+        rpc_username: BITCOIND_RPC_USERNAME.to_string(),
+        rpc_password: BITCOIND_RPC_PASSWORD.to_string(),
+    }
+}
+
+/// A [`Confirm`] implementation which records what the chain source tells it.
+#[derive(Default)]
+struct RecordingConfirm {
+    confirmed: Mutex<Vec<(Txid, u32)>>,
+    best_height: Mutex<Option<u32>>,
+}
+
+impl Confirm for RecordingConfirm {
+    fn transactions_confirmed(&self, _: &BlockHeader, txdata: &TransactionData<'_>, height: u32) {
+        let mut confirmed = self.confirmed.lock();
+        for (_, tx) in txdata.iter() {
+            confirmed.push((tx.txid(), height));
+        }
+    }
+
+    fn transaction_unconfirmed(&self, txid: &Txid) {
+        self.confirmed
+            .lock()
+            .retain(|(confirmed, _)| confirmed != txid);
+    }
+
+    fn best_block_updated(&self, _: &BlockHeader, height: u32) {
+        *self.best_height.lock() = Some(height);
+    }
+
+    fn get_relevant_txids(&self) -> Vec<(Txid, Option<BlockHash>)> {
+        Vec::new()
+    }
+}
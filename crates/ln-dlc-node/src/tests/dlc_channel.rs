@@ -3,15 +3,22 @@ use crate::node::Node;
 use crate::storage::TenTenOneInMemoryStorage;
 use crate::tests::bitcoind::mine;
 use crate::tests::dummy_contract_input;
+use crate::tests::dummy_contract_input_with_maturity;
 use crate::tests::init_tracing;
+use crate::tests::mock_oracle_info;
 use crate::tests::wait_until;
 use bitcoin::Amount;
 use dlc_manager::channel::signed_channel::SignedChannel;
 use dlc_manager::channel::signed_channel::SignedChannelStateType;
+use dlc_manager::contract::contract_input::ContractInput;
 use dlc_manager::contract::Contract;
 use dlc_manager::Storage;
+use mock_oracle::MockOracle;
+use mock_oracle::MockOracleServer;
 use std::sync::Arc;
 use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::task::block_in_place;
 
 #[tokio::test(flavor = "multi_thread")]
 #[ignore]
@@ -20,69 +27,24 @@ async fn can_open_and_settle_offchain() {
 
     // Arrange
 
+    let oracle = MockOracleServer::start(MockOracle::new()).unwrap();
+
     let (app, coordinator, coordinator_signed_channel, app_signed_channel) =
-        setup_channel_with_position().await;
+        setup_channel_with_position(&oracle).await;
 
     // Act
 
     let oracle_pk = *coordinator.oracle_pk().first().unwrap();
     let contract_input = dummy_contract_input(15_000, 5_000, oracle_pk);
 
-    coordinator
-        .propose_dlc_channel_update(&coordinator_signed_channel.channel_id, contract_input)
-        .await
-        .unwrap();
-
-    wait_until(Duration::from_secs(10), || async {
-        app.process_incoming_messages()?;
-
-        let dlc_channels = app
-            .dlc_manager
-            .get_store()
-            .get_signed_channels(Some(SignedChannelStateType::RenewOffered))?;
-
-        Ok(dlc_channels
-            .iter()
-            .find(|dlc_channel| dlc_channel.counter_party == coordinator.info.pubkey)
-            .cloned())
-    })
-    .await
-    .unwrap();
-
-    app.accept_dlc_channel_update(&app_signed_channel.channel_id)
-        .unwrap();
-
-    wait_until(Duration::from_secs(10), || async {
-        coordinator.process_incoming_messages()?;
-
-        let dlc_channels = coordinator
-            .dlc_manager
-            .get_store()
-            .get_signed_channels(Some(SignedChannelStateType::RenewConfirmed))?;
-
-        Ok(dlc_channels
-            .iter()
-            .find(|dlc_channel| dlc_channel.counter_party == app.info.pubkey)
-            .cloned())
-    })
-    .await
-    .unwrap();
-
-    wait_until(Duration::from_secs(10), || async {
-        app.process_incoming_messages()?;
-
-        let dlc_channels = app
-            .dlc_manager
-            .get_store()
-            .get_signed_channels(Some(SignedChannelStateType::RenewFinalized))?;
-
-        Ok(dlc_channels
-            .iter()
-            .find(|dlc_channel| dlc_channel.counter_party == coordinator.info.pubkey)
-            .cloned())
-    })
-    .await
-    .unwrap();
+    renew_dlc_channel(
+        &app,
+        &coordinator,
+        &coordinator_signed_channel,
+        &app_signed_channel,
+        contract_input,
+    )
+    .await;
 
     // Assert
 
@@ -125,8 +87,10 @@ async fn can_open_and_collaboratively_close_channel() {
     init_tracing();
 
     // Arrange
+    let oracle = MockOracleServer::start(MockOracle::new()).unwrap();
+
     let (app, coordinator, coordinator_signed_channel, app_signed_channel) =
-        setup_channel_with_position().await;
+        setup_channel_with_position(&oracle).await;
 
     let app_on_chain_balance_before_close = app.get_on_chain_balance().unwrap();
     let coordinator_on_chain_balance_before_close = coordinator.get_on_chain_balance().unwrap();
@@ -209,7 +173,10 @@ async fn can_open_and_force_close_channel() {
     init_tracing();
 
     // Arrange
-    let (app, coordinator, coordinator_signed_channel, _) = setup_channel_with_position().await;
+    let oracle = MockOracleServer::start(MockOracle::new()).unwrap();
+
+    let (app, coordinator, coordinator_signed_channel, _) =
+        setup_channel_with_position(&oracle).await;
 
     tracing::debug!("Force closing dlc channel");
 
@@ -244,12 +211,101 @@ async fn can_open_and_force_close_channel() {
     })
     .await
     .unwrap();
+}
 
-    // TODO: we could also test that the DLCs are being spent, but for that we would need a TARDIS
-    // or similar
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn can_open_and_settle_with_attestation_after_force_close() {
+    init_tracing();
+
+    // Arrange
+
+    let oracle = MockOracleServer::start(MockOracle::new()).unwrap();
+
+    let (app, coordinator, coordinator_signed_channel, app_signed_channel) =
+        setup_channel_with_position(&oracle).await;
+
+    // The mock oracle attests on demand, so the contract can mature right away.
+    let maturity_time = OffsetDateTime::now_utc() + time::Duration::seconds(30);
+
+    let oracle_pk = *coordinator.oracle_pk().first().unwrap();
+    let contract_input =
+        dummy_contract_input_with_maturity(15_000, 5_000, oracle_pk, maturity_time);
+    let event_id = contract_input.contract_infos[0].oracles.event_id.clone();
+
+    renew_dlc_channel(
+        &app,
+        &coordinator,
+        &coordinator_signed_channel,
+        &app_signed_channel,
+        contract_input,
+    )
+    .await;
+
+    let coordinator_signed_channel = wait_until(Duration::from_secs(10), || async {
+        coordinator.process_incoming_messages()?;
+
+        let dlc_channels = coordinator
+            .dlc_manager
+            .get_store()
+            .get_signed_channels(Some(SignedChannelStateType::Established))?;
+
+        Ok(dlc_channels
+            .iter()
+            .find(|dlc_channel| dlc_channel.counter_party == app.info.pubkey)
+            .cloned())
+    })
+    .await
+    .unwrap();
+
+    let contract_id = coordinator_signed_channel.get_contract_id().unwrap();
+
+    // Act
+
+    coordinator
+        .close_dlc_channel(coordinator_signed_channel.channel_id, true)
+        .await
+        .unwrap();
+
+    let until_maturity = maturity_time - OffsetDateTime::now_utc();
+    if until_maturity.is_positive() {
+        tokio::time::sleep(until_maturity.unsigned_abs()).await;
+    }
+
+    oracle.oracle().attest(&event_id, 55_000).unwrap();
+
+    // Assert
+
+    // The CETs can only be published once the buffer transaction is buried deep enough.
+    wait_until(Duration::from_secs(120), || async {
+        mine(10).await.unwrap();
+        coordinator.sync_wallets().await?;
+
+        block_in_place(|| {
+            coordinator.dlc_manager.periodic_chain_monitor()?;
+            coordinator.dlc_manager.periodic_check()?;
+
+            anyhow::Ok(())
+        })?;
+
+        let contract = coordinator
+            .dlc_manager
+            .get_store()
+            .get_contract(&contract_id)?;
+
+        Ok(matches!(
+            contract,
+            Some(Contract::PreClosed(_)) | Some(Contract::Closed(_))
+        )
+        .then_some(()))
+    })
+    .await
+    .unwrap();
 }
 
-async fn setup_channel_with_position() -> (
+async fn setup_channel_with_position(
+    oracle: &MockOracleServer,
+) -> (
     Arc<Node<TenTenOneInMemoryStorage, InMemoryStore>>,
     Arc<Node<TenTenOneInMemoryStorage, InMemoryStore>>,
     SignedChannel,
@@ -258,8 +314,9 @@ async fn setup_channel_with_position() -> (
     let app_dlc_collateral = 10_000;
     let coordinator_dlc_collateral = 10_000;
 
-    let (app, _running_app) = Node::start_test_app("app").unwrap();
-    let (coordinator, _running_coord) = Node::start_test_coordinator("coordinator").unwrap();
+    let (app, _running_app) = Node::start_test_app("app", mock_oracle_info(oracle)).unwrap();
+    let (coordinator, _running_coord) =
+        Node::start_test_coordinator("coordinator", mock_oracle_info(oracle)).unwrap();
 
     app.connect(coordinator.info).await.unwrap();
 
@@ -491,3 +548,69 @@ async fn setup_channel_with_position() -> (
         app_signed_channel,
     )
 }
+
+/// Proposes a new contract in the established DLC channel and waits until both parties have
+/// finalised the renewal.
+async fn renew_dlc_channel(
+    app: &Node<TenTenOneInMemoryStorage, InMemoryStore>,
+    coordinator: &Node<TenTenOneInMemoryStorage, InMemoryStore>,
+    coordinator_signed_channel: &SignedChannel,
+    app_signed_channel: &SignedChannel,
+    contract_input: ContractInput,
+) {
+    coordinator
+        .propose_dlc_channel_update(&coordinator_signed_channel.channel_id, contract_input)
+        .await
+        .unwrap();
+
+    wait_until(Duration::from_secs(10), || async {
+        app.process_incoming_messages()?;
+
+        let dlc_channels = app
+            .dlc_manager
+            .get_store()
+            .get_signed_channels(Some(SignedChannelStateType::RenewOffered))?;
+
+        Ok(dlc_channels
+            .iter()
+            .find(|dlc_channel| dlc_channel.counter_party == coordinator.info.pubkey)
+            .cloned())
+    })
+    .await
+    .unwrap();
+
+    app.accept_dlc_channel_update(&app_signed_channel.channel_id)
+        .unwrap();
+
+    wait_until(Duration::from_secs(10), || async {
+        coordinator.process_incoming_messages()?;
+
+        let dlc_channels = coordinator
+            .dlc_manager
+            .get_store()
+            .get_signed_channels(Some(SignedChannelStateType::RenewConfirmed))?;
+
+        Ok(dlc_channels
+            .iter()
+            .find(|dlc_channel| dlc_channel.counter_party == app.info.pubkey)
+            .cloned())
+    })
+    .await
+    .unwrap();
+
+    wait_until(Duration::from_secs(10), || async {
+        app.process_incoming_messages()?;
+
+        let dlc_channels = app
+            .dlc_manager
+            .get_store()
+            .get_signed_channels(Some(SignedChannelStateType::RenewFinalized))?;
+
+        Ok(dlc_channels
+            .iter()
+            .find(|dlc_channel| dlc_channel.counter_party == coordinator.info.pubkey)
+            .cloned())
+    })
+    .await
+    .unwrap();
+}
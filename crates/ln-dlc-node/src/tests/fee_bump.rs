@@ -0,0 +1,222 @@
+use crate::node::Fee;
+use crate::node::InMemoryStore;
+use crate::node::Node;
+use crate::storage::TenTenOneInMemoryStorage;
+use crate::tests::bitcoind;
+use crate::tests::init_tracing;
+use crate::tests::oracle_info;
+use crate::tests::random_address;
+use crate::tests::wait_until;
+use anyhow::Result;
+use bdk::FeeRate;
+use bdk::TransactionDetails;
+use bitcoin::Amount;
+use bitcoin::Txid;
+use std::time::Duration;
+use tokio::task::block_in_place;
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn can_replace_own_transaction_by_fee() {
+    init_tracing();
+
+    let (node, _running) = Node::start_test_app("app", oracle_info().unwrap()).unwrap();
+    node.fund(Amount::from_btc(0.1).unwrap()).await.unwrap();
+
+    let txid = block_in_place(|| {
+        node.send_to_address(
+            &random_address(),
+            50_000,
+            Fee::FeeRate(FeeRate::from_sat_per_vb(1.0)),
+            &[],
+        )
+    })
+    .unwrap();
+    let original = wait_for_transaction(&node, &txid).await.unwrap();
+
+    let replacement =
+        block_in_place(|| node.replace_by_fee(&txid, Fee::FeeRate(FeeRate::from_sat_per_vb(10.0))))
+            .unwrap();
+    assert_ne!(replacement, txid);
+
+    let replacement = wait_for_transaction(&node, &replacement).await.unwrap();
+    assert!(replacement.fee.unwrap() > original.fee.unwrap());
+
+    bitcoind::mine(1).await.unwrap();
+
+    wait_for_confirmation(&node, &replacement.txid)
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn can_bump_fee_of_incoming_transaction_with_child() {
+    init_tracing();
+
+    let (node, _running) = Node::start_test_app("app", oracle_info().unwrap()).unwrap();
+
+    // `bitcoind` pays 1 sat/vB for the incoming transaction.
+    let address = node.wallet.unused_address();
+    let parent = bitcoind::send_to_address(&address, Amount::from_sat(100_000))
+        .await
+        .unwrap();
+    wait_for_transaction(&node, &parent).await.unwrap();
+
+    let child = block_in_place(|| {
+        node.child_pays_for_parent(&parent, Fee::FeeRate(FeeRate::from_sat_per_vb(20.0)))
+    })
+    .unwrap();
+
+    let child_details = wait_for_transaction(&node, &child).await.unwrap();
+    let child_tx = block_in_place(|| node.wallet.get_transaction(&child)).unwrap();
+    assert!(child_tx
+        .input
+        .iter()
+        .all(|input| input.previous_output.txid == parent));
+
+    // The child pays more than the target fee rate, as it pays for its parent too.
+    let child_fee_rate = child_details.fee.unwrap() as f32 / child_tx.vsize() as f32;
+    assert!(child_fee_rate > 20.0);
+
+    bitcoind::mine(1).await.unwrap();
+
+    wait_for_confirmation(&node, &parent).await.unwrap();
+    wait_for_confirmation(&node, &child).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn child_is_rejected_if_parent_already_pays_enough() {
+    init_tracing();
+
+    let (node, _running) = Node::start_test_app("app", oracle_info().unwrap()).unwrap();
+    node.fund(Amount::from_btc(0.1).unwrap()).await.unwrap();
+
+    let parent = block_in_place(|| {
+        node.send_to_address(
+            &random_address(),
+            50_000,
+            Fee::FeeRate(FeeRate::from_sat_per_vb(50.0)),
+            &[],
+        )
+    })
+    .unwrap();
+    wait_for_transaction(&node, &parent).await.unwrap();
+
+    let error = block_in_place(|| {
+        node.child_pays_for_parent(&parent, Fee::FeeRate(FeeRate::from_sat_per_vb(2.0)))
+    })
+    .unwrap_err();
+
+    assert!(
+        error.to_string().contains("already pays"),
+        "Unexpected error: {error:#}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn replacement_does_not_spend_frozen_utxos() {
+    init_tracing();
+
+    let (node, _running) = Node::start_test_app("app", oracle_info().unwrap()).unwrap();
+    node.fund(Amount::from_btc(0.1).unwrap()).await.unwrap();
+
+    // Freeze all but two of the ten UTXOs of 0.01 BTC.
+    let utxos = node
+        .wallet
+        .ldk_wallet()
+        .get_utxos()
+        .unwrap()
+        .into_iter()
+        .map(|utxo| utxo.outpoint)
+        .collect::<Vec<_>>();
+    let (unfrozen, frozen) = utxos.split_at(2);
+    for outpoint in frozen {
+        node.wallet.set_utxo_frozen(*outpoint, true).unwrap();
+    }
+
+    // The change of the original transaction is too small to pay for the replacement, which
+    // therefore has to spend another UTXO.
+    let txid = block_in_place(|| {
+        node.send_to_address(
+            &random_address(),
+            990_000,
+            Fee::FeeRate(FeeRate::from_sat_per_vb(1.0)),
+            &[],
+        )
+    })
+    .unwrap();
+    wait_for_transaction(&node, &txid).await.unwrap();
+
+    let replacement = block_in_place(|| {
+        node.replace_by_fee(&txid, Fee::FeeRate(FeeRate::from_sat_per_vb(100.0)))
+    })
+    .unwrap();
+
+    wait_for_transaction(&node, &replacement).await.unwrap();
+    let replacement = block_in_place(|| node.wallet.get_transaction(&replacement)).unwrap();
+    assert!(replacement
+        .input
+        .iter()
+        .all(|input| unfrozen.contains(&input.previous_output)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn child_does_not_spend_frozen_outputs() {
+    init_tracing();
+
+    let (node, _running) = Node::start_test_app("app", oracle_info().unwrap()).unwrap();
+
+    let address = node.wallet.unused_address();
+    let parent = bitcoind::send_to_address(&address, Amount::from_sat(100_000))
+        .await
+        .unwrap();
+    wait_for_transaction(&node, &parent).await.unwrap();
+
+    let output = node
+        .wallet
+        .ldk_wallet()
+        .get_utxos()
+        .unwrap()
+        .into_iter()
+        .find(|utxo| utxo.outpoint.txid == parent)
+        .expect("output of incoming transaction")
+        .outpoint;
+    node.wallet.set_utxo_frozen(output, true).unwrap();
+
+    let result = block_in_place(|| {
+        node.child_pays_for_parent(&parent, Fee::FeeRate(FeeRate::from_sat_per_vb(20.0)))
+    });
+    assert!(result.is_err());
+}
+
+/// Sync the wallet of the `node` until it knows the transaction `txid`.
+async fn wait_for_transaction(
+    node: &Node<TenTenOneInMemoryStorage, InMemoryStore>,
+    txid: &Txid,
+) -> Result<TransactionDetails> {
+    wait_until(Duration::from_secs(30), || async {
+        node.sync_wallets().await?;
+
+        node.wallet.ldk_wallet().get_transaction(txid)
+    })
+    .await
+}
+
+/// Sync the wallet of the `node` until the transaction `txid` is confirmed.
+async fn wait_for_confirmation(
+    node: &Node<TenTenOneInMemoryStorage, InMemoryStore>,
+    txid: &Txid,
+) -> Result<TransactionDetails> {
+    wait_until(Duration::from_secs(30), || async {
+        node.sync_wallets().await?;
+
+        let transaction = node.wallet.ldk_wallet().get_transaction(txid)?;
+
+        Ok(transaction.filter(|transaction| transaction.confirmation_time.is_some()))
+    })
+    .await
+}
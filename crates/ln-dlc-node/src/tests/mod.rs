@@ -4,6 +4,7 @@ use crate::node::dlc_channel::send_dlc_message;
 use crate::node::event::NodeEvent;
 use crate::node::event::NodeEventHandler;
 use crate::node::peer_manager::alias_as_bytes;
+use crate::node::ChainSourceConfig;
 use crate::node::GossipSourceConfig;
 use crate::node::InMemoryStore;
 use crate::node::LnDlcNodeSettings;
@@ -19,8 +20,12 @@ use crate::AppEventHandler;
 use crate::CoordinatorEventHandler;
 use crate::EventHandlerTrait;
 use crate::EventSender;
+use crate::FeeRateEstimatorConfig;
 use crate::WalletSettings;
 use anyhow::Result;
+use bitcoin::secp256k1::Secp256k1;
+use bitcoin::secp256k1::SecretKey;
+use bitcoin::Address;
 use bitcoin::Amount;
 use bitcoin::Network;
 use bitcoin::XOnlyPublicKey;
@@ -39,6 +44,7 @@ use dlc_manager::subchannel::SubChannelState;
 use futures::Future;
 use lightning::events::Event;
 use lightning::util::config::UserConfig;
+use mock_oracle::MockOracleServer;
 use rand::distributions::Alphanumeric;
 use rand::thread_rng;
 use rand::Rng;
@@ -56,11 +62,18 @@ use tokio::sync::watch;
 use tokio::task::block_in_place;
 
 mod bitcoind;
+mod chain_source;
 mod dlc_channel;
+mod fee_bump;
+mod psbt;
 
 const ESPLORA_ORIGIN: &str = "http://localhost:3000";
 const FAUCET_ORIGIN: &str = "http://localhost:8080";
 const ORACLE_ORIGIN: &str = "http://localhost:8081";
+const BITCOIND_RPC_ORIGIN: &str = "http://localhost:18443";
+const BITCOIND_RPC_USERNAME: &str = "admin1";
+const BITCOIND_RPC_PASSWORD: &str = "123";
+const ELECTRUM_ORIGIN: &str = "tcp://localhost:50000";
 const ORACLE_PUBKEY: &str = "16f88cf7d21e6c0f46bcbc983a4e3b19726c6c98858cc31c83551a88fde171c0";
 
 fn init_tracing() {
@@ -86,7 +99,15 @@ fn init_tracing() {
 }
 
 impl Node<TenTenOneInMemoryStorage, InMemoryStore> {
-    fn start_test_app(name: &str) -> Result<(Arc<Self>, RunningNode)> {
+    fn start_test_app(name: &str, oracle: OracleInfo) -> Result<(Arc<Self>, RunningNode)> {
+        Self::start_test_app_internal(name, ln_dlc_node_settings_app(), oracle)
+    }
+
+    fn start_test_app_internal(
+        name: &str,
+        settings: LnDlcNodeSettings,
+        oracle: OracleInfo,
+    ) -> Result<(Arc<Self>, RunningNode)> {
         let app_event_handler = |node, event_sender| {
             Arc::new(AppEventHandler::new(node, event_sender)) as Arc<dyn EventHandlerTrait>
         };
@@ -96,21 +117,19 @@ impl Node<TenTenOneInMemoryStorage, InMemoryStore> {
             name,
             app_config(),
             ESPLORA_ORIGIN.to_string(),
-            OracleInfo {
-                endpoint: ORACLE_ORIGIN.to_string(),
-                public_key: XOnlyPublicKey::from_str(ORACLE_PUBKEY)?,
-            },
+            oracle,
             Arc::new(InMemoryStore::default()),
-            ln_dlc_node_settings_app(),
+            settings,
             None,
         )
     }
 
-    fn start_test_coordinator(name: &str) -> Result<(Arc<Self>, RunningNode)> {
+    fn start_test_coordinator(name: &str, oracle: OracleInfo) -> Result<(Arc<Self>, RunningNode)> {
         Self::start_test_coordinator_internal(
             name,
             Arc::new(InMemoryStore::default()),
             ln_dlc_node_settings_coordinator(),
+            oracle,
             None,
         )
     }
@@ -119,6 +138,7 @@ impl Node<TenTenOneInMemoryStorage, InMemoryStore> {
         name: &str,
         storage: Arc<InMemoryStore>,
         settings: LnDlcNodeSettings,
+        oracle: OracleInfo,
         ldk_event_sender: Option<watch::Sender<Option<Event>>>,
     ) -> Result<(Arc<Self>, RunningNode)> {
         let coordinator_event_handler = |node, event_sender| {
@@ -130,10 +150,7 @@ impl Node<TenTenOneInMemoryStorage, InMemoryStore> {
             name,
             coordinator_config(),
             ESPLORA_ORIGIN.to_string(),
-            OracleInfo {
-                endpoint: ORACLE_ORIGIN.to_string(),
-                public_key: XOnlyPublicKey::from_str(ORACLE_PUBKEY)?,
-            },
+            oracle,
             storage,
             settings,
             ldk_event_sender,
@@ -170,6 +187,7 @@ impl Node<TenTenOneInMemoryStorage, InMemoryStore> {
         };
 
         let storage = TenTenOneInMemoryStorage::new();
+        let oracle_pubkey = oracle.public_key;
 
         let event_handler = Arc::new(NodeEventHandler::new());
         let node = Node::new(
@@ -189,7 +207,7 @@ impl Node<TenTenOneInMemoryStorage, InMemoryStore> {
             settings,
             WalletSettings::default(),
             vec![oracle.into()],
-            XOnlyPublicKey::from_str(ORACLE_PUBKEY)?,
+            oracle_pubkey,
             event_handler.clone(),
         )?;
         let node = Arc::new(node);
@@ -303,6 +321,22 @@ impl Node<TenTenOneInMemoryStorage, InMemoryStore> {
     }
 }
 
+/// The oracle of the local test environment.
+fn oracle_info() -> Result<OracleInfo> {
+    Ok(OracleInfo {
+        endpoint: ORACLE_ORIGIN.to_string(),
+        public_key: XOnlyPublicKey::from_str(ORACLE_PUBKEY)?,
+    })
+}
+
+/// A [`MockOracleServer`] in the format expected by the nodes.
+fn mock_oracle_info(server: &MockOracleServer) -> OracleInfo {
+    OracleInfo {
+        endpoint: server.endpoint(),
+        public_key: server.oracle().public_key(),
+    }
+}
+
 fn random_tmp_dir() -> PathBuf {
     let tmp = if let Ok(tmp) = std::env::var("RUNNER_TEMP") {
         tracing::debug!("Running test on github actions - using temporary directory at {tmp}");
@@ -327,6 +361,13 @@ fn random_tmp_dir() -> PathBuf {
     tmp
 }
 
+fn random_address() -> Address {
+    let secret_key = SecretKey::from_slice(&thread_rng().gen::<[u8; 32]>()).unwrap();
+    let public_key = bitcoin::PublicKey::new(secret_key.public_key(&Secp256k1::new()));
+
+    Address::p2wpkh(&public_key, Network::Regtest).unwrap()
+}
+
 #[allow(dead_code)]
 fn log_channel_id(node: &Node<TenTenOneInMemoryStorage, InMemoryStore>, index: usize, pair: &str) {
     let details = match node.channel_manager.list_channels().get(index) {
@@ -422,6 +463,8 @@ fn ln_dlc_node_settings_coordinator() -> LnDlcNodeSettings {
         bdk_client_stop_gap: 20,
         bdk_client_concurrency: 4,
         gossip_source_config: GossipSourceConfig::P2pNetwork,
+        chain_source_config: ChainSourceConfig::Esplora,
+        fee_rate_estimator_config: FeeRateEstimatorConfig::default(),
     }
 }
 
@@ -437,6 +480,8 @@ fn ln_dlc_node_settings_app() -> LnDlcNodeSettings {
         bdk_client_stop_gap: 20,
         bdk_client_concurrency: 4,
         gossip_source_config: GossipSourceConfig::P2pNetwork,
+        chain_source_config: ChainSourceConfig::Esplora,
+        fee_rate_estimator_config: FeeRateEstimatorConfig::default(),
     }
 }
 
@@ -444,13 +489,28 @@ fn dummy_contract_input(
     offer_collateral: u64,
     accept_collateral: u64,
     oracle_pk: XOnlyPublicKey,
+) -> ContractInput {
+    let maturity_time = OffsetDateTime::now_utc() + time::Duration::days(7);
+
+    dummy_contract_input_with_maturity(
+        offer_collateral,
+        accept_collateral,
+        oracle_pk,
+        maturity_time,
+    )
+}
+
+fn dummy_contract_input_with_maturity(
+    offer_collateral: u64,
+    accept_collateral: u64,
+    oracle_pk: XOnlyPublicKey,
+    maturity_time: OffsetDateTime,
 ) -> ContractInput {
     let total_collateral = offer_collateral + accept_collateral;
 
     let n_cets = 100;
     let rounding_mod = total_collateral / (n_cets + 1);
 
-    let maturity_time = OffsetDateTime::now_utc() + time::Duration::days(7);
     let maturity_time = maturity_time.unix_timestamp() as u64;
 
     ContractInput {
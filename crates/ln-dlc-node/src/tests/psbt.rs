@@ -0,0 +1,113 @@
+use crate::node::Fee;
+use crate::node::Node;
+use crate::node::PsbtWallet;
+use crate::on_chain_wallet::OnChainWallet;
+use crate::tests::bitcoind;
+use crate::tests::init_tracing;
+use crate::tests::oracle_info;
+use crate::tests::random_address;
+use crate::tests::random_tmp_dir;
+use crate::tests::wait_until;
+use bdk::database::MemoryDatabase;
+use bdk::FeeRate;
+use bdk::SignOptions;
+use bitcoin::secp256k1::Secp256k1;
+use bitcoin::util::bip32::ExtendedPrivKey;
+use bitcoin::util::bip32::ExtendedPubKey;
+use bitcoin::Amount;
+use bitcoin::Network;
+use rand::thread_rng;
+use rand::Rng;
+use std::time::Duration;
+use tokio::task::block_in_place;
+
+#[test]
+fn watch_only_wallet_rejects_descriptor_with_private_keys() {
+    let key = random_extended_private_key();
+
+    let result = OnChainWallet::watch_only(
+        &random_tmp_dir(),
+        Network::Regtest,
+        &format!("wpkh({key}/0/*)"),
+        None,
+    );
+
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn can_spend_from_watch_only_wallet_with_externally_signed_psbt() {
+    init_tracing();
+
+    let (node, _running) =
+        Node::start_test_coordinator("coordinator", oracle_info().unwrap()).unwrap();
+
+    let key = random_extended_private_key();
+    let xpub = ExtendedPubKey::from_priv(&Secp256k1::new(), &key);
+
+    node.load_watch_only_wallet(
+        &random_tmp_dir(),
+        &format!("wpkh({xpub}/0/*)"),
+        Some(&format!("wpkh({xpub}/1/*)")),
+    )
+    .await
+    .unwrap();
+
+    // The private keys are kept in an external signer.
+    let signer = bdk::Wallet::new(
+        &format!("wpkh({key}/0/*)"),
+        Some(&format!("wpkh({key}/1/*)")),
+        Network::Regtest,
+        MemoryDatabase::new(),
+    )
+    .unwrap();
+
+    let address = node.get_watch_only_address().unwrap();
+    bitcoind::fund(address.to_string(), Amount::from_sat(100_000))
+        .await
+        .unwrap();
+    bitcoind::mine(1).await.unwrap();
+
+    wait_until(Duration::from_secs(30), || async {
+        node.sync_wallets().await?;
+
+        let balance = node.get_watch_only_balance()?;
+        Ok((balance.confirmed == 100_000).then_some(()))
+    })
+    .await
+    .unwrap();
+
+    let fee = || Fee::FeeRate(FeeRate::from_sat_per_vb(1.0));
+    let mut psbt = node
+        .create_unsigned_psbt(PsbtWallet::WatchOnly, &random_address(), 50_000, fee())
+        .unwrap();
+
+    // The only UTXO of the watch-only wallet remains reserved for the PSBT, even after a sync.
+    node.sync_wallets().await.unwrap();
+    assert!(node
+        .create_unsigned_psbt(PsbtWallet::WatchOnly, &random_address(), 10_000, fee())
+        .is_err());
+
+    assert!(signer.sign(&mut psbt, SignOptions::default()).unwrap());
+
+    block_in_place(|| node.broadcast_signed_psbt(psbt)).unwrap();
+    bitcoind::mine(1).await.unwrap();
+
+    let balance = wait_until(Duration::from_secs(30), || async {
+        node.sync_wallets().await?;
+
+        let balance = node.get_watch_only_balance()?;
+        Ok((balance.confirmed < 100_000).then_some(balance))
+    })
+    .await
+    .unwrap();
+
+    // What is left is the change, minus the fee.
+    assert!(balance.confirmed > 0);
+    assert!(balance.confirmed < 50_000);
+}
+
+fn random_extended_private_key() -> ExtendedPrivKey {
+    ExtendedPrivKey::new_master(Network::Regtest, &thread_rng().gen::<[u8; 32]>()).unwrap()
+}
@@ -13,6 +13,8 @@ pub struct Transaction {
     created_at: OffsetDateTime,
     updated_at: OffsetDateTime,
     raw: String,
+    /// The transaction which replaced this one by paying a higher fee (RBF).
+    replaced_by: Option<Txid>,
 }
 
 impl Transaction {
@@ -22,6 +24,7 @@ impl Transaction {
         created_at: OffsetDateTime,
         updated_at: OffsetDateTime,
         raw: String,
+        replaced_by: Option<Txid>,
     ) -> Self {
         Self {
             txid,
@@ -29,6 +32,7 @@ impl Transaction {
             created_at,
             updated_at,
             raw,
+            replaced_by,
         }
     }
 
@@ -59,21 +63,33 @@ impl Transaction {
     pub fn raw(&self) -> String {
         self.raw.clone()
     }
+
+    pub fn replaced_by(&self) -> Option<Txid> {
+        self.replaced_by
+    }
+
+    pub fn with_replaced_by(self, replaced_by: Txid) -> Self {
+        Self {
+            replaced_by: Some(replaced_by),
+            updated_at: OffsetDateTime::now_utc(),
+            ..self
+        }
+    }
 }
 
 impl From<&bitcoin::Transaction> for Transaction {
     fn from(value: &bitcoin::Transaction) -> Self {
         let now = OffsetDateTime::now_utc();
 
-        Self::new(value.txid(), 0, now, now, value.serialize().to_hex())
+        Self::new(value.txid(), 0, now, now, value.serialize().to_hex(), None)
     }
 }
 
 impl Display for Transaction {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         format!(
-            "txid: {}, fees: {}, created_at: {}, updated_at: {}",
-            self.txid, self.fee, self.created_at, self.updated_at
+            "txid: {}, fees: {}, created_at: {}, updated_at: {}, replaced_by: {:?}",
+            self.txid, self.fee, self.created_at, self.updated_at, self.replaced_by
         )
         .fmt(f)
     }
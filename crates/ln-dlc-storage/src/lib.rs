@@ -1,5 +1,7 @@
 use anyhow::Result;
+use bitcoin::hashes::Hash;
 use bitcoin::Address;
+use bitcoin::OutPoint;
 use bitcoin::Txid;
 use dlc_manager::chain_monitor::ChainMonitor;
 use dlc_manager::channel::accepted_channel::AcceptedChannel;
@@ -51,6 +53,7 @@ const KEY_PAIR: u8 = 6;
 const SUB_CHANNEL: u8 = 7;
 const ADDRESS: u8 = 8;
 const ACTION: u8 = 9;
+const UTXO_METADATA: u8 = 10;
 
 const CHAIN_MONITOR_KEY: &str = "chain_monitor";
 
@@ -66,6 +69,18 @@ pub trait WalletStorage {
     fn delete_utxo(&self, utxo: &Utxo) -> Result<()>;
     fn get_utxos(&self) -> Result<Vec<Utxo>>;
     fn unreserve_utxo(&self, txid: &Txid, vout: u32) -> Result<()>;
+    /// Store the coin control metadata of a UTXO of the on-chain wallet. Storing the default
+    /// metadata deletes it.
+    fn upsert_utxo_metadata(&self, outpoint: &OutPoint, metadata: &UtxoMetadata) -> Result<()>;
+    fn get_utxo_metadata(&self) -> Result<Vec<(OutPoint, UtxoMetadata)>>;
+}
+
+/// Coin control metadata of a UTXO of the on-chain wallet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UtxoMetadata {
+    pub label: Option<String>,
+    /// Frozen UTXOs are only spent if they are selected explicitly.
+    pub frozen: bool,
 }
 
 pub struct KeyValue {
@@ -697,6 +712,30 @@ impl<K: DlcStoreProvider> WalletStorage for DlcStorageProvider<K> {
 
         self.store.write(UTXO, key, buf)
     }
+
+    fn upsert_utxo_metadata(&self, outpoint: &OutPoint, metadata: &UtxoMetadata) -> Result<()> {
+        let key = get_utxo_key(&outpoint.txid, outpoint.vout);
+
+        if *metadata == UtxoMetadata::default() {
+            return self.store.delete(UTXO_METADATA, Some(key));
+        }
+
+        self.store
+            .write(UTXO_METADATA, key, serialize_utxo_metadata(metadata))
+    }
+
+    fn get_utxo_metadata(&self) -> Result<Vec<(OutPoint, UtxoMetadata)>> {
+        self.store
+            .read(UTXO_METADATA, None)?
+            .into_iter()
+            .map(|x| {
+                let outpoint = parse_utxo_key(&x.key)?;
+                let metadata = deserialize_utxo_metadata(&x.value)?;
+
+                Ok((outpoint, metadata))
+            })
+            .collect()
+    }
 }
 
 fn serialize_contract(contract: &Contract) -> Result<Vec<u8>, Error> {
@@ -832,6 +871,46 @@ fn get_utxo_key(txid: &Txid, vout: u32) -> Vec<u8> {
     key
 }
 
+fn parse_utxo_key(key: &[u8]) -> Result<OutPoint> {
+    if key.len() != 36 {
+        return Err(Error::InvalidState(format!(
+            "Invalid UTXO key of length {}",
+            key.len()
+        )))?;
+    }
+
+    let txid = Txid::from_slice(&key[..32])?;
+    let vout = u32::from_be_bytes(key[32..].try_into().expect("4 bytes"));
+
+    Ok(OutPoint { txid, vout })
+}
+
+/// The frozen flag as the first byte, followed by the label (if any) in UTF-8.
+fn serialize_utxo_metadata(metadata: &UtxoMetadata) -> Vec<u8> {
+    let mut buf = vec![metadata.frozen as u8];
+    if let Some(label) = &metadata.label {
+        buf.extend_from_slice(label.as_bytes());
+    }
+
+    buf
+}
+
+fn deserialize_utxo_metadata(buf: &[u8]) -> Result<UtxoMetadata> {
+    let (frozen, label) = buf
+        .split_first()
+        .ok_or_else(|| Error::InvalidState("Empty UTXO metadata".to_string()))?;
+
+    let label = match label.is_empty() {
+        true => None,
+        false => Some(String::from_utf8(label.to_vec())?),
+    };
+
+    Ok(UtxoMetadata {
+        label,
+        frozen: *frozen != 0,
+    })
+}
+
 fn serialize_sub_channel(sub_channel: &SubChannel) -> Result<Vec<u8>, ::std::io::Error> {
     let prefix = SubChannelPrefix::get_prefix(&sub_channel.state);
     let mut buf = Vec::new();
@@ -1238,4 +1317,31 @@ mod tests {
             .expect("Error getting sub channel actions");
         assert_eq!(actions.len(), 0);
     }
+
+    #[test]
+    fn utxo_metadata_round_trip() {
+        let storage = DlcStorageProvider::new(InMemoryDlcStoreProvider::new());
+        let outpoint = OutPoint {
+            txid: "44fe3d70a3058eb1bef62e24379b4865ada8332f9ee30752cf606f37343461a0"
+                .parse()
+                .unwrap(),
+            vout: 1,
+        };
+        let metadata = UtxoMetadata {
+            label: Some("cold storage".to_string()),
+            frozen: true,
+        };
+
+        storage.upsert_utxo_metadata(&outpoint, &metadata).unwrap();
+        assert_eq!(
+            storage.get_utxo_metadata().unwrap(),
+            vec![(outpoint, metadata)]
+        );
+
+        // Resetting the metadata removes it.
+        storage
+            .upsert_utxo_metadata(&outpoint, &UtxoMetadata::default())
+            .unwrap();
+        assert!(storage.get_utxo_metadata().unwrap().is_empty());
+    }
 }
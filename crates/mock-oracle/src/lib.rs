@@ -0,0 +1,305 @@
+//! An in-process oracle implementing the HTTP API of the P2PD oracle.
+//!
+//! The oracle announces price events for any contract symbol at arbitrary maturities as soon as
+//! they are requested and attests to them on demand at a price chosen by the test. This allows
+//! tests to drive contracts from opening to settlement without depending on a running oracle.
+
+use anyhow::bail;
+use anyhow::Context;
+use anyhow::Result;
+use bitcoin::hashes::sha256;
+use bitcoin::secp256k1::schnorr::Signature;
+use bitcoin::secp256k1::All;
+use bitcoin::secp256k1::KeyPair;
+use bitcoin::secp256k1::Message;
+use bitcoin::secp256k1::Secp256k1;
+use bitcoin::secp256k1::SecretKey;
+use bitcoin::secp256k1::XOnlyPublicKey;
+use dlc_messages::oracle_msgs::DigitDecompositionEventDescriptor;
+use dlc_messages::oracle_msgs::EventDescriptor;
+use dlc_messages::oracle_msgs::OracleAnnouncement;
+use dlc_messages::oracle_msgs::OracleEvent;
+use lightning::util::ser::Writeable;
+use parking_lot::Mutex;
+use rand::thread_rng;
+use rand::RngCore;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use trade::ContractSymbol;
+
+mod server;
+
+pub use crate::server::AttestParams;
+pub use crate::server::MockOracleServer;
+
+/// The unit of the attested prices.
+const UNIT: &str = "usd/btc";
+
+/// An oracle attesting to prices of contract symbols.
+///
+/// Clones share the same events, so the oracle can be driven by a test while being served by a
+/// [`MockOracleServer`].
+#[derive(Clone)]
+pub struct MockOracle {
+    secp: Secp256k1<All>,
+    key_pair: KeyPair,
+    events: Arc<Mutex<HashMap<String, Event>>>,
+}
+
+struct Event {
+    announcement: OracleAnnouncement,
+    /// The secret nonces used to sign the digits of the price, one per digit.
+    nonces: Vec<[u8; 32]>,
+    attestation: Option<Attestation>,
+}
+
+/// The attestation of an oracle event in the format of the P2PD oracle API.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Attestation {
+    pub event_id: String,
+    pub signatures: Vec<Signature>,
+    /// The binary digits of the attested price, most significant digit first.
+    pub values: Vec<String>,
+}
+
+impl MockOracle {
+    /// Creates an oracle with a random key.
+    pub fn new() -> Self {
+        Self::from_secret_key(random_secret_key())
+    }
+
+    /// Creates an oracle with the given key, e.g. to reuse an oracle public key which is already
+    /// configured somewhere.
+    pub fn from_secret_key(secret_key: SecretKey) -> Self {
+        let secp = Secp256k1::new();
+        let key_pair = KeyPair::from_secret_key(&secp, &secret_key);
+
+        Self {
+            secp,
+            key_pair,
+            events: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn public_key(&self) -> XOnlyPublicKey {
+        self.key_pair.x_only_public_key().0
+    }
+
+    /// Returns the announcement of the event with the given id, announcing it if it has not been
+    /// announced yet.
+    ///
+    /// The event id has to be of the form returned by [`ContractSymbol::oracle_event_id`].
+    pub fn announcement(&self, event_id: &str) -> Result<OracleAnnouncement> {
+        let mut events = self.events.lock();
+
+        if let Some(event) = events.get(event_id) {
+            return Ok(event.announcement.clone());
+        }
+
+        let event = self.announce(event_id)?;
+        let announcement = event.announcement.clone();
+        events.insert(event_id.to_string(), event);
+
+        tracing::debug!(%event_id, "Announced event");
+
+        Ok(announcement)
+    }
+
+    /// Attests to the event with the given id at the given price.
+    ///
+    /// Prices above the highest price of the contract symbol are attested as the highest price.
+    /// An event can only be attested once.
+    pub fn attest(&self, event_id: &str, price: u64) -> Result<Attestation> {
+        self.announcement(event_id)?;
+
+        let mut events = self.events.lock();
+        let event = events.get_mut(event_id).expect("event to be announced");
+
+        if event.attestation.is_some() {
+            bail!("Event {event_id} has already been attested");
+        }
+
+        let nb_digits = event.nonces.len();
+        let price = price.min((1 << nb_digits) - 1);
+
+        let values = (0..nb_digits)
+            .rev()
+            .map(|digit| ((price >> digit) & 1).to_string())
+            .collect::<Vec<_>>();
+
+        let signatures = values
+            .iter()
+            .zip(event.nonces.iter())
+            .map(|(value, nonce)| {
+                let message = Message::from_hashed_data::<sha256::Hash>(value.as_bytes());
+                dlc::secp_utils::schnorrsig_sign_with_nonce(
+                    &self.secp,
+                    &message,
+                    &self.key_pair,
+                    nonce,
+                )
+            })
+            .collect();
+
+        let attestation = Attestation {
+            event_id: event_id.to_string(),
+            signatures,
+            values,
+        };
+        event.attestation = Some(attestation.clone());
+
+        tracing::debug!(%event_id, %price, "Attested event");
+
+        Ok(attestation)
+    }
+
+    /// Returns the attestation of the event with the given id, if it has been attested already.
+    pub fn attestation(&self, event_id: &str) -> Option<Attestation> {
+        self.events
+            .lock()
+            .get(event_id)
+            .and_then(|event| event.attestation.clone())
+    }
+
+    fn announce(&self, event_id: &str) -> Result<Event> {
+        let contract_symbol = ContractSymbol::from_oracle_event_id(event_id)?;
+        let event_maturity_epoch = event_id[contract_symbol.label().len()..]
+            .parse::<u32>()
+            .with_context(|| format!("Invalid maturity in event id {event_id}"))?;
+
+        let nb_digits = contract_symbol.oracle_nb_digits();
+
+        let (nonces, oracle_nonces) = (0..nb_digits)
+            .map(|_| {
+                let nonce = random_secret_key();
+                let public_nonce = KeyPair::from_secret_key(&self.secp, &nonce)
+                    .x_only_public_key()
+                    .0;
+
+                (nonce.secret_bytes(), public_nonce)
+            })
+            .unzip();
+
+        let oracle_event = OracleEvent {
+            oracle_nonces,
+            event_maturity_epoch,
+            event_descriptor: EventDescriptor::DigitDecompositionEvent(
+                DigitDecompositionEventDescriptor {
+                    base: 2,
+                    is_signed: false,
+                    unit: UNIT.to_string(),
+                    precision: 0,
+                    nb_digits: nb_digits as u16,
+                },
+            ),
+            event_id: event_id.to_string(),
+        };
+
+        let message = Message::from_hashed_data::<sha256::Hash>(&oracle_event.encode());
+        let announcement_signature = self.secp.sign_schnorr_no_aux_rand(&message, &self.key_pair);
+
+        Ok(Event {
+            announcement: OracleAnnouncement {
+                announcement_signature,
+                oracle_public_key: self.public_key(),
+                oracle_event,
+            },
+            nonces,
+            attestation: None,
+        })
+    }
+}
+
+impl Default for MockOracle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn random_secret_key() -> SecretKey {
+    loop {
+        let mut bytes = [0u8; 32];
+        thread_rng().fill_bytes(&mut bytes);
+
+        if let Ok(secret_key) = SecretKey::from_slice(&bytes) {
+            return secret_key;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVENT_ID: &str = "btcusd1706860800";
+
+    #[test]
+    fn announcement_is_valid() {
+        let oracle = MockOracle::new();
+
+        let announcement = oracle.announcement(EVENT_ID).unwrap();
+
+        announcement.validate(&Secp256k1::new()).unwrap();
+        assert_eq!(announcement.oracle_event.event_maturity_epoch, 1706860800);
+        assert_eq!(
+            announcement.oracle_event.oracle_nonces.len(),
+            ContractSymbol::BtcUsd.oracle_nb_digits()
+        );
+    }
+
+    #[test]
+    fn announcement_is_only_created_once() {
+        let oracle = MockOracle::new();
+
+        let first = oracle.announcement(EVENT_ID).unwrap();
+        let second = oracle.announcement(EVENT_ID).unwrap();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn attestation_signs_price_with_announced_nonces() {
+        let oracle = MockOracle::new();
+        let announcement = oracle.announcement(EVENT_ID).unwrap();
+
+        let attestation = oracle.attest(EVENT_ID, 42_000).unwrap();
+
+        let price = attestation
+            .values
+            .iter()
+            .fold(0, |price, digit| price * 2 + digit.parse::<u64>().unwrap());
+        assert_eq!(price, 42_000);
+
+        let secp = Secp256k1::new();
+        for ((signature, value), nonce) in attestation
+            .signatures
+            .iter()
+            .zip(attestation.values.iter())
+            .zip(announcement.oracle_event.oracle_nonces.iter())
+        {
+            let message = Message::from_hashed_data::<sha256::Hash>(value.as_bytes());
+            secp.verify_schnorr(signature, &message, &oracle.public_key())
+                .unwrap();
+            assert_eq!(&signature[..32], &nonce.serialize());
+        }
+    }
+
+    #[test]
+    fn event_can_only_be_attested_once() {
+        let oracle = MockOracle::new();
+
+        oracle.attest(EVENT_ID, 42_000).unwrap();
+
+        assert!(oracle.attest(EVENT_ID, 43_000).is_err());
+    }
+
+    #[test]
+    fn unknown_contract_symbol_cannot_be_announced() {
+        let oracle = MockOracle::new();
+
+        assert!(oracle.announcement("ethusd1706860800").is_err());
+    }
+}
@@ -0,0 +1,218 @@
+use crate::Attestation;
+use crate::MockOracle;
+use anyhow::Context;
+use anyhow::Result;
+use axum::extract::Path;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use axum::routing::get;
+use axum::Json;
+use axum::Router;
+use bitcoin::secp256k1::XOnlyPublicKey;
+use dlc_messages::oracle_msgs::OracleAnnouncement;
+use futures::future::RemoteHandle;
+use futures::FutureExt;
+use serde::Deserialize;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+/// A [`MockOracle`] serving the P2PD oracle HTTP API on a local port.
+///
+/// The server is stopped once this is dropped.
+pub struct MockOracleServer {
+    oracle: MockOracle,
+    address: SocketAddr,
+    _handle: RemoteHandle<()>,
+}
+
+impl MockOracleServer {
+    /// Starts serving the given oracle on a random local port.
+    ///
+    /// Has to be called from within a tokio runtime.
+    pub fn start(oracle: MockOracle) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+
+        let server = axum::Server::from_tcp(listener)
+            .context("Failed to create mock oracle server")?
+            .serve(router(oracle.clone()).into_make_service());
+
+        let (fut, handle) = async move {
+            if let Err(e) = server.await {
+                tracing::error!("Mock oracle server stopped: {e:#}");
+            }
+        }
+        .remote_handle();
+
+        tokio::spawn(fut);
+
+        tracing::info!(%address, public_key = %oracle.public_key(), "Started mock oracle");
+
+        Ok(Self {
+            oracle,
+            address,
+            _handle: handle,
+        })
+    }
+
+    /// The endpoint of the oracle, e.g. to be used as endpoint of an `OracleInfo`.
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    pub fn oracle(&self) -> &MockOracle {
+        &self.oracle
+    }
+}
+
+fn router(oracle: MockOracle) -> Router {
+    Router::new()
+        .route("/oracle/publickey", get(get_public_key))
+        .route(
+            "/asset/:asset_id/announcement/:date_time",
+            get(get_announcement),
+        )
+        .route(
+            "/asset/:asset_id/attestation/:date_time",
+            get(get_attestation).post(post_attestation),
+        )
+        .with_state(oracle)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicKeyResponse {
+    public_key: XOnlyPublicKey,
+}
+
+/// The price to attest to.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttestParams {
+    pub price: u64,
+}
+
+async fn get_public_key(State(oracle): State<MockOracle>) -> Json<PublicKeyResponse> {
+    Json(PublicKeyResponse {
+        public_key: oracle.public_key(),
+    })
+}
+
+async fn get_announcement(
+    State(oracle): State<MockOracle>,
+    Path((asset_id, date_time)): Path<(String, String)>,
+) -> Result<Json<OracleAnnouncement>, AppError> {
+    let event_id = event_id(&asset_id, &date_time)?;
+
+    let announcement = oracle
+        .announcement(&event_id)
+        .map_err(|e| AppError::BadRequest(format!("{e:#}")))?;
+
+    Ok(Json(announcement))
+}
+
+async fn get_attestation(
+    State(oracle): State<MockOracle>,
+    Path((asset_id, date_time)): Path<(String, String)>,
+) -> Result<Json<Attestation>, AppError> {
+    let event_id = event_id(&asset_id, &date_time)?;
+
+    let attestation = oracle
+        .attestation(&event_id)
+        .ok_or_else(|| AppError::NotFound(format!("Event {event_id} has not been attested yet")))?;
+
+    Ok(Json(attestation))
+}
+
+/// Attests to an event at the given price. This is not part of the P2PD oracle API, but allows
+/// tests running the system in separate processes to trigger an attestation.
+async fn post_attestation(
+    State(oracle): State<MockOracle>,
+    Path((asset_id, date_time)): Path<(String, String)>,
+    Json(params): Json<AttestParams>,
+) -> Result<Json<Attestation>, AppError> {
+    let event_id = event_id(&asset_id, &date_time)?;
+
+    let attestation = oracle
+        .attest(&event_id, params.price)
+        .map_err(|e| AppError::BadRequest(format!("{e:#}")))?;
+
+    Ok(Json(attestation))
+}
+
+/// The event id is derived from the asset id and the maturity, in the same way the P2PD oracle
+/// client does it.
+fn event_id(asset_id: &str, date_time: &str) -> Result<String, AppError> {
+    let maturity = OffsetDateTime::parse(date_time, &Rfc3339)
+        .map_err(|e| AppError::BadRequest(format!("Invalid maturity {date_time}: {e}")))?;
+
+    Ok(format!("{asset_id}{}", maturity.unix_timestamp()))
+}
+
+enum AppError {
+    BadRequest(String),
+    NotFound(String),
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+        };
+
+        (status, error_message).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dlc_manager::Oracle;
+    use p2pd_oracle_client::P2PDOracleClient;
+    use tokio::task::spawn_blocking;
+
+    const EVENT_ID: &str = "btcusd1706860800";
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn p2pd_oracle_client_can_get_announcement_and_attestation() {
+        let server = MockOracleServer::start(MockOracle::new()).unwrap();
+
+        let client = P2PDOracleClient {
+            host: server.endpoint() + "/",
+            public_key: server.oracle().public_key(),
+        };
+
+        server.oracle().attest(EVENT_ID, 42_000).unwrap();
+
+        let (announcement, attestation) = spawn_blocking(move || {
+            let announcement = client.get_announcement(EVENT_ID).unwrap();
+            let attestation = client.get_attestation(EVENT_ID).unwrap();
+
+            (announcement, attestation)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            announcement,
+            server.oracle().announcement(EVENT_ID).unwrap()
+        );
+        assert_eq!(
+            attestation.outcomes,
+            server.oracle().attestation(EVENT_ID).unwrap().values
+        );
+    }
+
+    #[test]
+    fn event_id_is_derived_from_asset_id_and_maturity() {
+        assert_eq!(
+            event_id("btcusd", "2024-02-02T08:00:00Z").ok(),
+            Some(EVENT_ID.to_string())
+        );
+    }
+}
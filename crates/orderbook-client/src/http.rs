@@ -0,0 +1,222 @@
+use anyhow::bail;
+use anyhow::Result;
+use commons::AmendOrder;
+use commons::AmendTriggerOrder;
+use commons::CancelAllOrders;
+use commons::CancelOrder;
+use commons::CancelTriggerOrder;
+use commons::GetTriggerOrders;
+use commons::NewOrder;
+use commons::NewTriggerOrder;
+use commons::Order;
+use commons::Signature;
+use commons::SignedRequest;
+use commons::TriggerOrder;
+use reqwest::Url;
+use rust_decimal::Decimal;
+use secp256k1::Message;
+use secp256k1::PublicKey;
+use uuid::Uuid;
+
+/// Posts a new order to the orderbook.
+///
+/// All requests are signed with `sign`, which has to sign with the key of the trader the request
+/// is issued for.
+pub async fn post_order(
+    client: &reqwest::Client,
+    url: &Url,
+    order: NewOrder,
+    sign: impl Fn(Message) -> Signature,
+) -> Result<Order> {
+    let url = url.join("/api/orderbook/orders")?;
+    let order_id = order.id;
+    let new_order = SignedRequest::new(order, sign)?;
+
+    let response = client.post(url).json(&new_order).send().await?;
+
+    if response.status().is_success() {
+        let order = response.json().await?;
+        Ok(order)
+    } else {
+        let status = response.status();
+        let text = response.text().await?;
+        bail!("Could not post order {order_id}: {status} {text}")
+    }
+}
+
+/// Cancels the open limit order with the given id.
+pub async fn cancel_order(
+    client: &reqwest::Client,
+    url: &Url,
+    order_id: Uuid,
+    sign: impl Fn(Message) -> Signature,
+) -> Result<Order> {
+    let url = url.join(&format!("/api/orderbook/orders/{order_id}"))?;
+    let cancel_order = SignedRequest::new(CancelOrder { order_id }, sign)?;
+
+    let response = client.delete(url).json(&cancel_order).send().await?;
+
+    if response.status().is_success() {
+        let order = response.json().await?;
+        Ok(order)
+    } else {
+        let status = response.status();
+        let text = response.text().await?;
+        bail!("Could not cancel order {order_id}: {status} {text}")
+    }
+}
+
+/// Cancels all open limit orders of the trader.
+///
+/// Returns the cancelled orders.
+pub async fn cancel_all_orders(
+    client: &reqwest::Client,
+    url: &Url,
+    trader_id: PublicKey,
+    sign: impl Fn(Message) -> Signature,
+) -> Result<Vec<Order>> {
+    let url = url.join("/api/orderbook/orders")?;
+    let cancel_all_orders = SignedRequest::new(CancelAllOrders { trader_id }, sign)?;
+
+    let response = client.delete(url).json(&cancel_all_orders).send().await?;
+
+    if response.status().is_success() {
+        let orders = response.json().await?;
+        Ok(orders)
+    } else {
+        let status = response.status();
+        let text = response.text().await?;
+        bail!("Could not cancel orders: {status} {text}")
+    }
+}
+
+/// Replaces the price and the quantity of the open limit order with the given id.
+pub async fn amend_order(
+    client: &reqwest::Client,
+    url: &Url,
+    order_id: Uuid,
+    price: Decimal,
+    quantity: Decimal,
+    sign: impl Fn(Message) -> Signature,
+) -> Result<Order> {
+    let url = url.join(&format!("/api/orderbook/orders/{order_id}"))?;
+    let amend_order = SignedRequest::new(
+        AmendOrder {
+            order_id,
+            price,
+            quantity,
+        },
+        sign,
+    )?;
+
+    let response = client.patch(url).json(&amend_order).send().await?;
+
+    if response.status().is_success() {
+        let order = response.json().await?;
+        Ok(order)
+    } else {
+        let status = response.status();
+        let text = response.text().await?;
+        bail!("Could not amend order {order_id}: {status} {text}")
+    }
+}
+
+/// Loads the active trigger orders of the trader.
+pub async fn get_trigger_orders(
+    client: &reqwest::Client,
+    url: &Url,
+    trader_id: PublicKey,
+    sign: impl Fn(Message) -> Signature,
+) -> Result<Vec<TriggerOrder>> {
+    let url = url.join("/api/orderbook/trigger-orders/query")?;
+    let get_trigger_orders = SignedRequest::new(GetTriggerOrders { trader_id }, sign)?;
+
+    let response = client.post(url).json(&get_trigger_orders).send().await?;
+
+    if response.status().is_success() {
+        let trigger_orders = response.json().await?;
+        Ok(trigger_orders)
+    } else {
+        let status = response.status();
+        let text = response.text().await?;
+        bail!("Could not fetch trigger orders: {status} {text}")
+    }
+}
+
+/// Attaches a stop-loss or take-profit order to the open position of the trader.
+pub async fn post_trigger_order(
+    client: &reqwest::Client,
+    url: &Url,
+    trigger_order: NewTriggerOrder,
+    sign: impl Fn(Message) -> Signature,
+) -> Result<TriggerOrder> {
+    let url = url.join("/api/orderbook/trigger-orders")?;
+    let trigger_order_id = trigger_order.id;
+    let new_trigger_order = SignedRequest::new(trigger_order, sign)?;
+
+    let response = client.post(url).json(&new_trigger_order).send().await?;
+
+    if response.status().is_success() {
+        let trigger_order = response.json().await?;
+        Ok(trigger_order)
+    } else {
+        let status = response.status();
+        let text = response.text().await?;
+        bail!("Could not post trigger order {trigger_order_id}: {status} {text}")
+    }
+}
+
+/// Replaces the trigger price of the active trigger order with the given id.
+pub async fn amend_trigger_order(
+    client: &reqwest::Client,
+    url: &Url,
+    trigger_order_id: Uuid,
+    trigger_price: Decimal,
+    sign: impl Fn(Message) -> Signature,
+) -> Result<TriggerOrder> {
+    let url = url.join(&format!("/api/orderbook/trigger-orders/{trigger_order_id}"))?;
+    let amend_trigger_order = SignedRequest::new(
+        AmendTriggerOrder {
+            trigger_order_id,
+            trigger_price,
+        },
+        sign,
+    )?;
+
+    let response = client.put(url).json(&amend_trigger_order).send().await?;
+
+    if response.status().is_success() {
+        let trigger_order = response.json().await?;
+        Ok(trigger_order)
+    } else {
+        let status = response.status();
+        let text = response.text().await?;
+        bail!("Could not amend trigger order {trigger_order_id}: {status} {text}")
+    }
+}
+
+/// Cancels the active trigger order with the given id.
+pub async fn cancel_trigger_order(
+    client: &reqwest::Client,
+    url: &Url,
+    trigger_order_id: Uuid,
+    sign: impl Fn(Message) -> Signature,
+) -> Result<TriggerOrder> {
+    let url = url.join(&format!("/api/orderbook/trigger-orders/{trigger_order_id}"))?;
+    let cancel_trigger_order = SignedRequest::new(CancelTriggerOrder { trigger_order_id }, sign)?;
+
+    let response = client
+        .delete(url)
+        .json(&cancel_trigger_order)
+        .send()
+        .await?;
+
+    if response.status().is_success() {
+        let trigger_order = response.json().await?;
+        Ok(trigger_order)
+    } else {
+        let status = response.status();
+        let text = response.text().await?;
+        bail!("Could not cancel trigger order {trigger_order_id}: {status} {text}")
+    }
+}
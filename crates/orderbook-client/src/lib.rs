@@ -16,6 +16,17 @@ use tokio_tungstenite::tungstenite;
 use tokio_tungstenite::MaybeTlsStream;
 use tokio_tungstenite::WebSocketStream;
 
+mod http;
+
+pub use http::amend_order;
+pub use http::amend_trigger_order;
+pub use http::cancel_all_orders;
+pub use http::cancel_order;
+pub use http::cancel_trigger_order;
+pub use http::get_trigger_orders;
+pub use http::post_order;
+pub use http::post_trigger_order;
+
 /// Connects to the 10101 orderbook WebSocket API.
 ///
 /// If the connection needs authentication please use `subscribe_with_authentication` instead.
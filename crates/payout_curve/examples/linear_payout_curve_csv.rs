@@ -0,0 +1,273 @@
+#![allow(clippy::unwrap_used)]
+
+use anyhow::Context;
+use anyhow::Result;
+use bitcoin::Amount;
+use dlc_manager::payout_curve::PayoutFunction;
+use dlc_manager::payout_curve::PayoutFunctionPiece;
+use dlc_manager::payout_curve::PolynomialPayoutCurvePiece;
+use dlc_manager::payout_curve::RoundingInterval;
+use dlc_manager::payout_curve::RoundingIntervals;
+use payout_curve::build_linear_payout_function;
+use payout_curve::PartyParams;
+use payout_curve::PayoutPoint;
+use payout_curve::ROUNDING_PERCENT;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::fs::File;
+use trade::cfd::calculate_linear_long_liquidation_price;
+use trade::cfd::calculate_linear_margin;
+use trade::cfd::calculate_linear_pnl;
+use trade::cfd::calculate_linear_short_liquidation_price;
+use trade::Direction;
+
+/// The highest price the oracle attests to, in sats per contract.
+const MAX_PRICE: u64 = 1_048_575;
+
+/// The example below will export the computed linear payout curve and how it should look like as
+/// CSV.
+///
+/// Prices are quoted in sats per contract. Use gnuplot to create a chart for it. An example gnuplot
+/// file has been provided [`linear_payout_curve.gp`]
+fn main() -> Result<()> {
+    let initial_price = dec!(50_000);
+    let quantity = 1_000.0;
+    let leverage_short = 2.0;
+    let leverage_long = 2.0;
+
+    let long_liquidation_price = calculate_linear_long_liquidation_price(
+        Decimal::from_f32(leverage_long).expect("to be able to parse f32"),
+        initial_price,
+    );
+    let short_liquidation_price = calculate_linear_short_liquidation_price(
+        Decimal::from_f32(leverage_short).expect("to be able to parse f32"),
+        initial_price,
+    );
+
+    let price_params = payout_curve::PriceParams::new_with_max_price(
+        initial_price,
+        long_liquidation_price,
+        short_liquidation_price,
+        MAX_PRICE,
+    )?;
+
+    // Fee is e.g. 0.3% of the notional value = 150_000 sats.
+    //
+    // We compute it here so that can easily adjust the example.
+    let fee_offer = {
+        let fee = dec!(0.003)
+            * Decimal::from_f32(quantity).expect("to be able to parse into dec")
+            * initial_price;
+
+        Amount::from_sat(fee.to_u64().expect("to fit into u64"))
+    };
+
+    let margin_short = Amount::from_sat(calculate_linear_margin(
+        initial_price,
+        quantity,
+        leverage_short,
+    ));
+    let margin_long = Amount::from_sat(calculate_linear_margin(
+        initial_price,
+        quantity,
+        leverage_long,
+    ));
+
+    for direction_offer in [Direction::Long, Direction::Short] {
+        let (party_params_offer, party_params_accept) = match direction_offer {
+            Direction::Long => (
+                PartyParams::new(margin_long, fee_offer),
+                PartyParams::new(margin_short, Amount::ZERO),
+            ),
+            Direction::Short => (
+                PartyParams::new(margin_short, fee_offer),
+                PartyParams::new(margin_long, Amount::ZERO),
+            ),
+        };
+
+        let total_collateral =
+            party_params_offer.total_collateral() + party_params_accept.total_collateral();
+
+        let payout_points = build_linear_payout_function(
+            quantity,
+            party_params_offer,
+            party_params_accept,
+            price_params,
+            direction_offer,
+        )?;
+
+        let suffix = match direction_offer {
+            Direction::Long => "long",
+            Direction::Short => "short",
+        };
+
+        discretized_payouts_as_csv(
+            &format!("./crates/payout_curve/examples/linear_discretized_{suffix}.csv"),
+            payout_points.clone(),
+            total_collateral,
+        )?;
+
+        computed_payout_curve(
+            party_params_offer,
+            party_params_accept,
+            &format!("./crates/payout_curve/examples/linear_computed_payout_{suffix}.csv"),
+            payout_points,
+        )?;
+
+        should_payouts_as_csv(
+            party_params_offer,
+            total_collateral,
+            direction_offer,
+            margin_long.to_sat(),
+            margin_short.to_sat(),
+            quantity,
+            initial_price,
+            &format!("./crates/payout_curve/examples/linear_should_{suffix}.csv"),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// This is the discretized payout curve thrown into `to_rage_payouts` from rust-dlc, i.e. our DLCs
+/// will be based on these points
+fn computed_payout_curve(
+    party_params_coordinator: PartyParams,
+    party_params_trader: PartyParams,
+    csv_path: &str,
+    payout_points: Vec<(PayoutPoint, PayoutPoint)>,
+) -> Result<()> {
+    let long_liquidation_price = payout_points.first().unwrap().1.event_outcome;
+    let short_liquidation_price = payout_points.last().unwrap().0.event_outcome;
+
+    let mut pieces = vec![];
+    for (lower, upper) in payout_points {
+        let lower_range = PolynomialPayoutCurvePiece::new(vec![
+            dlc_manager::payout_curve::PayoutPoint {
+                event_outcome: lower.event_outcome,
+                outcome_payout: lower.outcome_payout,
+                extra_precision: lower.extra_precision,
+            },
+            dlc_manager::payout_curve::PayoutPoint {
+                event_outcome: upper.event_outcome,
+                outcome_payout: upper.outcome_payout,
+                extra_precision: upper.extra_precision,
+            },
+        ])?;
+        pieces.push(PayoutFunctionPiece::PolynomialPayoutCurvePiece(lower_range));
+    }
+
+    let payout_function =
+        PayoutFunction::new(pieces).context("could not create payout function")?;
+    let total_collateral =
+        party_params_coordinator.total_collateral() + party_params_trader.total_collateral();
+    let total_margin = party_params_coordinator.margin() + party_params_trader.margin();
+    let range_payouts = payout_function.to_range_payouts(
+        total_collateral,
+        &RoundingIntervals {
+            intervals: vec![
+                RoundingInterval {
+                    begin_interval: 0,
+                    rounding_mod: 1,
+                },
+                RoundingInterval {
+                    begin_interval: long_liquidation_price,
+                    rounding_mod: (total_margin as f32 * ROUNDING_PERCENT) as u64,
+                },
+                RoundingInterval {
+                    begin_interval: short_liquidation_price,
+                    rounding_mod: 1,
+                },
+            ],
+        },
+    )?;
+
+    let file = File::create(csv_path)?;
+    let mut wtr = csv::WriterBuilder::new().delimiter(b';').from_writer(file);
+    wtr.write_record(["price", "payout_offer", "trader"])
+        .context("to be able to write record")?;
+    for payout in &range_payouts {
+        wtr.write_record([
+            payout.start.to_string(),
+            payout.payout.offer.to_string(),
+            payout.payout.accept.to_string(),
+        ])?;
+    }
+    wtr.flush()?;
+
+    Ok(())
+}
+
+/// This is our approach to discretize the payout, i.e. we only call our internal library
+fn discretized_payouts_as_csv(
+    csv_path: &str,
+    payout_points: Vec<(PayoutPoint, PayoutPoint)>,
+    total_collateral: u64,
+) -> Result<()> {
+    let file = File::create(csv_path)?;
+    let mut wtr = csv::WriterBuilder::new().delimiter(b';').from_writer(file);
+    wtr.write_record(["price", "payout_offer", "trader"])
+        .context("to be able to write record")?;
+    for (lower, _upper) in &payout_points {
+        wtr.write_record([
+            lower.event_outcome.to_string(),
+            lower.outcome_payout.to_string(),
+            (total_collateral - lower.outcome_payout).to_string(),
+        ])?;
+    }
+    // need to add the last point because we ignored it explicitely above
+    let last_point = payout_points[payout_points.len() - 1];
+    wtr.write_record([
+        last_point.1.event_outcome.to_string(),
+        last_point.1.outcome_payout.to_string(),
+        (total_collateral - last_point.1.outcome_payout).to_string(),
+    ])?;
+    wtr.flush()?;
+    Ok(())
+}
+
+/// The payout of the offer party for every price, computed straight from the PnL.
+#[allow(clippy::too_many_arguments)]
+fn should_payouts_as_csv(
+    party_params_offer: PartyParams,
+    total_collateral: u64,
+    direction_offer: Direction,
+    long_margin: u64,
+    short_margin: u64,
+    quantity: f32,
+    initial_price: Decimal,
+    csv_path: &str,
+) -> Result<()> {
+    let total_collateral = total_collateral as i64;
+
+    let file = File::create(csv_path)?;
+    let mut wtr = csv::WriterBuilder::new().delimiter(b';').from_writer(file);
+    wtr.write_record(["price", "payout_offer", "trader"])?;
+
+    // We only sample every 100 sats to keep the file small.
+    for price in (0..=MAX_PRICE).step_by(100) {
+        let pnl = calculate_linear_pnl(
+            initial_price,
+            Decimal::from(price),
+            quantity,
+            direction_offer,
+            long_margin,
+            short_margin,
+        )?;
+
+        let offer_payout =
+            (party_params_offer.total_collateral() as i64 + pnl).clamp(0, total_collateral);
+        let trader_payout = total_collateral - offer_payout;
+
+        wtr.write_record(&[
+            price.to_string(),
+            offer_payout.to_string(),
+            trader_payout.to_string(),
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
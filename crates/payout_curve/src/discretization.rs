@@ -0,0 +1,443 @@
+use crate::PayoutPoint;
+use crate::ROUNDING_PERCENT;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+/// The factor by which the rounding grows from one band to the next one further away from the
+/// initial price.
+const ROUNDING_GROWTH_FACTOR: u64 = 2;
+
+/// Payouts of all outcomes from `begin_interval` up to the start of the next interval are rounded
+/// to the nearest multiple of `rounding_mod`.
+///
+/// Mirrors [`dlc_manager::payout_curve::RoundingInterval`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoundingInterval {
+    pub begin_interval: u64,
+    pub rounding_mod: u64,
+}
+
+/// Describes how coarsely the payout curve is discretised into CETs.
+///
+/// The closer a price is to the initial price, the more likely it is for the contract to settle
+/// at it. Hence, payouts close to the initial price are rounded finely, whereas the rounding grows
+/// with every band further away from the initial price. Coarser rounding results in fewer
+/// distinct payouts and therefore in fewer CETs.
+#[derive(Clone, Copy, Debug)]
+pub struct DiscretizationParams {
+    /// The rounding of payouts in the bands next to the initial price, as a fraction of the total
+    /// margin.
+    pub rounding_percent: f32,
+    /// The width of a band, as a fraction of the initial price.
+    pub band_width: f32,
+    /// The maximum amount by which rounding may change any payout, as a fraction of the total
+    /// margin.
+    ///
+    /// Payouts are rounded to the nearest multiple, so the rounding never exceeds twice this
+    /// value.
+    ///
+    /// Defaults to the error of rounding every payout to [`ROUNDING_PERCENT`], i.e. the rounding
+    /// only grows towards the liquidation prices if a larger error is allowed explicitly.
+    pub max_payout_error_percent: f32,
+}
+
+impl Default for DiscretizationParams {
+    fn default() -> Self {
+        Self {
+            rounding_percent: ROUNDING_PERCENT,
+            band_width: 0.05,
+            max_payout_error_percent: ROUNDING_PERCENT / 2.0,
+        }
+    }
+}
+
+impl DiscretizationParams {
+    /// The rounding of the payouts in the given band, where band `0` is next to the initial price.
+    fn rounding_mod(&self, total_margin: u64, band: u64) -> u64 {
+        let rounding_mod = (total_margin as f32 * self.rounding_percent) as u64;
+        let rounding_mod = u32::try_from(band)
+            .ok()
+            .and_then(|band| ROUNDING_GROWTH_FACTOR.checked_pow(band))
+            .and_then(|growth| rounding_mod.checked_mul(growth))
+            .unwrap_or(u64::MAX);
+
+        rounding_mod.min(self.max_rounding_mod(total_margin)).max(1)
+    }
+
+    fn max_rounding_mod(&self, total_margin: u64) -> u64 {
+        ((total_margin as f32 * self.max_payout_error_percent * 2.0) as u64).max(1)
+    }
+}
+
+/// Builds the rounding intervals for a payout curve with the given liquidation prices.
+///
+/// Outside of the liquidation prices the payout is constant, so we do not round there. Between
+/// them, the rounding grows with the distance to the initial price as described by
+/// [`DiscretizationParams`].
+pub fn build_rounding_intervals(
+    initial_price: Decimal,
+    total_margin: u64,
+    long_liquidation_price: u64,
+    short_liquidation_price: u64,
+    max_price: u64,
+    params: DiscretizationParams,
+) -> Vec<RoundingInterval> {
+    let liquidation_diff = short_liquidation_price
+        .checked_sub(long_liquidation_price)
+        .expect("short liquidation to be higher than long liquidation");
+    let low_price = long_liquidation_price + liquidation_diff / 10;
+    let high_price = short_liquidation_price - liquidation_diff / 10;
+
+    let edge_rounding_mod = ((total_margin as f32 * params.rounding_percent * 0.1) as u64).max(1);
+
+    let mut intervals = vec![];
+
+    push_interval(&mut intervals, 0, 1);
+    // HACK: We decrease the rounding here to prevent `rust-dlc` from rounding under the long
+    // liquidation price _payout_.
+    push_interval(&mut intervals, long_liquidation_price, edge_rounding_mod);
+
+    let initial_price = initial_price
+        .to_u64()
+        .expect("to fit into u64")
+        .clamp(low_price, high_price);
+    let band_width = ((initial_price as f32 * params.band_width) as u64).max(1);
+
+    let mut band_starts = vec![low_price];
+    band_starts.extend(
+        (1..)
+            .map(|band| initial_price.saturating_sub(band * band_width))
+            .take_while(|start| *start > low_price)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev(),
+    );
+    band_starts.extend(
+        (0..)
+            .map(|band| initial_price + band * band_width)
+            .take_while(|start| *start < high_price),
+    );
+
+    for start in band_starts {
+        let band = if start < initial_price {
+            (initial_price - start - 1) / band_width
+        } else {
+            (start - initial_price) / band_width
+        };
+
+        push_interval(
+            &mut intervals,
+            start,
+            params.rounding_mod(total_margin, band),
+        );
+    }
+
+    if short_liquidation_price < max_price {
+        // HACK: We decrease the rounding here to prevent `rust-dlc` from rounding over the short
+        // liquidation price _payout_.
+        push_interval(&mut intervals, high_price, edge_rounding_mod);
+        push_interval(&mut intervals, short_liquidation_price, 1);
+    }
+
+    intervals
+}
+
+/// Appends a rounding interval, keeping the intervals strictly ascending and merging it into the
+/// previous interval if they have the same rounding.
+fn push_interval(intervals: &mut Vec<RoundingInterval>, begin_interval: u64, rounding_mod: u64) {
+    match intervals.last_mut() {
+        Some(last) if last.begin_interval >= begin_interval => {
+            last.rounding_mod = rounding_mod;
+        }
+        Some(last) if last.rounding_mod == rounding_mod => {}
+        _ => intervals.push(RoundingInterval {
+            begin_interval,
+            rounding_mod,
+        }),
+    }
+}
+
+/// Counts the CETs needed for the given payout curve and rounding intervals, assuming a single
+/// oracle attesting to the price in base 2 using `nb_digits` digits.
+///
+/// This mirrors what `rust-dlc` does: every range of consecutive outcomes with the same rounded
+/// payout is covered by the smallest set of digit prefixes, each of which requires one CET.
+pub fn cet_count(
+    payout_points: &[(PayoutPoint, PayoutPoint)],
+    rounding_intervals: &[RoundingInterval],
+    total_collateral: u64,
+    nb_digits: usize,
+) -> u64 {
+    let max_outcome = (1u64 << nb_digits) - 1;
+
+    let mut count = 0;
+    // The start and rounded payout of the current range of outcomes.
+    let mut range: Option<(u64, u64)> = None;
+
+    for (i, (lower, upper)) in payout_points.iter().enumerate() {
+        let is_last_piece = i == payout_points.len() - 1;
+        let end = if is_last_piece {
+            upper.event_outcome.min(max_outcome)
+        } else {
+            upper.event_outcome.saturating_sub(1).min(max_outcome)
+        };
+
+        for outcome in lower.event_outcome..=end {
+            let payout = round_payout(
+                rounding_intervals,
+                outcome,
+                interpolate(lower, upper, outcome),
+            )
+            .min(total_collateral);
+
+            match range {
+                Some((_, range_payout)) if range_payout == payout => {}
+                Some((start, _)) => {
+                    count += nb_digit_prefixes(start, outcome - 1);
+                    range = Some((outcome, payout));
+                }
+                None => range = Some((outcome, payout)),
+            }
+        }
+    }
+
+    if let Some((start, _)) = range {
+        count += nb_digit_prefixes(start, max_outcome);
+    }
+
+    count
+}
+
+/// The payout at the given outcome on the straight line between `lower` and `upper`.
+fn interpolate(lower: &PayoutPoint, upper: &PayoutPoint, outcome: u64) -> f64 {
+    if upper.event_outcome == lower.event_outcome {
+        return lower.outcome_payout as f64;
+    }
+
+    let slope = (upper.outcome_payout as f64 - lower.outcome_payout as f64)
+        / (upper.event_outcome - lower.event_outcome) as f64;
+
+    lower.outcome_payout as f64 + slope * (outcome - lower.event_outcome) as f64
+}
+
+/// Rounds the payout to the nearest multiple of the rounding of the interval the outcome falls
+/// into.
+fn round_payout(rounding_intervals: &[RoundingInterval], outcome: u64, payout: f64) -> u64 {
+    let rounding_mod = rounding_intervals
+        .iter()
+        .rev()
+        .find(|interval| interval.begin_interval <= outcome)
+        .map(|interval| interval.rounding_mod)
+        .unwrap_or(1) as f64;
+
+    let remainder = payout % rounding_mod;
+    let rounded_payout = if remainder >= rounding_mod / 2.0 {
+        payout + rounding_mod - remainder
+    } else {
+        payout - remainder
+    };
+
+    rounded_payout.round() as u64
+}
+
+/// The smallest number of base 2 digit prefixes covering all outcomes from `start` to `end`.
+fn nb_digit_prefixes(mut start: u64, end: u64) -> u64 {
+    let mut count = 0;
+
+    while start <= end {
+        // The largest block of outcomes sharing a prefix which starts at `start` and does not
+        // exceed `end`.
+        let mut block_size = if start == 0 {
+            1 << 63
+        } else {
+            1 << start.trailing_zeros()
+        };
+        while block_size > end - start + 1 {
+            block_size >>= 1;
+        }
+
+        count += 1;
+
+        match start.checked_add(block_size) {
+            Some(next) => start = next,
+            None => break,
+        }
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_inverse_payout_function;
+    use crate::PartyParams;
+    use crate::PriceParams;
+    use bitcoin::Amount;
+    use rust_decimal::prelude::FromPrimitive;
+    use rust_decimal_macros::dec;
+    use trade::cfd::calculate_long_liquidation_price;
+    use trade::cfd::calculate_margin;
+    use trade::cfd::calculate_short_liquidation_price;
+    use trade::cfd::BTCUSD_MAX_PRICE;
+    use trade::Direction;
+
+    #[test]
+    fn digit_prefixes_cover_range() {
+        assert_eq!(nb_digit_prefixes(0, 7), 1);
+        assert_eq!(nb_digit_prefixes(3, 3), 1);
+        // 1, 2-3, 4-5, 6
+        assert_eq!(nb_digit_prefixes(1, 6), 4);
+        assert_eq!(nb_digit_prefixes(0, BTCUSD_MAX_PRICE), 1);
+    }
+
+    #[test]
+    fn rounding_is_finer_close_to_initial_price_and_bounded_by_max_payout_error() {
+        let total_margin = 1_000_000;
+        let params = DiscretizationParams {
+            max_payout_error_percent: 0.025,
+            ..DiscretizationParams::default()
+        };
+
+        let intervals = build_rounding_intervals(
+            dec!(30_000),
+            total_margin,
+            20_000,
+            60_000,
+            BTCUSD_MAX_PRICE,
+            params,
+        );
+
+        assert_eq!(intervals.first().unwrap().begin_interval, 0);
+        assert!(intervals
+            .windows(2)
+            .all(|w| w[0].begin_interval < w[1].begin_interval));
+
+        let rounding_at = |price: u64| {
+            intervals
+                .iter()
+                .rev()
+                .find(|interval| interval.begin_interval <= price)
+                .unwrap()
+                .rounding_mod
+        };
+
+        assert_eq!(rounding_at(30_000), 10_000);
+        assert_eq!(rounding_at(29_000), 10_000);
+        assert!(rounding_at(40_000) > rounding_at(30_000));
+        assert!(rounding_at(25_000) > rounding_at(30_000));
+
+        let max_rounding_mod = (total_margin as f32 * params.max_payout_error_percent * 2.0) as u64;
+        assert!(intervals
+            .iter()
+            .all(|interval| interval.rounding_mod <= max_rounding_mod));
+    }
+
+    #[test]
+    fn adaptive_rounding_reduces_cet_count() {
+        let initial_price = dec!(40_000);
+        let quantity = 1_000.0;
+        let leverage = 2.0;
+
+        let margin = calculate_margin(initial_price, quantity, leverage);
+        let total_margin = margin * 2;
+
+        let long_liquidation_price =
+            calculate_long_liquidation_price(Decimal::from_f32(leverage).unwrap(), initial_price);
+        let short_liquidation_price =
+            calculate_short_liquidation_price(Decimal::from_f32(leverage).unwrap(), initial_price);
+
+        let party_params = PartyParams::new(Amount::from_sat(margin), Amount::ZERO);
+        let payout_points = build_inverse_payout_function(
+            quantity,
+            party_params,
+            party_params,
+            PriceParams::new_btc_usd(
+                initial_price,
+                long_liquidation_price,
+                short_liquidation_price,
+            )
+            .unwrap(),
+            Direction::Long,
+        )
+        .unwrap();
+
+        let long_liquidation_price = payout_points.first().unwrap().1.event_outcome;
+        let short_liquidation_price = payout_points.last().unwrap().0.event_outcome;
+
+        let cet_count_with = |params: DiscretizationParams| {
+            let intervals = build_rounding_intervals(
+                initial_price,
+                total_margin,
+                long_liquidation_price,
+                short_liquidation_price,
+                BTCUSD_MAX_PRICE,
+                params,
+            );
+
+            cet_count(&payout_points, &intervals, total_margin, 20)
+        };
+
+        // Rounding every band like the one next to the initial price.
+        let uniform = cet_count_with(DiscretizationParams::default());
+        let adaptive = cet_count_with(DiscretizationParams {
+            max_payout_error_percent: 0.025,
+            ..DiscretizationParams::default()
+        });
+
+        assert!(
+            adaptive < uniform,
+            "adaptive: {adaptive}, uniform: {uniform}"
+        );
+    }
+
+    #[test]
+    fn default_rounding_matches_fixed_rounding() {
+        let total_margin = 1_000_000;
+
+        let intervals = build_rounding_intervals(
+            dec!(30_000),
+            total_margin,
+            20_000,
+            60_000,
+            BTCUSD_MAX_PRICE,
+            DiscretizationParams::default(),
+        );
+
+        let fixed_rounding_mod = (total_margin as f32 * ROUNDING_PERCENT) as u64;
+        assert!(intervals
+            .iter()
+            .all(|interval| interval.rounding_mod <= fixed_rounding_mod));
+        assert!(intervals
+            .iter()
+            .filter(|interval| (24_000..56_000).contains(&interval.begin_interval))
+            .all(|interval| interval.rounding_mod == fixed_rounding_mod));
+    }
+
+    #[test]
+    fn rounding_error_is_bounded() {
+        let params = DiscretizationParams::default();
+        let total_margin = 1_000_000;
+
+        let intervals = build_rounding_intervals(
+            dec!(30_000),
+            total_margin,
+            20_000,
+            60_000,
+            BTCUSD_MAX_PRICE,
+            params,
+        );
+
+        let max_payout_error = (total_margin as f32 * params.max_payout_error_percent) as u64;
+
+        for outcome in (0..70_000).step_by(7) {
+            let payout = 123_456.0 + outcome as f64 * 13.37;
+            let rounded_payout = round_payout(&intervals, outcome, payout);
+
+            assert!(
+                (rounded_payout as f64 - payout).abs() <= max_payout_error as f64,
+                "{rounded_payout} too far from {payout}"
+            );
+        }
+    }
+}
@@ -6,24 +6,35 @@ use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use serde::Serialize;
+use trade::cfd::calculate_linear_pnl;
 use trade::cfd::calculate_pnl;
-use trade::cfd::BTCUSD_MAX_PRICE;
+use trade::ContractSymbol;
 use trade::Direction;
 
+mod discretization;
+
+pub use crate::discretization::*;
+
 /// Factor by which we can multiply the total margin being wagered in order to get consistent
 /// rounding in the middle (non-constant) part of the payout function.
 ///
 /// E.g. with a value of 0.01 and a total margin of 20_000 sats would get payout jumps of 200 sats,
 /// for a total of ~100 intervals.
 ///
-/// TODO: We should not use the same rounding for all non-constant parts of the payout function,
-/// because not all intervals are equally as likely. That way we can avoid excessive CET generation.
+/// This is the rounding close to the initial price. See [`DiscretizationParams`] for how the
+/// rounding grows towards the liquidation prices.
 pub const ROUNDING_PERCENT: f32 = 0.01;
 
 /// Defines the steps to take in the payout curve for one point. A step of 2 means, that two points
 /// are $1 away from each other.
 const PAYOUT_CURVE_DISCRETIZATION_STEPS: u64 = 20;
 
+/// Computes the PnL in sats of a position, e.g. [`calculate_pnl`] for inverse contracts.
+///
+/// Arguments are the opening price, the closing price, the quantity, the direction of the party
+/// and the initial margins of the party going long and the party going short.
+type PnlFn = fn(Decimal, Decimal, f32, Direction, u64, u64) -> Result<i64>;
+
 /// A payout point representing a payout for a given outcome.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct PayoutPoint {
@@ -78,6 +89,8 @@ pub struct PriceParams {
     ///
     /// This is _higher_ than the initial price.
     short_liquidation_price: Decimal,
+    /// The highest price the oracle can attest to.
+    max_price: u64,
 }
 
 impl PriceParams {
@@ -86,17 +99,48 @@ impl PriceParams {
         long_liquidation: Decimal,
         short_liquidation: Decimal,
     ) -> Result<Self> {
-        // We cap the short liquidation at the maximum possible value of Bitcoin w.r.t to USD that
-        // we support.
-        let short_liquidation = short_liquidation.min(Decimal::from(BTCUSD_MAX_PRICE));
+        Self::new_for_contract(
+            ContractSymbol::BtcUsd,
+            initial,
+            long_liquidation,
+            short_liquidation,
+        )
+    }
 
-        Self::new(initial, short_liquidation, long_liquidation)
+    pub fn new_for_contract(
+        contract_symbol: ContractSymbol,
+        initial: Decimal,
+        long_liquidation: Decimal,
+        short_liquidation: Decimal,
+    ) -> Result<Self> {
+        Self::new_with_max_price(
+            initial,
+            long_liquidation,
+            short_liquidation,
+            contract_symbol.max_price(),
+        )
+    }
+
+    /// Creates the price parameters for a contract whose oracle attests to prices up to
+    /// `max_price`.
+    pub fn new_with_max_price(
+        initial: Decimal,
+        long_liquidation: Decimal,
+        short_liquidation: Decimal,
+        max_price: u64,
+    ) -> Result<Self> {
+        // We cap the short liquidation at the maximum possible price of the contract that we
+        // support.
+        let short_liquidation = short_liquidation.min(Decimal::from(max_price));
+
+        Self::new(initial, short_liquidation, long_liquidation, max_price)
     }
 
     fn new(
         initial: Decimal,
         short_liquidation: Decimal,
         long_liquidation: Decimal,
+        max_price: u64,
     ) -> Result<Self> {
         ensure!(
             long_liquidation <= initial,
@@ -112,6 +156,7 @@ impl PriceParams {
             initial_price: initial,
             short_liquidation_price: short_liquidation,
             long_liquidation_price: long_liquidation,
+            max_price,
         })
     }
 }
@@ -131,6 +176,49 @@ pub fn build_inverse_payout_function(
     accept_party: PartyParams,
     price_params: PriceParams,
     offer_party_direction: Direction,
+) -> Result<Vec<(PayoutPoint, PayoutPoint)>> {
+    build_payout_function(
+        quantity,
+        offer_party,
+        accept_party,
+        price_params,
+        offer_party_direction,
+        calculate_pnl,
+    )
+}
+
+/// Build a discretized payout function for a linear perpetual future from the perspective of the
+/// offer party.
+///
+/// The price of a linear contract is quoted in sats per contract, so the payout changes linearly
+/// with the price. See [`trade::cfd::calculate_linear_pnl`].
+///
+/// The output has the same shape as the output of [`build_inverse_payout_function`].
+pub fn build_linear_payout_function(
+    // The number of contracts.
+    quantity: f32,
+    offer_party: PartyParams,
+    accept_party: PartyParams,
+    price_params: PriceParams,
+    offer_party_direction: Direction,
+) -> Result<Vec<(PayoutPoint, PayoutPoint)>> {
+    build_payout_function(
+        quantity,
+        offer_party,
+        accept_party,
+        price_params,
+        offer_party_direction,
+        calculate_linear_pnl,
+    )
+}
+
+fn build_payout_function(
+    quantity: f32,
+    offer_party: PartyParams,
+    accept_party: PartyParams,
+    price_params: PriceParams,
+    offer_party_direction: Direction,
+    calculate_pnl: PnlFn,
 ) -> Result<Vec<(PayoutPoint, PayoutPoint)>> {
     let mut pieces = vec![];
 
@@ -154,10 +242,15 @@ pub fn build_inverse_payout_function(
             price_params.long_liquidation_price,
             collateral_reserve_long,
         )?;
-    pieces.push((
-        long_liquidation_interval_start,
-        long_liquidation_interval_end,
-    ));
+
+    // Without leverage, the party going long of a linear contract only gets liquidated at a price
+    // of 0, in which case there is no long liquidation interval.
+    if long_liquidation_interval_end.event_outcome > long_liquidation_interval_start.event_outcome {
+        pieces.push((
+            long_liquidation_interval_start,
+            long_liquidation_interval_end,
+        ));
+    }
 
     let mid_range = calculate_mid_range_payouts(
         offer_party,
@@ -168,8 +261,10 @@ pub fn build_inverse_payout_function(
             .short_liquidation_price
             .to_u64()
             .expect("to fit dec into u64"),
+        price_params.max_price,
         offer_party_direction,
         quantity,
+        calculate_pnl,
     )?;
 
     let (_, mid_range_interval_end_payout_point) = mid_range
@@ -180,14 +275,15 @@ pub fn build_inverse_payout_function(
         pieces.push((*lower, *upper));
     }
 
-    // If the last payout point of the mid range interval is already at [`BTCUSD_MAX_PRICE`], the
-    // short liquidation interval is already covered.
-    if mid_range_interval_end_payout_point.event_outcome < BTCUSD_MAX_PRICE {
+    // If the last payout point of the mid range interval is already at the max price, the short
+    // liquidation interval is already covered.
+    if mid_range_interval_end_payout_point.event_outcome < price_params.max_price {
         let short_liquidation_payout_points = calculate_short_liquidation_interval_payouts(
             offer_party_direction,
             total_collateral,
             *mid_range_interval_end_payout_point,
             collateral_reserve_short,
+            price_params.max_price,
         )?;
 
         pieces.push(short_liquidation_payout_points);
@@ -256,6 +352,7 @@ fn calculate_long_liquidation_interval_payouts(
 /// TODO: We should almost certainly define our own step function to avoid having to use the
 /// `rust-dlc` `RoundingIntervals`, which can cause problems on the boundaries between different
 /// `RoundingInterval`s.
+#[allow(clippy::too_many_arguments)]
 fn calculate_mid_range_payouts(
     offer_party: PartyParams,
     accept_party: PartyParams,
@@ -264,8 +361,10 @@ fn calculate_mid_range_payouts(
     // highest of the two points in terms of price.
     long_liquidation_interval_end_payout: &PayoutPoint,
     short_liquidation_price: u64,
+    max_price: u64,
     offer_direction: Direction,
     quantity: f32,
+    calculate_pnl: PnlFn,
 ) -> Result<Vec<(PayoutPoint, PayoutPoint)>> {
     let long_liquidation_price = long_liquidation_interval_end_payout.event_outcome;
 
@@ -319,7 +418,7 @@ fn calculate_mid_range_payouts(
             // Interval end payout point.
 
             let interval_end_price =
-                (interval_start_price + PAYOUT_CURVE_DISCRETIZATION_STEPS).min(BTCUSD_MAX_PRICE);
+                (interval_start_price + PAYOUT_CURVE_DISCRETIZATION_STEPS).min(max_price);
 
             let interval_end_payout = {
                 let pnl = calculate_pnl(
@@ -356,12 +455,13 @@ fn calculate_mid_range_payouts(
 /// Calculate the payout points for the interval where the party going short gets liquidated, from
 /// the perspective of the offer party.
 ///
-/// The price ranges from the `short_liquidation_price` to `BTCUSD_MAX_PRICE`.
+/// The price ranges from the `short_liquidation_price` to `max_price`.
 fn calculate_short_liquidation_interval_payouts(
     offer_direction: Direction,
     total_collateral: u64,
     mid_range_interval_end_payout_point: PayoutPoint,
     collateral_reserve_short: u64,
+    max_price: u64,
 ) -> Result<(PayoutPoint, PayoutPoint)> {
     // The last interval starts where the mid range interval ended.
     let interval_start = mid_range_interval_end_payout_point;
@@ -375,7 +475,7 @@ fn calculate_short_liquidation_interval_payouts(
             debug_assert!(outcome_payout >= interval_start.outcome_payout);
 
             let interval_end = PayoutPoint {
-                event_outcome: BTCUSD_MAX_PRICE,
+                event_outcome: max_price,
                 outcome_payout,
                 extra_precision: 0,
             };
@@ -389,7 +489,7 @@ fn calculate_short_liquidation_interval_payouts(
             debug_assert!(outcome_payout >= interval_start.outcome_payout);
 
             let interval_end = PayoutPoint {
-                event_outcome: BTCUSD_MAX_PRICE,
+                event_outcome: max_price,
                 outcome_payout,
                 extra_precision: 0,
             };
@@ -410,9 +510,13 @@ mod tests {
     use rust_decimal_macros::dec;
     use std::fs::File;
     use std::ops::Mul;
+    use trade::cfd::calculate_linear_long_liquidation_price;
+    use trade::cfd::calculate_linear_margin;
+    use trade::cfd::calculate_linear_short_liquidation_price;
     use trade::cfd::calculate_long_liquidation_price;
     use trade::cfd::calculate_margin;
     use trade::cfd::calculate_short_liquidation_price;
+    use trade::cfd::BTCUSD_MAX_PRICE;
 
     /// set this to true to export test data to csv files
     /// An example gnuplot file has been provided in [`payout_curve.gp`]
@@ -571,8 +675,10 @@ mod tests {
                     extra_precision: 0,
                 },
                 short_liquidation_price.to_u64().unwrap(),
+                BTCUSD_MAX_PRICE,
                 offer_direction,
                 quantity,
+                calculate_pnl,
             )
             .expect("To be able to compute mid range")
         };
@@ -592,8 +698,10 @@ mod tests {
                     extra_precision: 0,
                 },
                 short_liquidation_price.to_u64().unwrap(),
+                BTCUSD_MAX_PRICE,
                 offer_direction,
                 quantity,
+                calculate_pnl,
             )
             .expect("To be able to compute mid range")
         };
@@ -706,8 +814,10 @@ mod tests {
                 extra_precision: 0,
             },
             short_liquidation_price.to_u64().unwrap(),
+            BTCUSD_MAX_PRICE,
             offer_direction,
             quantity,
+            calculate_pnl,
         )
         .expect("To be able to compute mid range");
 
@@ -748,6 +858,7 @@ mod tests {
             total_collateral,
             last_mid_range_payout,
             collateral_reserve_offer,
+            BTCUSD_MAX_PRICE,
         )
         .unwrap();
 
@@ -787,6 +898,7 @@ mod tests {
             total_collateral,
             last_mid_range_payout,
             collateral_reserve_accept,
+            BTCUSD_MAX_PRICE,
         )
         .unwrap();
 
@@ -831,6 +943,7 @@ mod tests {
             total_collateral,
             last_mid_range_payout,
             collateral_reserve_accept,
+            BTCUSD_MAX_PRICE,
         )
         .unwrap();
 
@@ -924,7 +1037,7 @@ mod tests {
             let offer_direction = Direction::Short;
 
             let (lower, upper) =
-                calculate_short_liquidation_interval_payouts(offer_direction, total_collateral, last_payout, collateral_reserve_short).unwrap();
+                calculate_short_liquidation_interval_payouts(offer_direction, total_collateral, last_payout, collateral_reserve_short, BTCUSD_MAX_PRICE).unwrap();
 
             // assert
             prop_assert_eq!(lower.event_outcome, last_payout.event_outcome);
@@ -950,7 +1063,7 @@ mod tests {
             let offer_direction = Direction::Long;
 
             let (lower, upper) =
-                calculate_short_liquidation_interval_payouts(offer_direction, total_collateral, last_payout, collateral_reserve_short).unwrap();
+                calculate_short_liquidation_interval_payouts(offer_direction, total_collateral, last_payout, collateral_reserve_short, BTCUSD_MAX_PRICE).unwrap();
 
             // assert
             assert_eq!(lower.event_outcome, last_payout.event_outcome);
@@ -960,6 +1073,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn linear_payout_function_is_continuous_from_zero_to_max_price() {
+        // setup
+        let quantity = 100.0;
+        let initial_price = dec!(50_000);
+        let max_price = 1_048_575;
+
+        for (long_leverage, short_leverage) in [(1.0, 1.0), (2.0, 1.0), (1.0, 3.0)] {
+            let long_margin = calculate_linear_margin(initial_price, quantity, long_leverage);
+            let short_margin = calculate_linear_margin(initial_price, quantity, short_leverage);
+
+            let price_params = PriceParams::new_with_max_price(
+                initial_price,
+                calculate_linear_long_liquidation_price(
+                    Decimal::from_f32(long_leverage).unwrap(),
+                    initial_price,
+                ),
+                calculate_linear_short_liquidation_price(
+                    Decimal::from_f32(short_leverage).unwrap(),
+                    initial_price,
+                ),
+                max_price,
+            )
+            .unwrap();
+
+            let collateral_reserve_offer = 1_000;
+            let party_params_offer = PartyParams::new(
+                Amount::from_sat(long_margin),
+                Amount::from_sat(collateral_reserve_offer),
+            );
+            let party_params_accept =
+                PartyParams::new(Amount::from_sat(short_margin), Amount::ZERO);
+            let total_collateral =
+                party_params_offer.total_collateral() + party_params_accept.total_collateral();
+
+            // act
+            let pieces = build_linear_payout_function(
+                quantity,
+                party_params_offer,
+                party_params_accept,
+                price_params,
+                Direction::Long,
+            )
+            .unwrap();
+
+            // assert
+            assert_eq!(pieces.first().unwrap().0.event_outcome, 0);
+            assert_eq!(pieces.last().unwrap().1.event_outcome, max_price);
+            assert_eq!(
+                pieces.last().unwrap().1.outcome_payout,
+                total_collateral,
+                "offer party going long gets everything once the short party is liquidated"
+            );
+
+            for window in pieces.windows(2) {
+                let (_, previous_upper) = window[0];
+                let (lower, _) = window[1];
+
+                assert_eq!(previous_upper.event_outcome, lower.event_outcome);
+            }
+
+            for (lower, upper) in &pieces {
+                assert!(lower.event_outcome < upper.event_outcome);
+                assert!(lower.outcome_payout >= collateral_reserve_offer);
+                assert!(upper.outcome_payout <= total_collateral);
+            }
+        }
+    }
+
     proptest! {
         #[test]
         fn midrange_always_positive(initial_price in 20_000i32..50_000, short_leverage in 1i32..5) {
@@ -1011,8 +1193,10 @@ mod tests {
                     extra_precision: 0,
                 },
                 short_liquidation_price.to_u64().unwrap(),
+                BTCUSD_MAX_PRICE,
                 offer_direction,
                 quantity,
+                calculate_pnl,
             )
             .expect("To be able to compute mid range");
 
@@ -12,6 +12,7 @@ use native::api;
 use native::api::ContractSymbol;
 use native::trade::order::api::NewOrder;
 use native::trade::order::api::OrderType;
+use native::trade::order::api::TimeInForce;
 use native::trade::position::PositionState;
 use tokio::task::spawn_blocking;
 
@@ -155,5 +156,7 @@ pub fn dummy_order() -> NewOrder {
         quantity: 1000.0,
         order_type: Box::new(OrderType::Market),
         stable: false,
+        time_in_force: TimeInForce::FillOrKill,
+        post_only: false,
     }
 }
@@ -4,6 +4,7 @@ use native::api;
 use native::api::ContractSymbol;
 use native::trade::order::api::NewOrder;
 use native::trade::order::api::OrderType;
+use native::trade::order::api::TimeInForce;
 use native::trade::position::PositionState;
 use tests_e2e::setup;
 use tests_e2e::setup::dummy_order;
@@ -55,6 +56,8 @@ async fn can_open_close_open_close_position() {
         quantity: 500.0,
         order_type: Box::new(OrderType::Market),
         stable: false,
+        time_in_force: TimeInForce::FillOrKill,
+        post_only: false,
     };
 
     spawn_blocking({
@@ -4,6 +4,7 @@ use native::api::calculate_margin;
 use native::api::ContractSymbol;
 use native::trade::order::api::NewOrder;
 use native::trade::order::api::OrderType;
+use native::trade::order::api::TimeInForce;
 use native::trade::position::PositionState;
 use rust_decimal::prelude::ToPrimitive;
 use std::str::FromStr;
@@ -32,6 +33,8 @@ async fn can_open_position_with_multiple_small_utxos() {
         quantity: 100.0,
         order_type: Box::new(OrderType::Market),
         stable: false,
+        time_in_force: TimeInForce::FillOrKill,
+        post_only: false,
     };
 
     // We take the ask price because the app is going long.
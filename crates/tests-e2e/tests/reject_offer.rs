@@ -5,6 +5,7 @@ use native::health::Service;
 use native::health::ServiceStatus;
 use native::trade::order::api::NewOrder;
 use native::trade::order::api::OrderType;
+use native::trade::order::api::TimeInForce;
 use native::trade::order::OrderState;
 use native::trade::position::PositionState;
 use tests_e2e::setup::TestSetup;
@@ -27,6 +28,8 @@ async fn reject_offer() {
         quantity: 2000.0,
         order_type: Box::new(OrderType::Market),
         stable: false,
+        time_in_force: TimeInForce::FillOrKill,
+        post_only: false,
     };
 
     // submit order for which the app does not have enough liquidity. will fail with `Failed to
@@ -68,6 +71,8 @@ async fn reject_offer() {
         quantity: 100.0,
         order_type: Box::new(OrderType::Market),
         stable: false,
+        time_in_force: TimeInForce::FillOrKill,
+        post_only: false,
     };
 
     spawn_blocking({
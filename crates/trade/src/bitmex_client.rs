@@ -1,3 +1,4 @@
+use crate::ContractSymbol;
 use crate::Direction;
 use crate::Price;
 use anyhow::bail;
@@ -15,9 +16,13 @@ use time::OffsetDateTime;
 pub struct BitmexClient {}
 
 impl BitmexClient {
-    /// gets a quote for a given timestamp. An error is returned if the provided timestamp is
-    /// greater than the current timestamp
-    pub async fn get_quote(network: &Network, timestamp: &OffsetDateTime) -> Result<Quote> {
+    /// gets a quote of the given contract for a given timestamp. An error is returned if the
+    /// provided timestamp is greater than the current timestamp
+    pub async fn get_quote(
+        network: &Network,
+        timestamp: &OffsetDateTime,
+        contract_symbol: ContractSymbol,
+    ) -> Result<Quote> {
         if OffsetDateTime::now_utc().lt(timestamp) {
             bail!("timestamp must not be in the future!")
         }
@@ -33,7 +38,9 @@ impl BitmexClient {
         let start_time = timestamp.sub(Duration::from_secs(60)).format(&format)?;
         let end_time = timestamp.format(&format)?;
 
-        let quote: Vec<Quote> = reqwest::get(format!("https://{url}/api/v1/quote?symbol=XBTUSD&count=1&reverse=false&startTime={start_time}&endTime={end_time}"))
+        let symbol = bitmex_symbol(contract_symbol);
+
+        let quote: Vec<Quote> = reqwest::get(format!("https://{url}/api/v1/quote?symbol={symbol}&count=1&reverse=false&startTime={start_time}&endTime={end_time}"))
             .await?
             .json()
             .await?;
@@ -43,6 +50,13 @@ impl BitmexClient {
     }
 }
 
+/// The symbol of the BitMEX contract used as index for the given contract.
+fn bitmex_symbol(contract_symbol: ContractSymbol) -> &'static str {
+    match contract_symbol {
+        ContractSymbol::BtcUsd => "XBTUSD",
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Quote {
@@ -90,6 +90,89 @@ pub fn calculate_pnl(
         uncapped_pnl.round_dp_with_strategy(0, rust_decimal::RoundingStrategy::MidpointTowardZero)
     };
 
+    cap_pnl(
+        uncapped_pnl_long,
+        direction,
+        initial_margin_long,
+        initial_margin_short,
+    )
+}
+
+/// Calculate the margin in sats of a position in a linear contract.
+///
+/// The price of a linear contract is quoted in sats per contract, i.e. the contract is quoted,
+/// margined and settled in bitcoin.
+pub fn calculate_linear_margin(open_price: Decimal, quantity: f32, leverage: f32) -> u64 {
+    let quantity = Decimal::try_from(quantity).expect("quantity to fit into decimal");
+    let leverage = Decimal::try_from(leverage).expect("leverage to fit into decimal");
+
+    if leverage == Decimal::ZERO {
+        // just to avoid div by 0 errors
+        return 0;
+    }
+
+    let margin = quantity * open_price / leverage;
+
+    margin
+        .round_dp_with_strategy(0, rust_decimal::RoundingStrategy::MidpointAwayFromZero)
+        .to_u64()
+        .expect("margin to fit into u64")
+}
+
+/// Calculate the liquidation price for the party going long in a linear contract.
+///
+/// The party going long is liquidated once the price dropped by `1 / leverage`.
+pub fn calculate_linear_long_liquidation_price(leverage: Decimal, price: Decimal) -> Decimal {
+    price * (leverage - Decimal::ONE) / leverage
+}
+
+/// Calculate the liquidation price for the party going short in a linear contract.
+///
+/// The party going short is liquidated once the price rose by `1 / leverage`. Contrary to an
+/// inverse contract, this price is finite even without leverage.
+pub fn calculate_linear_short_liquidation_price(leverage: Decimal, price: Decimal) -> Decimal {
+    price * (leverage + Decimal::ONE) / leverage
+}
+
+/// Compute the payout in sats of a linear contract at a particular `closing_price`.
+///
+/// The price of a linear contract is quoted in sats per contract, hence the PnL of the party
+/// going long is simply `quantity * (closing_price - opening_price)`.
+///
+/// Like with [`calculate_pnl`], the PnL is capped by the margin of the losing party.
+pub fn calculate_linear_pnl(
+    opening_price: Decimal,
+    closing_price: Decimal,
+    quantity: f32,
+    direction: Direction,
+    initial_margin_long: u64,
+    initial_margin_short: u64,
+) -> Result<i64> {
+    let uncapped_pnl_long = {
+        let quantity = Decimal::try_from(quantity).expect("quantity to fit into decimal");
+
+        let uncapped_pnl = quantity * (closing_price - opening_price);
+
+        // we need to round to zero or else we might lose some sats somewhere
+        uncapped_pnl.round_dp_with_strategy(0, rust_decimal::RoundingStrategy::MidpointTowardZero)
+    };
+
+    cap_pnl(
+        uncapped_pnl_long,
+        direction,
+        initial_margin_long,
+        initial_margin_short,
+    )
+}
+
+/// Caps the PnL (in sats) of the party going long by the margins of both parties and returns it
+/// from the perspective of the given `direction`.
+fn cap_pnl(
+    uncapped_pnl_long: Decimal,
+    direction: Direction,
+    initial_margin_long: u64,
+    initial_margin_short: u64,
+) -> Result<i64> {
     let short_margin = Decimal::from_u64(initial_margin_short).context("be able to parse u64")?;
     let long_margin = Decimal::from_u64(initial_margin_long).context("to be abble to parse u64")?;
 
@@ -465,4 +548,109 @@ mod tests {
 
         assert_eq!(pnl_short, (margin as i64).neg());
     }
+
+    #[test]
+    fn given_linear_position_when_price_same_then_zero_pnl() {
+        let opening_price = Decimal::from(50_000);
+        let quantity = 100.0;
+        let long_margin = calculate_linear_margin(opening_price, quantity, 2.0);
+        let short_margin = calculate_linear_margin(opening_price, quantity, 1.0);
+
+        let pnl_long = calculate_linear_pnl(
+            opening_price,
+            opening_price,
+            quantity,
+            Direction::Long,
+            long_margin,
+            short_margin,
+        )
+        .unwrap();
+
+        assert_eq!(pnl_long, 0);
+    }
+
+    #[test]
+    fn given_linear_long_position_when_price_10_pc_up_then_20pc_profit() {
+        let opening_price = Decimal::from(50_000);
+        let closing_price = Decimal::from(55_000);
+        let quantity = 100.0;
+        let long_margin = calculate_linear_margin(opening_price, quantity, 2.0);
+        let short_margin = calculate_linear_margin(opening_price, quantity, 1.0);
+
+        assert_eq!(long_margin, 2_500_000);
+        assert_eq!(short_margin, 5_000_000);
+
+        let pnl_long = calculate_linear_pnl(
+            opening_price,
+            closing_price,
+            quantity,
+            Direction::Long,
+            long_margin,
+            short_margin,
+        )
+        .unwrap();
+        let pnl_short = calculate_linear_pnl(
+            opening_price,
+            closing_price,
+            quantity,
+            Direction::Short,
+            long_margin,
+            short_margin,
+        )
+        .unwrap();
+
+        assert_eq!(pnl_long, 500_000);
+        assert_eq!(pnl_short, -500_000);
+    }
+
+    #[test]
+    fn given_linear_long_position_when_price_at_liquidation_price_then_lose_margin() {
+        let opening_price = Decimal::from(50_000);
+        let quantity = 100.0;
+        let long_leverage = Decimal::TWO;
+        let long_margin = calculate_linear_margin(opening_price, quantity, 2.0);
+        let short_margin = calculate_linear_margin(opening_price, quantity, 1.0);
+
+        let liquidation_price =
+            calculate_linear_long_liquidation_price(long_leverage, opening_price);
+        assert_eq!(liquidation_price, Decimal::from(25_000));
+
+        let pnl_long = calculate_linear_pnl(
+            opening_price,
+            liquidation_price,
+            quantity,
+            Direction::Long,
+            long_margin,
+            short_margin,
+        )
+        .unwrap();
+
+        assert_eq!(pnl_long, -(long_margin as i64));
+    }
+
+    #[test]
+    fn given_linear_short_position_when_price_at_liquidation_price_then_lose_margin() {
+        let opening_price = Decimal::from(50_000);
+        let quantity = 100.0;
+        let short_leverage = Decimal::TWO;
+        let long_margin = calculate_linear_margin(opening_price, quantity, 1.0);
+        let short_margin = calculate_linear_margin(opening_price, quantity, 2.0);
+
+        let liquidation_price =
+            calculate_linear_short_liquidation_price(short_leverage, opening_price);
+        assert_eq!(liquidation_price, Decimal::from(75_000));
+
+        // Beyond the liquidation price, the loss is capped by the margin.
+        let pnl_short = calculate_linear_pnl(
+            opening_price,
+            liquidation_price * Decimal::TWO,
+            quantity,
+            Direction::Short,
+            long_margin,
+            short_margin,
+        )
+        .unwrap();
+
+        assert_eq!(pnl_short, -(short_margin as i64));
+    }
 }
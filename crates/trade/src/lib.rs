@@ -1,4 +1,5 @@
 use anyhow::bail;
+use anyhow::Context;
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use serde::Serialize;
@@ -13,12 +14,69 @@ pub enum ContractSymbol {
     BtcUsd,
 }
 
+/// How the payout of a contract is calculated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayoutType {
+    /// The contract is quoted in the counter currency (e.g. USD) but margined and settled in
+    /// bitcoin, e.g. BTCUSD.
+    Inverse,
+}
+
 impl ContractSymbol {
+    /// All contracts which can be traded.
+    pub const ALL: [ContractSymbol; 1] = [ContractSymbol::BtcUsd];
+
     pub fn label(self) -> String {
         match self {
             ContractSymbol::BtcUsd => "btcusd".to_string(),
         }
     }
+
+    pub fn payout_type(self) -> PayoutType {
+        match self {
+            ContractSymbol::BtcUsd => PayoutType::Inverse,
+        }
+    }
+
+    /// The highest price the oracle can attest to for this contract.
+    ///
+    /// The oracle attests to the price in binary, hence the max price is always `2^n - 1` where
+    /// `n` is the number of digits returned by [`ContractSymbol::oracle_nb_digits`].
+    pub fn max_price(self) -> u64 {
+        match self {
+            ContractSymbol::BtcUsd => cfd::BTCUSD_MAX_PRICE,
+        }
+    }
+
+    /// The number of binary digits the oracle uses to attest to the price of this contract.
+    pub fn oracle_nb_digits(self) -> usize {
+        (u64::BITS - self.max_price().leading_zeros()) as usize
+    }
+
+    /// The id of the oracle event attesting to the price of this contract at the given maturity
+    /// time (as unix timestamp).
+    pub fn oracle_event_id(self, maturity_time: i64) -> String {
+        format!("{}{maturity_time}", self.label())
+    }
+
+    /// Parses the contract symbol from the id of an oracle event created with
+    /// [`ContractSymbol::oracle_event_id`].
+    ///
+    /// The event id has to consist of the label of a contract followed by the maturity time, so
+    /// that labels which end in digits themselves are not cut short.
+    pub fn from_oracle_event_id(event_id: &str) -> anyhow::Result<Self> {
+        ContractSymbol::ALL
+            .into_iter()
+            .find(|contract_symbol| {
+                event_id
+                    .strip_prefix(&contract_symbol.label())
+                    .is_some_and(|maturity_time| {
+                        maturity_time.chars().all(|c| c.is_ascii_digit())
+                            && maturity_time.parse::<i64>().is_ok()
+                    })
+            })
+            .with_context(|| format!("Unknown oracle event id: {event_id}"))
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -108,4 +166,35 @@ mod tests {
         );
         assert!(ContractSymbol::from_str("dogeusd").is_err());
     }
+
+    #[test]
+    fn contract_symbol_from_oracle_event_id() {
+        for contract_symbol in ContractSymbol::ALL {
+            let event_id = contract_symbol.oracle_event_id(1_707_984_000);
+
+            assert_eq!(
+                ContractSymbol::from_oracle_event_id(&event_id).unwrap(),
+                contract_symbol
+            );
+        }
+
+        assert!(ContractSymbol::from_oracle_event_id("dogeusd1707984000").is_err());
+    }
+
+    #[test]
+    fn oracle_event_id_without_maturity_time_is_rejected() {
+        assert!(ContractSymbol::from_oracle_event_id("btcusd").is_err());
+        assert!(ContractSymbol::from_oracle_event_id("btcusd-1707984000").is_err());
+        assert!(ContractSymbol::from_oracle_event_id("btcusdx1707984000").is_err());
+        assert!(ContractSymbol::from_oracle_event_id("1707984000").is_err());
+    }
+
+    #[test]
+    fn max_price_is_representable_by_oracle() {
+        for contract_symbol in ContractSymbol::ALL {
+            let nb_digits = contract_symbol.oracle_nb_digits() as u32;
+
+            assert_eq!(contract_symbol.max_price(), 2_u64.pow(nb_digits) - 1);
+        }
+    }
 }
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE hedge_executions;
+DROP TABLE tentenone_fills;
+DROP TYPE "ContractSymbol_Type";
@@ -0,0 +1,25 @@
+-- Your SQL goes here
+CREATE TYPE "ContractSymbol_Type" AS ENUM ('BtcUsd');
+
+CREATE TABLE "tentenone_fills" (
+    id SERIAL PRIMARY KEY NOT NULL,
+    order_id UUID UNIQUE NOT NULL,
+    contract_symbol "ContractSymbol_Type" NOT NULL,
+    contracts REAL NOT NULL,
+    price REAL NOT NULL,
+    timestamp TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE TABLE "hedge_executions" (
+    id SERIAL PRIMARY KEY NOT NULL,
+    execution_id TEXT UNIQUE NOT NULL,
+    contract_symbol "ContractSymbol_Type" NOT NULL,
+    contracts INTEGER NOT NULL,
+    price REAL NOT NULL,
+    reference_price REAL,
+    fee_sats BIGINT NOT NULL,
+    timestamp TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX tentenone_fills_timestamp ON tentenone_fills (timestamp);
+CREATE INDEX hedge_executions_timestamp ON hedge_executions (timestamp);
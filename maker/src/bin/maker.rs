@@ -1,6 +1,7 @@
 use anyhow::Context;
 use anyhow::Result;
 use bitcoin::Network;
+use bitmex_stream::Credentials;
 use diesel::r2d2;
 use diesel::r2d2::ConnectionManager;
 use diesel::PgConnection;
@@ -9,6 +10,7 @@ use ln_dlc_node::node::InMemoryStore;
 use ln_dlc_node::seed::Bip39Seed;
 use ln_dlc_node::WalletSettings;
 use maker::cli::Opts;
+use maker::cli::Venue;
 use maker::health;
 use maker::ln::ldk_config;
 use maker::ln::EventHandler;
@@ -17,12 +19,20 @@ use maker::logger;
 use maker::metrics;
 use maker::metrics::init_meter;
 use maker::orderbook_ws;
+use maker::pnl::Period;
 use maker::position;
+use maker::position::GetPnl;
 use maker::probing::send_payment_probes_regularly;
+use maker::risk::RiskLimits;
+use maker::risk::RiskMonitor;
 use maker::routes::router;
 use maker::run_migration;
 use maker::storage::MakerTenTenOneStorage;
 use maker::trading;
+use maker::trading::strategy::LadderStrategy;
+use maker::trading::strategy::RequoteLimiter;
+use maker::venue;
+use maker::venue::HedgingVenue;
 use rand::thread_rng;
 use rand::RngCore;
 use std::backtrace::Backtrace;
@@ -114,43 +124,92 @@ async fn main() -> Result<()> {
 
     let (health, health_tx) = health::Health::new();
 
-    let bitmex_http_client = bitmex_client::client::Client::new(match network {
-        Network::Bitcoin => bitmex_client::models::Network::Mainnet,
-        _ => bitmex_client::models::Network::Testnet,
-    });
-
-    let bitmex_http_client = match (bitmex_api_key.clone(), bitmex_api_secret.clone()) {
-        (Some(bitmex_api_key), Some(bitmex_secret)) => {
-            tracing::info!("BitMEX credentials provided");
-            bitmex_http_client.with_credentials(bitmex_api_key, bitmex_secret)
+    let venue: Arc<dyn HedgingVenue> = match opts.hedging_venue {
+        Venue::Bitmex => {
+            let bitmex_http_client = bitmex_client::client::Client::new(match network {
+                Network::Bitcoin => bitmex_client::models::Network::Mainnet,
+                _ => bitmex_client::models::Network::Testnet,
+            });
+
+            let (bitmex_http_client, credentials) = match (bitmex_api_key, bitmex_api_secret) {
+                (Some(api_key), Some(secret)) => {
+                    tracing::info!("BitMEX credentials provided");
+                    (
+                        bitmex_http_client.with_credentials(api_key.clone(), secret.clone()),
+                        Some(Credentials { api_key, secret }),
+                    )
+                }
+                _ => {
+                    tracing::info!("BitMEX credentials not provided");
+                    (bitmex_http_client, None)
+                }
+            };
+
+            Arc::new(venue::bitmex::Bitmex::new(
+                bitmex_http_client,
+                match network {
+                    Network::Bitcoin => bitmex_stream::Network::Mainnet,
+                    _ => bitmex_stream::Network::Testnet,
+                },
+                credentials,
+                opts.hedging_lot_size,
+            ))
         }
-        _ => {
-            tracing::info!("BitMEX credentials not provided");
-            bitmex_http_client
+        Venue::Simulated => {
+            tracing::info!("Hedging on simulated venue");
+            Arc::new(venue::simulated::SimulatedVenue::new(opts.hedging_lot_size))
         }
     };
 
+    let manager = ConnectionManager::<PgConnection>::new(opts.database);
+    let pool = r2d2::Pool::builder()
+        .build(manager)
+        .expect("Failed to create pool.");
+
+    let mut conn = pool.get().expect("to get connection from pool");
+    run_migration(&mut conn);
+
+    let (risk_monitor, risk_status) = RiskMonitor::new(RiskLimits {
+        max_position: opts.max_position,
+        max_unhedged: opts.max_unhedged,
+        max_daily_loss: opts.max_daily_loss_sats,
+    });
+
     let (position_manager, mailbox) = xtra::Mailbox::unbounded();
     tokio::spawn(xtra::run(
         mailbox,
-        position::Manager::new(bitmex_http_client),
+        position::Manager::new(venue, risk_monitor, pool),
     ));
 
     let node_pubkey = node.info.pubkey;
+    let node_key = node.node_key();
     tokio::spawn({
         let orderbook_url = opts.orderbook.clone();
         let position_manager = position_manager.clone();
+        let risk_status = risk_status.clone();
         async move {
             trading::run(
                 &orderbook_url,
-                node_pubkey,
+                node_key,
                 network,
-                opts.concurrent_orders,
+                LadderStrategy {
+                    spread_bps: opts.spread_bps,
+                    level_step_bps: opts.level_step_bps,
+                    level_sizes: opts.level_sizes,
+                    max_skew_bps: opts.max_skew_bps,
+                    max_inventory: opts.max_inventory,
+                },
+                // Orders are replaced halfway through their lifetime, so that the maker never runs
+                // out of orders in the orderbook.
+                RequoteLimiter::new(
+                    Duration::from_secs(opts.min_requote_interval_seconds),
+                    Duration::from_secs(opts.order_expiry_after_seconds / 2),
+                    opts.requote_threshold_bps,
+                ),
                 time::Duration::seconds(opts.order_expiry_after_seconds as i64),
                 health_tx.bitmex_pricefeed,
                 position_manager,
-                bitmex_api_key,
-                bitmex_api_secret,
+                risk_status,
                 PRICEFEED_RECONNECT_INTERVAL,
             )
             .await;
@@ -169,11 +228,27 @@ async fn main() -> Result<()> {
     let _collect_prometheus_metrics = tokio::spawn({
         let node = node.clone();
         let health = health.clone();
+        let risk_status = risk_status.clone();
+        let position_manager = position_manager.clone();
         async move {
             loop {
                 let node = node.clone();
                 let health = health.clone();
-                spawn_blocking(move || metrics::collect(node, health))
+                let risk_status = *risk_status.borrow();
+                let pnl = match position_manager
+                    .send(GetPnl {
+                        period: Period::Day,
+                    })
+                    .await
+                {
+                    Ok(Ok(report)) => Some(report.total),
+                    Ok(Err(e)) => {
+                        tracing::error!("Failed to get PnL for metrics: {e:#}");
+                        None
+                    }
+                    Err(_) => None,
+                };
+                spawn_blocking(move || metrics::collect(node, health, risk_status, pnl))
                     .await
                     .expect("To spawn blocking thread");
                 tokio::time::sleep(PROCESS_PROMETHEUS_METRICS).await;
@@ -181,14 +256,6 @@ async fn main() -> Result<()> {
         }
     });
 
-    let manager = ConnectionManager::<PgConnection>::new(opts.database);
-    let pool = r2d2::Pool::builder()
-        .build(manager)
-        .expect("Failed to create pool.");
-
-    let mut conn = pool.get().expect("to get connection from pool");
-    run_migration(&mut conn);
-
     orderbook_ws::Client::new(
         opts.orderbook,
         node_pubkey,
@@ -205,6 +272,7 @@ async fn main() -> Result<()> {
         exporter,
         position_manager,
         health,
+        risk_status,
         announcement_addresses.clone(),
         node_alias,
     );
@@ -2,6 +2,7 @@ use anyhow::Result;
 use clap::Parser;
 use ln_dlc_node::node::OracleInfo;
 use reqwest::Url;
+use rust_decimal::Decimal;
 use std::env::current_dir;
 use std::net::SocketAddr;
 use std::path::PathBuf;
@@ -42,9 +43,39 @@ pub struct Opts {
     #[clap(short, long)]
     pub json: bool,
 
-    /// Amount of concurrent orders (buy,sell) that the maker will create at a time.
+    /// Spread between the best buy and sell order of the maker, in basis points of BitMEX's mid
+    /// price.
+    #[clap(long, default_value = "10")]
+    pub spread_bps: Decimal,
+
+    /// Distance between two price levels of the maker on the same side, in basis points.
+    #[clap(long, default_value = "5")]
+    pub level_step_bps: Decimal,
+
+    /// Comma-separated quantities in contracts of the price levels on each side, best level first.
+    #[clap(
+        long,
+        value_delimiter = ',',
+        default_value = "5000,5000,5000,5000,5000"
+    )]
+    pub level_sizes: Vec<Decimal>,
+
+    /// Maximum shift of all price levels against the inventory of the maker, in basis points.
+    #[clap(long, default_value = "10")]
+    pub max_skew_bps: Decimal,
+
+    /// Inventory in contracts at which the price levels are shifted by `max_skew_bps`.
+    #[clap(long, default_value = "50000")]
+    pub max_inventory: Decimal,
+
+    /// Minimum number of seconds between two replacements of the orders of the maker.
     #[clap(long, default_value = "5")]
-    pub concurrent_orders: usize,
+    pub min_requote_interval_seconds: u64,
+
+    /// Minimum move of BitMEX's mid price in basis points for the orders of the maker to be
+    /// replaced.
+    #[clap(long, default_value = "2")]
+    pub requote_threshold_bps: Decimal,
 
     /// Orders created by maker will be valid for this number of seconds.
     #[clap(long, default_value = "60")]
@@ -61,6 +92,30 @@ pub struct Opts {
     )]
     oracle_pubkey: String,
 
+    /// Where to hedge the 10101 position of the maker. The simulated venue fills all orders
+    /// immediately at BitMEX's mid price, which allows running the maker in paper mode.
+    #[clap(long, value_enum, default_value = "bitmex")]
+    pub hedging_venue: Venue,
+
+    /// The number of contracts in one lot on the hedging venue. The maker only hedges whole lots.
+    #[clap(long, default_value = "100", value_parser = clap::value_parser!(u32).range(1..))]
+    pub hedging_lot_size: u32,
+
+    /// Maximum absolute 10101 position in contracts before the kill switch is engaged, pulling all
+    /// orders of the maker.
+    #[clap(long, default_value = "100000")]
+    pub max_position: Decimal,
+
+    /// Maximum absolute difference in contracts between the 10101 position and the position on
+    /// the hedging venue before the kill switch is engaged.
+    #[clap(long, default_value = "10000")]
+    pub max_unhedged: Decimal,
+
+    /// Maximum loss in satoshi of the unhedged position within one UTC day before the kill switch
+    /// is engaged.
+    #[clap(long, default_value = "1000000")]
+    pub max_daily_loss_sats: Decimal,
+
     /// BitMEX API key.
     #[clap(long)]
     pub bitmex_api_key: Option<String>,
@@ -82,6 +137,12 @@ pub enum Network {
     Mainnet,
 }
 
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum Venue {
+    Bitmex,
+    Simulated,
+}
+
 impl From<Network> for bitcoin::Network {
     fn from(network: Network) -> Self {
         match network {
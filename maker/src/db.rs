@@ -0,0 +1,3 @@
+pub mod custom_types;
+pub mod hedge_executions;
+pub mod tentenone_fills;
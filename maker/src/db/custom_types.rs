@@ -0,0 +1,63 @@
+use crate::schema::sql_types::ContractSymbolType;
+use diesel::deserialize;
+use diesel::deserialize::FromSql;
+use diesel::pg::Pg;
+use diesel::pg::PgValue;
+use diesel::query_builder::QueryId;
+use diesel::serialize;
+use diesel::serialize::IsNull;
+use diesel::serialize::Output;
+use diesel::serialize::ToSql;
+use diesel::AsExpression;
+use diesel::FromSqlRow;
+use std::any::TypeId;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, FromSqlRow, AsExpression)]
+#[diesel(sql_type = ContractSymbolType)]
+pub enum ContractSymbol {
+    BtcUsd,
+}
+
+impl QueryId for ContractSymbolType {
+    type QueryId = ContractSymbolType;
+    const HAS_STATIC_QUERY_ID: bool = false;
+
+    fn query_id() -> Option<TypeId> {
+        None
+    }
+}
+
+impl ToSql<ContractSymbolType, Pg> for ContractSymbol {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            ContractSymbol::BtcUsd => out.write_all(b"BtcUsd")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<ContractSymbolType, Pg> for ContractSymbol {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"BtcUsd" => Ok(ContractSymbol::BtcUsd),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+impl From<ContractSymbol> for crate::position::ContractSymbol {
+    fn from(value: ContractSymbol) -> Self {
+        match value {
+            ContractSymbol::BtcUsd => crate::position::ContractSymbol::BtcUsd,
+        }
+    }
+}
+
+impl From<crate::position::ContractSymbol> for ContractSymbol {
+    fn from(value: crate::position::ContractSymbol) -> Self {
+        match value {
+            crate::position::ContractSymbol::BtcUsd => ContractSymbol::BtcUsd,
+        }
+    }
+}
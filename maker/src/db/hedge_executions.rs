@@ -0,0 +1,93 @@
+use crate::db::custom_types::ContractSymbol;
+use crate::schema::hedge_executions;
+use diesel::prelude::*;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use time::OffsetDateTime;
+
+#[derive(Queryable, Debug, Clone)]
+struct HedgeExecution {
+    // this id is only internally but needs to be here or diesel complains
+    #[allow(dead_code)]
+    id: i32,
+    execution_id: String,
+    contract_symbol: ContractSymbol,
+    contracts: i32,
+    price: f32,
+    reference_price: Option<f32>,
+    fee_sats: i64,
+    timestamp: OffsetDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = hedge_executions)]
+struct NewHedgeExecution {
+    execution_id: String,
+    contract_symbol: ContractSymbol,
+    contracts: i32,
+    price: f32,
+    reference_price: Option<f32>,
+    fee_sats: i64,
+    timestamp: OffsetDateTime,
+}
+
+/// Inserts the given execution, unless it has already been recorded.
+///
+/// Returns `true` if the execution is new.
+pub fn insert(conn: &mut PgConnection, execution: crate::pnl::HedgeExecution) -> QueryResult<bool> {
+    let affected_rows = diesel::insert_into(hedge_executions::table)
+        .values(NewHedgeExecution::from(execution))
+        .on_conflict(hedge_executions::execution_id)
+        .do_nothing()
+        .execute(conn)?;
+
+    Ok(affected_rows > 0)
+}
+
+/// Returns all executions, ordered by their timestamp.
+pub fn get_all(conn: &mut PgConnection) -> QueryResult<Vec<crate::pnl::HedgeExecution>> {
+    let executions = hedge_executions::table
+        .order_by((
+            hedge_executions::timestamp.asc(),
+            hedge_executions::id.asc(),
+        ))
+        .load::<HedgeExecution>(conn)?;
+
+    Ok(executions
+        .into_iter()
+        .map(crate::pnl::HedgeExecution::from)
+        .collect())
+}
+
+impl From<crate::pnl::HedgeExecution> for NewHedgeExecution {
+    fn from(value: crate::pnl::HedgeExecution) -> Self {
+        NewHedgeExecution {
+            execution_id: value.execution_id,
+            contract_symbol: value.contract_symbol.into(),
+            contracts: value.contracts,
+            price: value.price.to_f32().expect("to fit into f32"),
+            reference_price: value
+                .reference_price
+                .map(|price| price.to_f32().expect("to fit into f32")),
+            fee_sats: value.fee_sats,
+            timestamp: value.timestamp,
+        }
+    }
+}
+
+impl From<HedgeExecution> for crate::pnl::HedgeExecution {
+    fn from(value: HedgeExecution) -> Self {
+        crate::pnl::HedgeExecution {
+            execution_id: value.execution_id,
+            contract_symbol: value.contract_symbol.into(),
+            contracts: value.contracts,
+            price: Decimal::from_f32(value.price).expect("to fit into decimal"),
+            reference_price: value
+                .reference_price
+                .map(|price| Decimal::from_f32(price).expect("to fit into decimal")),
+            fee_sats: value.fee_sats,
+            timestamp: value.timestamp,
+        }
+    }
+}
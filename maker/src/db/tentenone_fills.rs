@@ -0,0 +1,89 @@
+use crate::db::custom_types::ContractSymbol;
+use crate::schema::tentenone_fills;
+use diesel::prelude::*;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Queryable, Debug, Clone)]
+struct TenTenOneFill {
+    // this id is only internally but needs to be here or diesel complains
+    #[allow(dead_code)]
+    id: i32,
+    order_id: Uuid,
+    contract_symbol: ContractSymbol,
+    contracts: f32,
+    price: f32,
+    timestamp: OffsetDateTime,
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = tentenone_fills)]
+struct NewTenTenOneFill {
+    order_id: Uuid,
+    contract_symbol: ContractSymbol,
+    contracts: f32,
+    price: f32,
+    timestamp: OffsetDateTime,
+}
+
+/// Inserts the given fills, ignoring the ones which have already been recorded.
+///
+/// Returns the number of newly recorded fills.
+pub fn insert(conn: &mut PgConnection, fills: &[crate::pnl::TenTenOneFill]) -> QueryResult<usize> {
+    let mut inserted = 0;
+
+    // Postgres limits the number of bind parameters of a single query.
+    for fills in fills.chunks(1_000) {
+        let fills = fills
+            .iter()
+            .map(|fill| NewTenTenOneFill::from(*fill))
+            .collect::<Vec<_>>();
+
+        inserted += diesel::insert_into(tentenone_fills::table)
+            .values(fills)
+            .on_conflict(tentenone_fills::order_id)
+            .do_nothing()
+            .execute(conn)?;
+    }
+
+    Ok(inserted)
+}
+
+/// Returns all fills, ordered by their timestamp.
+pub fn get_all(conn: &mut PgConnection) -> QueryResult<Vec<crate::pnl::TenTenOneFill>> {
+    let fills = tentenone_fills::table
+        .order_by((tentenone_fills::timestamp.asc(), tentenone_fills::id.asc()))
+        .load::<TenTenOneFill>(conn)?;
+
+    Ok(fills
+        .into_iter()
+        .map(crate::pnl::TenTenOneFill::from)
+        .collect())
+}
+
+impl From<crate::pnl::TenTenOneFill> for NewTenTenOneFill {
+    fn from(value: crate::pnl::TenTenOneFill) -> Self {
+        NewTenTenOneFill {
+            order_id: value.order_id,
+            contract_symbol: value.contract_symbol.into(),
+            contracts: value.contracts.to_f32().expect("to fit into f32"),
+            price: value.price.to_f32().expect("to fit into f32"),
+            timestamp: value.timestamp,
+        }
+    }
+}
+
+impl From<TenTenOneFill> for crate::pnl::TenTenOneFill {
+    fn from(value: TenTenOneFill) -> Self {
+        crate::pnl::TenTenOneFill {
+            order_id: value.order_id,
+            contract_symbol: value.contract_symbol.into(),
+            contracts: Decimal::from_f32(value.contracts).expect("to fit into decimal"),
+            price: Decimal::from_f32(value.price).expect("to fit into decimal"),
+            timestamp: value.timestamp,
+        }
+    }
+}
@@ -2,22 +2,28 @@ use diesel::PgConnection;
 use diesel_migrations::embed_migrations;
 use diesel_migrations::EmbeddedMigrations;
 use diesel_migrations::MigrationHarness;
+use ln_dlc_node::node::ChainSourceConfig;
 use ln_dlc_node::node::GossipSourceConfig;
 use ln_dlc_node::node::LnDlcNodeSettings;
+use ln_dlc_node::FeeRateEstimatorConfig;
 use std::time::Duration;
 
 pub mod cli;
+pub mod db;
 pub mod health;
 pub mod ln;
 pub mod logger;
 pub mod metrics;
 pub mod orderbook_ws;
+pub mod pnl;
 pub mod position;
 pub mod probing;
+pub mod risk;
 pub mod routes;
 pub mod schema;
 pub mod storage;
 pub mod trading;
+pub mod venue;
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
 
@@ -43,5 +49,7 @@ pub fn ln_dlc_node_settings(rgs_server_url: Option<String>) -> LnDlcNodeSettings
         bdk_client_stop_gap: 20,
         bdk_client_concurrency: 4,
         gossip_source_config,
+        chain_source_config: ChainSourceConfig::Esplora,
+        fee_rate_estimator_config: FeeRateEstimatorConfig::default(),
     }
 }
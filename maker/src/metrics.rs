@@ -1,5 +1,8 @@
 use crate::health::Health;
 use crate::health::ServiceStatus;
+use crate::pnl::Pnl;
+use crate::risk::KillSwitch;
+use crate::risk::RiskStatus;
 use crate::storage::MakerTenTenOneStorage;
 use lazy_static::lazy_static;
 use lightning::ln::channelmanager::ChannelDetails;
@@ -15,6 +18,7 @@ use opentelemetry::sdk::metrics::selectors;
 use opentelemetry::Context;
 use opentelemetry::KeyValue;
 use opentelemetry_prometheus::PrometheusExporter;
+use rust_decimal::prelude::ToPrimitive;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -61,6 +65,42 @@ lazy_static! {
         .u64_observable_gauge("node_balance_satoshi")
         .with_description("Node balance in satoshi")
         .init();
+
+    // risk metrics
+    pub static ref KILL_SWITCH_ENGAGED: ObservableGauge<u64> = METER
+        .u64_observable_gauge("kill_switch_engaged")
+        .with_description("If the kill switch is engaged")
+        .init();
+    pub static ref KILL_SWITCH_ORDERS_PULLED: ObservableGauge<u64> = METER
+        .u64_observable_gauge("kill_switch_orders_pulled")
+        .with_description("If all orders have been pulled since the kill switch has been engaged")
+        .init();
+    pub static ref RISK_POSITION_CONTRACTS: ObservableGauge<f64> = METER
+        .f64_observable_gauge("risk_position_contracts")
+        .with_description("Current 10101 position in contracts")
+        .init();
+    pub static ref RISK_UNHEDGED_CONTRACTS: ObservableGauge<f64> = METER
+        .f64_observable_gauge("risk_unhedged_contracts")
+        .with_description("Current unhedged 10101 position in contracts")
+        .init();
+    pub static ref RISK_DAILY_PNL_SATOSHI: ObservableGauge<f64> = METER
+        .f64_observable_gauge("risk_daily_pnl_satoshi")
+        .with_description("PnL of the unhedged position in satoshi in the current UTC day")
+        .init();
+
+    // pnl metrics
+    pub static ref PNL_SATOSHI: ObservableGauge<f64> = METER
+        .f64_observable_gauge("pnl_satoshi")
+        .with_description("Total realised and unrealised PnL in satoshi")
+        .init();
+    pub static ref HEDGE_FEES_SATOSHI: ObservableGauge<f64> = METER
+        .f64_observable_gauge("hedge_fees_satoshi")
+        .with_description("Total fees paid to the hedging venue in satoshi")
+        .init();
+    pub static ref HEDGE_SLIPPAGE_SATOSHI: ObservableGauge<f64> = METER
+        .f64_observable_gauge("hedge_slippage_satoshi")
+        .with_description("Total slippage of hedge executions against the mid price in satoshi")
+        .init();
 }
 
 pub fn init_meter() -> PrometheusExporter {
@@ -74,13 +114,82 @@ pub fn init_meter() -> PrometheusExporter {
     opentelemetry_prometheus::exporter(controller).init()
 }
 
-pub fn collect(node: Arc<Node<MakerTenTenOneStorage, InMemoryStore>>, health: Health) {
+pub fn collect(
+    node: Arc<Node<MakerTenTenOneStorage, InMemoryStore>>,
+    health: Health,
+    risk_status: RiskStatus,
+    pnl: Option<Pnl>,
+) {
     let cx = opentelemetry::Context::current();
 
     let channels = node.channel_manager.list_channels();
     channel_metrics(&cx, channels);
     node_metrics(&cx, node);
     health_metrics(&cx, &health);
+    risk_metrics(&cx, risk_status);
+
+    if let Some(pnl) = pnl {
+        pnl_metrics(&cx, pnl);
+    }
+}
+
+fn pnl_metrics(cx: &Context, pnl: Pnl) {
+    PNL_SATOSHI.observe(
+        cx,
+        pnl.realised.to_f64().expect("realised PnL to fit into f64"),
+        &[KeyValue::new("type", "realised")],
+    );
+    PNL_SATOSHI.observe(
+        cx,
+        pnl.unrealised
+            .to_f64()
+            .expect("unrealised PnL to fit into f64"),
+        &[KeyValue::new("type", "unrealised")],
+    );
+    HEDGE_FEES_SATOSHI.observe(cx, pnl.fees.to_f64().expect("fees to fit into f64"), &[]);
+    HEDGE_SLIPPAGE_SATOSHI.observe(
+        cx,
+        pnl.hedge_slippage
+            .to_f64()
+            .expect("hedge slippage to fit into f64"),
+        &[],
+    );
+}
+
+fn risk_metrics(cx: &Context, risk_status: RiskStatus) {
+    match risk_status.kill_switch {
+        KillSwitch::Released => KILL_SWITCH_ENGAGED.observe(cx, 0, &[]),
+        KillSwitch::Engaged(reason) => {
+            KILL_SWITCH_ENGAGED.observe(cx, 1, &[KeyValue::new("reason", format!("{reason:?}"))])
+        }
+    }
+
+    KILL_SWITCH_ORDERS_PULLED.observe(cx, risk_status.orders_pulled as u64, &[]);
+
+    RISK_POSITION_CONTRACTS.observe(
+        cx,
+        risk_status
+            .position
+            .to_f64()
+            .expect("position to fit into f64"),
+        &[],
+    );
+    RISK_UNHEDGED_CONTRACTS.observe(
+        cx,
+        risk_status
+            .unhedged
+            .to_f64()
+            .expect("unhedged position to fit into f64"),
+        &[],
+    );
+    RISK_DAILY_PNL_SATOSHI.observe(
+        cx,
+        risk_status
+            .daily_pnl
+            .to_f64()
+            .expect("daily PnL to fit into f64"),
+        &[],
+    );
 }
 
 fn health_metrics(cx: &Context, health: &Health) {
@@ -162,12 +162,13 @@ async fn process_message(
 
             let orders = matches
                 .into_iter()
-                .map(|(order_id, contracts)| {
+                .map(|filled_match| {
                     OrderTenTenOne::new(
-                        order_id,
-                        // TODO: Get `ContractSymbol` from the orderbook.
-                        position::ContractSymbol::BtcUsd,
-                        contracts,
+                        filled_match.order_id,
+                        position::ContractSymbol::from(filled_match.contract_symbol),
+                        filled_match.quantity,
+                        filled_match.execution_price,
+                        filled_match.timestamp,
                     )
                 })
                 .collect::<Vec<_>>();
@@ -197,7 +198,11 @@ async fn process_message(
         | Message::AsyncMatch { .. }
         | Message::Rollover { .. }
         | Message::DlcChannelCollaborativeRevert { .. }
-        | Message::CollaborativeRevert { .. } => {
+        | Message::CollaborativeRevert { .. }
+        | Message::MarketTrade(_)
+        | Message::Candle(_)
+        | Message::DepthSnapshot(_)
+        | Message::DepthUpdate(_) => {
             // Nothing to do.
         }
     }
@@ -0,0 +1,367 @@
+use crate::position::ContractSymbol;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::UtcOffset;
+use uuid::Uuid;
+
+const SATS_PER_BTC: Decimal = dec!(100_000_000);
+
+/// A fill of one of the maker's orders on 10101.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TenTenOneFill {
+    pub order_id: Uuid,
+    pub contract_symbol: ContractSymbol,
+    /// The sign determines the direction: positive is long; negative is short.
+    pub contracts: Decimal,
+    pub price: Decimal,
+    pub timestamp: OffsetDateTime,
+}
+
+/// An execution of one of the maker's hedging orders on the hedging venue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgeExecution {
+    pub execution_id: String,
+    pub contract_symbol: ContractSymbol,
+    /// The sign determines the direction: positive bought; negative sold.
+    pub contracts: i32,
+    pub price: Decimal,
+    /// The mid price at the time of the execution, used to measure the slippage of the hedge.
+    pub reference_price: Option<Decimal>,
+    /// The fee paid to the venue in sats. Negative for rebates.
+    pub fee_sats: i64,
+    pub timestamp: OffsetDateTime,
+}
+
+/// The length of the periods over which the PnL is aggregated. Periods start at UTC boundaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Hour,
+    #[default]
+    Day,
+    Month,
+}
+
+/// The PnL of the maker in sats.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct Pnl {
+    /// The PnL of closed contracts, before fees.
+    pub realised: Decimal,
+    /// The PnL of the open contracts, marked to the latest known price.
+    pub unrealised: Decimal,
+    /// The fees paid to the hedging venue.
+    pub fees: Decimal,
+    /// The cost of hedging at a worse price than the mid price at the time of the execution.
+    pub hedge_slippage: Decimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PeriodPnl {
+    #[serde(with = "time::serde::rfc3339")]
+    pub start: OffsetDateTime,
+    #[serde(flatten)]
+    pub pnl: Pnl,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PnlReport {
+    pub total: Pnl,
+    pub periods: Vec<PeriodPnl>,
+}
+
+/// Replays the journal of the maker to compute its PnL in every `period` with trades.
+///
+/// 10101 fills are booked against the hedge executions, i.e. with the opposite sign, since the
+/// position on the hedging venue follows the 10101 position. The unrealised PnL of a period is
+/// marked to the last traded price within it, except for the current period, which is marked to
+/// `mark_price` if known.
+pub fn pnl_report(
+    fills: &[TenTenOneFill],
+    executions: &[HedgeExecution],
+    period: Period,
+    mark_price: Option<Decimal>,
+) -> PnlReport {
+    let mut trades = fills
+        .iter()
+        .map(Trade::from)
+        .chain(executions.iter().map(Trade::from))
+        .collect::<Vec<_>>();
+    trades.sort_by_key(|trade| trade.timestamp);
+
+    let mut book = Book::default();
+    let mut periods: Vec<PeriodPnl> = vec![];
+    let mut last_price = None;
+
+    for trade in trades {
+        let start = period.start(trade.timestamp);
+        if periods.last().map(|last| last.start) != Some(start) {
+            periods.push(PeriodPnl {
+                start,
+                pnl: Pnl::default(),
+            });
+        }
+
+        let current = &mut periods.last_mut().expect("period to exist").pnl;
+        current.realised += book.apply(trade.contracts, trade.price);
+        current.unrealised = book.unrealised(trade.price);
+        current.fees += trade.fee;
+        current.hedge_slippage += trade.slippage;
+
+        last_price = Some(trade.price);
+    }
+
+    if let (Some(current), Some(mark_price)) = (periods.last_mut(), mark_price) {
+        current.pnl.unrealised = book.unrealised(mark_price);
+    }
+
+    let total = Pnl {
+        realised: periods.iter().map(|period| period.pnl.realised).sum(),
+        unrealised: match mark_price.or(last_price) {
+            Some(price) => book.unrealised(price),
+            None => Decimal::ZERO,
+        },
+        fees: periods.iter().map(|period| period.pnl.fees).sum(),
+        hedge_slippage: periods.iter().map(|period| period.pnl.hedge_slippage).sum(),
+    };
+
+    PnlReport { total, periods }
+}
+
+impl Period {
+    fn start(&self, timestamp: OffsetDateTime) -> OffsetDateTime {
+        let timestamp = timestamp.to_offset(UtcOffset::UTC);
+        let date = timestamp.date();
+
+        let start = match self {
+            Period::Hour => date.with_hms(timestamp.hour(), 0, 0).expect("valid time"),
+            Period::Day => date.midnight(),
+            Period::Month => date.replace_day(1).expect("valid day").midnight(),
+        };
+
+        start.assume_utc()
+    }
+}
+
+/// A trade in the combined book of the maker.
+#[derive(Debug, Clone, Copy)]
+struct Trade {
+    contracts: Decimal,
+    price: Decimal,
+    fee: Decimal,
+    slippage: Decimal,
+    timestamp: OffsetDateTime,
+}
+
+impl From<&TenTenOneFill> for Trade {
+    fn from(fill: &TenTenOneFill) -> Self {
+        Self {
+            contracts: -fill.contracts,
+            price: fill.price,
+            fee: Decimal::ZERO,
+            slippage: Decimal::ZERO,
+            timestamp: fill.timestamp,
+        }
+    }
+}
+
+impl From<&HedgeExecution> for Trade {
+    fn from(execution: &HedgeExecution) -> Self {
+        let contracts = Decimal::from(execution.contracts);
+
+        let slippage = match execution.reference_price {
+            Some(mid) => contracts * (Decimal::ONE / mid - Decimal::ONE / execution.price),
+            None => Decimal::ZERO,
+        };
+
+        Self {
+            contracts,
+            price: execution.price,
+            fee: Decimal::from(execution.fee_sats),
+            slippage: slippage * SATS_PER_BTC,
+            timestamp: execution.timestamp,
+        }
+    }
+}
+
+/// The net position of the maker in inverse contracts, at its average entry price.
+#[derive(Debug, Default)]
+struct Book {
+    contracts: Decimal,
+    average_price: Decimal,
+}
+
+impl Book {
+    /// Adds the trade to the position, returning the realised PnL in sats.
+    fn apply(&mut self, contracts: Decimal, price: Decimal) -> Decimal {
+        if contracts.is_zero() {
+            return Decimal::ZERO;
+        }
+
+        if self.contracts.is_zero()
+            || self.contracts.is_sign_positive() == contracts.is_sign_positive()
+        {
+            let value = match self.contracts.is_zero() {
+                true => Decimal::ZERO,
+                false => self.contracts.abs() / self.average_price,
+            };
+
+            // The average entry price of inverse contracts is the harmonic mean of the prices.
+            self.contracts += contracts;
+            self.average_price = self.contracts.abs() / (value + contracts.abs() / price);
+
+            return Decimal::ZERO;
+        }
+
+        let closed = contracts.abs().min(self.contracts.abs());
+        let closed = match self.contracts.is_sign_positive() {
+            true => closed,
+            false => -closed,
+        };
+        let realised =
+            closed * (Decimal::ONE / self.average_price - Decimal::ONE / price) * SATS_PER_BTC;
+
+        let flipped = contracts.abs() > self.contracts.abs();
+        self.contracts += contracts;
+
+        if self.contracts.is_zero() {
+            self.average_price = Decimal::ZERO;
+        } else if flipped {
+            self.average_price = price;
+        }
+
+        realised
+    }
+
+    fn unrealised(&self, price: Decimal) -> Decimal {
+        if self.contracts.is_zero() {
+            return Decimal::ZERO;
+        }
+
+        self.contracts * (Decimal::ONE / self.average_price - Decimal::ONE / price) * SATS_PER_BTC
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn fill(contracts: Decimal, price: Decimal, timestamp: OffsetDateTime) -> TenTenOneFill {
+        TenTenOneFill {
+            order_id: Uuid::new_v4(),
+            contract_symbol: ContractSymbol::BtcUsd,
+            contracts,
+            price,
+            timestamp,
+        }
+    }
+
+    fn execution(
+        contracts: i32,
+        price: Decimal,
+        reference_price: Decimal,
+        timestamp: OffsetDateTime,
+    ) -> HedgeExecution {
+        HedgeExecution {
+            execution_id: Uuid::new_v4().to_string(),
+            contract_symbol: ContractSymbol::BtcUsd,
+            contracts,
+            price,
+            reference_price: Some(reference_price),
+            fee_sats: 10,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn book_realises_pnl_of_inverse_contracts() {
+        let mut book = Book::default();
+
+        assert_eq!(book.apply(dec!(1_000), dec!(40_000)), dec!(0));
+        assert_eq!(book.apply(dec!(1_000), dec!(50_000)), dec!(0));
+
+        // The harmonic mean of 40,000 and 50,000.
+        assert_eq!(book.average_price.round_dp(2), dec!(44_444.44));
+
+        // Closing 1,000 of 2,000 contracts at 50,000 gains 0.0025 BTC.
+        assert_eq!(
+            book.apply(dec!(-1_000), dec!(50_000)).round(),
+            dec!(250_000)
+        );
+        assert_eq!(book.unrealised(dec!(50_000)).round(), dec!(250_000));
+
+        // Flipping the position opens the remaining contracts at the new price.
+        assert_eq!(
+            book.apply(dec!(-1_500), dec!(40_000)).round(),
+            dec!(-250_000)
+        );
+        assert_eq!(book.contracts, dec!(-500));
+        assert_eq!(book.average_price, dec!(40_000));
+    }
+
+    #[test]
+    fn hedged_fill_realises_spread_minus_costs() {
+        // A 10101 fill at 50,000 is hedged at 40,000, which was the mid price at the time.
+        let report = pnl_report(
+            &[fill(
+                dec!(1_000),
+                dec!(50_000),
+                datetime!(2024-02-20 10:00 UTC),
+            )],
+            &[execution(
+                1_000,
+                dec!(40_000),
+                dec!(40_000),
+                datetime!(2024-02-20 10:01 UTC),
+            )],
+            Period::Day,
+            Some(dec!(45_000)),
+        );
+
+        assert_eq!(
+            report.total,
+            Pnl {
+                realised: dec!(500_000),
+                unrealised: dec!(0),
+                fees: dec!(10),
+                hedge_slippage: dec!(0),
+            }
+        );
+    }
+
+    #[test]
+    fn pnl_is_aggregated_per_period() {
+        let report = pnl_report(
+            &[
+                fill(dec!(-1_000), dec!(40_000), datetime!(2024-02-20 10:00 UTC)),
+                fill(dec!(-1_000), dec!(40_000), datetime!(2024-02-21 10:00 UTC)),
+            ],
+            &[
+                // Hedging at a worse price than the mid price costs slippage.
+                execution(
+                    -1_000,
+                    dec!(40_000),
+                    dec!(50_000),
+                    datetime!(2024-02-20 10:01 UTC),
+                ),
+            ],
+            Period::Day,
+            Some(dec!(50_000)),
+        );
+
+        assert_eq!(report.periods.len(), 2);
+
+        assert_eq!(report.periods[0].start, datetime!(2024-02-20 00:00 UTC));
+        assert_eq!(report.periods[0].pnl.realised, dec!(0));
+        assert_eq!(report.periods[0].pnl.hedge_slippage, dec!(500_000));
+
+        // The unhedged 10101 fill of the second day is marked to the current price.
+        assert_eq!(report.periods[1].start, datetime!(2024-02-21 00:00 UTC));
+        assert_eq!(report.periods[1].pnl.unrealised, dec!(500_000));
+        assert_eq!(report.total.unrealised, dec!(500_000));
+        assert_eq!(report.total.fees, dec!(10));
+    }
+}
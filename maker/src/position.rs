@@ -1,5 +1,17 @@
+use crate::db;
+use crate::pnl;
+use crate::pnl::Period;
+use crate::pnl::PnlReport;
+use crate::risk::Reason;
+use crate::risk::RiskMonitor;
+use crate::venue::Fill;
+use crate::venue::HedgingVenue;
 use anyhow::Result;
 use async_trait::async_trait;
+use diesel::r2d2::ConnectionManager;
+use diesel::r2d2::Pool;
+use diesel::PgConnection;
+use futures::TryStreamExt;
 use hedging::derive_hedging_action;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
@@ -10,17 +22,26 @@ use std::collections::HashSet;
 use std::fmt::Display;
 use std::hash::Hash;
 use std::ops::Add;
+use std::sync::Arc;
 use std::time::Duration;
+use time::OffsetDateTime;
 use uuid::Uuid;
 use xtra::Mailbox;
 
-mod bitmex;
 mod hedging;
 mod tentenone;
+mod venue;
+
+/// Interval after which we try to subscribe to the fills of the hedging venue again.
+const FILLS_RECONNECT_INTERVAL: Duration = Duration::from_secs(10);
 
 pub struct Manager {
     position: Position,
-    bitmex_http_client: bitmex_client::client::Client,
+    venue: Arc<dyn HedgingVenue>,
+    risk: RiskMonitor,
+    pool: Pool<ConnectionManager<PgConnection>>,
+    /// The latest mid price of each contract.
+    prices: HashMap<ContractSymbol, Decimal>,
 }
 
 #[async_trait]
@@ -32,8 +53,8 @@ impl xtra::Actor for Manager {
             let mailbox = mailbox.clone();
             async move {
                 loop {
-                    // We sleep first to allow the 10101 and BitMEX positions to be up-to-date
-                    // before we start hedging.
+                    // We sleep first to allow the 10101 and hedging venue positions to be
+                    // up-to-date before we start hedging.
                     tokio::time::sleep(Duration::from_secs(60)).await;
 
                     let _ = mailbox.address().send(Hedge).await;
@@ -41,6 +62,33 @@ impl xtra::Actor for Manager {
             }
         });
 
+        tokio::spawn({
+            let venue = self.venue.clone();
+            let address = mailbox.address();
+            async move {
+                loop {
+                    let mut fills = venue.fills();
+                    loop {
+                        match fills.try_next().await {
+                            Ok(Some(fill)) => {
+                                let _ = address.send(fill).await;
+                            }
+                            Ok(None) => {
+                                tracing::error!("Hedging venue fills stream ended");
+                                break;
+                            }
+                            Err(e) => {
+                                tracing::error!("Hedging venue fills stream failed: {e:#}");
+                                break;
+                            }
+                        }
+                    }
+
+                    tokio::time::sleep(FILLS_RECONNECT_INTERVAL).await;
+                }
+            }
+        });
+
         Ok(())
     }
 
@@ -48,16 +96,69 @@ impl xtra::Actor for Manager {
 }
 
 impl Manager {
-    pub fn new(bitmex_http_client: bitmex_client::client::Client) -> Self {
+    pub fn new(
+        venue: Arc<dyn HedgingVenue>,
+        risk: RiskMonitor,
+        pool: Pool<ConnectionManager<PgConnection>>,
+    ) -> Self {
         Self {
             position: Position::new(),
-            bitmex_http_client,
+            venue,
+            risk,
+            pool,
+            prices: HashMap::default(),
+        }
+    }
+
+    /// Record the 10101 fills in the journal of the maker, ignoring the ones we already know about.
+    fn record_tentenone_fills(&self, fills: &[pnl::TenTenOneFill]) -> Result<()> {
+        let mut conn = self.pool.get()?;
+
+        let n = db::tentenone_fills::insert(&mut conn, fills)?;
+        if n > 0 {
+            tracing::info!(%n, "Recorded new 10101 fills");
         }
+
+        Ok(())
+    }
+
+    /// Record the execution of a hedging order in the journal of the maker.
+    fn record_hedge_execution(&self, execution: pnl::HedgeExecution) -> Result<()> {
+        let mut conn = self.pool.get()?;
+
+        if !db::hedge_executions::insert(&mut conn, execution)? {
+            tracing::debug!("Hedge execution already recorded");
+        }
+
+        Ok(())
+    }
+
+    /// Check the positions against the risk limits.
+    ///
+    /// The risk limits only apply to [`ContractSymbol::BtcUsd`], the only contract traded so far.
+    fn update_risk(&mut self) {
+        let tentenone = self.position.get_tentenone(&ContractSymbol::BtcUsd);
+        let venue = Decimal::from(self.position.get_venue(&ContractSymbol::BtcUsd));
+
+        self.risk.update_position(tentenone, venue);
     }
 
-    /// Adjust hedging on _BitMEX_ based on the balance between the [`bitmex::Position`] and the
-    /// [`tentenone::Position`].
-    async fn hedge(&self, contract_symbol: &ContractSymbol) {
+    /// Adjust hedging on the [`HedgingVenue`] based on the balance between the
+    /// [`venue::Position`] and the [`tentenone::Position`].
+    async fn hedge(&mut self, contract_symbol: &ContractSymbol) {
+        // The fills might not have reached us yet, so we fetch the position to avoid hedging the
+        // same 10101 position twice.
+        match self.venue.position(*contract_symbol).await {
+            Ok(contracts) => {
+                self.position.update_venue(*contract_symbol, contracts);
+                self.update_risk();
+            }
+            Err(e) => {
+                tracing::error!("Not hedging without position on hedging venue: {e:#}");
+                return;
+            }
+        }
+
         let tentenone = self.position.get_tentenone(contract_symbol);
 
         // For the purposes of hedging we have to round to the number of 10101 contracts to the
@@ -67,43 +168,27 @@ impl Manager {
             .to_i32()
             .expect("10101 position to fit in i32");
 
-        let bitmex = self.position.get_bitmex(contract_symbol);
+        let venue = self.position.get_venue(contract_symbol);
 
-        let action = derive_hedging_action(tentenone, bitmex);
+        let lot_size = self.venue.lot_size();
+        let action = derive_hedging_action(tentenone, venue, lot_size);
 
-        if let Err(e) = self.create_bitmex_order(&action, contract_symbol).await {
-            tracing::error!(
-                ?action,
-                "Failed to create order on BitMEX based on required hedging action: {e:#}"
-            )
+        let contracts = action.contracts(lot_size);
+        if contracts == 0 {
+            return;
         }
-    }
-
-    async fn create_bitmex_order(
-        &self,
-        action: &hedging::Action,
-        contract_symbol: &ContractSymbol,
-    ) -> Result<()> {
-        let (contracts, side) = match action.contracts() {
-            0 => return Ok(()),
-            n @ 1..=i32::MAX => (n.abs(), bitmex_client::models::Side::Buy),
-            n @ i32::MIN..=-1 => (n.abs(), bitmex_client::models::Side::Sell),
-        };
 
         tracing::info!(
             ?action,
-            "Creating BitMEX order based on required hedging action"
+            "Creating order on hedging venue based on required hedging action"
         );
 
-        let contract_symbol = match contract_symbol {
-            ContractSymbol::BtcUsd => bitmex_client::models::ContractSymbol::XbtUsd,
-        };
-
-        self.bitmex_http_client
-            .create_order(contract_symbol, contracts, side, None)
-            .await?;
-
-        Ok(())
+        if let Err(e) = self.venue.place_order(*contract_symbol, contracts).await {
+            tracing::error!(
+                ?action,
+                "Failed to create order on hedging venue based on required hedging action: {e:#}"
+            )
+        }
     }
 }
 
@@ -117,25 +202,50 @@ pub struct OrderTenTenOne {
     ///
     /// The sign determines the direction: positive is long; negative is short.
     contracts: Decimal,
+    price: Decimal,
+    timestamp: OffsetDateTime,
 }
 
 impl OrderTenTenOne {
-    pub fn new(order_id: Uuid, contract_symbol: ContractSymbol, contracts: Decimal) -> Self {
+    pub fn new(
+        order_id: Uuid,
+        contract_symbol: ContractSymbol,
+        contracts: Decimal,
+        price: Decimal,
+        timestamp: OffsetDateTime,
+    ) -> Self {
         Self {
             order_id,
             contract_symbol,
             contracts,
+            price,
+            timestamp,
         }
     }
 }
 
-pub struct PositionUpdateBitmex {
+/// The latest price of a contract, used to fill orders on venues without their own market data.
+pub struct PriceUpdate {
     pub contract_symbol: ContractSymbol,
-    pub contracts: i32,
+    pub price: Decimal,
 }
 
 pub struct GetPosition;
 
+/// Get the PnL of the maker, based on its journal of 10101 fills and hedge executions.
+pub struct GetPnl {
+    pub period: Period,
+}
+
+/// Pull all quotes of the maker until the kill switch is released.
+pub struct EngageKillSwitch;
+
+pub struct ReleaseKillSwitch;
+
+/// Reports whether all quotes of the maker have been pulled after the kill switch has been
+/// engaged.
+pub struct OrdersPulled(pub bool);
+
 pub struct GetPositionResponse {
     pub tentenone: HashMap<ContractSymbol, Decimal>,
 }
@@ -151,29 +261,108 @@ impl xtra::Handler<PositionUpdateTenTenOne> for Manager {
         update: PositionUpdateTenTenOne,
         _: &mut xtra::Context<Self>,
     ) -> Self::Return {
+        let mut fills = Vec::with_capacity(update.0.len());
         for OrderTenTenOne {
             order_id,
             contract_symbol,
             contracts,
+            price,
+            timestamp,
         } in update.0
         {
             self.position
                 .update_tentenone(contract_symbol, order_id, contracts);
+
+            fills.push(pnl::TenTenOneFill {
+                order_id,
+                contract_symbol,
+                contracts,
+                price,
+                timestamp,
+            });
+        }
+
+        self.update_risk();
+
+        if let Err(e) = self.record_tentenone_fills(&fills) {
+            tracing::error!("Failed to record 10101 fills: {e:#}");
+        }
+    }
+}
+
+#[async_trait]
+impl xtra::Handler<Fill> for Manager {
+    type Return = ();
+
+    async fn handle(&mut self, fill: Fill, _: &mut xtra::Context<Self>) -> Self::Return {
+        tracing::info!(?fill, "Order filled on hedging venue");
+
+        let contracts = self.position.get_venue(&fill.contract_symbol) + fill.contracts;
+        self.position.update_venue(fill.contract_symbol, contracts);
+
+        self.update_risk();
+
+        let execution = pnl::HedgeExecution {
+            reference_price: self.prices.get(&fill.contract_symbol).copied(),
+            execution_id: fill.id,
+            contract_symbol: fill.contract_symbol,
+            contracts: fill.contracts,
+            price: fill.price,
+            fee_sats: fill.fee_sats,
+            timestamp: fill.timestamp,
+        };
+
+        if let Err(e) = self.record_hedge_execution(execution) {
+            tracing::error!("Failed to record hedge execution: {e:#}");
+        }
+    }
+}
+
+#[async_trait]
+impl xtra::Handler<PriceUpdate> for Manager {
+    type Return = ();
+
+    async fn handle(&mut self, update: PriceUpdate, _: &mut xtra::Context<Self>) -> Self::Return {
+        self.prices.insert(update.contract_symbol, update.price);
+
+        self.venue
+            .update_price(update.contract_symbol, update.price);
+
+        if update.contract_symbol == ContractSymbol::BtcUsd {
+            self.risk
+                .update_price(update.price, OffsetDateTime::now_utc());
         }
     }
 }
 
 #[async_trait]
-impl xtra::Handler<PositionUpdateBitmex> for Manager {
+impl xtra::Handler<EngageKillSwitch> for Manager {
+    type Return = ();
+
+    async fn handle(&mut self, _: EngageKillSwitch, _: &mut xtra::Context<Self>) -> Self::Return {
+        self.risk.engage(Reason::Manual);
+    }
+}
+
+#[async_trait]
+impl xtra::Handler<ReleaseKillSwitch> for Manager {
+    type Return = ();
+
+    async fn handle(&mut self, _: ReleaseKillSwitch, _: &mut xtra::Context<Self>) -> Self::Return {
+        self.risk.release();
+    }
+}
+
+#[async_trait]
+impl xtra::Handler<OrdersPulled> for Manager {
     type Return = ();
 
     async fn handle(
         &mut self,
-        update: PositionUpdateBitmex,
+        OrdersPulled(orders_pulled): OrdersPulled,
         _: &mut xtra::Context<Self>,
     ) -> Self::Return {
-        self.position
-            .update_bitmex(update.contract_symbol, update.contracts);
+        self.risk.set_orders_pulled(orders_pulled);
     }
 }
 
@@ -194,6 +383,28 @@ impl xtra::Handler<GetPosition> for Manager {
     }
 }
 
+#[async_trait]
+impl xtra::Handler<GetPnl> for Manager {
+    type Return = Result<PnlReport>;
+
+    async fn handle(&mut self, get_pnl: GetPnl, _: &mut xtra::Context<Self>) -> Self::Return {
+        let mut conn = self.pool.get()?;
+
+        let fills = db::tentenone_fills::get_all(&mut conn)?;
+        let executions = db::hedge_executions::get_all(&mut conn)?;
+
+        // TODO: Report the PnL for all `ContractSymbol` enum variants.
+        let report = pnl::pnl_report(
+            &fills,
+            &executions,
+            get_pnl.period,
+            self.prices.get(&ContractSymbol::BtcUsd).copied(),
+        );
+
+        Ok(report)
+    }
+}
+
 #[async_trait]
 impl xtra::Handler<Hedge> for Manager {
     type Return = ();
@@ -208,14 +419,14 @@ impl xtra::Handler<Hedge> for Manager {
 #[derive(Debug)]
 struct Position {
     tentenone: HashSet<tentenone::Position>,
-    bitmex: HashSet<bitmex::Position>,
+    venue: HashSet<venue::Position>,
 }
 
 impl Position {
     pub fn new() -> Self {
         Self {
             tentenone: HashSet::from_iter([tentenone::Position::new(ContractSymbol::BtcUsd)]),
-            bitmex: HashSet::from_iter([bitmex::Position::new(ContractSymbol::BtcUsd)]),
+            venue: HashSet::from_iter([venue::Position::new(ContractSymbol::BtcUsd)]),
         }
     }
 
@@ -236,16 +447,16 @@ impl Position {
         self.tentenone.replace(position);
     }
 
-    fn update_bitmex(&mut self, contract_symbol: ContractSymbol, contracts: i32) {
+    fn update_venue(&mut self, contract_symbol: ContractSymbol, contracts: i32) {
         let mut position = self
-            .bitmex
+            .venue
             .get(&contract_symbol)
             .cloned()
-            .unwrap_or(bitmex::Position::new(ContractSymbol::BtcUsd));
+            .unwrap_or(venue::Position::new(ContractSymbol::BtcUsd));
 
         position.update(contracts);
 
-        self.bitmex.replace(position);
+        self.venue.replace(position);
     }
 
     fn get_tentenone(&self, contract_symbol: &ContractSymbol) -> Decimal {
@@ -259,8 +470,8 @@ impl Position {
         }
     }
 
-    fn get_bitmex(&self, contract_symbol: &ContractSymbol) -> i32 {
-        match self.bitmex.get(contract_symbol) {
+    fn get_venue(&self, contract_symbol: &ContractSymbol) -> i32 {
+        match self.venue.get(contract_symbol) {
             Some(position) => position.contracts(),
             None => 0,
         }
@@ -1,60 +1,34 @@
 use std::cmp::Ordering;
 use std::num::NonZeroU32;
-use std::ops::Add;
 
 #[derive(Debug, PartialEq)]
 pub enum Action {
     StandPat,
-    Buy { hundreds_of_contracts: NonZeroU32 },
-    Sell { hundreds_of_contracts: NonZeroU32 },
+    Buy { lots: NonZeroU32 },
+    Sell { lots: NonZeroU32 },
 }
 
-pub fn derive_hedging_action(tentenone: i32, bitmex: i32) -> Action {
-    fn derive_hedging_action_rec(tentenone: i32, bitmex: i32, action_acc: Action) -> Action {
-        let diff = tentenone - bitmex;
-        let diff_hundreds = diff / 100;
-
-        if 1 <= diff_hundreds {
-            derive_hedging_action_rec(
-                tentenone,
-                bitmex + 100,
-                action_acc + Action::buy_one_hundred(),
-            )
-        } else if diff_hundreds <= -1 {
-            derive_hedging_action_rec(
-                tentenone,
-                bitmex - 100,
-                action_acc + Action::sell_one_hundred(),
-            )
-        } else {
-            action_acc
-        }
-    }
+/// Derive the number of lots to buy or sell on the hedging venue so that the `venue` position
+/// matches the `tentenone` position, ignoring differences smaller than one lot.
+pub fn derive_hedging_action(tentenone: i32, venue: i32, lot_size: u32) -> Action {
+    let diff = tentenone - venue;
+    let lots = diff / lot_size as i32;
 
-    derive_hedging_action_rec(tentenone, bitmex, Action::StandPat)
+    Action::new(lots)
 }
 
 impl Action {
-    pub fn contracts(&self) -> i32 {
-        self.to_int() * 100
-    }
-
-    fn buy_hundreds(n: u32) -> Self {
-        Self::new(n as i32)
-    }
-
-    fn sell_hundreds(n: u32) -> Self {
-        Self::new(-(n as i32))
+    pub fn contracts(&self, lot_size: u32) -> i32 {
+        self.to_int() * lot_size as i32
     }
 
     fn new(n: i32) -> Self {
-        let hundreds_of_contracts = NonZeroU32::new(n.unsigned_abs()).expect("not zero");
         match n.cmp(&0) {
             Ordering::Greater => Self::Buy {
-                hundreds_of_contracts,
+                lots: NonZeroU32::new(n.unsigned_abs()).expect("not zero"),
             },
             Ordering::Less => Self::Sell {
-                hundreds_of_contracts,
+                lots: NonZeroU32::new(n.unsigned_abs()).expect("not zero"),
             },
             Ordering::Equal => Self::StandPat,
         }
@@ -63,33 +37,10 @@ impl Action {
     fn to_int(&self) -> i32 {
         match self {
             Action::StandPat => 0,
-            Action::Buy {
-                hundreds_of_contracts,
-            } => hundreds_of_contracts.get() as i32,
-            Action::Sell {
-                hundreds_of_contracts,
-            } => -(hundreds_of_contracts.get() as i32),
+            Action::Buy { lots } => lots.get() as i32,
+            Action::Sell { lots } => -(lots.get() as i32),
         }
     }
-
-    fn buy_one_hundred() -> Action {
-        Action::buy_hundreds(1)
-    }
-
-    fn sell_one_hundred() -> Action {
-        Action::sell_hundreds(1)
-    }
-}
-
-impl Add for Action {
-    type Output = Action;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        let lhs = self.to_int();
-        let rhs = rhs.to_int();
-
-        Action::new(lhs + rhs)
-    }
 }
 
 #[cfg(test)]
@@ -98,34 +49,65 @@ mod tests {
 
     #[test]
     fn derive_hedging_action_singleton() {
-        check(0, 0, Action::StandPat);
+        check(0, 0, 100, Action::StandPat);
 
-        check(99, 0, Action::StandPat);
-        check(100, 0, Action::buy_one_hundred());
-        check(101, 0, Action::buy_one_hundred());
+        check(99, 0, 100, Action::StandPat);
+        check(100, 0, 100, buy_lots(1));
+        check(101, 0, 100, buy_lots(1));
 
-        check(-99, 0, Action::StandPat);
-        check(-100, 0, Action::sell_one_hundred());
-        check(-101, 0, Action::sell_one_hundred());
+        check(-99, 0, 100, Action::StandPat);
+        check(-100, 0, 100, sell_lots(1));
+        check(-101, 0, 100, sell_lots(1));
 
-        check(0, -99, Action::StandPat);
-        check(0, -100, Action::buy_one_hundred());
-        check(0, -101, Action::buy_one_hundred());
+        check(0, -99, 100, Action::StandPat);
+        check(0, -100, 100, buy_lots(1));
+        check(0, -101, 100, buy_lots(1));
 
-        check(0, 99, Action::StandPat);
-        check(0, 100, Action::sell_one_hundred());
-        check(0, 101, Action::sell_one_hundred());
+        check(0, 99, 100, Action::StandPat);
+        check(0, 100, 100, sell_lots(1));
+        check(0, 101, 100, sell_lots(1));
 
-        check(550, 300, Action::buy_hundreds(2));
-        check(-330, 200, Action::sell_hundreds(5));
+        check(550, 300, 100, buy_lots(2));
+        check(-330, 200, 100, sell_lots(5));
     }
 
-    // TODO(lucas): Verify that executing a single `Action` and then calling
-    // `derive_hedging_action_singleton` again always leads to `Action::StandPat`.
+    #[test]
+    fn derive_hedging_action_with_other_lot_sizes() {
+        check(550, 300, 1, buy_lots(250));
+        check(-330, 200, 1, sell_lots(530));
+
+        check(550, 300, 1000, Action::StandPat);
+        check(2500, 0, 1000, buy_lots(2));
+        assert_eq!(buy_lots(2).contracts(1000), 2000);
+    }
+
+    #[test]
+    fn executing_action_leads_to_stand_pat() {
+        for (tentenone, venue) in [(550, 300), (-330, 200), (0, -101), (1234, 1234)] {
+            let lot_size = 100;
+            let action = derive_hedging_action(tentenone, venue, lot_size);
+
+            let venue = venue + action.contracts(lot_size);
+
+            check(tentenone, venue, lot_size, Action::StandPat);
+        }
+    }
+
+    fn buy_lots(n: u32) -> Action {
+        Action::Buy {
+            lots: NonZeroU32::new(n).unwrap(),
+        }
+    }
+
+    fn sell_lots(n: u32) -> Action {
+        Action::Sell {
+            lots: NonZeroU32::new(n).unwrap(),
+        }
+    }
 
     #[track_caller]
-    fn check(tentenone: i32, bitmex: i32, expected: Action) {
-        let actual = derive_hedging_action(tentenone, bitmex);
+    fn check(tentenone: i32, venue: i32, lot_size: u32, expected: Action) {
+        let actual = derive_hedging_action(tentenone, venue, lot_size);
         assert_eq!(expected, actual);
     }
 }
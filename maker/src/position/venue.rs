@@ -3,37 +3,26 @@ use std::borrow::Borrow;
 use std::hash::Hash;
 use std::hash::Hasher;
 
-/// The maker's position on BitMEX.
+/// The maker's position on the hedging venue.
 #[derive(Clone, Eq, Debug)]
 pub struct Position {
     contract_symbol: ContractSymbol,
-    contracts: HundredsOfContracts,
-}
-
-/// Hundreds of contracts, with the sign representing the direction: positive long; negative short.
-#[derive(Clone, PartialEq, Eq, Debug)]
-struct HundredsOfContracts(i32);
-
-impl HundredsOfContracts {
-    pub fn new(contracts: i32) -> Self {
-        let hundreds = contracts / 100;
-
-        Self(hundreds)
-    }
+    /// The sign represents the direction: positive long; negative short.
+    contracts: i32,
 }
 
 impl Position {
     pub fn new(contract_symbol: ContractSymbol) -> Self {
         Self {
             contract_symbol,
-            contracts: HundredsOfContracts(0),
+            contracts: 0,
         }
     }
 
     pub fn update(&mut self, new_contracts: i32) {
         let before = self.contracts();
 
-        self.contracts = HundredsOfContracts::new(new_contracts);
+        self.contracts = new_contracts;
 
         let after = self.contracts();
 
@@ -42,13 +31,13 @@ impl Position {
                 contract_symbol = ?self.contract_symbol,
                 %before,
                 %after,
-                "Updated BitMEX position"
+                "Updated hedging venue position"
             );
         }
     }
 
     pub(super) fn contracts(&self) -> i32 {
-        self.contracts.0 * 100
+        self.contracts
     }
 }
 
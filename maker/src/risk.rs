@@ -0,0 +1,329 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use time::Date;
+use time::OffsetDateTime;
+use tokio::sync::watch;
+
+const SATS_PER_BTC: Decimal = dec!(100_000_000);
+
+/// Limits on the exposure of the maker.
+///
+/// Once a limit is breached the [`KillSwitch`] is engaged, which pulls all quotes of the maker from
+/// the orderbook.
+#[derive(Debug, Clone, Copy)]
+pub struct RiskLimits {
+    /// The maximum absolute 10101 position in contracts.
+    pub max_position: Decimal,
+    /// The maximum absolute difference in contracts between the 10101 position and the position
+    /// on the hedging venue.
+    pub max_unhedged: Decimal,
+    /// The maximum loss in sats within one UTC day.
+    pub max_daily_loss: Decimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", content = "reason")]
+pub enum KillSwitch {
+    /// The maker is quoting.
+    Released,
+    /// The maker has pulled all its quotes and does not post new ones until the kill switch is
+    /// released.
+    Engaged(Reason),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Reason {
+    Manual,
+    MaxPosition,
+    MaxUnhedged,
+    MaxDailyLoss,
+}
+
+/// The exposure of the maker as seen by the [`RiskMonitor`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RiskStatus {
+    pub kill_switch: KillSwitch,
+    /// The 10101 position in contracts.
+    pub position: Decimal,
+    /// The difference in contracts between the 10101 position and the position on the hedging
+    /// venue.
+    pub unhedged: Decimal,
+    /// The PnL of the current UTC day in sats.
+    pub daily_pnl: Decimal,
+    /// Whether all quotes of the maker have been pulled from the orderbook since the kill switch
+    /// has been engaged.
+    ///
+    /// If the kill switch is engaged but the quotes have not been pulled, the maker may still be
+    /// quoting.
+    pub orders_pulled: bool,
+}
+
+impl KillSwitch {
+    pub fn is_engaged(&self) -> bool {
+        matches!(self, KillSwitch::Engaged(_))
+    }
+}
+
+/// Watches the exposure of the maker and engages the [`KillSwitch`] if the [`RiskLimits`] are
+/// breached.
+///
+/// The kill switch stays engaged until it is released manually, even if the exposure is back
+/// within the limits.
+pub struct RiskMonitor {
+    limits: RiskLimits,
+    kill_switch: KillSwitch,
+    orders_pulled: bool,
+    tentenone: Decimal,
+    venue: Decimal,
+    daily_pnl: DailyPnl,
+    status_tx: watch::Sender<RiskStatus>,
+}
+
+/// The mark-to-market PnL of the unhedged position of the maker within one UTC day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DailyPnl {
+    day: Date,
+    /// The PnL in sats.
+    pnl: Decimal,
+    last_price: Option<Decimal>,
+}
+
+impl RiskMonitor {
+    pub fn new(limits: RiskLimits) -> (Self, watch::Receiver<RiskStatus>) {
+        let daily_pnl = DailyPnl::new(OffsetDateTime::now_utc());
+        let (status_tx, status_rx) = watch::channel(RiskStatus {
+            kill_switch: KillSwitch::Released,
+            position: Decimal::ZERO,
+            unhedged: Decimal::ZERO,
+            daily_pnl: daily_pnl.pnl,
+            orders_pulled: false,
+        });
+
+        let monitor = Self {
+            limits,
+            kill_switch: KillSwitch::Released,
+            orders_pulled: false,
+            tentenone: Decimal::ZERO,
+            venue: Decimal::ZERO,
+            daily_pnl,
+            status_tx,
+        };
+
+        (monitor, status_rx)
+    }
+
+    /// Updates the 10101 position and the position on the hedging venue in contracts.
+    pub fn update_position(&mut self, tentenone: Decimal, venue: Decimal) {
+        self.tentenone = tentenone;
+        self.venue = venue;
+
+        self.check();
+    }
+
+    /// Marks the unhedged position of the maker to the new `price`.
+    pub fn update_price(&mut self, price: Decimal, now: OffsetDateTime) {
+        // The position on the hedging venue follows the 10101 position, so the maker is only
+        // exposed to the part of the 10101 position which has not been hedged yet.
+        let exposure = self.venue - self.tentenone;
+        self.daily_pnl.update(exposure, price, now);
+
+        self.check();
+    }
+
+    pub fn engage(&mut self, reason: Reason) {
+        if let KillSwitch::Released = self.kill_switch {
+            tracing::warn!(?reason, "Engaging kill switch");
+            self.kill_switch = KillSwitch::Engaged(reason);
+            self.orders_pulled = false;
+        }
+
+        self.publish();
+    }
+
+    pub fn release(&mut self) {
+        if let KillSwitch::Engaged(reason) = self.kill_switch {
+            tracing::info!(?reason, "Releasing kill switch");
+            self.kill_switch = KillSwitch::Released;
+            self.orders_pulled = false;
+        }
+
+        // The kill switch is engaged again right away if a limit is still breached.
+        self.check();
+    }
+
+    /// Records whether pulling all quotes of the maker after engaging the kill switch succeeded.
+    pub fn set_orders_pulled(&mut self, orders_pulled: bool) {
+        if !self.kill_switch.is_engaged() {
+            return;
+        }
+
+        if !orders_pulled {
+            tracing::error!(kill_switch = ?self.kill_switch, "Failed to pull all orders");
+        }
+
+        self.orders_pulled = orders_pulled;
+        self.publish();
+    }
+
+    fn check(&mut self) {
+        match breached_limit(&self.limits, self.tentenone, self.venue, self.daily_pnl.pnl) {
+            Some(reason) => self.engage(reason),
+            None => self.publish(),
+        }
+    }
+
+    fn publish(&self) {
+        self.status_tx.send_replace(RiskStatus {
+            kill_switch: self.kill_switch,
+            position: self.tentenone,
+            unhedged: self.tentenone - self.venue,
+            daily_pnl: self.daily_pnl.pnl,
+            orders_pulled: self.orders_pulled,
+        });
+    }
+}
+
+impl DailyPnl {
+    fn new(now: OffsetDateTime) -> Self {
+        Self {
+            day: now.date(),
+            pnl: Decimal::ZERO,
+            last_price: None,
+        }
+    }
+
+    fn update(&mut self, exposure: Decimal, price: Decimal, now: OffsetDateTime) {
+        if now.date() != self.day {
+            self.day = now.date();
+            self.pnl = Decimal::ZERO;
+        }
+
+        // The PnL of inverse contracts is settled in bitcoin.
+        if let Some(last_price) = self.last_price {
+            self.pnl +=
+                exposure * (Decimal::ONE / last_price - Decimal::ONE / price) * SATS_PER_BTC;
+        }
+
+        self.last_price = Some(price);
+    }
+}
+
+fn breached_limit(
+    limits: &RiskLimits,
+    tentenone: Decimal,
+    venue: Decimal,
+    daily_pnl: Decimal,
+) -> Option<Reason> {
+    if tentenone.abs() > limits.max_position {
+        Some(Reason::MaxPosition)
+    } else if (tentenone - venue).abs() > limits.max_unhedged {
+        Some(Reason::MaxUnhedged)
+    } else if -daily_pnl > limits.max_daily_loss {
+        Some(Reason::MaxDailyLoss)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    const LIMITS: RiskLimits = RiskLimits {
+        max_position: dec!(10_000),
+        max_unhedged: dec!(1_000),
+        max_daily_loss: dec!(100_000),
+    };
+
+    #[test]
+    fn limits_are_checked() {
+        assert_eq!(
+            breached_limit(&LIMITS, dec!(-10_000), dec!(-9_500), dec!(0)),
+            None
+        );
+        assert_eq!(
+            breached_limit(&LIMITS, dec!(-10_001), dec!(-10_001), dec!(0)),
+            Some(Reason::MaxPosition)
+        );
+        assert_eq!(
+            breached_limit(&LIMITS, dec!(5_000), dec!(3_900), dec!(0)),
+            Some(Reason::MaxUnhedged)
+        );
+        assert_eq!(
+            breached_limit(&LIMITS, dec!(0), dec!(0), dec!(-100_001)),
+            Some(Reason::MaxDailyLoss)
+        );
+    }
+
+    #[test]
+    fn daily_pnl_marks_exposure_to_market() {
+        let mut pnl = DailyPnl::new(datetime!(2024-02-20 10:00 UTC));
+
+        pnl.update(dec!(1_000), dec!(50_000), datetime!(2024-02-20 10:00 UTC));
+        assert_eq!(pnl.pnl, dec!(0));
+
+        // 1,000 contracts long from 50,000 to 40,000 lose 0.005 BTC.
+        pnl.update(dec!(1_000), dec!(40_000), datetime!(2024-02-20 11:00 UTC));
+        assert_eq!(pnl.pnl, dec!(-500_000));
+
+        // The PnL is reset at the start of a new day, before the short position loses 0.005 BTC.
+        pnl.update(dec!(-1_000), dec!(50_000), datetime!(2024-02-21 00:01 UTC));
+        assert_eq!(pnl.pnl, dec!(-500_000));
+    }
+
+    #[test]
+    fn kill_switch_stays_engaged_until_released() {
+        let (mut monitor, status) = RiskMonitor::new(LIMITS);
+
+        monitor.update_position(dec!(20_000), dec!(20_000));
+        assert_eq!(
+            status.borrow().kill_switch,
+            KillSwitch::Engaged(Reason::MaxPosition)
+        );
+
+        // A manual engagement does not override the original reason.
+        monitor.engage(Reason::Manual);
+        monitor.update_position(dec!(0), dec!(0));
+        assert_eq!(
+            status.borrow().kill_switch,
+            KillSwitch::Engaged(Reason::MaxPosition)
+        );
+
+        monitor.release();
+        assert_eq!(status.borrow().kill_switch, KillSwitch::Released);
+
+        // Releasing the kill switch while a limit is breached engages it again.
+        monitor.update_position(dec!(0), dec!(-5_000));
+        monitor.release();
+        assert_eq!(
+            status.borrow().kill_switch,
+            KillSwitch::Engaged(Reason::MaxUnhedged)
+        );
+    }
+
+    #[test]
+    fn pulled_orders_are_reported_while_kill_switch_is_engaged() {
+        let (mut monitor, status) = RiskMonitor::new(LIMITS);
+
+        // Nothing to pull while the maker is quoting.
+        monitor.set_orders_pulled(true);
+        assert!(!status.borrow().orders_pulled);
+
+        monitor.engage(Reason::Manual);
+        monitor.set_orders_pulled(false);
+        assert!(status.borrow().kill_switch.is_engaged());
+        assert!(!status.borrow().orders_pulled);
+
+        monitor.set_orders_pulled(true);
+        assert!(status.borrow().orders_pulled);
+
+        monitor.release();
+        assert!(!status.borrow().orders_pulled);
+
+        // The orders have to be pulled again once the kill switch is engaged again.
+        monitor.engage(Reason::Manual);
+        assert!(!status.borrow().orders_pulled);
+    }
+}
@@ -1,10 +1,17 @@
 use crate::health::Health;
 use crate::health::OverallMakerHealth;
+use crate::pnl::Period;
+use crate::pnl::PnlReport;
 use crate::position;
 use crate::position::ContractSymbol;
+use crate::position::EngageKillSwitch;
+use crate::position::GetPnl;
 use crate::position::GetPosition;
+use crate::position::ReleaseKillSwitch;
+use crate::risk::RiskStatus;
 use crate::storage::MakerTenTenOneStorage;
 use axum::extract::Path;
+use axum::extract::Query;
 use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
@@ -33,6 +40,7 @@ use std::hash::Hash;
 use std::hash::Hasher;
 use std::str::FromStr;
 use std::sync::Arc;
+use tokio::sync::watch;
 use tokio::task::spawn_blocking;
 
 pub struct AppState {
@@ -42,6 +50,7 @@ pub struct AppState {
     announcement_addresses: Vec<SocketAddress>,
     node_alias: String,
     health: Health,
+    risk_status: watch::Receiver<RiskStatus>,
 }
 
 pub fn router(
@@ -49,6 +58,7 @@ pub fn router(
     exporter: PrometheusExporter,
     position_manager: xtra::Address<position::Manager>,
     health: Health,
+    risk_status: watch::Receiver<RiskStatus>,
     announcement_addresses: Vec<SocketAddress>,
     node_alias: &str,
 ) -> Router {
@@ -57,6 +67,7 @@ pub fn router(
         exporter,
         position_manager,
         health,
+        risk_status,
         announcement_addresses,
         node_alias: node_alias.to_string(),
     });
@@ -71,6 +82,10 @@ pub fn router(
         .route("/api/pay-invoice/:invoice", post(pay_invoice))
         .route("/api/sync", post(sync))
         .route("/api/position", get(get_position))
+        .route(
+            "/api/killswitch",
+            get(get_kill_switch).post(post_kill_switch),
+        )
         .route("/api/node", get(get_node_info))
         .route("/metrics", get(get_metrics))
         .route("/health", get(get_health))
@@ -317,6 +332,57 @@ pub async fn get_position(State(state): State<Arc<AppState>>) -> Result<Json<Pos
     }))
 }
 
+#[derive(Deserialize)]
+pub struct PnlParams {
+    #[serde(default)]
+    period: Period,
+}
+
+/// Returns the PnL of the maker in sats, in total and per `period` (`hour`, `day` or `month`).
+pub async fn get_pnl(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<PnlParams>,
+) -> Result<Json<PnlReport>, AppError> {
+    let report = state
+        .position_manager
+        .send(GetPnl {
+            period: params.period,
+        })
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to get PnL: {e:#}")))?
+        .map_err(|e| AppError::InternalServerError(format!("Failed to get PnL: {e:#}")))?;
+
+    Ok(Json(report))
+}
+
+pub async fn get_kill_switch(State(state): State<Arc<AppState>>) -> Json<RiskStatus> {
+    Json(*state.risk_status.borrow())
+}
+
+#[derive(Deserialize)]
+pub struct KillSwitchParams {
+    engaged: bool,
+}
+
+/// Engages or releases the kill switch, returning the resulting [`RiskStatus`].
+///
+/// Releasing the kill switch has no effect while any of the risk limits is breached.
+pub async fn post_kill_switch(
+    State(state): State<Arc<AppState>>,
+    params: Json<KillSwitchParams>,
+) -> Result<Json<RiskStatus>, AppError> {
+    let result = match params.engaged {
+        true => state.position_manager.send(EngageKillSwitch).await,
+        false => state.position_manager.send(ReleaseKillSwitch).await,
+    };
+
+    result.map_err(|e| {
+        AppError::InternalServerError(format!("Failed to update kill switch: {e:#}"))
+    })?;
+
+    Ok(Json(*state.risk_status.borrow()))
+}
+
 pub async fn get_metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     let exporter = state.exporter.clone();
     let encoder = TextEncoder::new();
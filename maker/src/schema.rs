@@ -1 +1,39 @@
 // @generated automatically by Diesel CLI.
+
+pub mod sql_types {
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "ContractSymbol_Type"))]
+    pub struct ContractSymbolType;
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ContractSymbolType;
+
+    hedge_executions (id) {
+        id -> Int4,
+        execution_id -> Text,
+        contract_symbol -> ContractSymbolType,
+        contracts -> Int4,
+        price -> Float4,
+        reference_price -> Nullable<Float4>,
+        fee_sats -> Int8,
+        timestamp -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ContractSymbolType;
+
+    tentenone_fills (id) {
+        id -> Int4,
+        order_id -> Uuid,
+        contract_symbol -> ContractSymbolType,
+        contracts -> Float4,
+        price -> Float4,
+        timestamp -> Timestamptz,
+    }
+}
+
+diesel::allow_tables_to_appear_in_same_query!(hedge_executions, tentenone_fills,);
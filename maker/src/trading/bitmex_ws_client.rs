@@ -1,7 +1,6 @@
 use anyhow::anyhow;
 use anyhow::Result;
 use async_stream::stream;
-use bitmex_stream::Credentials;
 use bitmex_stream::Network;
 use futures::Stream;
 use futures::StreamExt;
@@ -11,26 +10,9 @@ use std::fmt;
 use time::OffsetDateTime;
 use trade::ContractSymbol;
 
-pub async fn stream(
-    network: Network,
-    credentials: Option<Credentials>,
-) -> impl Stream<Item = Result<Event>> + Unpin {
+pub async fn stream(network: Network) -> impl Stream<Item = Result<Event>> + Unpin {
     let stream = stream! {
-        let mut stream = match credentials {
-            Some(credentials) => {
-                bitmex_stream::subscribe_with_credentials(
-                    ["quoteBin1m:XBTUSD".to_owned(), "position:XBTUSD".to_owned()],
-                    network,
-                    credentials
-                ).boxed()
-            }
-            None => {
-                bitmex_stream::subscribe(
-                    ["quoteBin1m:XBTUSD".to_owned()],
-                    network,
-                ).boxed()
-            }
-        };
+        let mut stream = bitmex_stream::subscribe(["quoteBin1m:XBTUSD".to_owned()], network);
 
         loop {
             match stream.try_next().await {
@@ -65,7 +47,6 @@ pub async fn stream(
 #[derive(Debug, Clone)]
 pub enum Event {
     Quote(Quote),
-    Position(Position),
 }
 
 impl From<wire::TableUpdate> for Event {
@@ -77,11 +58,6 @@ impl From<wire::TableUpdate> for Event {
                 ask: quote.ask_price,
                 timestamp: quote.timestamp,
             }),
-            wire::TableUpdate::Position(position) => Self::Position(Position {
-                contract_symbol: position.symbol.into(),
-                contracts: position.contracts,
-                timestamp: position.timestamp,
-            }),
         }
     }
 }
@@ -127,28 +103,6 @@ impl Quote {
     }
 }
 
-#[derive(Clone, Copy)]
-pub struct Position {
-    pub contract_symbol: ContractSymbol,
-    pub contracts: i32,
-    pub timestamp: OffsetDateTime,
-}
-
-impl fmt::Debug for Position {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let rfc3339_timestamp = self
-            .timestamp
-            .format(&time::format_description::well_known::Rfc3339)
-            .expect("Timestamp to be formatted");
-
-        f.debug_struct("Quote")
-            .field("contract_symbol", &self.contract_symbol)
-            .field("contracts", &self.contracts)
-            .field("timestamp", &rfc3339_timestamp)
-            .finish()
-    }
-}
-
 mod wire {
     use core::fmt;
     use rust_decimal::Decimal;
@@ -159,7 +113,6 @@ mod wire {
     #[derive(Debug)]
     pub enum TableUpdate {
         QuoteBin1m(QuoteData),
-        Position(PositionData),
     }
 
     #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
@@ -176,15 +129,6 @@ mod wire {
         pub timestamp: OffsetDateTime,
     }
 
-    #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
-    pub struct PositionData {
-        pub symbol: ContractSymbol,
-        #[serde(rename = "currentQty")]
-        pub contracts: i32,
-        #[serde(with = "time::serde::rfc3339")]
-        pub timestamp: OffsetDateTime,
-    }
-
     #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
     pub enum ContractSymbol {
         #[serde(rename = "XBTUSD")]
@@ -210,7 +154,7 @@ mod wire {
                 type Value = TableUpdate;
 
                 fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                    formatter.write_str("either a `QuoteBin1m` or a `Position` table update")
+                    formatter.write_str("a `QuoteBin1m` table update")
                 }
 
                 fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
@@ -220,7 +164,6 @@ mod wire {
                     #[derive(Debug)]
                     enum TableUpdateKind {
                         QuoteBin1m,
-                        Position,
                     }
 
                     let mut table = None;
@@ -235,7 +178,6 @@ mod wire {
 
                                 let value = match map.next_value()? {
                                     "quoteBin1m" => TableUpdateKind::QuoteBin1m,
-                                    "position" => TableUpdateKind::Position,
                                     _ => return Err(serde::de::Error::custom("unexpected table")),
                                 };
 
@@ -271,14 +213,6 @@ mod wire {
                             })?[0]
                                 .clone(),
                         ),
-                        TableUpdateKind::Position => TableUpdate::Position(
-                            serde_json::from_str::<[PositionData; 1]>(data.get()).map_err(|e| {
-                                serde::de::Error::custom(format!(
-                                    "could not deserialize position data: {e}"
-                                ))
-                            })?[0]
-                                .clone(),
-                        ),
                     };
 
                     Ok(value)
@@ -316,25 +250,6 @@ mod tests {
                 assert_eq!(ask_price, dec!(42641));
                 assert_eq!(timestamp.unix_timestamp(), 1632192000);
             }
-            _ => panic!("Unexpected table update"),
-        }
-    }
-
-    #[test]
-    fn can_deserialize_position_update() {
-        let table_update = serde_json::from_str(r#"{"table":"position","action":"update","data":[{"account":396867,"symbol":"XBTUSD","currency":"XBt","currentQty":100,"markPrice":27452.26,"markValue":-364269,"riskValue":364269,"homeNotional":0.00364269,"maintMargin":65585,"unrealisedPnl":-6327,"unrealisedPnlPcnt":-0.0177,"unrealisedRoePcnt":-0.0884,"liquidationPrice":23349.5,"timestamp":"2023-10-05T17:36:45.781Z"}]}"#).unwrap();
-
-        match table_update {
-            wire::TableUpdate::Position(wire::PositionData {
-                symbol,
-                contracts,
-                timestamp,
-            }) => {
-                assert_eq!(symbol, wire::ContractSymbol::XbtUsd);
-                assert_eq!(contracts, 100);
-                assert_eq!(timestamp.unix_timestamp(), 1696527405)
-            }
-            _ => panic!("Unexpected table update"),
         }
     }
 
@@ -1,46 +1,64 @@
 use crate::health::ServiceStatus;
 use crate::position;
-use crate::position::PositionUpdateBitmex;
+use crate::position::GetPosition;
+use crate::position::OrdersPulled;
+use crate::position::PriceUpdate;
+use crate::risk::RiskStatus;
 use crate::trading::bitmex_ws_client::Event;
+use crate::trading::strategy::QuotingStrategy;
+use crate::trading::strategy::RequoteLimiter;
+use bitcoin::secp256k1::Message;
 use bitcoin::secp256k1::PublicKey;
+use bitcoin::secp256k1::SecretKey;
+use bitcoin::secp256k1::SECP256K1;
 use bitcoin::Network;
-use bitmex_stream::Credentials;
 use commons::NewOrder;
-use commons::OrderResponse;
+use commons::Order;
 use commons::OrderType;
+use commons::Signature;
+use commons::TimeInForce;
 use futures::TryStreamExt;
 use orderbook_http_client::OrderbookClient;
 use reqwest::Url;
 use rust_decimal::Decimal;
-use rust_decimal_macros::dec;
 use std::time::Duration;
+use std::time::Instant;
 use time::OffsetDateTime;
 use tokio::sync::watch;
+use tokio::time::MissedTickBehavior;
 use trade::ContractSymbol;
 use trade::Direction;
 use uuid::Uuid;
 
 mod bitmex_ws_client;
 mod orderbook_http_client;
+pub mod strategy;
+
+/// How often the maker tries to pull its orders again if that failed after engaging the kill
+/// switch.
+const PULL_ORDERS_RETRY_INTERVAL: Duration = Duration::from_secs(5);
 
 /// Perform trading related actions based on a subscription to BitMEX's WebSocket API. Specifically:
 ///
-/// - Create orders based on relevant price updates from BitMEX.
-/// - Forward updates about all BitMEX positions.
+/// - Replace the orders of the maker with the ones derived by the `strategy` from relevant price
+///   updates from BitMEX, at the rate allowed by the `requote_limiter`.
+/// - Forward BitMEX's mid price to the [`position::Manager`].
+/// - Pull all orders of the maker while the [`crate::risk::KillSwitch`] is engaged or the price
+///   feed is down, retrying until the orders have been pulled after engaging the kill switch.
 ///
 /// In the unlikely event that the stream is closed, the function will continue to try to reconnect
 /// after the [`Duration`] specified by `reconnect_after`.
 #[allow(clippy::too_many_arguments)]
 pub async fn run(
     orderbook_url: &Url,
-    maker_id: PublicKey,
+    maker_key: SecretKey,
     network: Network,
-    concurrent_orders: usize,
+    strategy: impl QuotingStrategy,
+    mut requote_limiter: RequoteLimiter,
     order_expiry_after: time::Duration,
     bitmex_pricefeed_tx: watch::Sender<ServiceStatus>,
     position_manager: xtra::Address<position::Manager>,
-    bitmex_api_key: Option<String>,
-    bitmex_api_secret: Option<String>,
+    mut risk_status: watch::Receiver<RiskStatus>,
     reconnect_after: Duration,
 ) {
     let network = match network {
@@ -50,68 +68,139 @@ pub async fn run(
 
     let orderbook_client = OrderbookClient::new();
 
-    let mut orders: Vec<OrderResponse> = Vec::new();
+    let maker_id = maker_key.public_key(SECP256K1);
+    let sign = |message: Message| Signature {
+        pubkey: maker_id,
+        signature: maker_key.sign_ecdsa(message),
+    };
 
     // Closure to avoid repeating the same code
-    let add_new_10101_order = |price, direction| {
+    let add_new_10101_order = |price, direction, quantity| {
         add_10101_order(
             &orderbook_client,
             orderbook_url,
             price,
             direction,
             maker_id,
-            dec!(5000),
+            quantity,
             OffsetDateTime::now_utc() + order_expiry_after,
+            sign,
         )
     };
 
-    let credentials = match (bitmex_api_key, bitmex_api_secret) {
-        (Some(api_key), Some(secret)) => Some(Credentials { api_key, secret }),
-        _ => None,
-    };
+    // Closure to avoid repeating the same code
+    let cancel_all_10101_orders =
+        || cancel_10101_orders(&orderbook_client, orderbook_url, maker_id, sign);
+
+    // Whether the kill switch has been engaged and all orders have been pulled since. The orders
+    // are pulled again every `PULL_ORDERS_RETRY_INTERVAL` until that succeeds.
+    let mut kill_switch_engaged = false;
+    let mut orders_pulled = false;
+    let mut pull_orders_retry = tokio::time::interval(PULL_ORDERS_RETRY_INTERVAL);
+    pull_orders_retry.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
     loop {
-        let mut stream = bitmex_ws_client::stream(network, credentials.clone()).await;
+        let mut stream = bitmex_ws_client::stream(network).await;
         loop {
-            match stream.try_next().await {
-                Ok(Some(Event::Quote(quote))) => {
-                    let _ = bitmex_pricefeed_tx.send(ServiceStatus::Online);
-                    tracing::debug!("Received new quote {quote:?}");
+            tokio::select! {
+                event = stream.try_next() => match event {
+                    Ok(Some(Event::Quote(quote))) => {
+                        let _ = bitmex_pricefeed_tx.send(ServiceStatus::Online);
+                        tracing::debug!("Received new quote {quote:?}");
 
-                    orders.clear();
+                        let contract_symbol = position::ContractSymbol::from(quote.contract_symbol);
 
-                    for _i in 0..concurrent_orders {
-                        if let Some(order) = add_new_10101_order(quote.bid(), Direction::Long).await
-                        {
-                            orders.push(order)
-                        };
+                        let _ = position_manager
+                            .send(PriceUpdate {
+                                contract_symbol,
+                                price: (quote.bid() + quote.ask()) / Decimal::TWO,
+                            })
+                            .await;
 
-                        if let Some(order) =
-                            add_new_10101_order(quote.ask(), Direction::Short).await
-                        {
-                            orders.push(order)
+                        if risk_status.borrow().kill_switch.is_engaged() {
+                            tracing::debug!("Not quoting while the kill switch is engaged");
+                            continue;
+                        }
+
+                        let inventory = match position_manager.send(GetPosition).await {
+                            Ok(position) => position
+                                .tentenone
+                                .get(&contract_symbol)
+                                .copied()
+                                .unwrap_or_default(),
+                            Err(e) => {
+                                tracing::error!(
+                                    "Failed to get position, not replacing orders: {e:#}"
+                                );
+                                continue;
+                            }
                         };
+
+                        if !requote_limiter.should_requote(
+                            quote.bid(),
+                            quote.ask(),
+                            inventory,
+                            Instant::now(),
+                        ) {
+                            continue;
+                        }
+
+                        // The previous orders have to be cancelled before posting the new ones, as
+                        // the new orders could otherwise be matched with them.
+                        cancel_all_10101_orders().await;
+
+                        for level in strategy.quotes(quote.bid(), quote.ask(), inventory) {
+                            add_new_10101_order(level.price, level.direction, level.quantity)
+                                .await;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Closing BitMEX WS after encountering error: {e:#}");
+                        break;
+                    }
+                    Ok(None) => {
+                        tracing::error!("BitMEX WS closed");
+                        break;
+                    }
+                },
+                Ok(()) = risk_status.changed() => {
+                    let kill_switch = risk_status.borrow_and_update().kill_switch;
+                    match (kill_switch.is_engaged(), kill_switch_engaged) {
+                        (true, false) => {
+                            tracing::warn!(?kill_switch, "Pulling all orders");
+                            kill_switch_engaged = true;
+
+                            orders_pulled = cancel_all_10101_orders().await;
+                            requote_limiter.reset();
+
+                            let _ = position_manager.send(OrdersPulled(orders_pulled)).await;
+                        }
+                        (false, true) => {
+                            kill_switch_engaged = false;
+                            orders_pulled = false;
+                        }
+                        _ => {}
                     }
                 }
-                Ok(Some(Event::Position(position))) => {
-                    let _ = position_manager
-                        .send(PositionUpdateBitmex {
-                            contract_symbol: position.contract_symbol.into(),
-                            contracts: position.contracts,
-                        })
-                        .await;
-                }
-                Err(e) => {
-                    tracing::error!("Closing BitMEX WS after encountering error: {e:#}");
-                    break;
-                }
-                Ok(None) => {
-                    tracing::error!("BitMEX WS closed");
-                    break;
+                _ = pull_orders_retry.tick(), if kill_switch_engaged && !orders_pulled => {
+                    tracing::warn!("Retrying to pull all orders");
+
+                    orders_pulled = cancel_all_10101_orders().await;
+
+                    let _ = position_manager.send(OrdersPulled(orders_pulled)).await;
                 }
             }
         }
 
+        // Without a price feed the orders of the maker would go stale.
+        let cancelled = cancel_all_10101_orders().await;
+        requote_limiter.reset();
+
+        if kill_switch_engaged && !orders_pulled && cancelled {
+            orders_pulled = true;
+            let _ = position_manager.send(OrdersPulled(orders_pulled)).await;
+        }
+
         let _ = bitmex_pricefeed_tx.send(ServiceStatus::Offline);
 
         tracing::error!(timeout = ?reconnect_after, "Reconnecting to BitMEX WS after timeout");
@@ -120,6 +209,28 @@ pub async fn run(
     }
 }
 
+async fn cancel_10101_orders(
+    orderbook_client: &OrderbookClient,
+    orderbook_url: &Url,
+    maker_id: PublicKey,
+    sign: impl Fn(Message) -> Signature,
+) -> bool {
+    match orderbook_client
+        .cancel_all_orders(orderbook_url, maker_id, sign)
+        .await
+    {
+        Ok(orders) => {
+            tracing::debug!("Cancelled {} orders", orders.len());
+            true
+        }
+        Err(e) => {
+            tracing::error!("Failed to cancel orders: {e:#}");
+            false
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn add_10101_order(
     orderbook_client: &OrderbookClient,
     orderbook_url: &Url,
@@ -128,7 +239,8 @@ async fn add_10101_order(
     maker_id: PublicKey,
     quantity: Decimal,
     expiry: OffsetDateTime,
-) -> Option<OrderResponse> {
+    sign: impl Fn(Message) -> Signature,
+) -> Option<Order> {
     orderbook_client
         .post_new_order(
             orderbook_url,
@@ -143,7 +255,11 @@ async fn add_10101_order(
                 order_type: OrderType::Limit,
                 expiry,
                 stable: false,
+                time_in_force: TimeInForce::GoodTillTime,
+                // The maker only provides liquidity, it is not able to execute trades as a taker.
+                post_only: true,
             },
+            sign,
         )
         .await
         .map_err(|err| {
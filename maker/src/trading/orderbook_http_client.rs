@@ -1,7 +1,9 @@
-use anyhow::bail;
 use anyhow::Result;
+use bitcoin::secp256k1::Message;
+use bitcoin::secp256k1::PublicKey;
 use commons::NewOrder;
-use commons::OrderResponse;
+use commons::Order;
+use commons::Signature;
 use reqwest::Url;
 
 pub struct OrderbookClient {
@@ -18,17 +20,22 @@ impl OrderbookClient {
         }
     }
 
-    pub async fn post_new_order(&self, url: &Url, order: NewOrder) -> Result<OrderResponse> {
-        let url = url.join("/api/orderbook/orders")?;
-
-        let response = self.client.post(url).json(&order).send().await?;
+    pub async fn post_new_order(
+        &self,
+        url: &Url,
+        order: NewOrder,
+        sign: impl Fn(Message) -> Signature,
+    ) -> Result<Order> {
+        orderbook_client::post_order(&self.client, url, order, sign).await
+    }
 
-        if response.status().as_u16() == 200 {
-            let response = response.json().await?;
-            Ok(response)
-        } else {
-            tracing::error!("Could not create new order");
-            bail!("Could not create new order ")
-        }
+    /// Cancels all open orders of the maker, returning the cancelled orders.
+    pub async fn cancel_all_orders(
+        &self,
+        url: &Url,
+        maker_id: PublicKey,
+        sign: impl Fn(Message) -> Signature,
+    ) -> Result<Vec<Order>> {
+        orderbook_client::cancel_all_orders(&self.client, url, maker_id, sign).await
     }
 }
@@ -0,0 +1,243 @@
+use rust_decimal::Decimal;
+use rust_decimal::RoundingStrategy;
+use rust_decimal_macros::dec;
+use std::time::Duration;
+use std::time::Instant;
+use trade::Direction;
+
+const BPS: Decimal = dec!(10_000);
+
+/// An order to be posted to the 10101 orderbook.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuoteLevel {
+    pub direction: Direction,
+    pub price: Decimal,
+    pub quantity: Decimal,
+}
+
+/// Decides which orders the maker posts to the 10101 orderbook.
+pub trait QuotingStrategy: Send + Sync {
+    /// Derives the orders to be posted from BitMEX's best `bid` and `ask` and the maker's 10101
+    /// position in contracts, where a positive `inventory` is long and a negative one is short.
+    fn quotes(&self, bid: Decimal, ask: Decimal, inventory: Decimal) -> Vec<QuoteLevel>;
+}
+
+/// Quotes a ladder of orders on both sides of BitMEX's mid price.
+///
+/// The best bid and ask are `spread_bps` apart and every further level is `level_step_bps` away
+/// from the previous one. The whole ladder is shifted against the inventory of the maker, by up to
+/// `max_skew_bps` once the inventory reaches `max_inventory` contracts, so that a long maker
+/// becomes more likely to sell and a short maker more likely to buy.
+#[derive(Debug, Clone)]
+pub struct LadderStrategy {
+    pub spread_bps: Decimal,
+    pub level_step_bps: Decimal,
+    /// The quantity in contracts of every level on each side, best level first.
+    pub level_sizes: Vec<Decimal>,
+    pub max_skew_bps: Decimal,
+    pub max_inventory: Decimal,
+}
+
+impl LadderStrategy {
+    /// The shift of the ladder in basis points, negative if the maker is long.
+    fn skew_bps(&self, inventory: Decimal) -> Decimal {
+        if self.max_inventory <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+
+        let ratio = (inventory / self.max_inventory).clamp(Decimal::NEGATIVE_ONE, Decimal::ONE);
+
+        -ratio * self.max_skew_bps
+    }
+}
+
+impl QuotingStrategy for LadderStrategy {
+    fn quotes(&self, bid: Decimal, ask: Decimal, inventory: Decimal) -> Vec<QuoteLevel> {
+        let mid = (bid + ask) / dec!(2);
+        let half_spread_bps = self.spread_bps / dec!(2);
+        let skew_bps = self.skew_bps(inventory);
+
+        let mut levels = Vec::with_capacity(self.level_sizes.len() * 2);
+        for (i, quantity) in self.level_sizes.iter().enumerate() {
+            let distance_bps = half_spread_bps + self.level_step_bps * Decimal::from(i);
+
+            // Prices are rounded away from the mid price, so that the spread is never tighter
+            // than configured.
+            let bid = mid * (Decimal::ONE - (distance_bps - skew_bps) / BPS);
+            let ask = mid * (Decimal::ONE + (distance_bps + skew_bps) / BPS);
+
+            levels.push(QuoteLevel {
+                direction: Direction::Long,
+                price: bid.round_dp_with_strategy(1, RoundingStrategy::ToNegativeInfinity),
+                quantity: *quantity,
+            });
+            levels.push(QuoteLevel {
+                direction: Direction::Short,
+                price: ask.round_dp_with_strategy(1, RoundingStrategy::ToPositiveInfinity),
+                quantity: *quantity,
+            });
+        }
+
+        levels
+    }
+}
+
+/// Limits how often the maker replaces its orders.
+///
+/// Orders are only replaced if `min_interval` has passed since the last time and either the mid
+/// price moved by at least `threshold_bps` or the inventory of the maker changed. Regardless of
+/// price movements, orders are replaced once they are `max_age` old, so that they are renewed
+/// before they expire.
+#[derive(Debug)]
+pub struct RequoteLimiter {
+    min_interval: Duration,
+    max_age: Duration,
+    threshold_bps: Decimal,
+    last_quote: Option<LastQuote>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LastQuote {
+    mid: Decimal,
+    inventory: Decimal,
+    timestamp: Instant,
+}
+
+impl RequoteLimiter {
+    pub fn new(min_interval: Duration, max_age: Duration, threshold_bps: Decimal) -> Self {
+        Self {
+            min_interval,
+            max_age,
+            threshold_bps,
+            last_quote: None,
+        }
+    }
+
+    /// Whether the orders should be replaced, in which case the given state is remembered as the
+    /// last quote.
+    pub fn should_requote(
+        &mut self,
+        bid: Decimal,
+        ask: Decimal,
+        inventory: Decimal,
+        now: Instant,
+    ) -> bool {
+        let mid = (bid + ask) / dec!(2);
+
+        let requote = match self.last_quote {
+            None => true,
+            Some(last) => {
+                let age = now.saturating_duration_since(last.timestamp);
+                let moved_bps = ((mid - last.mid) / last.mid).abs() * BPS;
+
+                age >= self.max_age
+                    || (age >= self.min_interval
+                        && (moved_bps >= self.threshold_bps || inventory != last.inventory))
+            }
+        };
+
+        if requote {
+            self.last_quote = Some(LastQuote {
+                mid,
+                inventory,
+                timestamp: now,
+            });
+        }
+
+        requote
+    }
+
+    /// Forgets the last quote, so that the orders are replaced on the next price update.
+    pub fn reset(&mut self) {
+        self.last_quote = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strategy() -> LadderStrategy {
+        LadderStrategy {
+            spread_bps: dec!(10),
+            level_step_bps: dec!(5),
+            level_sizes: vec![dec!(1000), dec!(2000)],
+            max_skew_bps: dec!(10),
+            max_inventory: dec!(10_000),
+        }
+    }
+
+    fn level(direction: Direction, price: Decimal, quantity: Decimal) -> QuoteLevel {
+        QuoteLevel {
+            direction,
+            price,
+            quantity,
+        }
+    }
+
+    #[test]
+    fn flat_maker_quotes_symmetric_ladder_around_mid() {
+        let quotes = strategy().quotes(dec!(39_990), dec!(40_010), Decimal::ZERO);
+
+        assert_eq!(
+            quotes,
+            vec![
+                level(Direction::Long, dec!(39_980), dec!(1000)),
+                level(Direction::Short, dec!(40_020), dec!(1000)),
+                level(Direction::Long, dec!(39_960), dec!(2000)),
+                level(Direction::Short, dec!(40_040), dec!(2000)),
+            ]
+        );
+    }
+
+    #[test]
+    fn long_maker_skews_ladder_down() {
+        let quotes = strategy().quotes(dec!(40_000), dec!(40_000), dec!(5_000));
+
+        // Half of the maximum skew of 10 bps shifts both sides down by 5 bps.
+        assert_eq!(quotes[0], level(Direction::Long, dec!(39_960), dec!(1000)));
+        assert_eq!(quotes[1], level(Direction::Short, dec!(40_000), dec!(1000)));
+    }
+
+    #[test]
+    fn skew_is_capped_at_max_inventory() {
+        let strategy = strategy();
+
+        assert_eq!(
+            strategy.quotes(dec!(40_000), dec!(40_000), dec!(-50_000)),
+            strategy.quotes(dec!(40_000), dec!(40_000), dec!(-10_000)),
+        );
+    }
+
+    #[test]
+    fn prices_are_rounded_away_from_mid() {
+        let quotes = strategy().quotes(dec!(40_000.33), dec!(40_000.33), Decimal::ZERO);
+
+        assert_eq!(quotes[0].price, dec!(39_980.3));
+        assert_eq!(quotes[1].price, dec!(40_020.4));
+    }
+
+    #[test]
+    fn requotes_are_rate_limited() {
+        let mut limiter =
+            RequoteLimiter::new(Duration::from_secs(5), Duration::from_secs(30), dec!(2));
+        let start = Instant::now();
+
+        assert!(limiter.should_requote(dec!(40_000), dec!(40_000), dec!(0), start));
+
+        // The price moved by 5 bps, but not enough time has passed.
+        let now = start + Duration::from_secs(1);
+        assert!(!limiter.should_requote(dec!(40_020), dec!(40_020), dec!(0), now));
+
+        // Enough time has passed, but the price moved by only 1 bps.
+        let now = start + Duration::from_secs(6);
+        assert!(!limiter.should_requote(dec!(40_004), dec!(40_004), dec!(0), now));
+
+        // The inventory changed.
+        assert!(limiter.should_requote(dec!(40_004), dec!(40_004), dec!(100), now));
+
+        // The orders are about to expire.
+        let now = start + Duration::from_secs(36);
+        assert!(limiter.should_requote(dec!(40_004), dec!(40_004), dec!(100), now));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::position::ContractSymbol;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use rust_decimal::Decimal;
+use time::OffsetDateTime;
+
+pub mod bitmex;
+pub mod simulated;
+
+/// An exchange on which the maker hedges its 10101 position.
+#[async_trait]
+pub trait HedgingVenue: Send + Sync + 'static {
+    /// The number of contracts in one lot. Orders on the venue are always a multiple of it.
+    fn lot_size(&self) -> u32;
+
+    /// Places a market order for the given number of contracts.
+    ///
+    /// The sign determines the direction: positive buys; negative sells.
+    async fn place_order(&self, contract_symbol: ContractSymbol, contracts: i32) -> Result<()>;
+
+    /// The number of contracts of the maker's position on the venue.
+    ///
+    /// The sign determines the direction: positive is long; negative is short.
+    async fn position(&self, contract_symbol: ContractSymbol) -> Result<i32>;
+
+    /// A stream of the executions of the maker's orders on the venue.
+    fn fills(&self) -> BoxStream<'static, Result<Fill>>;
+
+    /// Informs the venue about the latest price of a contract.
+    ///
+    /// Only needed by venues without their own market data, such as the
+    /// [`simulated::SimulatedVenue`].
+    fn update_price(&self, _contract_symbol: ContractSymbol, _price: Decimal) {}
+}
+
+/// The (partial) execution of an order on a [`HedgingVenue`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    /// The ID of the execution on the venue.
+    pub id: String,
+    pub contract_symbol: ContractSymbol,
+    /// The number of contracts executed.
+    ///
+    /// The sign determines the direction: positive bought; negative sold.
+    pub contracts: i32,
+    pub price: Decimal,
+    /// The fee paid to the venue in sats. Negative for rebates.
+    pub fee_sats: i64,
+    pub timestamp: OffsetDateTime,
+}
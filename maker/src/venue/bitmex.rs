@@ -0,0 +1,220 @@
+use crate::position::ContractSymbol;
+use crate::venue::Fill;
+use crate::venue::HedgingVenue;
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::Result;
+use async_stream::stream;
+use async_trait::async_trait;
+use bitmex_client::models::Side;
+use bitmex_stream::Credentials;
+use bitmex_stream::Network;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+
+/// Hedges on BitMEX with market orders.
+pub struct Bitmex {
+    client: bitmex_client::client::Client,
+    network: Network,
+    credentials: Option<Credentials>,
+    lot_size: u32,
+}
+
+impl Bitmex {
+    pub fn new(
+        client: bitmex_client::client::Client,
+        network: Network,
+        credentials: Option<Credentials>,
+        lot_size: u32,
+    ) -> Self {
+        Self {
+            client,
+            network,
+            credentials,
+            lot_size,
+        }
+    }
+}
+
+#[async_trait]
+impl HedgingVenue for Bitmex {
+    fn lot_size(&self) -> u32 {
+        self.lot_size
+    }
+
+    async fn place_order(&self, contract_symbol: ContractSymbol, contracts: i32) -> Result<()> {
+        let side = match contracts.is_positive() {
+            true => Side::Buy,
+            false => Side::Sell,
+        };
+
+        self.client
+            .create_order(contract_symbol.into(), contracts.abs(), side, None)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn position(&self, contract_symbol: ContractSymbol) -> Result<i32> {
+        let symbol = bitmex_client::models::ContractSymbol::from(contract_symbol);
+
+        let contracts = self
+            .client
+            .positions()
+            .await?
+            .into_iter()
+            .find(|position| position.symbol == symbol)
+            .and_then(|position| position.current_qty)
+            .unwrap_or_default();
+
+        let contracts = i32::try_from(contracts)?;
+
+        Ok(contracts)
+    }
+
+    fn fills(&self) -> BoxStream<'static, Result<Fill>> {
+        let credentials = match self.credentials.clone() {
+            Some(credentials) => credentials,
+            None => {
+                return futures::stream::once(async {
+                    Err(anyhow!(
+                        "Cannot subscribe to BitMEX executions without credentials"
+                    ))
+                })
+                .boxed()
+            }
+        };
+
+        let mut stream = bitmex_stream::subscribe_with_credentials(
+            ["execution:XBTUSD".to_owned()],
+            self.network,
+            credentials,
+        );
+
+        stream! {
+            loop {
+                match stream.try_next().await {
+                    Ok(Some(text)) => match serde_json::from_str::<wire::ExecutionUpdate>(&text) {
+                        Ok(update) => {
+                            for fill in update.fills() {
+                                yield fill;
+                            }
+                        }
+                        Err(_) => {
+                            tracing::debug!("Unexpected table update: {text}");
+                        }
+                    },
+                    Err(error) => {
+                        yield Err(error);
+                    }
+                    Ok(None) => {
+                        yield Err(anyhow!("Stream ended"));
+                        break;
+                    }
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+impl From<ContractSymbol> for bitmex_client::models::ContractSymbol {
+    fn from(value: ContractSymbol) -> Self {
+        match value {
+            ContractSymbol::BtcUsd => Self::XbtUsd,
+        }
+    }
+}
+
+mod wire {
+    use super::*;
+    use rust_decimal::Decimal;
+    use serde::Deserialize;
+    use time::OffsetDateTime;
+
+    #[derive(Debug, Deserialize)]
+    pub struct ExecutionUpdate {
+        table: String,
+        data: Vec<ExecutionData>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ExecutionData {
+        #[serde(rename = "execID")]
+        pub exec_id: String,
+        pub symbol: bitmex_client::models::ContractSymbol,
+        pub side: Side,
+        pub last_qty: Option<i32>,
+        #[serde(default, with = "rust_decimal::serde::float_option")]
+        pub last_px: Option<Decimal>,
+        pub exec_type: String,
+        /// The commission in sats. Negative for rebates.
+        pub exec_comm: Option<i64>,
+        #[serde(with = "time::serde::rfc3339")]
+        pub timestamp: OffsetDateTime,
+    }
+
+    impl ExecutionUpdate {
+        /// The trades among the executions, ignoring executions such as order placements and
+        /// cancellations.
+        pub fn fills(self) -> Vec<Result<Fill>> {
+            if self.table != "execution" {
+                return vec![];
+            }
+
+            self.data
+                .into_iter()
+                .filter(|execution| execution.exec_type == "Trade")
+                .map(|execution| {
+                    let (contracts, price) = match (execution.last_qty, execution.last_px) {
+                        (Some(contracts), Some(price)) => (contracts, price),
+                        _ => bail!("Trade execution without quantity or price: {execution:?}"),
+                    };
+
+                    let contracts = match execution.side {
+                        Side::Buy => contracts,
+                        Side::Sell => -contracts,
+                        Side::Unknown => bail!("Trade execution without side: {execution:?}"),
+                    };
+
+                    Ok(Fill {
+                        id: execution.exec_id,
+                        contract_symbol: match execution.symbol {
+                            bitmex_client::models::ContractSymbol::XbtUsd => ContractSymbol::BtcUsd,
+                        },
+                        contracts,
+                        price,
+                        fee_sats: execution.exec_comm.unwrap_or_default(),
+                        timestamp: execution.timestamp,
+                    })
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn can_deserialize_execution_update() {
+        let update = serde_json::from_str::<wire::ExecutionUpdate>(r#"{"table":"execution","action":"insert","data":[{"execID":"0193e879-cb6f-2891-d099-2c4eb40fee21","orderID":"00000000-0000-0000-0000-000000000000","symbol":"XBTUSD","side":"Sell","lastQty":100,"lastPx":42641,"execType":"Trade","execComm":2345,"timestamp":"2024-02-20T10:00:00.000Z"},{"execID":"0193e879-cb6f-2891-d099-2c4eb40fee22","orderID":"00000000-0000-0000-0000-000000000000","symbol":"XBTUSD","side":"Buy","lastQty":null,"lastPx":null,"execType":"New","execComm":null,"timestamp":"2024-02-20T10:00:00.000Z"}]}"#).unwrap();
+
+        let fills = update
+            .fills()
+            .into_iter()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].contract_symbol, ContractSymbol::BtcUsd);
+        assert_eq!(fills[0].contracts, -100);
+        assert_eq!(fills[0].price, dec!(42641));
+        assert_eq!(fills[0].fee_sats, 2345);
+        assert_eq!(fills[0].timestamp.unix_timestamp(), 1708423200);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::position::ContractSymbol;
+use crate::venue::Fill;
+use crate::venue::HedgingVenue;
+use anyhow::ensure;
+use anyhow::Context;
+use anyhow::Result;
+use async_stream::stream;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use time::OffsetDateTime;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+/// An in-memory [`HedgingVenue`] which fills every order immediately and without fees at the latest
+/// price.
+///
+/// Used to run the maker in paper mode and in tests.
+pub struct SimulatedVenue {
+    lot_size: u32,
+    positions: Mutex<HashMap<ContractSymbol, i32>>,
+    prices: Mutex<HashMap<ContractSymbol, Decimal>>,
+    fills: broadcast::Sender<Fill>,
+}
+
+impl SimulatedVenue {
+    pub fn new(lot_size: u32) -> Self {
+        let (fills, _) = broadcast::channel(100);
+
+        Self {
+            lot_size,
+            positions: Mutex::default(),
+            prices: Mutex::default(),
+            fills,
+        }
+    }
+}
+
+#[async_trait]
+impl HedgingVenue for SimulatedVenue {
+    fn lot_size(&self) -> u32 {
+        self.lot_size
+    }
+
+    async fn place_order(&self, contract_symbol: ContractSymbol, contracts: i32) -> Result<()> {
+        ensure!(
+            contracts % self.lot_size as i32 == 0,
+            "{contracts} contracts are not a multiple of the lot size {}",
+            self.lot_size
+        );
+
+        let price = self
+            .prices
+            .lock()
+            .expect("to get lock")
+            .get(&contract_symbol)
+            .copied()
+            .context("No price to fill order at")?;
+
+        *self
+            .positions
+            .lock()
+            .expect("to get lock")
+            .entry(contract_symbol)
+            .or_default() += contracts;
+
+        // An error only means that nobody is listening for fills at the moment.
+        let _ = self.fills.send(Fill {
+            id: Uuid::new_v4().to_string(),
+            contract_symbol,
+            contracts,
+            price,
+            fee_sats: 0,
+            timestamp: OffsetDateTime::now_utc(),
+        });
+
+        Ok(())
+    }
+
+    async fn position(&self, contract_symbol: ContractSymbol) -> Result<i32> {
+        let contracts = self
+            .positions
+            .lock()
+            .expect("to get lock")
+            .get(&contract_symbol)
+            .copied()
+            .unwrap_or_default();
+
+        Ok(contracts)
+    }
+
+    fn fills(&self) -> BoxStream<'static, Result<Fill>> {
+        let mut fills = self.fills.subscribe();
+
+        stream! {
+            loop {
+                match fills.recv().await {
+                    Ok(fill) => yield Ok(fill),
+                    Err(RecvError::Lagged(skip)) => {
+                        tracing::warn!(%skip, "Lagging behind on simulated fills");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }
+        .boxed()
+    }
+
+    fn update_price(&self, contract_symbol: ContractSymbol, price: Decimal) {
+        self.prices
+            .lock()
+            .expect("to get lock")
+            .insert(contract_symbol, price);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn orders_are_filled_at_latest_price() {
+        let venue = SimulatedVenue::new(100);
+        let mut fills = venue.fills();
+
+        venue.update_price(ContractSymbol::BtcUsd, dec!(40_000));
+        venue
+            .place_order(ContractSymbol::BtcUsd, 300)
+            .await
+            .unwrap();
+        venue
+            .place_order(ContractSymbol::BtcUsd, -500)
+            .await
+            .unwrap();
+
+        assert_eq!(venue.position(ContractSymbol::BtcUsd).await.unwrap(), -200);
+
+        let fill = fills.try_next().await.unwrap().unwrap();
+        assert_eq!(fill.contracts, 300);
+        assert_eq!(fill.price, dec!(40_000));
+    }
+
+    #[tokio::test]
+    async fn orders_must_be_multiple_of_lot_size() {
+        let venue = SimulatedVenue::new(100);
+        venue.update_price(ContractSymbol::BtcUsd, dec!(40_000));
+
+        assert!(venue
+            .place_order(ContractSymbol::BtcUsd, 150)
+            .await
+            .is_err());
+        assert_eq!(venue.position(ContractSymbol::BtcUsd).await.unwrap(), 0);
+    }
+}
@@ -14,7 +14,9 @@ class OrderService {
         contractSymbol: contractSymbol.toApi(),
         direction: direction.toApi(),
         orderType: const rust.OrderType.market(),
-        stable: stable);
+        stable: stable,
+        timeInForce: rust.TimeInForce.FillOrKill,
+        postOnly: false);
 
     return await rust.api.submitOrder(order: order);
   }
@@ -6,7 +6,9 @@ import 'package:get_10101/features/trade/domain/leverage.dart';
 
 enum OrderReason {
   manual,
-  expired;
+  expired,
+  liquidated,
+  triggered;
 
   static OrderReason fromApi(bridge.OrderReason orderReason) {
     switch (orderReason) {
@@ -14,6 +16,10 @@ enum OrderReason {
         return OrderReason.manual;
       case bridge.OrderReason.Expired:
         return OrderReason.expired;
+      case bridge.OrderReason.Liquidated:
+        return OrderReason.liquidated;
+      case bridge.OrderReason.Triggered:
+        return OrderReason.triggered;
     }
   }
 
@@ -50,6 +56,7 @@ enum FailureReasonType {
   failed,
   timeout,
   rejected,
+  cancelled,
   unknown;
 }
 
@@ -68,6 +75,8 @@ class FailureReason {
       details: "The order timed out before finding a match");
   static const FailureReason rejected =
       FailureReason._(failureType: FailureReasonType.rejected, details: "The order was rejected.");
+  static const FailureReason cancelled = FailureReason._(
+      failureType: FailureReasonType.cancelled, details: "The order was cancelled.");
   static const FailureReason unknown = FailureReason._(
       failureType: FailureReasonType.unknown, details: "An unknown error occurred.");
 
@@ -91,6 +100,8 @@ class FailureReason {
         return timeout;
       case bridge.FailureReason_OrderRejected():
         return rejected;
+      case bridge.FailureReason_Cancelled():
+        return cancelled;
       case bridge.FailureReason_Unknown():
         return unknown;
     }
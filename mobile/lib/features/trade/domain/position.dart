@@ -39,6 +39,10 @@ class Position {
   final Amount collateral;
   final DateTime expiry;
 
+  // The total funding fee settled with the rollovers of the position. Positive if paid, negative if
+  // received.
+  final Amount fundingFee;
+
   Position(
       {required this.averageEntryPrice,
       required this.liquidationPrice,
@@ -50,7 +54,8 @@ class Position {
       this.unrealizedPnl,
       required this.collateral,
       required this.expiry,
-      required this.stable});
+      required this.stable,
+      required this.fundingFee});
 
   bool isStable() => stable;
 
@@ -74,6 +79,7 @@ class Position {
       collateral: Amount(position.collateral),
       expiry: DateTime.fromMillisecondsSinceEpoch(position.expiry * 1000),
       stable: position.stable,
+      fundingFee: Amount(position.fundingFeeSat),
     );
   }
 
@@ -89,6 +95,7 @@ class Position {
       collateral: 0,
       expiry: 0,
       stable: false,
+      fundingFeeSat: 0,
     );
   }
 }
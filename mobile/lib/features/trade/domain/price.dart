@@ -14,7 +14,9 @@ class Price {
     return Price(bid: bestPrice.bid, ask: bestPrice.ask);
   }
 
-  static bridge.BestPrice apiDummy() {
-    return const bridge.BestPrice(bid: null, ask: null);
+  static List<bridge.BestPrice> apiDummy() {
+    return const [
+      bridge.BestPrice(contractSymbol: bridge.ContractSymbol.BtcUsd, bid: null, ask: null)
+    ];
   }
 }
@@ -16,7 +16,17 @@ class PositionChangeNotifier extends ChangeNotifier implements Subscriber {
 
   Map<ContractSymbol, Position> positions = {};
 
-  Price? price;
+  Map<ContractSymbol, Price> prices = {};
+
+  Price? get price => prices[ContractSymbol.btcusd];
+
+  set price(Price? price) {
+    if (price == null) {
+      prices.remove(ContractSymbol.btcusd);
+    } else {
+      prices[ContractSymbol.btcusd] = price;
+    }
+  }
 
   /// Amount of stabilised bitcoin in terms of USD (fiat)
   double getStableUSDAmountInFiat() {
@@ -117,11 +127,14 @@ class PositionChangeNotifier extends ChangeNotifier implements Subscriber {
 
       notifyListeners();
     } else if (event is bridge.Event_PriceUpdateNotification) {
-      price = Price.fromApi(event.field0);
+      for (bridge.BestPrice bestPrice in event.field0) {
+        prices[ContractSymbol.fromApi(bestPrice.contractSymbol)] = Price.fromApi(bestPrice);
+      }
       for (ContractSymbol symbol in positions.keys) {
+        final price = prices[symbol];
         if (price != null) {
           if (positions[symbol] != null) {
-            final pnl = _positionService.calculatePnl(positions[symbol]!, price!);
+            final pnl = _positionService.calculatePnl(positions[symbol]!, price);
             positions[symbol]!.unrealizedPnl = pnl != null ? Amount(pnl) : null;
           }
         }
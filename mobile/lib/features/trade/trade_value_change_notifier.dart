@@ -4,6 +4,7 @@ import 'package:get_10101/common/application/event_service.dart';
 import 'package:get_10101/common/domain/model.dart';
 import 'package:get_10101/common/dummy_values.dart';
 import 'package:get_10101/features/trade/application/trade_values_service.dart';
+import 'package:get_10101/features/trade/domain/contract_symbol.dart';
 import 'package:get_10101/features/trade/domain/direction.dart';
 import 'package:get_10101/features/trade/domain/leverage.dart';
 import 'package:get_10101/features/trade/domain/price.dart';
@@ -115,7 +116,11 @@ class TradeValuesChangeNotifier extends ChangeNotifier implements Subscriber {
   @override
   void notify(bridge.Event event) {
     if (event is bridge.Event_PriceUpdateNotification) {
-      updatePrice(Price.fromApi(event.field0));
+      for (bridge.BestPrice bestPrice in event.field0) {
+        if (ContractSymbol.fromApi(bestPrice.contractSymbol) == ContractSymbol.btcusd) {
+          updatePrice(Price.fromApi(bestPrice));
+        }
+      }
     }
   }
 }
@@ -103,7 +103,7 @@ class WalletService {
     var sats = amount!.sats;
     var address = destination.raw;
     logger.i("Sending payment of $amount to $address with fee $feeApi");
-    return rust.api.sendOnChainPayment(address: address, amount: sats, fee: feeApi);
+    return rust.api.sendOnChainPayment(address: address, amount: sats, fee: feeApi, inputs: []);
   }
 
   String getUnusedAddress() {
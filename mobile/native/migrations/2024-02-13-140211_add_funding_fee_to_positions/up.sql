@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE
+    positions
+    ADD
+        COLUMN funding_fee_sat BIGINT NOT NULL DEFAULT 0;
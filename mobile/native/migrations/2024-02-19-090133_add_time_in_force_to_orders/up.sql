@@ -0,0 +1,9 @@
+-- Your SQL goes here
+ALTER TABLE
+    orders
+    ADD
+        COLUMN time_in_force TEXT NOT NULL DEFAULT 'GoodTillTime';
+ALTER TABLE
+    orders
+    ADD
+        COLUMN post_only BOOLEAN NOT NULL DEFAULT false;
@@ -24,6 +24,8 @@ use crate::polls;
 use crate::trade::order;
 use crate::trade::order::api::NewOrder;
 use crate::trade::order::api::Order;
+use crate::trade::order::api::TriggerOrder;
+use crate::trade::order::api::TriggerType;
 use crate::trade::position;
 use crate::trade::position::api::Position;
 use crate::trade::users;
@@ -52,6 +54,7 @@ use tokio::sync::broadcast;
 use tokio::sync::broadcast::channel;
 pub use trade::ContractSymbol;
 pub use trade::Direction;
+use uuid::Uuid;
 
 /// Initialise logging infrastructure for Rust
 pub fn init_logging(sink: StreamSink<logger::LogEntry>) {
@@ -339,6 +342,69 @@ pub async fn submit_order(order: NewOrder) -> Result<String> {
         .map(|id| id.to_string())
 }
 
+#[tokio::main(flavor = "current_thread")]
+pub async fn cancel_order(order_id: String) -> Result<()> {
+    let order_id = Uuid::parse_str(&order_id)?;
+    order::handler::cancel_order(order_id).await?;
+
+    Ok(())
+}
+
+#[tokio::main(flavor = "current_thread")]
+pub async fn cancel_all_orders() -> Result<()> {
+    order::handler::cancel_all_orders().await
+}
+
+#[tokio::main(flavor = "current_thread")]
+pub async fn amend_order(order_id: String, price: f32, quantity: f32) -> Result<Order> {
+    let order_id = Uuid::parse_str(&order_id)?;
+    let order = order::handler::amend_order(order_id, price, quantity).await?;
+
+    Ok(order.into())
+}
+
+#[tokio::main(flavor = "current_thread")]
+pub async fn submit_trigger_order(
+    contract_symbol: ContractSymbol,
+    trigger_type: TriggerType,
+    trigger_price: f32,
+) -> Result<TriggerOrder> {
+    let trigger_order =
+        order::handler::submit_trigger_order(contract_symbol, trigger_type.into(), trigger_price)
+            .await?;
+
+    Ok(trigger_order.into())
+}
+
+#[tokio::main(flavor = "current_thread")]
+pub async fn amend_trigger_order(
+    trigger_order_id: String,
+    trigger_price: f32,
+) -> Result<TriggerOrder> {
+    let trigger_order_id = Uuid::parse_str(&trigger_order_id)?;
+    let trigger_order =
+        order::handler::amend_trigger_order(trigger_order_id, trigger_price).await?;
+
+    Ok(trigger_order.into())
+}
+
+#[tokio::main(flavor = "current_thread")]
+pub async fn cancel_trigger_order(trigger_order_id: String) -> Result<()> {
+    let trigger_order_id = Uuid::parse_str(&trigger_order_id)?;
+    order::handler::cancel_trigger_order(trigger_order_id).await
+}
+
+#[tokio::main(flavor = "current_thread")]
+pub async fn get_trigger_orders() -> Result<Vec<TriggerOrder>> {
+    let trigger_orders = order::handler::get_trigger_orders()
+        .await?
+        .into_iter()
+        .map(|trigger_order| trigger_order.into())
+        .collect::<Vec<TriggerOrder>>();
+
+    Ok(trigger_orders)
+}
+
 #[tokio::main(flavor = "current_thread")]
 pub async fn get_orders() -> Result<Vec<Order>> {
     let orders = order::handler::get_orders_for_ui()
@@ -727,8 +793,63 @@ pub fn send_payment(payment: SendPayment) -> Result<()> {
     runtime.block_on(async { ln_dlc::send_payment(payment).await })
 }
 
-pub fn send_on_chain_payment(address: String, amount: u64, fee: Fee) -> Result<SyncReturn<String>> {
-    ln_dlc::send_on_chain_payment(address, amount, fee).map(|txid| SyncReturn(txid.to_string()))
+/// Sends `amount` sats to `address`. If `inputs` are given, exactly these UTXOs are spent.
+/// Otherwise they are selected automatically, leaving out frozen UTXOs.
+pub fn send_on_chain_payment(
+    address: String,
+    amount: u64,
+    fee: Fee,
+    inputs: Vec<String>,
+) -> Result<SyncReturn<String>> {
+    ln_dlc::send_on_chain_payment(address, amount, fee, inputs)
+        .map(|txid| SyncReturn(txid.to_string()))
+}
+
+pub struct Utxo {
+    /// The outpoint of the UTXO, formatted as `txid:vout`.
+    pub outpoint: String,
+    pub amount: u64,
+    pub label: Option<String>,
+    /// Frozen UTXOs are left out of automatic coin selection.
+    pub frozen: bool,
+}
+
+pub fn list_utxos() -> Result<Vec<Utxo>> {
+    ln_dlc::list_utxos()
+}
+
+pub fn set_utxo_frozen(outpoint: String, frozen: bool) -> Result<SyncReturn<()>> {
+    ln_dlc::set_utxo_frozen(outpoint, frozen).map(SyncReturn)
+}
+
+/// Labels the UTXO at `outpoint`. An empty or missing `label` removes the label.
+pub fn set_utxo_label(outpoint: String, label: Option<String>) -> Result<SyncReturn<()>> {
+    ln_dlc::set_utxo_label(outpoint, label).map(SyncReturn)
+}
+
+/// Merges the given UTXOs (or all UTXOs which are not frozen if none are given) into a single
+/// output of the on-chain wallet, returning the id of the consolidation transaction.
+pub fn consolidate_utxos(inputs: Vec<String>, fee: Fee) -> Result<SyncReturn<String>> {
+    ln_dlc::consolidate_utxos(inputs, fee).map(|txid| SyncReturn(txid.to_string()))
+}
+
+/// How to speed up the confirmation of an unconfirmed on-chain transaction.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum FeeBumpMethod {
+    /// Replace the transaction with one paying a higher fee. Only works for transactions we sent.
+    Rbf,
+    /// Spend our output of the transaction with a child transaction paying for both.
+    Cpfp,
+}
+
+/// Bumps the fee of the unconfirmed on-chain transaction `txid` to `fee`, returning the id of the
+/// replacement or child transaction.
+pub fn bump_on_chain_fee(
+    txid: String,
+    method: FeeBumpMethod,
+    fee: Fee,
+) -> Result<SyncReturn<String>> {
+    ln_dlc::bump_on_chain_fee(txid, method, fee).map(|txid| SyncReturn(txid.to_string()))
 }
 
 pub fn send_preflight_probe(payment: SendPayment) -> Result<u64> {
@@ -9,6 +9,7 @@ use crate::db::models::OrderReason;
 use crate::db::models::OrderState;
 use crate::db::models::OrderType;
 use crate::db::models::PositionState;
+use crate::db::models::TimeInForce;
 use diesel::backend;
 use diesel::deserialize;
 use diesel::deserialize::FromSql;
@@ -47,6 +48,8 @@ impl ToSql<Text, Sqlite> for OrderReason {
         let text = match *self {
             OrderReason::Manual => "Manual".to_string(),
             OrderReason::Expired => "Expired".to_string(),
+            OrderReason::Liquidated => "Liquidated".to_string(),
+            OrderReason::Triggered => "Triggered".to_string(),
         };
         out.set_value(text);
         Ok(IsNull::No)
@@ -60,6 +63,35 @@ impl FromSql<Text, Sqlite> for OrderReason {
         return match string.as_str() {
             "Manual" => Ok(OrderReason::Manual),
             "Expired" => Ok(OrderReason::Expired),
+            "Liquidated" => Ok(OrderReason::Liquidated),
+            "Triggered" => Ok(OrderReason::Triggered),
+            _ => Err("Unrecognized enum variant".into()),
+        };
+    }
+}
+
+impl ToSql<Text, Sqlite> for TimeInForce {
+    fn to_sql(&self, out: &mut Output<Sqlite>) -> serialize::Result {
+        let text = match *self {
+            TimeInForce::ImmediateOrCancel => "ImmediateOrCancel".to_string(),
+            TimeInForce::FillOrKill => "FillOrKill".to_string(),
+            TimeInForce::GoodTillCancelled => "GoodTillCancelled".to_string(),
+            TimeInForce::GoodTillTime => "GoodTillTime".to_string(),
+        };
+        out.set_value(text);
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Sqlite> for TimeInForce {
+    fn from_sql(bytes: backend::RawValue<Sqlite>) -> deserialize::Result<Self> {
+        let string = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+
+        return match string.as_str() {
+            "ImmediateOrCancel" => Ok(TimeInForce::ImmediateOrCancel),
+            "FillOrKill" => Ok(TimeInForce::FillOrKill),
+            "GoodTillCancelled" => Ok(TimeInForce::GoodTillCancelled),
+            "GoodTillTime" => Ok(TimeInForce::GoodTillTime),
             _ => Err("Unrecognized enum variant".into()),
         };
     }
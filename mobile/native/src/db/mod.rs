@@ -166,6 +166,24 @@ pub fn update_order_state(
     Ok(order.try_into()?)
 }
 
+pub fn amend_order(order_id: Uuid, limit_price: f32, quantity: f32) -> Result<trade::order::Order> {
+    let mut db = connection()?;
+
+    let order = Order::amend(order_id.to_string(), limit_price, quantity, &mut db)
+        .context("Failed to amend order")?;
+
+    Ok(order.try_into()?)
+}
+
+pub fn set_order_quantity(order_id: Uuid, quantity: f32) -> Result<trade::order::Order> {
+    let mut db = connection()?;
+
+    let order = Order::set_quantity(order_id.to_string(), quantity, &mut db)
+        .context("Failed to set order quantity")?;
+
+    Ok(order.try_into()?)
+}
+
 pub fn get_order(order_id: Uuid) -> Result<trade::order::Order> {
     let mut db = connection()?;
     let order = Order::get(order_id.to_string(), &mut db)?;
@@ -173,6 +191,13 @@ pub fn get_order(order_id: Uuid) -> Result<trade::order::Order> {
     Ok(order.try_into()?)
 }
 
+pub fn maybe_get_order(order_id: Uuid) -> Result<Option<trade::order::Order>> {
+    let mut db = connection()?;
+    let order = Order::get(order_id.to_string(), &mut db).optional()?;
+
+    Ok(order.map(|order| order.try_into()).transpose()?)
+}
+
 pub fn get_orders_for_ui() -> Result<Vec<trade::order::Order>> {
     let mut db = connection()?;
     let orders = Order::get_without_rejected_and_initial(&mut db)?;
@@ -339,6 +364,25 @@ pub fn rollover_position(
     Ok(())
 }
 
+pub fn update_position_after_rollover(
+    contract_symbol: ::trade::ContractSymbol,
+    funding_fee_sat: i64,
+    collateral: u64,
+    liquidation_price: f32,
+) -> Result<()> {
+    let mut db = connection()?;
+    Position::update_after_rollover(
+        &mut db,
+        contract_symbol.into(),
+        funding_fee_sat,
+        collateral as i64,
+        liquidation_price,
+    )
+    .context("Failed to update position after rollover")?;
+
+    Ok(())
+}
+
 pub fn insert_payment(
     payment_hash: lightning::ln::PaymentHash,
     info: ln_dlc_node::PaymentInfo,
@@ -67,6 +67,8 @@ pub(crate) struct Order {
     pub order_expiry_timestamp: i64,
     pub reason: OrderReason,
     pub stable: bool,
+    pub time_in_force: TimeInForce,
+    pub post_only: bool,
 }
 
 impl Order {
@@ -156,6 +158,44 @@ impl Order {
         })
     }
 
+    /// Updates the limit price and the quantity of an amended limit order.
+    pub fn amend(
+        order_id: String,
+        limit_price: f32,
+        quantity: f32,
+        conn: &mut SqliteConnection,
+    ) -> Result<Order> {
+        let affected_rows = diesel::update(orders::table)
+            .filter(schema::orders::id.eq(order_id.clone()))
+            .set((
+                schema::orders::limit_price.eq(limit_price),
+                schema::orders::quantity.eq(quantity),
+            ))
+            .execute(conn)?;
+
+        ensure!(affected_rows > 0, "Could not amend order {order_id}");
+
+        Ok(Order::get(order_id, conn)?)
+    }
+
+    pub fn set_quantity(
+        order_id: String,
+        quantity: f32,
+        conn: &mut SqliteConnection,
+    ) -> Result<Order> {
+        let affected_rows = diesel::update(orders::table)
+            .filter(schema::orders::id.eq(order_id.clone()))
+            .set(schema::orders::quantity.eq(quantity))
+            .execute(conn)?;
+
+        ensure!(
+            affected_rows > 0,
+            "Could not set quantity of order {order_id}"
+        );
+
+        Ok(Order::get(order_id, conn)?)
+    }
+
     pub fn get(order_id: String, conn: &mut SqliteConnection) -> QueryResult<Order> {
         orders::table
             .filter(schema::orders::id.eq(order_id))
@@ -176,13 +216,13 @@ impl Order {
     }
 
     /// Gets any async order in the database. An async order is defined by any order which has been
-    /// generated by the orderbook. e.g. if the position expired.
+    /// generated by the orderbook. e.g. if the position expired or got liquidated.
     pub fn get_async_order(conn: &mut SqliteConnection) -> QueryResult<Option<Order>> {
         orders::table
             .filter(
                 orders::state
                     .eq(OrderState::Filling)
-                    .and(orders::reason.eq(OrderReason::Expired)),
+                    .and(orders::reason.ne(OrderReason::Manual)),
             )
             .first(conn)
             .optional()
@@ -225,6 +265,30 @@ impl From<crate::trade::order::Order> for Order {
             order_expiry_timestamp: value.order_expiry_timestamp.unix_timestamp(),
             reason: value.reason.into(),
             stable: value.stable,
+            time_in_force: value.time_in_force.into(),
+            post_only: value.post_only,
+        }
+    }
+}
+
+impl From<crate::trade::order::TimeInForce> for TimeInForce {
+    fn from(value: crate::trade::order::TimeInForce) -> Self {
+        match value {
+            crate::trade::order::TimeInForce::ImmediateOrCancel => TimeInForce::ImmediateOrCancel,
+            crate::trade::order::TimeInForce::FillOrKill => TimeInForce::FillOrKill,
+            crate::trade::order::TimeInForce::GoodTillCancelled => TimeInForce::GoodTillCancelled,
+            crate::trade::order::TimeInForce::GoodTillTime => TimeInForce::GoodTillTime,
+        }
+    }
+}
+
+impl From<TimeInForce> for crate::trade::order::TimeInForce {
+    fn from(value: TimeInForce) -> Self {
+        match value {
+            TimeInForce::ImmediateOrCancel => crate::trade::order::TimeInForce::ImmediateOrCancel,
+            TimeInForce::FillOrKill => crate::trade::order::TimeInForce::FillOrKill,
+            TimeInForce::GoodTillCancelled => crate::trade::order::TimeInForce::GoodTillCancelled,
+            TimeInForce::GoodTillTime => crate::trade::order::TimeInForce::GoodTillTime,
         }
     }
 }
@@ -234,6 +298,8 @@ impl From<crate::trade::order::OrderReason> for OrderReason {
         match value {
             crate::trade::order::OrderReason::Manual => OrderReason::Manual,
             crate::trade::order::OrderReason::Expired => OrderReason::Expired,
+            crate::trade::order::OrderReason::Liquidated => OrderReason::Liquidated,
+            crate::trade::order::OrderReason::Triggered => OrderReason::Triggered,
         }
     }
 }
@@ -243,6 +309,8 @@ impl From<OrderReason> for crate::trade::order::OrderReason {
         match value {
             OrderReason::Manual => crate::trade::order::OrderReason::Manual,
             OrderReason::Expired => crate::trade::order::OrderReason::Expired,
+            OrderReason::Liquidated => crate::trade::order::OrderReason::Liquidated,
+            OrderReason::Triggered => crate::trade::order::OrderReason::Triggered,
         }
     }
 }
@@ -273,6 +341,8 @@ impl TryFrom<Order> for crate::trade::order::Order {
             reason: value.reason.into(),
             stable: value.stable,
             failure_reason: value.failure_reason.map(|reason| reason.into()),
+            time_in_force: value.time_in_force.into(),
+            post_only: value.post_only,
         };
 
         Ok(order)
@@ -294,6 +364,7 @@ pub(crate) struct Position {
     pub expiry_timestamp: i64,
     pub updated_timestamp: i64,
     pub stable: bool,
+    pub funding_fee_sat: i64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, FromSqlRow, AsExpression)]
@@ -361,6 +432,33 @@ impl Position {
         Ok(())
     }
 
+    /// Updates the total funding fee, the collateral and the liquidation price of the position
+    /// once the funding fees have been settled with a rollover.
+    pub fn update_after_rollover(
+        conn: &mut SqliteConnection,
+        contract_symbol: ContractSymbol,
+        funding_fee_sat: i64,
+        collateral: i64,
+        liquidation_price: f32,
+    ) -> Result<()> {
+        let affected_rows = diesel::update(positions::table)
+            .filter(schema::positions::contract_symbol.eq(contract_symbol))
+            .set((
+                positions::funding_fee_sat.eq(funding_fee_sat),
+                positions::collateral.eq(collateral),
+                positions::liquidation_price.eq(liquidation_price),
+                positions::updated_timestamp.eq(OffsetDateTime::now_utc().unix_timestamp()),
+            ))
+            .execute(conn)?;
+
+        ensure!(
+            affected_rows > 0,
+            "Could not update position after rollover"
+        );
+
+        Ok(())
+    }
+
     /// Updates the status of the given order in the DB.
     pub fn update_position(conn: &mut SqliteConnection, position: Position) -> Result<()> {
         let Position {
@@ -425,6 +523,7 @@ impl From<Position> for crate::trade::position::Position {
             created: OffsetDateTime::from_unix_timestamp(value.creation_timestamp)
                 .expect("to fit into unix timestamp"),
             stable: value.stable,
+            funding_fee_sat: value.funding_fee_sat,
         }
     }
 }
@@ -444,6 +543,7 @@ impl From<crate::trade::position::Position> for Position {
             updated_timestamp: OffsetDateTime::now_utc().unix_timestamp(),
             expiry_timestamp: value.expiry.unix_timestamp(),
             stable: value.stable,
+            funding_fee_sat: value.funding_fee_sat,
         }
     }
 }
@@ -554,6 +654,17 @@ impl TryFrom<(OrderType, Option<f32>)> for crate::trade::order::OrderType {
 pub enum OrderReason {
     Manual,
     Expired,
+    Liquidated,
+    Triggered,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, FromSqlRow, AsExpression)]
+#[diesel(sql_type = Text)]
+pub enum TimeInForce {
+    ImmediateOrCancel,
+    FillOrKill,
+    GoodTillCancelled,
+    GoodTillTime,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, FromSqlRow, AsExpression)]
@@ -660,6 +771,7 @@ pub enum FailureReason {
     SubchannelOfferDateUndetermined,
     SubchannelOfferUnacceptable,
     OrderRejected,
+    Cancelled,
     Unknown,
 }
 
@@ -694,6 +806,7 @@ impl From<FailureReason> for crate::trade::order::FailureReason {
                 )
             }
             FailureReason::OrderRejected => crate::trade::order::FailureReason::OrderRejected,
+            FailureReason::Cancelled => crate::trade::order::FailureReason::Cancelled,
             FailureReason::Unknown => crate::trade::order::FailureReason::Unknown,
         }
     }
@@ -722,6 +835,7 @@ impl From<crate::trade::order::FailureReason> for FailureReason {
                 InvalidSubchannelOffer::Unacceptable => FailureReason::SubchannelOfferUnacceptable,
             },
             crate::trade::order::FailureReason::OrderRejected => FailureReason::OrderRejected,
+            crate::trade::order::FailureReason::Cancelled => FailureReason::Cancelled,
             crate::trade::order::FailureReason::Unknown => FailureReason::Unknown,
         }
     }
@@ -1244,6 +1358,7 @@ pub(crate) struct Transaction {
     pub created_at: i64,
     pub updated_at: i64,
     pub raw: String,
+    pub replaced_by: Option<String>,
 }
 
 impl Transaction {
@@ -1282,6 +1397,7 @@ impl From<ln_dlc_node::transaction::Transaction> for Transaction {
             created_at: value.created_at().unix_timestamp(),
             updated_at: value.updated_at().unix_timestamp(),
             raw: value.raw(),
+            replaced_by: value.replaced_by().map(|txid| txid.to_string()),
         }
     }
 }
@@ -1294,6 +1410,9 @@ impl From<Transaction> for ln_dlc_node::transaction::Transaction {
             OffsetDateTime::from_unix_timestamp(value.created_at).expect("valid timestamp"),
             OffsetDateTime::from_unix_timestamp(value.updated_at).expect("valid timestamp"),
             value.raw,
+            value
+                .replaced_by
+                .map(|txid| Txid::from_str(&txid).expect("valid txid")),
         )
     }
 }
@@ -1508,6 +1627,8 @@ pub mod test {
             order_expiry_timestamp: expiry_timestamp.unix_timestamp(),
             reason: OrderReason::Manual,
             stable: false,
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
         };
 
         Order::insert(
@@ -1524,6 +1645,8 @@ pub mod test {
                 reason: crate::trade::order::OrderReason::Manual,
                 stable: false,
                 failure_reason: None,
+                time_in_force: crate::trade::order::TimeInForce::FillOrKill,
+                post_only: false,
             }
             .into(),
             &mut connection,
@@ -1545,6 +1668,8 @@ pub mod test {
                 reason: crate::trade::order::OrderReason::Manual,
                 stable: false,
                 failure_reason: None,
+                time_in_force: crate::trade::order::TimeInForce::FillOrKill,
+                post_only: false,
             }
             .into(),
             &mut connection,
@@ -1615,6 +1740,8 @@ pub mod test {
                 reason: crate::trade::order::OrderReason::Manual,
                 stable: false,
                 failure_reason: None,
+                time_in_force: crate::trade::order::TimeInForce::FillOrKill,
+                post_only: false,
             }
             .into(),
             &mut connection,
@@ -1639,6 +1766,8 @@ pub mod test {
                 reason: crate::trade::order::OrderReason::Manual,
                 stable: false,
                 failure_reason: None,
+                time_in_force: crate::trade::order::TimeInForce::FillOrKill,
+                post_only: false,
             }
             .into(),
             &mut connection,
@@ -1942,6 +2071,7 @@ pub mod test {
             OffsetDateTime::now_utc().replace_time(Time::from_hms(0, 0, 0).unwrap()),
             OffsetDateTime::now_utc().replace_time(Time::from_hms(0, 0, 0).unwrap()),
             "0200...doesntmattermuch".to_string(),
+            None,
         );
 
         Transaction::upsert(transaction.clone().into(), &mut connection).unwrap();
@@ -1964,6 +2094,7 @@ pub mod test {
             OffsetDateTime::now_utc(),
             OffsetDateTime::now_utc(),
             "0200...doesntmattermuch".to_string(),
+            None,
         );
         Transaction::upsert(second_tx.into(), &mut connection).unwrap();
         // Verify that we can load all transactions without fees
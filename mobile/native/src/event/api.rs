@@ -23,7 +23,7 @@ pub enum Event {
     WalletInfoUpdateNotification(WalletInfo),
     PositionUpdateNotification(Position),
     PositionClosedNotification(PositionClosed),
-    PriceUpdateNotification(BestPrice),
+    PriceUpdateNotification(Vec<BestPrice>),
     ServiceHealthUpdate(ServiceUpdate),
     BackgroundNotification(BackgroundTask),
     PaymentClaimed(u64, String),
@@ -68,12 +68,14 @@ impl From<EventInternal> for Event {
                 Event::PositionClosedNotification(PositionClosed { contract_symbol })
             }
             EventInternal::PriceUpdateNotification(prices) => {
-                let best_price = prices
-                    .get(&ContractSymbol::BtcUsd)
-                    .cloned()
-                    .unwrap_or_default()
-                    .into();
-                Event::PriceUpdateNotification(best_price)
+                let best_prices = ContractSymbol::ALL
+                    .into_iter()
+                    .map(|contract_symbol| {
+                        let price = prices.get(&contract_symbol).cloned().unwrap_or_default();
+                        BestPrice::new(contract_symbol, price)
+                    })
+                    .collect();
+                Event::PriceUpdateNotification(best_prices)
             }
             EventInternal::ServiceHealthUpdate(update) => Event::ServiceHealthUpdate(update),
             EventInternal::ChannelReady(_) => {
@@ -179,15 +181,17 @@ impl From<event::TaskStatus> for TaskStatus {
 /// Best prices come from an orderbook. Contrary to the `Price` struct, we can have no price
 /// available, due to no orders in the orderbook.
 #[frb]
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct BestPrice {
+    pub contract_symbol: ContractSymbol,
     pub bid: Option<f64>,
     pub ask: Option<f64>,
 }
 
-impl From<commons::Price> for BestPrice {
-    fn from(value: commons::Price) -> Self {
+impl BestPrice {
+    fn new(contract_symbol: ContractSymbol, value: commons::Price) -> Self {
         BestPrice {
+            contract_symbol,
             bid: value
                 .bid
                 .map(|bid| bid.to_f64().expect("price bid to fit into f64")),
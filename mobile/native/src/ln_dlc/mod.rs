@@ -1,8 +1,10 @@
 use crate::api;
 use crate::api::Fee;
+use crate::api::FeeBumpMethod;
 use crate::api::PaymentFlow;
 use crate::api::SendPayment;
 use crate::api::Status;
+use crate::api::Utxo;
 use crate::api::WalletHistoryItem;
 use crate::api::WalletHistoryItemType;
 use crate::backup::DBBackupSubscriber;
@@ -25,6 +27,7 @@ use crate::trade::order::Order;
 use crate::trade::order::OrderReason;
 use crate::trade::order::OrderState;
 use crate::trade::order::OrderType;
+use crate::trade::order::TimeInForce;
 use crate::trade::position;
 use anyhow::anyhow;
 use anyhow::bail;
@@ -40,6 +43,7 @@ use bdk::BlockTime;
 use bdk::FeeRate;
 use bdk::TransactionDetails;
 use bitcoin::hashes::hex::ToHex;
+use bitcoin::secp256k1::Message;
 use bitcoin::secp256k1::PublicKey;
 use bitcoin::secp256k1::Secp256k1;
 use bitcoin::secp256k1::SECP256K1;
@@ -53,7 +57,10 @@ use bitcoin::TxOut;
 use commons::CollaborativeRevertTraderResponse;
 use commons::LegacyCollaborativeRevertTraderResponse;
 use commons::OnboardingParam;
+use commons::RolloverResponse;
 use commons::RouteHintHop;
+use commons::Signature;
+use commons::SignedRequest;
 use commons::TradeParams;
 use dlc::PartyParams;
 use dlc_manager::channel::Channel as DlcChannel;
@@ -79,6 +86,7 @@ use ln_dlc_node::node::rust_dlc_manager::subchannel::SubChannelState;
 use ln_dlc_node::node::rust_dlc_manager::DlcChannelId;
 use ln_dlc_node::node::rust_dlc_manager::Signer;
 use ln_dlc_node::node::rust_dlc_manager::Storage as DlcStorage;
+use ln_dlc_node::node::ChainSourceConfig;
 use ln_dlc_node::node::GossipSourceConfig;
 use ln_dlc_node::node::LnDlcNodeSettings;
 use ln_dlc_node::node::Storage as LnDlcNodeStorage;
@@ -86,6 +94,7 @@ use ln_dlc_node::scorer;
 use ln_dlc_node::seed::Bip39Seed;
 use ln_dlc_node::util;
 use ln_dlc_node::AppEventHandler;
+use ln_dlc_node::FeeRateEstimatorConfig;
 use ln_dlc_node::HTLCStatus;
 use ln_dlc_node::WalletSettings;
 use ln_dlc_node::CONFIRMATION_TARGET;
@@ -233,6 +242,15 @@ pub fn get_node_pubkey() -> PublicKey {
     get_node_key().public_key(SECP256K1)
 }
 
+/// Signs a request to the coordinator with the node key, which identifies the trader.
+pub fn sign_with_node_key(message: Message) -> Signature {
+    let secret_key = get_node_key();
+    Signature {
+        pubkey: secret_key.public_key(SECP256K1),
+        signature: secret_key.sign_ecdsa(message),
+    }
+}
+
 pub async fn update_node_settings(settings: LnDlcNodeSettings) {
     let node = state::get_node();
     node.inner.update_settings(settings).await;
@@ -876,6 +894,8 @@ fn update_state_after_collab_revert(
                 reason: OrderReason::Expired,
                 stable: position.stable,
                 failure_reason: None,
+                time_in_force: TimeInForce::FillOrKill,
+                post_only: false,
             };
             db::insert_order(order.clone())?;
             event::publish(&EventInternal::OrderUpdateNotification(order.clone()));
@@ -1107,6 +1127,8 @@ fn update_state_after_legacy_collab_revert(
                 reason: OrderReason::Expired,
                 stable: position.stable,
                 failure_reason: None,
+                time_in_force: TimeInForce::FillOrKill,
+                post_only: false,
             };
             db::insert_order(order.clone())?;
             event::publish(&EventInternal::OrderUpdateNotification(order.clone()));
@@ -1394,17 +1416,83 @@ pub async fn send_payment(payment: SendPayment) -> Result<()> {
             let address = Address::from_str(&address)?;
             state::get_node()
                 .inner
-                .send_to_address(&address, amount, fee.into())?;
+                .send_to_address(&address, amount, fee.into(), &[])?;
         }
     }
     Ok(())
 }
 
-pub fn send_on_chain_payment(address: String, amount: u64, fee: Fee) -> Result<Txid> {
+pub fn send_on_chain_payment(
+    address: String,
+    amount: u64,
+    fee: Fee,
+    inputs: Vec<String>,
+) -> Result<Txid> {
     let address = Address::from_str(&address)?;
+    let inputs = parse_outpoints(inputs)?;
+
+    state::get_node()
+        .inner
+        .send_to_address(&address, amount, fee.into(), &inputs)
+}
+
+pub fn list_utxos() -> Result<Vec<Utxo>> {
+    let node = state::get_node();
+
+    let utxos = node.inner.ldk_wallet().get_utxos()?;
+    let mut metadata = node.inner.get_utxo_metadata()?;
+
+    let utxos = utxos
+        .into_iter()
+        .map(|utxo| {
+            let metadata = metadata.remove(&utxo.outpoint).unwrap_or_default();
+
+            Utxo {
+                outpoint: utxo.outpoint.to_string(),
+                amount: utxo.txout.value,
+                label: metadata.label,
+                frozen: metadata.frozen,
+            }
+        })
+        .collect();
+
+    Ok(utxos)
+}
+
+pub fn set_utxo_frozen(outpoint: String, frozen: bool) -> Result<()> {
+    let outpoint = OutPoint::from_str(&outpoint)?;
+    state::get_node().inner.set_utxo_frozen(outpoint, frozen)
+}
+
+pub fn set_utxo_label(outpoint: String, label: Option<String>) -> Result<()> {
+    let outpoint = OutPoint::from_str(&outpoint)?;
+    state::get_node().inner.set_utxo_label(outpoint, label)
+}
+
+pub fn consolidate_utxos(inputs: Vec<String>, fee: Fee) -> Result<Txid> {
+    let inputs = parse_outpoints(inputs)?;
     state::get_node()
         .inner
-        .send_to_address(&address, amount, fee.into())
+        .consolidate_utxos(&inputs, fee.into())
+}
+
+fn parse_outpoints(outpoints: Vec<String>) -> Result<Vec<OutPoint>> {
+    outpoints
+        .iter()
+        .map(|outpoint| {
+            OutPoint::from_str(outpoint).with_context(|| format!("Invalid outpoint {outpoint}"))
+        })
+        .collect()
+}
+
+pub fn bump_on_chain_fee(txid: String, method: FeeBumpMethod, fee: Fee) -> Result<Txid> {
+    let txid = Txid::from_str(&txid)?;
+    let node = state::get_node();
+
+    match method {
+        FeeBumpMethod::Rbf => node.inner.replace_by_fee(&txid, fee.into()),
+        FeeBumpMethod::Cpfp => node.inner.child_pays_for_parent(&txid, fee.into()),
+    }
 }
 
 pub async fn estimate_payment_fee_msat(payment: SendPayment) -> Result<u64> {
@@ -1439,6 +1527,9 @@ pub async fn estimate_payment_fee_msat(payment: SendPayment) -> Result<u64> {
 }
 
 pub async fn trade(trade_params: TradeParams) -> Result<(), (FailureReason, anyhow::Error)> {
+    let trade_params = SignedRequest::new(trade_params, sign_with_node_key)
+        .map_err(|e| (FailureReason::TradeRequest, e))?;
+
     let client = reqwest_client();
     let response = client
         .post(format!("http://{}/api/trade", config::get_http_endpoint()))
@@ -1467,8 +1558,11 @@ pub async fn trade(trade_params: TradeParams) -> Result<(), (FailureReason, anyh
     Ok(())
 }
 
-/// initiates the rollover protocol with the coordinator
-pub async fn rollover(contract_id: Option<String>) -> Result<()> {
+/// Initiates the rollover protocol with the coordinator.
+///
+/// Returns the total funding fee of the position, including the funding fee settled with this
+/// rollover.
+pub async fn rollover(contract_id: Option<String>) -> Result<RolloverResponse> {
     let node = state::get_node();
 
     let dlc_channels = node.inner.list_signed_dlc_channels()?;
@@ -1515,9 +1609,17 @@ pub async fn rollover(contract_id: Option<String>) -> Result<()> {
         )
     }
 
-    tracing::info!("Sent rollover request to coordinator successfully");
+    let response: RolloverResponse = response
+        .json()
+        .await
+        .context("Failed to parse rollover response")?;
 
-    Ok(())
+    tracing::info!(
+        funding_fee_sat = response.funding_fee.to_sat(),
+        "Sent rollover request to coordinator successfully"
+    );
+
+    Ok(response)
 }
 
 fn ln_dlc_node_settings() -> LnDlcNodeSettings {
@@ -1537,5 +1639,7 @@ fn ln_dlc_node_settings() -> LnDlcNodeSettings {
         bdk_client_stop_gap: 20,
         bdk_client_concurrency: 4,
         gossip_source_config,
+        chain_source_config: ChainSourceConfig::Esplora,
+        fee_rate_estimator_config: FeeRateEstimatorConfig::default(),
     }
 }
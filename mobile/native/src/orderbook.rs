@@ -13,26 +13,22 @@ use anyhow::Result;
 use bdk::bitcoin::secp256k1::SecretKey;
 use bdk::bitcoin::secp256k1::SECP256K1;
 use bitcoin::hashes::hex::ToHex;
-use commons::best_current_price;
+use commons::DepthSnapshot;
 use commons::Message;
-use commons::Order;
 use commons::OrderbookRequest;
 use commons::Prices;
 use commons::Signature;
 use futures::SinkExt;
 use futures::TryStreamExt;
 use lightning::ln::ChannelId;
-use parking_lot::Mutex;
 use std::collections::HashMap;
-use std::sync::Arc;
 use std::time::Duration;
-use time::OffsetDateTime;
 use tokio::runtime::Runtime;
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::watch;
 use tokio_tungstenite::tungstenite;
-use uuid::Uuid;
+use trade::ContractSymbol;
 
 /// FIXME(holzeis): There is an edge case where the app is still open while we move into the
 /// rollover window. If the coordinator restarts while the app remains open in that scenario, the
@@ -41,8 +37,6 @@ use uuid::Uuid;
 /// trader.
 const WS_RECONNECT_TIMEOUT: Duration = Duration::from_millis(200);
 
-const EXPIRED_ORDER_PRUNING_INTERVAL: Duration = Duration::from_secs(30);
-
 pub fn subscribe(
     secret_key: SecretKey,
     runtime: &Runtime,
@@ -62,47 +56,6 @@ pub fn subscribe(
             Signature { pubkey, signature }
         };
 
-        // Need a Mutex as it's being accessed from websocket stream and pruning task
-        let orders = Arc::new(Mutex::new(Vec::<Order>::new()));
-
-        let _prune_expired_orders_task = {
-            let orders = orders.clone();
-            tokio::spawn(async move {
-                loop {
-                    {
-                        tracing::debug!("Pruning expired orders");
-                        let mut orders = orders.lock();
-                        let orders_before_pruning = orders.len();
-                        *orders = orders
-                            .iter()
-                            .filter(|order| order.expiry >= OffsetDateTime::now_utc())
-                            .cloned()
-                            .collect::<Vec<_>>();
-                        let orders_after_pruning = orders.len();
-
-                        if orders_after_pruning < orders_before_pruning {
-                            let amount_pruned = orders_before_pruning - orders_after_pruning;
-                            tracing::debug!(
-                                orders_before_pruning,
-                                orders_after_pruning,
-                                "Pruned {amount_pruned} expired orders"
-                            );
-
-                            // Current best price might have changed
-                            if let Err(e) =
-                                position::handler::price_update(best_current_price(&orders))
-                            {
-                                tracing::error!(
-                                    "Price update from the orderbook failed. Error: {e:#}"
-                                );
-                            }
-                        }
-                    }
-                    tokio::time::sleep(EXPIRED_ORDER_PRUNING_INTERVAL).await;
-                }
-            })
-        };
-
         let fcm_token = if fcm_token.is_empty() {
             None
         } else {
@@ -147,6 +100,8 @@ pub fn subscribe(
                         }
                     });
 
+                    // The coordinator sends a depth snapshot of every contract on connect.
+                    let mut depth = HashMap::new();
                     let mut cached_best_price: Prices = HashMap::new();
                     loop {
                         let msg = match stream.try_next().await {
@@ -162,9 +117,13 @@ pub fn subscribe(
                             }
                         };
 
-                        if let Err(e) =
-                            handle_orderbook_message(orders.clone(), &mut cached_best_price, msg)
-                                .await
+                        if let Err(e) = handle_orderbook_message(
+                            &mut depth,
+                            &mut cached_best_price,
+                            &tx_websocket,
+                            msg,
+                        )
+                        .await
                         {
                             tracing::error!("Failed to handle event: {e:#}");
                         }
@@ -198,8 +157,9 @@ pub fn subscribe(
 }
 
 async fn handle_orderbook_message(
-    orders: Arc<Mutex<Vec<Order>>>,
+    depth: &mut HashMap<ContractSymbol, DepthSnapshot>,
     cached_best_price: &mut Prices,
+    tx_websocket: &broadcast::Sender<OrderbookRequest>,
     msg: String,
 ) -> Result<()> {
     let msg =
@@ -255,51 +215,35 @@ async fn handle_orderbook_message(
                     format!("Trade request sent to coordinator for order {order_id} failed")
                 })?;
         }
-        Message::AllOrders(initial_orders) => {
-            let mut orders = orders.lock();
-            if !orders.is_empty() {
-                tracing::debug!(
-                    "Received new set of initial orders from orderbook, \
-                     replacing the previously stored orders"
-                );
-            } else {
-                tracing::debug!(?orders, "Received all orders from orderbook");
-            }
-
-            *orders = initial_orders;
-
-            // if we receive a full set of new orders, we can clear the cached best price as it is
-            // outdated information.
-            cached_best_price.clear();
-            update_prices_if_needed(cached_best_price, &orders);
-        }
-        Message::NewOrder(order) => {
-            let mut orders = orders.lock();
-            orders.push(order);
+        Message::DepthSnapshot(snapshot) => {
+            tracing::debug!(
+                contract_symbol = %snapshot.contract_symbol,
+                sequence = snapshot.sequence,
+                "Received depth snapshot from orderbook"
+            );
 
-            update_prices_if_needed(cached_best_price, &orders);
+            depth.insert(snapshot.contract_symbol, snapshot);
+            update_prices_if_needed(cached_best_price, depth);
         }
-        Message::DeleteOrder(order_id) => {
-            let mut orders = orders.lock();
+        Message::DepthUpdate(update) => {
+            // Updates are only applied once we received the snapshot they build upon.
+            let Some(snapshot) = depth.get_mut(&update.contract_symbol) else {
+                return Ok(());
+            };
 
-            let found = remove_order(&mut orders, order_id);
-            if !found {
-                tracing::warn!(%order_id, "Could not remove non-existing order");
-            }
+            if let Err(e) = snapshot.apply(&update) {
+                tracing::warn!("Resyncing orderbook depth: {e:#}");
 
-            update_prices_if_needed(cached_best_price, &orders);
-        }
-        Message::Update(updated_order) => {
-            let mut orders = orders.lock();
+                let contract_symbol = update.contract_symbol;
+                depth.remove(&contract_symbol);
+                tx_websocket
+                    .send(OrderbookRequest::DepthSnapshot { contract_symbol })
+                    .context("Failed to request depth snapshot")?;
 
-            let found = remove_order(&mut orders, updated_order.id);
-            if !found {
-                tracing::warn!(?updated_order, "Update without prior knowledge of order");
+                return Ok(());
             }
 
-            orders.push(updated_order);
-
-            update_prices_if_needed(cached_best_price, &orders);
+            update_prices_if_needed(cached_best_price, depth);
         }
         Message::DlcChannelCollaborativeRevert {
             channel_id,
@@ -369,7 +313,14 @@ async fn handle_orderbook_message(
                 ));
             }
         }
-        msg @ Message::LimitOrderFilledMatches { .. } | msg @ Message::InvalidAuthentication(_) => {
+        msg @ Message::LimitOrderFilledMatches { .. }
+        | msg @ Message::InvalidAuthentication(_)
+        | msg @ Message::AllOrders(_)
+        | msg @ Message::NewOrder(_)
+        | msg @ Message::DeleteOrder(_)
+        | msg @ Message::Update(_)
+        | msg @ Message::MarketTrade(_)
+        | msg @ Message::Candle(_) => {
             tracing::debug!(?msg, "Skipping message from orderbook");
         }
     };
@@ -377,8 +328,15 @@ async fn handle_orderbook_message(
     Ok(())
 }
 
-fn update_prices_if_needed(cached_best_price: &mut Prices, orders: &[Order]) {
-    let best_price = best_current_price(orders);
+fn update_prices_if_needed(
+    cached_best_price: &mut Prices,
+    depth: &HashMap<ContractSymbol, DepthSnapshot>,
+) {
+    let best_price = depth
+        .iter()
+        .map(|(contract_symbol, snapshot)| (*contract_symbol, snapshot.depth.best_price()))
+        .collect::<Prices>();
+
     if *cached_best_price != best_price {
         if let Err(e) = position::handler::price_update(best_price.clone()) {
             tracing::error!("Price update from the orderbook failed. Error: {e:#}");
@@ -386,16 +344,3 @@ fn update_prices_if_needed(cached_best_price: &mut Prices, orders: &[Order]) {
         *cached_best_price = best_price;
     }
 }
-
-// Returns true if the order was found and removed
-fn remove_order(orders: &mut Vec<Order>, order_id: Uuid) -> bool {
-    let mut found = false;
-    for (index, element) in orders.iter().enumerate() {
-        if element.id == order_id {
-            found = true;
-            orders.remove(index);
-            break;
-        }
-    }
-    found
-}
@@ -68,6 +68,8 @@ diesel::table! {
         order_expiry_timestamp -> BigInt,
         reason -> Text,
         stable -> Bool,
+        time_in_force -> Text,
+        post_only -> Bool,
     }
 }
 
@@ -103,6 +105,7 @@ diesel::table! {
         expiry_timestamp -> BigInt,
         updated_timestamp -> BigInt,
         stable -> Bool,
+        funding_fee_sat -> BigInt,
     }
 }
 
@@ -136,6 +139,7 @@ diesel::table! {
         created_at -> BigInt,
         updated_at -> BigInt,
         raw -> Text,
+        replaced_by -> Nullable<Text>,
     }
 }
 
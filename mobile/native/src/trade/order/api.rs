@@ -30,6 +30,8 @@ pub enum OrderState {
 pub enum OrderReason {
     Manual,
     Expired,
+    Liquidated,
+    Triggered,
 }
 
 #[frb]
@@ -45,9 +47,39 @@ pub enum FailureReason {
     TimedOut,
     InvalidDlcOffer,
     OrderRejected,
+    Cancelled,
     Unknown,
 }
 
+/// Defines how long an order remains in the orderbook
+///
+/// Please refer to [`crate::trade::order::TimeInForce`]
+#[frb]
+#[derive(Debug, Clone, Copy)]
+pub enum TimeInForce {
+    ImmediateOrCancel,
+    FillOrKill,
+    GoodTillCancelled,
+    GoodTillTime,
+}
+
+#[frb]
+#[derive(Debug, Clone, Copy)]
+pub enum TriggerType {
+    StopLoss,
+    TakeProfit,
+}
+
+#[frb]
+#[derive(Debug, Clone)]
+pub struct TriggerOrder {
+    pub id: String,
+    pub contract_symbol: ContractSymbol,
+    pub trigger_type: TriggerType,
+    pub trigger_price: f32,
+    pub timestamp: i64,
+}
+
 #[frb]
 #[derive(Debug, Clone)]
 pub struct NewOrder {
@@ -65,6 +97,11 @@ pub struct NewOrder {
     pub order_type: Box<OrderType>,
     #[frb(non_final)]
     pub stable: bool,
+    #[frb(non_final)]
+    pub time_in_force: TimeInForce,
+    /// Only allowed for limit orders resting in the orderbook
+    #[frb(non_final)]
+    pub post_only: bool,
 }
 
 #[frb]
@@ -82,6 +119,8 @@ pub struct Order {
     pub order_expiry_timestamp: i64,
     pub reason: OrderReason,
     pub failure_reason: Option<FailureReason>,
+    pub time_in_force: TimeInForce,
+    pub post_only: bool,
 }
 
 impl From<order::OrderType> for OrderType {
@@ -113,6 +152,8 @@ impl From<order::Order> for Order {
             order_expiry_timestamp: value.order_expiry_timestamp.unix_timestamp(),
             reason: value.reason.into(),
             failure_reason: value.failure_reason.map(|reason| reason.into()),
+            time_in_force: value.time_in_force.into(),
+            post_only: value.post_only,
         }
     }
 }
@@ -122,6 +163,8 @@ impl From<OrderReason> for order::OrderReason {
         match value {
             OrderReason::Manual => order::OrderReason::Manual,
             OrderReason::Expired => order::OrderReason::Expired,
+            OrderReason::Liquidated => order::OrderReason::Liquidated,
+            OrderReason::Triggered => order::OrderReason::Triggered,
         }
     }
 }
@@ -131,6 +174,8 @@ impl From<order::OrderReason> for OrderReason {
         match value {
             order::OrderReason::Manual => OrderReason::Manual,
             order::OrderReason::Expired => OrderReason::Expired,
+            order::OrderReason::Liquidated => OrderReason::Liquidated,
+            order::OrderReason::Triggered => OrderReason::Triggered,
         }
     }
 }
@@ -145,6 +190,7 @@ impl From<order::FailureReason> for FailureReason {
             order::FailureReason::TimedOut => FailureReason::TimedOut,
             order::FailureReason::InvalidDlcOffer(_) => FailureReason::InvalidDlcOffer,
             order::FailureReason::OrderRejected => FailureReason::OrderRejected,
+            order::FailureReason::Cancelled => FailureReason::Cancelled,
             order::FailureReason::CollabRevert => FailureReason::CollabRevert,
             order::FailureReason::Unknown => FailureReason::Unknown,
         }
@@ -177,20 +223,76 @@ impl From<order::OrderState> for OrderState {
 
 impl From<NewOrder> for order::Order {
     fn from(value: NewOrder) -> Self {
+        let order_type: order::OrderType = (*value.order_type).into();
+
         order::Order {
             id: Uuid::new_v4(),
             leverage: value.leverage,
             quantity: value.quantity,
             contract_symbol: value.contract_symbol,
             direction: value.direction,
-            order_type: (*value.order_type).into(),
+            order_type,
             state: order::OrderState::Initial,
             creation_timestamp: OffsetDateTime::now_utc(),
             // We do not support setting order expiry from the frontend for now
-            order_expiry_timestamp: OffsetDateTime::now_utc() + time::Duration::minutes(1),
+            order_expiry_timestamp: OffsetDateTime::now_utc() + order_type.expiry(),
             reason: order::OrderReason::Manual,
             stable: value.stable,
             failure_reason: None,
+            time_in_force: value.time_in_force.into(),
+            post_only: value.post_only,
+        }
+    }
+}
+
+impl From<TimeInForce> for order::TimeInForce {
+    fn from(value: TimeInForce) -> Self {
+        match value {
+            TimeInForce::ImmediateOrCancel => order::TimeInForce::ImmediateOrCancel,
+            TimeInForce::FillOrKill => order::TimeInForce::FillOrKill,
+            TimeInForce::GoodTillCancelled => order::TimeInForce::GoodTillCancelled,
+            TimeInForce::GoodTillTime => order::TimeInForce::GoodTillTime,
+        }
+    }
+}
+
+impl From<order::TimeInForce> for TimeInForce {
+    fn from(value: order::TimeInForce) -> Self {
+        match value {
+            order::TimeInForce::ImmediateOrCancel => TimeInForce::ImmediateOrCancel,
+            order::TimeInForce::FillOrKill => TimeInForce::FillOrKill,
+            order::TimeInForce::GoodTillCancelled => TimeInForce::GoodTillCancelled,
+            order::TimeInForce::GoodTillTime => TimeInForce::GoodTillTime,
+        }
+    }
+}
+
+impl From<TriggerType> for order::TriggerType {
+    fn from(value: TriggerType) -> Self {
+        match value {
+            TriggerType::StopLoss => order::TriggerType::StopLoss,
+            TriggerType::TakeProfit => order::TriggerType::TakeProfit,
+        }
+    }
+}
+
+impl From<order::TriggerType> for TriggerType {
+    fn from(value: order::TriggerType) -> Self {
+        match value {
+            order::TriggerType::StopLoss => TriggerType::StopLoss,
+            order::TriggerType::TakeProfit => TriggerType::TakeProfit,
+        }
+    }
+}
+
+impl From<order::TriggerOrder> for TriggerOrder {
+    fn from(value: order::TriggerOrder) -> Self {
+        TriggerOrder {
+            id: value.id.to_string(),
+            contract_symbol: value.contract_symbol,
+            trigger_type: value.trigger_type.into(),
+            trigger_price: value.trigger_price,
+            timestamp: value.timestamp.unix_timestamp(),
         }
     }
 }
@@ -4,11 +4,16 @@ use crate::db::get_order_in_filling;
 use crate::db::maybe_get_open_orders;
 use crate::event;
 use crate::event::EventInternal;
+use crate::ln_dlc;
 use crate::ln_dlc::is_dlc_channel_confirmed;
+use crate::ln_dlc::sign_with_node_key;
 use crate::trade::order::orderbook_client::OrderbookClient;
 use crate::trade::order::FailureReason;
 use crate::trade::order::Order;
 use crate::trade::order::OrderState;
+use crate::trade::order::OrderType;
+use crate::trade::order::TriggerOrder;
+use crate::trade::order::TriggerType;
 use crate::trade::position;
 use crate::trade::position::handler::update_position_after_order_submitted;
 use crate::trade::position::PositionState;
@@ -17,8 +22,11 @@ use anyhow::bail;
 use anyhow::Context;
 use anyhow::Result;
 use reqwest::Url;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
 use time::Duration;
 use time::OffsetDateTime;
+use trade::ContractSymbol;
 use trade::Direction;
 use uuid::Uuid;
 
@@ -84,7 +92,10 @@ pub async fn submit_order(order: Order) -> Result<Uuid, SubmitOrderError> {
 
     db::insert_order(order.clone()).map_err(SubmitOrderError::Storage)?;
 
-    if let Err(err) = orderbook_client.post_new_order(order.clone().into()).await {
+    if let Err(err) = orderbook_client
+        .post_new_order(order.clone().into(), sign_with_node_key)
+        .await
+    {
         let order_id = order.id.clone().to_string();
 
         tracing::error!(order_id, "Failed to post new order: {err:#}");
@@ -106,7 +117,12 @@ pub async fn submit_order(order: Order) -> Result<Uuid, SubmitOrderError> {
 
     update_order_state_in_db_and_ui(order.id, OrderState::Open)
         .map_err(SubmitOrderError::Storage)?;
-    update_position_after_order_submitted(&order).map_err(SubmitOrderError::Storage)?;
+
+    // A limit order may rest in the orderbook for a while, so we only update the position once it
+    // gets matched.
+    if order.order_type == OrderType::Market {
+        update_position_after_order_submitted(&order).map_err(SubmitOrderError::Storage)?;
+    }
 
     Ok(order.id)
 }
@@ -195,6 +211,118 @@ pub(crate) fn order_failed(
     Ok(())
 }
 
+/// Attaches a stop-loss or take-profit order to the open position.
+pub async fn submit_trigger_order(
+    contract_symbol: ContractSymbol,
+    trigger_type: TriggerType,
+    trigger_price: f32,
+) -> Result<TriggerOrder> {
+    let trigger_order = commons::NewTriggerOrder {
+        id: Uuid::new_v4(),
+        trader_id: ln_dlc::get_node_pubkey(),
+        contract_symbol,
+        trigger_type: trigger_type.into(),
+        trigger_price: Decimal::try_from(trigger_price).context("Invalid trigger price")?,
+    };
+
+    let trigger_order = orderbook_client()
+        .post_trigger_order(trigger_order, sign_with_node_key)
+        .await?;
+
+    Ok(trigger_order.into())
+}
+
+pub async fn amend_trigger_order(
+    trigger_order_id: Uuid,
+    trigger_price: f32,
+) -> Result<TriggerOrder> {
+    let trigger_price = Decimal::try_from(trigger_price).context("Invalid trigger price")?;
+
+    let trigger_order = orderbook_client()
+        .amend_trigger_order(trigger_order_id, trigger_price, sign_with_node_key)
+        .await?;
+
+    Ok(trigger_order.into())
+}
+
+pub async fn cancel_trigger_order(trigger_order_id: Uuid) -> Result<()> {
+    orderbook_client()
+        .cancel_trigger_order(trigger_order_id, sign_with_node_key)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_trigger_orders() -> Result<Vec<TriggerOrder>> {
+    let trigger_orders = orderbook_client()
+        .get_trigger_orders(ln_dlc::get_node_pubkey(), sign_with_node_key)
+        .await?;
+
+    Ok(trigger_orders.into_iter().map(TriggerOrder::from).collect())
+}
+
+/// Cancels the open limit order with the given id.
+pub async fn cancel_order(order_id: Uuid) -> Result<Order> {
+    orderbook_client()
+        .cancel_order(order_id, sign_with_node_key)
+        .await?;
+
+    update_order_state_in_db_and_ui(
+        order_id,
+        OrderState::Failed {
+            reason: FailureReason::Cancelled,
+        },
+    )
+}
+
+/// Cancels all open limit orders of the app.
+pub async fn cancel_all_orders() -> Result<()> {
+    let orders = orderbook_client()
+        .cancel_all_orders(ln_dlc::get_node_pubkey(), sign_with_node_key)
+        .await?;
+
+    for order in orders {
+        if let Err(e) = update_order_state_in_db_and_ui(
+            order.id,
+            OrderState::Failed {
+                reason: FailureReason::Cancelled,
+            },
+        ) {
+            tracing::warn!(order_id = %order.id, "Failed to set order to cancelled: {e:#}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Replaces the price and the quantity of the open limit order with the given id.
+pub async fn amend_order(order_id: Uuid, price: f32, quantity: f32) -> Result<Order> {
+    let order = orderbook_client()
+        .amend_order(
+            order_id,
+            Decimal::try_from(price).context("Invalid price")?,
+            Decimal::try_from(quantity).context("Invalid quantity")?,
+            sign_with_node_key,
+        )
+        .await?;
+
+    let order = db::amend_order(
+        order.id,
+        order.price.to_f32().expect("price to fit into f32"),
+        order.quantity.to_f32().expect("quantity to fit into f32"),
+    )?;
+
+    ui_update(order.clone());
+
+    Ok(order)
+}
+
+fn orderbook_client() -> OrderbookClient {
+    let url = format!("http://{}", config::get_http_endpoint());
+    let url = Url::parse(&url).expect("correct URL");
+    OrderbookClient::new(url)
+}
+
 pub async fn get_orders_for_ui() -> Result<Vec<Order>> {
     db::get_orders_for_ui()
 }
@@ -215,11 +343,18 @@ pub fn check_open_orders() -> Result<()> {
 
     for open_order in open_orders {
         tracing::debug!(?open_order, "Checking order if it is still up to date");
-        if open_order.creation_timestamp + ORDER_OUTDATED_AFTER < now {
+
+        // Limit orders rest in the orderbook until they expire.
+        let outdated_at = match open_order.order_type {
+            OrderType::Market => open_order.creation_timestamp + ORDER_OUTDATED_AFTER,
+            OrderType::Limit { .. } => open_order.order_expiry_timestamp,
+        };
+
+        if outdated_at < now {
             order_failed(
                 Some(open_order.id),
                 FailureReason::TimedOut,
-                anyhow!("Order was not matched within {ORDER_OUTDATED_AFTER:?}"),
+                anyhow!("Order was not matched by {outdated_at}"),
             )?;
         }
     }
@@ -1,7 +1,10 @@
 use crate::calculations::calculate_margin;
 use crate::ln_dlc;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
+use serde::Deserialize;
 use serde::Serialize;
+use time::Duration;
 use time::OffsetDateTime;
 use trade::ContractSymbol;
 use trade::Direction;
@@ -20,6 +23,30 @@ pub enum OrderType {
     Limit { price: f32 },
 }
 
+impl OrderType {
+    /// How long an order of this type remains valid in the orderbook.
+    ///
+    /// Market orders are matched immediately or not at all, whereas limit orders rest in the
+    /// orderbook until they are matched.
+    pub fn expiry(&self) -> Duration {
+        match self {
+            OrderType::Market => Duration::minutes(1),
+            OrderType::Limit { .. } => Duration::days(1),
+        }
+    }
+}
+
+/// Defines how long an order remains in the orderbook.
+///
+/// Please refer to [`commons::TimeInForce`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TimeInForce {
+    ImmediateOrCancel,
+    FillOrKill,
+    GoodTillCancelled,
+    GoodTillTime,
+}
+
 /// Internal type so we still have Copy on order
 #[derive(Debug, Clone, Serialize, PartialEq)]
 pub enum FailureReason {
@@ -39,6 +66,8 @@ pub enum FailureReason {
     InvalidDlcOffer(InvalidSubchannelOffer),
     /// The order has been rejected by the orderbook
     OrderRejected,
+    /// The limit order has been cancelled by the trader before it got matched
+    Cancelled,
     Unknown,
 }
 
@@ -118,6 +147,8 @@ pub enum OrderState {
 pub enum OrderReason {
     Manual,
     Expired,
+    Liquidated,
+    Triggered,
 }
 
 impl From<OrderReason> for commons::OrderReason {
@@ -125,6 +156,8 @@ impl From<OrderReason> for commons::OrderReason {
         match value {
             OrderReason::Manual => commons::OrderReason::Manual,
             OrderReason::Expired => commons::OrderReason::Expired,
+            OrderReason::Liquidated => commons::OrderReason::Liquidated,
+            OrderReason::Triggered => commons::OrderReason::Triggered,
         }
     }
 }
@@ -134,6 +167,8 @@ impl From<commons::OrderReason> for OrderReason {
         match value {
             commons::OrderReason::Manual => OrderReason::Manual,
             commons::OrderReason::Expired => OrderReason::Expired,
+            commons::OrderReason::Liquidated => OrderReason::Liquidated,
+            commons::OrderReason::Triggered => OrderReason::Triggered,
         }
     }
 }
@@ -152,6 +187,8 @@ pub struct Order {
     pub reason: OrderReason,
     pub stable: bool,
     pub failure_reason: Option<FailureReason>,
+    pub time_in_force: TimeInForce,
+    pub post_only: bool,
 }
 
 impl Order {
@@ -189,12 +226,17 @@ impl From<Order> for commons::NewOrder {
     fn from(order: Order) -> Self {
         let quantity = Decimal::try_from(order.quantity).expect("to parse into decimal");
         let trader_id = ln_dlc::get_node_pubkey();
+        let price = match order.order_type {
+            OrderType::Limit { price } => Decimal::try_from(price).expect("to parse into decimal"),
+            // todo: this is left out intentionally as market orders do not set a price. this field
+            // should either be an option or differently modelled for a market order.
+            OrderType::Market => Decimal::ZERO,
+        };
+
         commons::NewOrder {
             id: order.id,
             contract_symbol: order.contract_symbol,
-            // todo: this is left out intentionally as market orders do not set a price. this field
-            // should either be an option or differently modelled for a market order.
-            price: Decimal::ZERO,
+            price,
             quantity,
             trader_id,
             direction: order.direction,
@@ -202,6 +244,30 @@ impl From<Order> for commons::NewOrder {
             order_type: order.order_type.into(),
             expiry: order.order_expiry_timestamp,
             stable: order.stable,
+            time_in_force: order.time_in_force.into(),
+            post_only: order.post_only,
+        }
+    }
+}
+
+impl From<TimeInForce> for commons::TimeInForce {
+    fn from(value: TimeInForce) -> Self {
+        match value {
+            TimeInForce::ImmediateOrCancel => commons::TimeInForce::ImmediateOrCancel,
+            TimeInForce::FillOrKill => commons::TimeInForce::FillOrKill,
+            TimeInForce::GoodTillCancelled => commons::TimeInForce::GoodTillCancelled,
+            TimeInForce::GoodTillTime => commons::TimeInForce::GoodTillTime,
+        }
+    }
+}
+
+impl From<commons::TimeInForce> for TimeInForce {
+    fn from(value: commons::TimeInForce) -> Self {
+        match value {
+            commons::TimeInForce::ImmediateOrCancel => TimeInForce::ImmediateOrCancel,
+            commons::TimeInForce::FillOrKill => TimeInForce::FillOrKill,
+            commons::TimeInForce::GoodTillCancelled => TimeInForce::GoodTillCancelled,
+            commons::TimeInForce::GoodTillTime => TimeInForce::GoodTillTime,
         }
     }
 }
@@ -214,3 +280,52 @@ impl From<OrderType> for commons::OrderType {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TriggerType {
+    StopLoss,
+    TakeProfit,
+}
+
+impl From<TriggerType> for commons::TriggerType {
+    fn from(value: TriggerType) -> Self {
+        match value {
+            TriggerType::StopLoss => commons::TriggerType::StopLoss,
+            TriggerType::TakeProfit => commons::TriggerType::TakeProfit,
+        }
+    }
+}
+
+impl From<commons::TriggerType> for TriggerType {
+    fn from(value: commons::TriggerType) -> Self {
+        match value {
+            commons::TriggerType::StopLoss => TriggerType::StopLoss,
+            commons::TriggerType::TakeProfit => TriggerType::TakeProfit,
+        }
+    }
+}
+
+/// A stop-loss or take-profit order attached to the open position.
+///
+/// Trigger orders are kept by the coordinator, which closes the position once the trigger price
+/// is reached, even if the app is offline at that time.
+#[derive(Debug, Clone)]
+pub struct TriggerOrder {
+    pub id: Uuid,
+    pub contract_symbol: ContractSymbol,
+    pub trigger_type: TriggerType,
+    pub trigger_price: f32,
+    pub timestamp: OffsetDateTime,
+}
+
+impl From<commons::TriggerOrder> for TriggerOrder {
+    fn from(value: commons::TriggerOrder) -> Self {
+        TriggerOrder {
+            id: value.id,
+            contract_symbol: value.contract_symbol,
+            trigger_type: value.trigger_type.into(),
+            trigger_price: value.trigger_price.to_f32().expect("to fit into f32"),
+            timestamp: value.timestamp,
+        }
+    }
+}
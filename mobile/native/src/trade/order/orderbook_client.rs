@@ -1,9 +1,15 @@
 use crate::commons::reqwest_client;
-use anyhow::bail;
 use anyhow::Result;
+use bitcoin::secp256k1::Message;
+use bitcoin::secp256k1::PublicKey;
 use commons::NewOrder;
-use commons::OrderResponse;
+use commons::NewTriggerOrder;
+use commons::Order;
+use commons::Signature;
+use commons::TriggerOrder;
 use reqwest::Url;
+use rust_decimal::Decimal;
+use uuid::Uuid;
 
 pub struct OrderbookClient {
     url: Url,
@@ -14,18 +20,87 @@ impl OrderbookClient {
         Self { url }
     }
 
-    pub(crate) async fn post_new_order(&self, order: NewOrder) -> Result<OrderResponse> {
-        let url = self.url.join("/api/orderbook/orders")?;
-        let client = reqwest_client();
+    pub(crate) async fn post_new_order(
+        &self,
+        order: NewOrder,
+        sign: impl Fn(Message) -> Signature,
+    ) -> Result<Order> {
+        orderbook_client::post_order(&reqwest_client(), &self.url, order, sign).await
+    }
+
+    pub(crate) async fn cancel_order(
+        &self,
+        order_id: Uuid,
+        sign: impl Fn(Message) -> Signature,
+    ) -> Result<Order> {
+        orderbook_client::cancel_order(&reqwest_client(), &self.url, order_id, sign).await
+    }
+
+    pub(crate) async fn cancel_all_orders(
+        &self,
+        trader_id: PublicKey,
+        sign: impl Fn(Message) -> Signature,
+    ) -> Result<Vec<Order>> {
+        orderbook_client::cancel_all_orders(&reqwest_client(), &self.url, trader_id, sign).await
+    }
+
+    pub(crate) async fn amend_order(
+        &self,
+        order_id: Uuid,
+        price: Decimal,
+        quantity: Decimal,
+        sign: impl Fn(Message) -> Signature,
+    ) -> Result<Order> {
+        orderbook_client::amend_order(
+            &reqwest_client(),
+            &self.url,
+            order_id,
+            price,
+            quantity,
+            sign,
+        )
+        .await
+    }
+
+    pub(crate) async fn get_trigger_orders(
+        &self,
+        trader_id: PublicKey,
+        sign: impl Fn(Message) -> Signature,
+    ) -> Result<Vec<TriggerOrder>> {
+        orderbook_client::get_trigger_orders(&reqwest_client(), &self.url, trader_id, sign).await
+    }
 
-        let response = client.post(url).json(&order).send().await?;
+    pub(crate) async fn post_trigger_order(
+        &self,
+        trigger_order: NewTriggerOrder,
+        sign: impl Fn(Message) -> Signature,
+    ) -> Result<TriggerOrder> {
+        orderbook_client::post_trigger_order(&reqwest_client(), &self.url, trigger_order, sign)
+            .await
+    }
+
+    pub(crate) async fn amend_trigger_order(
+        &self,
+        trigger_order_id: Uuid,
+        trigger_price: Decimal,
+        sign: impl Fn(Message) -> Signature,
+    ) -> Result<TriggerOrder> {
+        orderbook_client::amend_trigger_order(
+            &reqwest_client(),
+            &self.url,
+            trigger_order_id,
+            trigger_price,
+            sign,
+        )
+        .await
+    }
 
-        if response.status().as_u16() == 200 {
-            let response = response.json().await?;
-            Ok(response)
-        } else {
-            tracing::error!("Could not create new order");
-            bail!("Could not create new order: {response:?}")
-        }
+    pub(crate) async fn cancel_trigger_order(
+        &self,
+        trigger_order_id: Uuid,
+        sign: impl Fn(Message) -> Signature,
+    ) -> Result<TriggerOrder> {
+        orderbook_client::cancel_trigger_order(&reqwest_client(), &self.url, trigger_order_id, sign)
+            .await
     }
 }
@@ -53,6 +53,7 @@ pub struct Position {
     pub collateral: u64,
     pub expiry: i64,
     pub stable: bool,
+    pub funding_fee_sat: i64,
 }
 
 impl From<position::PositionState> for PositionState {
@@ -79,6 +80,7 @@ impl From<position::Position> for Position {
             collateral: value.collateral,
             expiry: value.expiry.unix_timestamp(),
             stable: value.stable,
+            funding_fee_sat: value.funding_fee_sat,
         }
     }
 }
@@ -7,6 +7,7 @@ use crate::trade::order;
 use crate::trade::order::Order;
 use crate::trade::order::OrderState;
 use crate::trade::order::OrderType;
+use crate::trade::order::TimeInForce;
 use crate::trade::position::compute_relative_contracts;
 use crate::trade::position::Position;
 use crate::trade::position::PositionState;
@@ -20,6 +21,7 @@ use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use time::OffsetDateTime;
 use trade::ContractSymbol;
+use uuid::Uuid;
 
 /// Sets up a trade with the counterparty
 ///
@@ -27,8 +29,45 @@ use trade::ContractSymbol;
 /// The DLC that represents the position will be stored in the database.
 /// Errors are handled within the scope of this function.
 pub async fn trade(filled: FilledWith) -> Result<()> {
+    let filled_quantity = filled.quantity().to_f32().expect("to fit into f32");
+
+    // The orderbook split the filled quantity off our partially filled limit order into a new
+    // order, unless we have already processed the match before.
+    if let Some(parent_order_id) = filled.parent_order_id {
+        if db::maybe_get_order(filled.order_id)?.is_none() {
+            let parent_order = split_off_parent_order(parent_order_id, filled_quantity)?
+                .with_context(|| format!("Could not find parent order {parent_order_id}"))?;
+
+            let order = db::insert_order(Order {
+                id: filled.order_id,
+                quantity: filled_quantity,
+                ..parent_order
+            })?;
+
+            event::publish(&EventInternal::OrderUpdateNotification(order));
+        }
+    }
+
     let order = db::get_order(filled.order_id).context("Could not load order from db")?;
 
+    // The orderbook cancels the quantity of an immediate-or-cancel order which could not be filled
+    // right away, hence we only trade the filled quantity.
+    let order = if order.time_in_force == TimeInForce::ImmediateOrCancel
+        && filled_quantity < order.quantity
+    {
+        tracing::info!(
+            order_id = %order.id,
+            quantity = order.quantity,
+            filled_quantity,
+            "Order has only been partially filled"
+        );
+
+        db::set_order_quantity(order.id, filled_quantity)
+            .context("Could not update quantity of partially filled order")?
+    } else {
+        order
+    };
+
     tracing::debug!(?order, ?filled, "Filling order with id: {}", order.id);
 
     let trade_params = TradeParams {
@@ -104,11 +143,28 @@ pub async fn async_trade(order: commons::Order, filled_with: FilledWith) -> Resu
         reason: order.order_reason.into(),
         stable: order.stable,
         failure_reason: None,
+        time_in_force: order.time_in_force.into(),
+        post_only: order.post_only,
     };
 
-    db::insert_order(order.clone())?;
+    // A resting limit order submitted by us is already known, whereas orders generated by the
+    // orderbook (e.g. to close an expired position) or split off a partially filled limit order
+    // are not.
+    match db::maybe_get_order(order.id)? {
+        Some(_) => {
+            order::handler::order_filling(order.id, execution_price)
+                .context("Could not update order to filling")?;
+        }
+        None => {
+            if let Some(parent_order_id) = filled_with.parent_order_id {
+                split_off_parent_order(parent_order_id, order.quantity)?;
+            }
+
+            db::insert_order(order.clone())?;
 
-    event::publish(&EventInternal::OrderUpdateNotification(order.clone()));
+            event::publish(&EventInternal::OrderUpdateNotification(order.clone()));
+        }
+    }
 
     let trade_params = TradeParams {
         pubkey: ln_dlc::get_node_pubkey(),
@@ -127,9 +183,56 @@ pub async fn async_trade(order: commons::Order, filled_with: FilledWith) -> Resu
     Ok(())
 }
 
+/// Reduces the quantity of our order identified by `parent_order_id` by the `filled_quantity`,
+/// which the orderbook has split off into a new order.
+///
+/// Returns the parent order as it was before the split, or `None` if we don't know the order.
+fn split_off_parent_order(parent_order_id: Uuid, filled_quantity: f32) -> Result<Option<Order>> {
+    let parent_order = match db::maybe_get_order(parent_order_id)? {
+        Some(parent_order) => parent_order,
+        None => return Ok(None),
+    };
+
+    let remaining_order =
+        db::set_order_quantity(parent_order_id, parent_order.quantity - filled_quantity)
+            .context("Could not update quantity of partially filled order")?;
+
+    tracing::info!(
+        order_id = %parent_order_id,
+        quantity = parent_order.quantity,
+        filled_quantity,
+        "Order has been partially filled"
+    );
+
+    event::publish(&EventInternal::OrderUpdateNotification(remaining_order));
+
+    Ok(Some(parent_order))
+}
+
 /// Rollover dlc to new expiry timestamp
 pub async fn rollover(contract_id: Option<String>) -> Result<()> {
-    ln_dlc::rollover(contract_id).await
+    let response = ln_dlc::rollover(contract_id).await?;
+
+    // The coordinator settles the outstanding funding fees with the renewed contract. As it
+    // reports the total funding fee of the position, retrying a failed rollover does not charge
+    // the funding fee twice. Paying the funding fee might have reduced our margin.
+    if let Some(position) = db::get_positions()?.first() {
+        let mut position = position.clone();
+        position.funding_fee_sat = response.funding_fee.to_sat();
+        position.collateral = response.trader_margin;
+        position.liquidation_price = response.trader_liquidation_price;
+
+        db::update_position_after_rollover(
+            position.contract_symbol,
+            position.funding_fee_sat,
+            position.collateral,
+            position.liquidation_price,
+        )?;
+
+        event::publish(&EventInternal::PositionUpdateNotification(position));
+    }
+
+    Ok(())
 }
 
 /// Fetch the positions from the database
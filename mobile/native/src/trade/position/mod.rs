@@ -75,6 +75,10 @@ pub struct Position {
     #[serde(with = "time::serde::rfc3339")]
     pub created: OffsetDateTime,
     pub stable: bool,
+    /// The total funding fee settled with the rollovers of the position. A positive amount has
+    /// been paid by the trader, a negative amount has been received by the trader.
+    #[serde(default)]
+    pub funding_fee_sat: i64,
 }
 
 impl Position {
@@ -131,6 +135,7 @@ impl Position {
             updated: now_timestamp,
             created: now_timestamp,
             stable: order.stable,
+            funding_fee_sat: 0,
         };
 
         let average_entry_price = decimal_from_f32(average_entry_price);
@@ -353,6 +358,7 @@ impl Position {
                     updated: now_timestamp,
                     created: self.created,
                     stable: self.stable,
+                    funding_fee_sat: self.funding_fee_sat,
                 };
 
                 let fee = order_matching_fee_taker(order.quantity, order_execution_price);
@@ -529,6 +535,7 @@ impl Position {
                 updated: now_timestamp,
                 created: self.created,
                 stable,
+                funding_fee_sat: self.funding_fee_sat,
             };
 
             let fee = order_matching_fee_taker(order.quantity, order_execution_price);
@@ -614,6 +621,7 @@ fn compute_relative_contracts(contracts: f32, direction: Direction) -> Decimal {
 mod tests {
     use super::*;
     use crate::trade::order::OrderReason;
+    use crate::trade::order::TimeInForce;
     use rust_decimal_macros::dec;
     use uuid::Uuid;
 
@@ -638,6 +646,8 @@ mod tests {
             reason: OrderReason::Manual,
             stable: true,
             failure_reason: None,
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
         };
 
         let (position, opening_trade) = Position::new_open(order.clone(), dlc_collateral, now);
@@ -682,6 +692,7 @@ mod tests {
             updated: now,
             created: now,
             stable: false,
+            funding_fee_sat: 0,
         };
 
         let order = Order {
@@ -699,6 +710,8 @@ mod tests {
             reason: OrderReason::Manual,
             stable: false,
             failure_reason: None,
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
         };
 
         // The DLC channel has been closed.
@@ -744,6 +757,7 @@ mod tests {
             updated: now,
             created: now,
             stable: false,
+            funding_fee_sat: 0,
         };
 
         let order = Order {
@@ -761,6 +775,8 @@ mod tests {
             reason: OrderReason::Manual,
             stable: false,
             failure_reason: None,
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
         };
 
         let dlc_collateral_after_resize = 20_578;
@@ -815,6 +831,7 @@ mod tests {
             updated: now,
             created: now,
             stable: false,
+            funding_fee_sat: 0,
         };
 
         let order = Order {
@@ -832,6 +849,8 @@ mod tests {
             reason: OrderReason::Manual,
             stable: false,
             failure_reason: None,
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
         };
 
         let dlc_collateral_after_resize = 6_855;
@@ -892,6 +911,7 @@ mod tests {
             updated: now,
             created: now,
             stable: false,
+            funding_fee_sat: 0,
         };
 
         let order = Order {
@@ -909,6 +929,8 @@ mod tests {
             reason: OrderReason::Manual,
             stable: false,
             failure_reason: None,
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
         };
 
         let dlc_collateral_after_resize = 13_736;
@@ -10,6 +10,9 @@ class Payment {
 
 enum PaymentFlow { outbound, inbound }
 
+/// Replace-by-fee only works for outbound payments, child-pays-for-parent for both directions.
+enum FeeBumpMethod { rbf, cpfp }
+
 class OnChainPayment {
   final PaymentFlow flow;
   final Amount amount;
@@ -0,0 +1,92 @@
+import 'package:flutter/material.dart';
+import 'package:get_10101/common/amount_text_input_form_field.dart';
+import 'package:get_10101/common/model.dart';
+import 'package:get_10101/common/payment.dart';
+import 'package:get_10101/common/snack_bar.dart';
+import 'package:get_10101/wallet/wallet_change_notifier.dart';
+import 'package:provider/provider.dart';
+
+/// Lets the user speed up an unconfirmed on-chain payment by bumping its fee rate.
+class BumpFeeDialog extends StatefulWidget {
+  final OnChainPayment payment;
+
+  const BumpFeeDialog({super.key, required this.payment});
+
+  @override
+  State<BumpFeeDialog> createState() => _BumpFeeDialogState();
+}
+
+class _BumpFeeDialogState extends State<BumpFeeDialog> {
+  final GlobalKey<FormState> _formKey = GlobalKey<FormState>();
+
+  late FeeBumpMethod method;
+  Amount? fee;
+
+  @override
+  void initState() {
+    super.initState();
+    method =
+        widget.payment.flow == PaymentFlow.outbound ? FeeBumpMethod.rbf : FeeBumpMethod.cpfp;
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    return AlertDialog(
+      title: const Text("Bump fee"),
+      content: Form(
+        key: _formKey,
+        child: Column(mainAxisSize: MainAxisSize.min, children: [
+          SelectableText(widget.payment.txid, style: const TextStyle(fontSize: 12)),
+          const SizedBox(height: 20),
+          SegmentedButton<FeeBumpMethod>(
+            segments: [
+              ButtonSegment(
+                  value: FeeBumpMethod.rbf,
+                  label: const Text("Replace (RBF)"),
+                  enabled: widget.payment.flow == PaymentFlow.outbound),
+              const ButtonSegment(value: FeeBumpMethod.cpfp, label: Text("Child (CPFP)")),
+            ],
+            selected: {method},
+            onSelectionChanged: (selection) => setState(() => method = selection.first),
+          ),
+          const SizedBox(height: 20),
+          AmountInputField(
+            value: fee ?? Amount.zero(),
+            label: "New fee rate in sats/vb",
+            validator: (value) {
+              if (value == null || value.isEmpty || value == "0") {
+                return "The fee rate must be greater than 0";
+              }
+              return null;
+            },
+            onChanged: (value) => setState(() => fee = Amount.parseAmount(value)),
+          ),
+        ]),
+      ),
+      actions: [
+        TextButton(onPressed: () => Navigator.pop(context), child: const Text("Cancel")),
+        ElevatedButton(
+            onPressed: () async {
+              if (!_formKey.currentState!.validate()) {
+                return;
+              }
+
+              final messenger = ScaffoldMessenger.of(context);
+              final navigator = Navigator.of(context);
+              try {
+                final txid = await context
+                    .read<WalletChangeNotifier>()
+                    .service
+                    .bumpFee(widget.payment.txid, method, fee!);
+
+                showSnackBar(messenger, "Fee has been bumped with transaction $txid.");
+                navigator.pop();
+              } catch (e) {
+                showSnackBar(messenger, "Failed to bump fee. $e");
+              }
+            },
+            child: const Text("Bump fee")),
+      ],
+    );
+  }
+}
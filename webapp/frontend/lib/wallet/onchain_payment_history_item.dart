@@ -1,5 +1,6 @@
 import 'package:flutter/material.dart';
 import 'package:get_10101/common/payment.dart';
+import 'package:get_10101/wallet/bump_fee_dialog.dart';
 import 'package:intl/intl.dart';
 import 'package:timeago/timeago.dart' as timeago;
 
@@ -28,9 +29,11 @@ class OnChainPaymentHistoryItem extends StatelessWidget {
           margin: const EdgeInsets.all(0),
           elevation: 0,
           child: ListTile(
-              onTap: () async {
-                // todo
-              },
+              onTap: data.confirmations == 0
+                  ? () => showDialog(
+                      context: context,
+                      builder: (BuildContext context) => BumpFeeDialog(payment: data))
+                  : null,
               leading: Stack(children: [
                 Container(
                   padding: const EdgeInsets.only(bottom: 20.0),
@@ -42,6 +42,22 @@ class WalletService {
     }
   }
 
+  /// Bumps the fee of an unconfirmed transaction and returns the id of the replacing (RBF) or
+  /// child (CPFP) transaction.
+  Future<String> bumpFee(String txid, FeeBumpMethod method, Amount fee) async {
+    final response = await HttpClientManager.instance.post(Uri(path: '/api/bumpfee'),
+        headers: <String, String>{
+          'Content-Type': 'application/json; charset=UTF-8',
+        },
+        body: jsonEncode(<String, dynamic>{'txid': txid, 'method': method.name, 'fee': fee.sats}));
+
+    if (response.statusCode == 200) {
+      return jsonDecode(response.body) as String;
+    } else {
+      throw FlutterError("Failed to bump fee. ${response.body}");
+    }
+  }
+
   Future<List<OnChainPayment>> getOnChainPaymentHistory() async {
     final response = await HttpClientManager.instance.get(Uri(path: '/api/history'));
 
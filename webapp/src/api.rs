@@ -8,6 +8,7 @@ use axum::response::IntoResponse;
 use axum::response::Response;
 use axum::routing::get;
 use axum::routing::post;
+use axum::routing::put;
 use axum::Json;
 use axum::Router;
 use bitcoin::Amount;
@@ -16,6 +17,7 @@ use commons::Price;
 use native::api::ContractSymbol;
 use native::api::Direction;
 use native::api::Fee;
+use native::api::FeeBumpMethod;
 use native::api::SendPayment;
 use native::api::WalletHistoryItemType;
 use native::calculations::calculate_pnl;
@@ -23,6 +25,8 @@ use native::ln_dlc;
 use native::trade::order::FailureReason;
 use native::trade::order::InvalidSubchannelOffer;
 use native::trade::order::OrderType;
+use native::trade::order::TimeInForce;
+use native::trade::order::TriggerType;
 use native::trade::position::PositionState;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
@@ -37,8 +41,17 @@ pub fn router(subscribers: Arc<AppSubscribers>) -> Router {
         .route("/api/balance", get(get_balance))
         .route("/api/newaddress", get(get_unused_address))
         .route("/api/sendpayment", post(send_payment))
+        .route("/api/bumpfee", post(bump_fee))
         .route("/api/history", get(get_onchain_payment_history))
         .route("/api/orders", get(get_orders).post(post_new_order))
+        .route(
+            "/api/trigger-orders",
+            get(get_trigger_orders).post(post_trigger_order),
+        )
+        .route(
+            "/api/trigger-orders/:id",
+            put(put_trigger_order).delete(delete_trigger_order),
+        )
         .route("/api/positions", get(get_positions))
         .route("/api/quotes/:contract_symbol", get(get_best_quote))
         .route("/api/node", get(get_node_id))
@@ -161,6 +174,33 @@ pub async fn send_payment(params: Json<Payment>) -> Result<(), AppError> {
     Ok(())
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BumpFeeMethod {
+    Rbf,
+    Cpfp,
+}
+
+#[derive(Deserialize)]
+pub struct BumpFee {
+    txid: String,
+    method: BumpFeeMethod,
+    fee: u64,
+}
+
+pub async fn bump_fee(params: Json<BumpFee>) -> Result<Json<String>, AppError> {
+    let method = match params.0.method {
+        BumpFeeMethod::Rbf => FeeBumpMethod::Rbf,
+        BumpFeeMethod::Cpfp => FeeBumpMethod::Cpfp,
+    };
+
+    let txid =
+        ln_dlc::bump_on_chain_fee(params.0.txid, method, Fee::FeeRate { sats: params.0.fee })?;
+
+    ln_dlc::refresh_wallet_info().await?;
+    Ok(Json(txid.to_string()))
+}
+
 pub async fn get_node_id() -> impl IntoResponse {
     ln_dlc::get_node_pubkey().to_string()
 }
@@ -181,11 +221,34 @@ pub struct NewOrderParams {
     #[serde(with = "rust_decimal::serde::float")]
     pub quantity: Decimal,
     pub direction: Direction,
+    /// If a price is given, a limit order is placed at this price. Otherwise, a market order is
+    /// placed.
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub price: Option<Decimal>,
+    /// Defaults to fill-or-kill for market orders and good-till-time for limit orders.
+    #[serde(default)]
+    pub time_in_force: Option<TimeInForce>,
+    #[serde(default)]
+    pub post_only: bool,
 }
 
 impl TryFrom<NewOrderParams> for native::trade::order::Order {
     type Error = anyhow::Error;
     fn try_from(value: NewOrderParams) -> Result<Self> {
+        let order_type = match value.price {
+            Some(price) => OrderType::Limit {
+                price: price
+                    .to_f32()
+                    .context("To be able to parse price into f32")?,
+            },
+            None => OrderType::Market,
+        };
+
+        let time_in_force = value.time_in_force.unwrap_or(match order_type {
+            OrderType::Market => TimeInForce::FillOrKill,
+            OrderType::Limit { .. } => TimeInForce::GoodTillTime,
+        });
+
         Ok(native::trade::order::Order {
             id: Uuid::new_v4(),
             leverage: value
@@ -198,15 +261,16 @@ impl TryFrom<NewOrderParams> for native::trade::order::Order {
                 .context("To be able to parse leverage into f32")?,
             contract_symbol: ContractSymbol::BtcUsd,
             direction: value.direction,
-            // We only support market orders for now
-            order_type: OrderType::Market,
+            order_type,
             state: native::trade::order::OrderState::Initial,
             creation_timestamp: OffsetDateTime::now_utc(),
             // We do not support setting order expiry from the frontend for now
-            order_expiry_timestamp: OffsetDateTime::now_utc() + time::Duration::minutes(1),
+            order_expiry_timestamp: OffsetDateTime::now_utc() + order_type.expiry(),
             reason: native::trade::order::OrderReason::Manual,
             stable: false,
             failure_reason: None,
+            time_in_force,
+            post_only: value.post_only,
         })
     }
 }
@@ -223,6 +287,89 @@ pub async fn post_new_order(params: Json<NewOrderParams>) -> Result<Json<OrderId
     Ok(Json(OrderId { id: order_id }))
 }
 
+#[derive(Deserialize)]
+pub struct NewTriggerOrderParams {
+    pub trigger_type: TriggerType,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub trigger_price: Decimal,
+}
+
+#[derive(Deserialize)]
+pub struct AmendTriggerOrderParams {
+    #[serde(with = "rust_decimal::serde::float")]
+    pub trigger_price: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TriggerOrder {
+    pub id: Uuid,
+    pub contract_symbol: ContractSymbol,
+    pub trigger_type: TriggerType,
+    pub trigger_price: f32,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
+
+impl From<native::trade::order::TriggerOrder> for TriggerOrder {
+    fn from(value: native::trade::order::TriggerOrder) -> Self {
+        TriggerOrder {
+            id: value.id,
+            contract_symbol: value.contract_symbol,
+            trigger_type: value.trigger_type,
+            trigger_price: value.trigger_price,
+            timestamp: value.timestamp,
+        }
+    }
+}
+
+pub async fn get_trigger_orders() -> Result<Json<Vec<TriggerOrder>>, AppError> {
+    let trigger_orders = native::trade::order::handler::get_trigger_orders()
+        .await?
+        .into_iter()
+        .map(TriggerOrder::from)
+        .collect();
+
+    Ok(Json(trigger_orders))
+}
+
+pub async fn post_trigger_order(
+    params: Json<NewTriggerOrderParams>,
+) -> Result<Json<TriggerOrder>, AppError> {
+    let trigger_order = native::trade::order::handler::submit_trigger_order(
+        ContractSymbol::BtcUsd,
+        params.trigger_type,
+        params
+            .trigger_price
+            .to_f32()
+            .context("To be able to parse trigger price into f32")?,
+    )
+    .await?;
+
+    Ok(Json(trigger_order.into()))
+}
+
+pub async fn put_trigger_order(
+    Path(id): Path<Uuid>,
+    params: Json<AmendTriggerOrderParams>,
+) -> Result<Json<TriggerOrder>, AppError> {
+    let trigger_order = native::trade::order::handler::amend_trigger_order(
+        id,
+        params
+            .trigger_price
+            .to_f32()
+            .context("To be able to parse trigger price into f32")?,
+    )
+    .await?;
+
+    Ok(Json(trigger_order.into()))
+}
+
+pub async fn delete_trigger_order(Path(id): Path<Uuid>) -> Result<(), AppError> {
+    native::trade::order::handler::cancel_trigger_order(id).await?;
+
+    Ok(())
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Position {
     pub leverage: f32,
@@ -319,8 +466,7 @@ pub struct Order {
     pub id: Uuid,
     pub leverage: f32,
     pub quantity: f32,
-    /// An order only has a price if it either was filled or if it was a limit order (which is not
-    /// implemented yet).
+    /// An order only has a price if it either was filled or if it was a limit order.
     pub price: Option<f32>,
     pub contract_symbol: ContractSymbol,
     pub direction: Direction,
@@ -331,6 +477,8 @@ pub struct Order {
     #[serde(with = "time::serde::rfc3339")]
     pub order_expiry_timestamp: OffsetDateTime,
     pub failure_reason: Option<String>,
+    pub time_in_force: TimeInForce,
+    pub post_only: bool,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -386,6 +534,7 @@ impl From<&native::trade::order::Order> for Order {
                         InvalidSubchannelOffer::Unacceptable => "OfferUnacceptable",
                     },
                     FailureReason::OrderRejected => "OrderRejected",
+                    FailureReason::Cancelled => "Cancelled",
                     FailureReason::Unknown => "Unknown",
                 }
                 .to_string();
@@ -417,6 +566,8 @@ impl From<&native::trade::order::Order> for Order {
             creation_timestamp: value.creation_timestamp,
             order_expiry_timestamp: value.order_expiry_timestamp,
             failure_reason,
+            time_in_force: value.time_in_force,
+            post_only: value.post_only,
         }
     }
 }